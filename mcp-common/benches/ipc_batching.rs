@@ -0,0 +1,78 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use mcp_common::{IpcClient, IpcConnection, IpcMessage, IpcServer, LogEntry, LogLevel, ProxyId};
+use tempfile::tempdir;
+use tokio::runtime::Runtime;
+
+const MESSAGES: usize = 1000;
+const BATCH_SIZE: usize = 50;
+
+fn entries(proxy_id: &ProxyId) -> Vec<LogEntry> {
+    (0..MESSAGES)
+        .map(|i| {
+            LogEntry::new(
+                LogLevel::Request,
+                format!(r#"→ {{"jsonrpc":"2.0","id":{},"method":"ping"}}"#, i),
+                proxy_id.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Send `messages` and read until every entry they carry has arrived
+async fn round_trip(client: &mut IpcClient, server: &mut IpcConnection, messages: Vec<IpcMessage>) {
+    let send = async {
+        for message in messages {
+            client.send(message).await.unwrap();
+        }
+    };
+    let receive = async {
+        let mut received = 0;
+        while received < MESSAGES {
+            match server.receive_message().await.unwrap().unwrap().message {
+                IpcMessage::LogEntry(_) => received += 1,
+                IpcMessage::LogsBatch(entries) => received += entries.len(),
+                _ => {}
+            }
+        }
+    };
+    tokio::join!(send, receive);
+}
+
+fn bench_log_batching(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("bench.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let (mut client, mut server) = runtime.block_on(async {
+        let listener = IpcServer::bind(&socket_path).await.unwrap();
+        let client = IpcClient::connect(&socket_path).await.unwrap();
+        let server = listener.accept().await.unwrap();
+        (client, server)
+    });
+    let entries = entries(&ProxyId::new());
+
+    let mut group = c.benchmark_group("1000 log entries");
+    group.bench_function("one envelope per entry", |b| {
+        b.iter(|| {
+            let messages = entries.iter().cloned().map(IpcMessage::LogEntry).collect();
+            runtime.block_on(round_trip(&mut client, &mut server, messages));
+        })
+    });
+    group.bench_function("batches of 50", |b| {
+        b.iter(|| {
+            let messages = entries
+                .chunks(BATCH_SIZE)
+                .map(|chunk| IpcMessage::LogsBatch(chunk.to_vec()))
+                .collect();
+            runtime.block_on(round_trip(&mut client, &mut server, messages));
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_log_batching);
+criterion_main!(benches);
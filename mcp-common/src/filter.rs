@@ -0,0 +1,74 @@
+use crate::validation::strip_direction;
+use crate::{LogEntry, LogLevel, ProxyId, ProxyInfo};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+/// Conditions a log entry must all meet, for processing recorded logs offline
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub level: Option<LogLevel>,
+    pub proxy_name: Option<String>,
+    pub method: Option<String>, // JSON-RPC method of requests and notifications
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub search: Option<String>, // Case-insensitive text in the message
+    pub proxy_ids: HashSet<ProxyId>, // Proxies named `proxy_name`, filled by `note_proxy`
+}
+
+impl LogFilter {
+    /// Learn a proxy's name, which entries only refer to by id
+    pub fn note_proxy(&mut self, info: &ProxyInfo) {
+        if self.proxy_name.as_ref() == Some(&info.name) {
+            self.proxy_ids.insert(info.id.clone());
+        }
+    }
+
+    /// Whether `matches` looks at the message JSON, so callers only parse it when needed
+    pub fn needs_json(&self) -> bool {
+        self.method.is_some()
+    }
+
+    /// Check `entry` against every condition; `parsed_json` is its message body, see
+    /// `message_json`
+    pub fn matches(&self, entry: &LogEntry, parsed_json: Option<&serde_json::Value>) -> bool {
+        if self
+            .level
+            .as_ref()
+            .is_some_and(|level| &entry.level != level)
+        {
+            return false;
+        }
+        if self.proxy_name.is_some() && !self.proxy_ids.contains(&entry.proxy_id) {
+            return false;
+        }
+        if self.from.is_some_and(|from| entry.timestamp < from) {
+            return false;
+        }
+        if self.to.is_some_and(|to| entry.timestamp > to) {
+            return false;
+        }
+        if let Some(ref method) = self.method {
+            let entry_method = parsed_json
+                .and_then(|json| json.get("method"))
+                .and_then(|method| method.as_str());
+            if entry_method != Some(method.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref search) = self.search {
+            if !entry
+                .message
+                .to_lowercase()
+                .contains(&search.to_lowercase())
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The JSON body of a logged request or response, without its direction marker
+pub fn message_json(entry: &LogEntry) -> Option<serde_json::Value> {
+    serde_json::from_str(strip_direction(&entry.message)).ok()
+}
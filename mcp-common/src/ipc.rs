@@ -1,49 +1,344 @@
 use crate::{IpcEnvelope, IpcMessage};
 use anyhow::Result;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+#[cfg(unix)]
 use tokio::net::{UnixListener, UnixStream};
-use tracing::{debug, error, info};
+use tracing::{debug, info, warn};
+
+/// Longest message a connection accepts unless told otherwise
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Where the monitor listens unless told otherwise; Unix sockets need a Unix platform
+#[cfg(unix)]
+pub const DEFAULT_IPC_SOCKET: &str = "/tmp/mcp-monitor.sock";
+#[cfg(not(unix))]
+pub const DEFAULT_IPC_SOCKET: &str = "tcp://127.0.0.1:47474";
+
+/// An IPC endpoint as given to `--ipc-socket`: `tcp://HOST:PORT`, or else a Unix
+/// socket path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcAddress {
+    Unix(PathBuf),
+    Tcp(String), // HOST:PORT
+}
+
+impl IpcAddress {
+    pub fn parse(address: &str) -> Self {
+        match address.strip_prefix("tcp://") {
+            Some(host_port) => IpcAddress::Tcp(host_port.to_string()),
+            None => IpcAddress::Unix(PathBuf::from(address)),
+        }
+    }
+}
+
+impl fmt::Display for IpcAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpcAddress::Unix(path) => write!(f, "{}", path.display()),
+            IpcAddress::Tcp(host_port) => write!(f, "tcp://{}", host_port),
+        }
+    }
+}
+
+/// How messages are delimited on a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IpcFraming {
+    /// One JSON envelope per line
+    #[default]
+    Lines,
+    /// A 4-byte big-endian length, then that many bytes of JSON envelope
+    LengthPrefixed,
+}
+
+impl IpcFraming {
+    /// Guess from the first byte a peer sent: any length prefix under 128MB opens with
+    /// a byte below 8, which no line of text (or garbage meant as one) starts with
+    fn detect(first_byte: u8) -> Self {
+        if first_byte < 0x08 {
+            IpcFraming::LengthPrefixed
+        } else {
+            IpcFraming::Lines
+        }
+    }
+}
+
+impl fmt::Display for IpcFraming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpcFraming::Lines => write!(f, "lines"),
+            IpcFraming::LengthPrefixed => write!(f, "length-prefixed"),
+        }
+    }
+}
+
+impl std::str::FromStr for IpcFraming {
+    type Err = String;
+
+    fn from_str(framing: &str) -> std::result::Result<Self, Self::Err> {
+        match framing {
+            "lines" => Ok(IpcFraming::Lines),
+            "length-prefixed" => Ok(IpcFraming::LengthPrefixed),
+            other => Err(format!(
+                "unknown IPC framing '{}', expected lines or length-prefixed",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn unix_unsupported() -> anyhow::Error {
+    anyhow::anyhow!("Unix sockets are not supported here; use tcp://127.0.0.1:PORT")
+}
+
+enum Listener {
+    #[cfg(unix)]
+    Unix(UnixListener),
+    Tcp(TcpListener),
+}
+
+/// IPC failures callers may want to tell apart from I/O errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcError {
+    /// The server already has its maximum number of open connections
+    ConnectionLimitReached,
+    /// A connection to a server with a token didn't open with a `Hello` carrying it
+    Unauthorized,
+    /// The peer sent a message of `actual` bytes, over the connection's `limit`
+    MessageTooLarge { actual: usize, limit: usize },
+    /// The peer sent a message that isn't an IPC envelope, for this reason
+    InvalidMessage(String),
+}
+
+impl fmt::Display for IpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IpcError::ConnectionLimitReached => write!(f, "IPC connection limit reached"),
+            IpcError::Unauthorized => write!(f, "IPC client sent a missing or wrong token"),
+            IpcError::MessageTooLarge { actual, limit } => write!(
+                f,
+                "IPC message of {} bytes is over the {} byte limit",
+                actual, limit
+            ),
+            IpcError::InvalidMessage(reason) => {
+                write!(f, "IPC message is not a valid envelope: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IpcError {}
 
 pub struct IpcServer {
-    listener: UnixListener,
+    listener: Listener,
+    address: IpcAddress,   // With the actual port when bound to port 0
+    token: Option<String>, // Required from TCP clients
+    max_connections: Option<usize>,
+    max_message_bytes: usize,           // For every accepted connection
+    connection_count: Arc<AtomicUsize>, // Connections accepted and not yet dropped
 }
 
 impl IpcServer {
-    pub async fn bind(socket_path: &str) -> Result<Self> {
-        // Remove existing socket file if it exists
-        let _ = tokio::fs::remove_file(socket_path).await;
+    /// Listen on `address`, a Unix socket path or `tcp://HOST:PORT`
+    pub async fn bind(address: &str) -> Result<Self> {
+        let (listener, address) = match IpcAddress::parse(address) {
+            #[cfg(unix)]
+            IpcAddress::Unix(path) => {
+                // Remove existing socket file if it exists
+                let _ = tokio::fs::remove_file(&path).await;
+                (
+                    Listener::Unix(UnixListener::bind(&path)?),
+                    IpcAddress::Unix(path),
+                )
+            }
+            #[cfg(not(unix))]
+            IpcAddress::Unix(_) => return Err(unix_unsupported()),
+            IpcAddress::Tcp(host_port) => {
+                let listener = TcpListener::bind(&host_port).await?;
+                let address = IpcAddress::Tcp(listener.local_addr()?.to_string());
+                (Listener::Tcp(listener), address)
+            }
+        };
+        info!("IPC server listening on {}", address);
+
+        Ok(Self {
+            listener,
+            address,
+            token: None,
+            max_connections: None,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            connection_count: Arc::new(AtomicUsize::new(0)),
+        })
+    }
 
-        let listener = UnixListener::bind(socket_path)?;
-        info!("IPC server listening on {}", socket_path);
+    /// Require TCP clients to open with a `Hello` carrying `token`. Unix socket clients
+    /// are let in without one, as the socket file's permissions already guard it.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Limit the messages of accepted connections, see `IpcConnection::with_max_message_bytes`
+    pub fn with_max_message_bytes(mut self, max_message_bytes: usize) -> Self {
+        self.max_message_bytes = max_message_bytes;
+        self
+    }
 
-        Ok(Self { listener })
+    /// Reject connections beyond `max_connections` open at once
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = Some(max_connections);
+        self
     }
 
+    /// Accept the next connection. At the connection limit it is closed right away and
+    /// `IpcError::ConnectionLimitReached` returned instead.
+    ///
+    /// The connection takes its framing from the first message the client sends, and
+    /// answers in kind; anything sent before that goes out as lines.
     pub async fn accept(&self) -> Result<IpcConnection> {
-        let (stream, _) = self.listener.accept().await?;
-        Ok(IpcConnection::new(stream))
+        let mut connection = match self.listener {
+            #[cfg(unix)]
+            Listener::Unix(ref listener) => IpcConnection::new(listener.accept().await?.0),
+            Listener::Tcp(ref listener) => {
+                let (stream, _) = listener.accept().await?;
+                stream.set_nodelay(true)?;
+                let mut connection = IpcConnection::new(stream);
+                connection.expected_token = self.token.clone();
+                connection
+            }
+        };
+
+        if let Some(max) = self.max_connections {
+            if self.connection_count() >= max {
+                warn!("Rejecting IPC connection: {} already open", max);
+                return Err(IpcError::ConnectionLimitReached.into());
+            }
+        }
+
+        connection.framing = None;
+        connection.max_message_bytes = self.max_message_bytes;
+        connection.guard = Some(ConnectionGuard::new(self.connection_count.clone()));
+        Ok(connection)
+    }
+
+    /// The address clients connect to, e.g. `tcp://127.0.0.1:40123` after binding port 0
+    pub fn address(&self) -> &IpcAddress {
+        &self.address
+    }
+
+    /// Number of accepted connections still open
+    pub fn connection_count(&self) -> usize {
+        self.connection_count.load(Ordering::SeqCst)
     }
 }
 
+/// Counts a server connection as open until it is dropped
+struct ConnectionGuard {
+    count: Arc<AtomicUsize>,
+}
+
+impl ConnectionGuard {
+    fn new(count: Arc<AtomicUsize>) -> Self {
+        count.fetch_add(1, Ordering::SeqCst);
+        Self { count }
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+type ReadHalf = Box<dyn AsyncRead + Send + Unpin>;
+type WriteHalf = Box<dyn AsyncWrite + Send + Unpin>;
+
 pub struct IpcConnection {
-    reader: BufReader<tokio::net::unix::OwnedReadHalf>,
-    writer: tokio::net::unix::OwnedWriteHalf,
+    reader: BufReader<ReadHalf>,
+    writer: WriteHalf,
+    read_buf: Vec<u8>, // Bytes of a message not yet complete, kept across cancelled reads
+    framing: Option<IpcFraming>, // `None` until learned from the peer's first message
+    frame_len: Option<usize>, // Length of the frame being read, once its prefix is in
+    max_message_bytes: usize,
+    oversized: Option<usize>, // Bytes so far of a message over the limit, being skipped
+    guard: Option<ConnectionGuard>, // Set on connections accepted by an `IpcServer`
+    expected_token: Option<String>, // Until a `Hello` with this token arrives
+    session_id: uuid::Uuid,   // Sent with every envelope, new for each connection
+    send_seq: u64,            // Sequence number of the next envelope sent
+    peer_session: Option<uuid::Uuid>, // Session of the envelopes being received
+    expected_seq: u64,        // Sequence number the next one should have
+    sequence_gaps: u64,       // Envelopes missing between the ones received
 }
 
 impl IpcConnection {
-    pub fn new(stream: UnixStream) -> Self {
-        let (read_half, write_half) = stream.into_split();
-        let reader = BufReader::new(read_half);
+    /// A connection over any byte stream, such as a `UnixStream` or `TcpStream`
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let (read_half, write_half) = tokio::io::split(stream);
+        let reader = BufReader::new(Box::new(read_half) as ReadHalf);
 
         Self {
             reader,
-            writer: write_half,
+            writer: Box::new(write_half),
+            read_buf: Vec::new(),
+            framing: Some(IpcFraming::Lines),
+            frame_len: None,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            oversized: None,
+            guard: None,
+            expected_token: None,
+            session_id: uuid::Uuid::new_v4(),
+            send_seq: 0,
+            peer_session: None,
+            expected_seq: 0,
+            sequence_gaps: 0,
         }
     }
 
-    pub async fn connect(socket_path: &str) -> Result<Self> {
-        let stream = UnixStream::connect(socket_path).await?;
-        Ok(Self::new(stream))
+    /// Refuse messages longer than `max_message_bytes` instead of buffering them.
+    /// Only what is being received is limited; sending is up to the peer's limit.
+    pub fn with_max_message_bytes(mut self, max_message_bytes: usize) -> Self {
+        self.max_message_bytes = max_message_bytes;
+        self
+    }
+
+    /// Delimit messages with `framing`; the peer has to use the same
+    pub fn with_framing(mut self, framing: IpcFraming) -> Self {
+        self.framing = Some(framing);
+        self
+    }
+
+    /// How messages are delimited, `None` on an accepted connection that has yet to
+    /// receive anything
+    pub fn framing(&self) -> Option<IpcFraming> {
+        self.framing
+    }
+
+    /// How many envelopes went missing on the way here, going by their sequence numbers
+    pub fn sequence_gaps(&self) -> u64 {
+        self.sequence_gaps
+    }
+
+    /// Connect to `address`, a Unix socket path or `tcp://HOST:PORT`
+    pub async fn connect(address: &str) -> Result<Self> {
+        match IpcAddress::parse(address) {
+            #[cfg(unix)]
+            IpcAddress::Unix(path) => Ok(Self::new(UnixStream::connect(path).await?)),
+            #[cfg(not(unix))]
+            IpcAddress::Unix(_) => Err(unix_unsupported()),
+            IpcAddress::Tcp(host_port) => {
+                let stream = TcpStream::connect(host_port).await?;
+                stream.set_nodelay(true)?;
+                Ok(Self::new(stream))
+            }
+        }
     }
 
     pub async fn send_message(&mut self, message: IpcMessage) -> Result<()> {
@@ -51,39 +346,214 @@ impl IpcConnection {
             message,
             timestamp: chrono::Utc::now(),
             correlation_id: Some(uuid::Uuid::new_v4()),
+            sequence: self.send_seq,
+            session_id: Some(self.session_id),
         };
+        self.send_seq += 1;
 
         let json = serde_json::to_string(&envelope)?;
         debug!("Sending IPC message: {}", json);
 
-        self.writer.write_all(json.as_bytes()).await?;
-        self.writer.write_all(b"\n").await?;
+        match self.framing.unwrap_or_default() {
+            IpcFraming::Lines => {
+                self.writer.write_all(json.as_bytes()).await?;
+                self.writer.write_all(b"\n").await?;
+            }
+            IpcFraming::LengthPrefixed => {
+                let length = u32::try_from(json.len())?;
+                self.writer.write_all(&length.to_be_bytes()).await?;
+                self.writer.write_all(json.as_bytes()).await?;
+            }
+        }
         self.writer.flush().await?;
 
         Ok(())
     }
 
+    /// Read the next envelope, or `None` once the peer closes the connection.
+    ///
+    /// Cancel safe, so it can be raced in `tokio::select!`: a partly read message is
+    /// kept and finished by the next call.
+    ///
+    /// On a connection that needs a token, anything before the right `Hello` gives
+    /// `IpcError::Unauthorized`. `Hello` itself is consumed, never returned.
+    ///
+    /// A message over the size limit is skipped without being buffered and gives
+    /// `IpcError::MessageTooLarge`, and one that doesn't parse gives
+    /// `IpcError::InvalidMessage`; either way the connection stays usable for the next.
+    /// Nothing is sent back: the peer only sends notifications, so there is no request
+    /// to answer with an `IpcMessage::Error`.
     pub async fn receive_message(&mut self) -> Result<Option<IpcEnvelope>> {
-        let mut line = String::new();
-        let bytes_read = self.reader.read_line(&mut line).await?;
+        loop {
+            let framing = match self.framing {
+                Some(framing) => framing,
+                None => match self.reader.fill_buf().await?.first() {
+                    Some(&first_byte) => *self.framing.insert(IpcFraming::detect(first_byte)),
+                    None => return Ok(None), // Closed before saying anything
+                },
+            };
+            let message = match framing {
+                IpcFraming::Lines => self.read_line().await?,
+                IpcFraming::LengthPrefixed => self.read_frame().await?,
+            };
+            let Some(message) = message else {
+                return Ok(None); // Connection closed
+            };
+            let line = String::from_utf8_lossy(&message);
+            let envelope = match serde_json::from_str::<IpcEnvelope>(line.trim()) {
+                Ok(envelope) => envelope,
+                Err(e) => {
+                    warn!("Failed to deserialize IPC message: {}", e);
+                    return Err(IpcError::InvalidMessage(e.to_string()).into());
+                }
+            };
+            debug!("Received IPC message: {:?}", envelope.message);
+            self.check_sequence(&envelope);
+
+            if let IpcMessage::Hello { ref token } = envelope.message {
+                if let Some(ref expected) = self.expected_token {
+                    if !tokens_match(token, expected) {
+                        return Err(IpcError::Unauthorized.into());
+                    }
+                    self.expected_token = None;
+                }
+                continue;
+            }
+            if self.expected_token.is_some() {
+                return Err(IpcError::Unauthorized.into());
+            }
+            return Ok(Some(envelope));
+        }
+    }
+
+    /// Count and warn about envelopes skipped since the last one from the same session.
+    /// A new session, as after the peer reconnects, starts again from 0.
+    fn check_sequence(&mut self, envelope: &IpcEnvelope) {
+        let Some(session_id) = envelope.session_id else {
+            return; // A sender that doesn't number its envelopes
+        };
+        if self.peer_session != Some(session_id) {
+            self.peer_session = Some(session_id);
+            self.expected_seq = 0;
+        }
+        if envelope.sequence > self.expected_seq {
+            let missing = envelope.sequence - self.expected_seq;
+            warn!(
+                "IPC sequence gap: expected {}, got {} ({} missing)",
+                self.expected_seq, envelope.sequence, missing
+            );
+            self.sequence_gaps += missing;
+        }
+        self.expected_seq = self.expected_seq.max(envelope.sequence + 1);
+    }
+
+    /// Next line without its newline, or `None` at the end of the stream. Never holds
+    /// more than `max_message_bytes` of it; cancel safe like `receive_message`.
+    async fn read_line(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            let available = self.reader.fill_buf().await?;
+            let (length, complete) = match available.iter().position(|&b| b == b'\n') {
+                Some(newline) => (newline + 1, true),
+                None => (available.len(), false),
+            };
+            let at_end = length == 0;
+            let line_bytes = length - complete as usize;
+
+            match self.oversized {
+                Some(ref mut skipped) => *skipped += line_bytes,
+                None if self.read_buf.len() + line_bytes > self.max_message_bytes => {
+                    self.oversized = Some(self.read_buf.len() + line_bytes);
+                    self.read_buf = Vec::new();
+                }
+                None => self.read_buf.extend_from_slice(&available[..line_bytes]),
+            }
+            self.reader.consume(length);
 
-        if bytes_read == 0 {
-            return Ok(None); // Connection closed
+            if complete || at_end {
+                if let Some(actual) = self.oversized.take() {
+                    return Err(IpcError::MessageTooLarge {
+                        actual,
+                        limit: self.max_message_bytes,
+                    }
+                    .into());
+                }
+                if at_end && self.read_buf.is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(std::mem::take(&mut self.read_buf)));
+            }
         }
+    }
 
-        match serde_json::from_str::<IpcEnvelope>(&line.trim()) {
-            Ok(envelope) => {
-                debug!("Received IPC message: {:?}", envelope.message);
-                Ok(Some(envelope))
+    /// Next length-prefixed frame, or `None` at the end of the stream. Like `read_line`
+    /// it skips frames over `max_message_bytes` rather than holding them.
+    async fn read_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            if let Some(length) = self.frame_len {
+                let progress = self.oversized.unwrap_or(self.read_buf.len());
+                if progress == length {
+                    self.frame_len = None;
+                    if self.oversized.take().is_some() {
+                        return Err(IpcError::MessageTooLarge {
+                            actual: length,
+                            limit: self.max_message_bytes,
+                        }
+                        .into());
+                    }
+                    return Ok(Some(std::mem::take(&mut self.read_buf)));
+                }
             }
-            Err(e) => {
-                error!("Failed to deserialize IPC message: {}", e);
-                Err(e.into())
+
+            let available = self.reader.fill_buf().await?;
+            if available.is_empty() {
+                if self.frame_len.is_none() && self.read_buf.is_empty() {
+                    return Ok(None);
+                }
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "IPC connection closed in the middle of a message",
+                )
+                .into());
+            }
+
+            match self.frame_len {
+                None => {
+                    let length = available.len().min(4 - self.read_buf.len());
+                    self.read_buf.extend_from_slice(&available[..length]);
+                    self.reader.consume(length);
+                    if let Ok(prefix) = <[u8; 4]>::try_from(self.read_buf.as_slice()) {
+                        let length = u32::from_be_bytes(prefix) as usize;
+                        self.read_buf.clear();
+                        if length > self.max_message_bytes {
+                            self.oversized = Some(0);
+                        }
+                        self.frame_len = Some(length);
+                    }
+                }
+                Some(length) => {
+                    let progress = self.oversized.unwrap_or(self.read_buf.len());
+                    let chunk = available.len().min(length - progress);
+                    match self.oversized {
+                        Some(ref mut skipped) => *skipped += chunk,
+                        None => self.read_buf.extend_from_slice(&available[..chunk]),
+                    }
+                    self.reader.consume(chunk);
+                }
             }
         }
     }
 }
 
+/// Compare in time independent of where the tokens differ
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
 pub struct IpcClient {
     connection: IpcConnection,
 }
@@ -94,6 +564,32 @@ impl IpcClient {
         Ok(Self { connection })
     }
 
+    /// Connect and, given a `token`, introduce the client with a `Hello` carrying it
+    pub async fn connect_with_token(socket_path: &str, token: Option<&str>) -> Result<Self> {
+        Self::connect_with_framing(socket_path, IpcFraming::Lines, token).await
+    }
+
+    /// Like `connect_with_token`, delimiting messages with `framing`. An `IpcServer`
+    /// picks it up from the first message, so it needs no setting of its own.
+    pub async fn connect_with_framing(
+        socket_path: &str,
+        framing: IpcFraming,
+        token: Option<&str>,
+    ) -> Result<Self> {
+        let connection = IpcConnection::connect(socket_path)
+            .await?
+            .with_framing(framing);
+        let mut client = Self { connection };
+        if let Some(token) = token {
+            client
+                .send(IpcMessage::Hello {
+                    token: token.to_string(),
+                })
+                .await?;
+        }
+        Ok(client)
+    }
+
     pub async fn send(&mut self, message: IpcMessage) -> Result<()> {
         self.connection.send_message(message).await
     }
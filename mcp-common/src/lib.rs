@@ -1,9 +1,13 @@
+pub mod filter;
 pub mod ipc;
 pub mod mcp;
 pub mod messages;
 pub mod types;
+pub mod validation;
 
+pub use filter::*;
 pub use ipc::*;
 pub use mcp::*;
 pub use messages::*;
 pub use types::*;
+pub use validation::*;
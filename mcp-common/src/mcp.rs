@@ -11,6 +11,7 @@ pub enum JsonRpcMessage {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcRequest {
+    #[serde(default)]
     pub jsonrpc: String,
     pub id: Value,
     pub method: String,
@@ -19,6 +20,7 @@ pub struct JsonRpcRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcResponse {
+    #[serde(default)]
     pub jsonrpc: String,
     pub id: Value,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,6 +31,7 @@ pub struct JsonRpcResponse {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcNotification {
+    #[serde(default)]
     pub jsonrpc: String,
     pub method: String,
     pub params: Option<Value>,
@@ -47,6 +50,24 @@ impl JsonRpcMessage {
         serde_json::from_str(input)
     }
 
+    /// The message in `input`, or each message of a batch; anything that isn't JSON-RPC
+    /// is left out
+    pub fn parse_all(input: &str) -> Vec<Self> {
+        if !input.trim_start().starts_with('[') {
+            return Self::parse(input).into_iter().collect();
+        }
+        serde_json::from_str::<Vec<Value>>(input)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|value| serde_json::from_value(value).ok())
+            .collect()
+    }
+
+    /// JSON-RPC 2.0 violations in this message (a missing `jsonrpc` parses as empty)
+    pub fn validate(&self) -> Vec<crate::validation::ValidationError> {
+        crate::validation::validate_message(self)
+    }
+
     pub fn to_string(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
     }
@@ -7,16 +7,45 @@ pub enum IpcMessage {
     ProxyStarted(ProxyInfo),
     ProxyStopped(ProxyId),
     LogEntry(LogEntry),
+    /// Several entries from a busy proxy, in order, sent as one envelope
+    LogsBatch(Vec<LogEntry>),
     StatsUpdate(ProxyStats),
+    /// What the proxy learned about its server, sent once its `initialize` is answered
+    ProxyMetadata {
+        proxy_id: ProxyId,
+        protocol_version: String,
+    },
 
     // Monitor -> Proxy messages
     GetStatus(ProxyId),
+    /// Ask a proxy for the newest `limit` entries it has sent (100 by default), including
+    /// ones sent over earlier connections; it answers with a `LogsBatch`, or nothing when
+    /// there are none
     GetLogs {
         proxy_id: ProxyId,
         limit: Option<usize>,
     },
     Shutdown(ProxyId),
 
+    // Client -> Monitor messages
+    /// First message of a client that has an IPC token; a server that requires one drops
+    /// connections that don't open with the right token. Never surfaced by
+    /// `IpcConnection::receive_message`.
+    Hello {
+        token: String,
+    },
+    /// Turn this connection into a log stream: the monitor replays known proxies and
+    /// recent entries, then either closes the connection or keeps streaming (`follow`)
+    Subscribe {
+        follow: bool,
+    },
+    /// Ask for the latest stats of every known proxy; the monitor answers with a
+    /// `ProxyStarted` per proxy (names and status) followed by `AllStatsResponse`
+    GetAllStats,
+
+    // Monitor -> Client messages
+    AllStatsResponse(Vec<ProxyStats>),
+
     // Bidirectional messages
     Ping,
     Pong,
@@ -33,4 +62,10 @@ pub struct IpcEnvelope {
     pub message: IpcMessage,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub correlation_id: Option<uuid::Uuid>,
+    // Counts up from 0 within each connection of the sender, named by `session_id`;
+    // envelopes from older senders have neither
+    #[serde(default)]
+    pub sequence: u64,
+    #[serde(default)]
+    pub session_id: Option<uuid::Uuid>,
 }
@@ -1,5 +1,8 @@
+use crate::mcp::JsonRpcMessage;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -9,6 +12,34 @@ impl ProxyId {
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
+
+    /// The nil id, for entries the monitor logs about itself
+    pub fn monitor() -> Self {
+        Self(Uuid::nil())
+    }
+
+    /// The first 8 hex characters, enough to tell proxies apart on screen. Owned because
+    /// the id holds a `Uuid`, not its text.
+    pub fn short(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Shows the short form; use `.0` for the full UUID
+impl std::fmt::Display for ProxyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut buffer = Uuid::encode_buffer();
+        f.write_str(&self.0.simple().encode_lower(&mut buffer)[..8])
+    }
+}
+
+/// Parses a full UUID only; the short form can't be resolved without the known ids
+impl std::str::FromStr for ProxyId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
 }
 
 impl Default for ProxyId {
@@ -17,7 +48,9 @@ impl Default for ProxyId {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Ordered as declared, from `Debug` to `Response`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
 pub enum LogLevel {
     Debug,
     Info,
@@ -27,6 +60,35 @@ pub enum LogLevel {
     Response,
 }
 
+impl LogLevel {
+    /// Parse a level name in any case, also accepting `warn`
+    pub fn from_str_case_insensitive(s: &str) -> Option<LogLevel> {
+        match s.to_ascii_lowercase().as_str() {
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warning" | "warn" => Some(LogLevel::Warning),
+            "error" => Some(LogLevel::Error),
+            "request" => Some(LogLevel::Request),
+            "response" => Some(LogLevel::Response),
+            _ => None,
+        }
+    }
+}
+
+/// The lowercase level name, as `from_str_case_insensitive` reads it
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warning => "warning",
+            LogLevel::Error => "error",
+            LogLevel::Request => "request",
+            LogLevel::Response => "response",
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogEntry {
     pub id: Uuid,
@@ -36,6 +98,30 @@ pub struct LogEntry {
     pub proxy_id: ProxyId,
     pub request_id: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    /// Time from the request to this response, on responses the proxy could pair up
+    #[serde(default, with = "duration_serde")]
+    pub duration: Option<Duration>,
+}
+
+/// `Option<Duration>` as whole nanoseconds, so recordings stay plain numbers
+mod duration_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        duration
+            .map(|duration| u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX))
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_nanos))
+    }
 }
 
 impl LogEntry {
@@ -48,6 +134,7 @@ impl LogEntry {
             proxy_id,
             request_id: None,
             metadata: None,
+            duration: None,
         }
     }
 
@@ -60,17 +147,108 @@ impl LogEntry {
         self.metadata = Some(metadata);
         self
     }
+
+    /// Note the code of the first JSON-RPC error response among `messages` under
+    /// `"error_code"` in the metadata, so failed responses can be told from results
+    pub fn with_error_code(mut self, messages: &[JsonRpcMessage]) -> Self {
+        let Some(code) = messages.iter().find_map(|message| match message {
+            JsonRpcMessage::Response(response) => response.error.as_ref().map(|e| e.code),
+            _ => None,
+        }) else {
+            return self;
+        };
+        match self.metadata {
+            Some(serde_json::Value::Object(ref mut metadata)) => {
+                metadata.insert("error_code".to_string(), code.into());
+            }
+            _ => self.metadata = Some(serde_json::json!({ "error_code": code })),
+        }
+        self
+    }
+
+    /// The JSON-RPC error code `with_error_code` noted, on entries of error responses
+    pub fn error_code(&self) -> Option<i64> {
+        self.metadata.as_ref()?.get("error_code")?.as_i64()
+    }
+
+    /// Cut the message to at most `max_bytes` (at a character boundary), noting the
+    /// original size under `"truncated"` in the metadata
+    pub fn truncated(mut self, max_bytes: usize) -> Self {
+        if self.message.len() <= max_bytes {
+            return self;
+        }
+        let original_bytes = self.message.len();
+        let mut end = max_bytes;
+        while !self.message.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.message.truncate(end);
+
+        let note = serde_json::json!({ "original_bytes": original_bytes });
+        match self.metadata {
+            Some(serde_json::Value::Object(ref mut metadata)) => {
+                metadata.insert("truncated".to_string(), note);
+            }
+            _ => self.metadata = Some(serde_json::json!({ "truncated": note })),
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyStats {
     pub proxy_id: ProxyId,
+    /// JSON-RPC requests, and responses answering with a `result` or an `error`
     pub total_requests: u64,
     pub successful_requests: u64,
     pub failed_requests: u64,
     pub active_connections: u32,
+    /// JSON-RPC requests sent to the server that it hasn't answered yet
+    #[serde(default)]
+    pub in_flight_requests: u32,
+    /// How long the proxy had been running when it sent these stats
     pub uptime: std::time::Duration,
+    /// Both directions together, kept for monitors that predate the split
     pub bytes_transferred: u64,
+    /// Bytes the client sent to the server, and the server sent back
+    #[serde(default)]
+    pub bytes_client_to_server: u64,
+    #[serde(default)]
+    pub bytes_server_to_client: u64,
+    /// Request times within the rolling rate window (local to the proxy)
+    #[serde(skip)]
+    pub request_timestamps: VecDeque<Instant>,
+    /// Last computed requests per second, carried over IPC for the monitor
+    #[serde(default)]
+    pub current_rps: f64,
+    #[serde(default)]
+    pub peak_rps: f64,
+    /// Largest single request and response seen, in bytes
+    #[serde(default)]
+    pub max_request_bytes: u64,
+    #[serde(default)]
+    pub max_response_bytes: u64,
+    /// Times the proxy has restarted its MCP server
+    #[serde(default)]
+    pub restarts: u32,
+    /// JSON-RPC notifications either way, which aren't requests
+    #[serde(default)]
+    pub notifications: u64,
+    /// The proxy's link to the monitor: whether it is up, messages waiting for it, messages
+    /// dropped for lack of room and reconnections so far. Proxies that don't report these
+    /// are taken as connected, since the monitor is hearing from them
+    #[serde(default = "connected_by_default")]
+    pub ipc_connected: bool,
+    #[serde(default)]
+    pub ipc_buffered: u64,
+    #[serde(default)]
+    pub ipc_dropped: u64,
+    #[serde(default)]
+    pub ipc_reconnects: u64,
+}
+
+fn connected_by_default() -> bool {
+    true
 }
 
 impl Default for ProxyStats {
@@ -81,20 +259,117 @@ impl Default for ProxyStats {
             successful_requests: 0,
             failed_requests: 0,
             active_connections: 0,
+            in_flight_requests: 0,
             uptime: std::time::Duration::from_secs(0),
             bytes_transferred: 0,
+            bytes_client_to_server: 0,
+            bytes_server_to_client: 0,
+            request_timestamps: VecDeque::new(),
+            current_rps: 0.0,
+            peak_rps: 0.0,
+            max_request_bytes: 0,
+            max_response_bytes: 0,
+            restarts: 0,
+            notifications: 0,
+            ipc_connected: true,
+            ipc_buffered: 0,
+            ipc_dropped: 0,
+            ipc_reconnects: 0,
+        }
+    }
+}
+
+impl ProxyStats {
+    /// Width of the rolling window used for request rates
+    pub const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+    /// Count a JSON-RPC message passing through at `now`: requests (and their rate),
+    /// responses by outcome, and notifications
+    pub fn record_message(&mut self, message: &JsonRpcMessage, now: Instant) {
+        match message {
+            JsonRpcMessage::Request(_) => {
+                self.total_requests += 1;
+                self.record_request(now);
+            }
+            JsonRpcMessage::Response(response) if response.error.is_some() => {
+                self.failed_requests += 1;
+            }
+            JsonRpcMessage::Response(response) if response.result.is_some() => {
+                self.successful_requests += 1;
+            }
+            JsonRpcMessage::Response(_) => {}
+            JsonRpcMessage::Notification(_) => self.notifications += 1,
+        }
+    }
+
+    /// Count `size` bytes the client sent the server as one request
+    pub fn record_request_bytes(&mut self, size: u64) {
+        self.bytes_client_to_server += size;
+        self.bytes_transferred += size;
+        self.max_request_bytes = self.max_request_bytes.max(size);
+    }
+
+    /// Count `size` bytes the server sent the client as one response
+    pub fn record_response_bytes(&mut self, size: u64) {
+        self.bytes_server_to_client += size;
+        self.bytes_transferred += size;
+        self.max_response_bytes = self.max_response_bytes.max(size);
+    }
+
+    /// Record a request made at `now` and refresh the rates
+    pub fn record_request(&mut self, now: Instant) {
+        self.request_timestamps.push_back(now);
+        self.update_rate(now);
+    }
+
+    /// Drop timestamps older than the rate window and refresh `current_rps`/`peak_rps`
+    pub fn update_rate(&mut self, now: Instant) {
+        while let Some(&oldest) = self.request_timestamps.front() {
+            if now.saturating_duration_since(oldest) < Self::RATE_WINDOW {
+                break;
+            }
+            self.request_timestamps.pop_front();
         }
+
+        self.current_rps = self.requests_per_second_at(now);
+        if self.current_rps > self.peak_rps {
+            self.peak_rps = self.current_rps;
+        }
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        self.requests_per_second_at(Instant::now())
+    }
+
+    /// Number of requests within the rate window ending at `now`
+    pub fn requests_per_second_at(&self, now: Instant) -> f64 {
+        self.request_timestamps
+            .iter()
+            .filter(|&&at| at <= now && now.duration_since(at) < Self::RATE_WINDOW)
+            .count() as f64
     }
 }
 
+/// Stands in for environment variable values sent to the monitor, which often hold tokens
+pub const REDACTED_ENV_VALUE: &str = "<redacted>";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyInfo {
     pub id: ProxyId,
     pub name: String,
     pub listen_address: String,
     pub target_command: Vec<String>,
+    /// Variables set for the server with `--env`, values masked with `REDACTED_ENV_VALUE`
+    #[serde(default)]
+    pub env_vars: Vec<(String, String)>,
     pub status: ProxyStatus,
     pub stats: ProxyStats,
+    /// MCP protocol version the server agreed to in its `initialize` response, once seen
+    #[serde(default)]
+    pub protocol_version: Option<String>,
+    /// Whether the proxy is connected to the monitor, as it last reported
+    #[serde(default = "connected_by_default")]
+    pub monitor_connected: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
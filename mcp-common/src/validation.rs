@@ -0,0 +1,204 @@
+use crate::{JsonRpcMessage, LogEntry, LogLevel, ProxyId};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// Not JSON, or JSON that is no kind of JSON-RPC message
+    InvalidMessage,
+    /// `jsonrpc` is missing or not `"2.0"`
+    InvalidVersion,
+    /// A request or response without an `id`
+    MissingId,
+    /// A notification (`notifications/...`) that carries an `id`
+    NotificationWithId,
+    /// A response with both or neither of `result` and `error`
+    InvalidResponse,
+    /// A response whose `id` matches no earlier request
+    OrphanResponse,
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ViolationKind::InvalidMessage => "invalid_message",
+            ViolationKind::InvalidVersion => "invalid_version",
+            ViolationKind::MissingId => "missing_id",
+            ViolationKind::NotificationWithId => "notification_with_id",
+            ViolationKind::InvalidResponse => "invalid_response",
+            ViolationKind::OrphanResponse => "orphan_response",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationError {
+    pub kind: ViolationKind,
+    pub detail: String,
+}
+
+impl ValidationError {
+    fn new(kind: ViolationKind, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Whether `method` names a notification, which must not carry an `id`
+pub fn is_notification_method(method: &str) -> bool {
+    method.starts_with("notifications/") || method == crate::methods::INITIALIZED
+}
+
+/// Check a single parsed message against JSON-RPC 2.0
+pub fn validate_message(msg: &JsonRpcMessage) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    let version = match msg {
+        JsonRpcMessage::Request(req) => &req.jsonrpc,
+        JsonRpcMessage::Response(resp) => &resp.jsonrpc,
+        JsonRpcMessage::Notification(notif) => &notif.jsonrpc,
+    };
+    if version.is_empty() {
+        errors.push(ValidationError::new(
+            ViolationKind::InvalidVersion,
+            "missing \"jsonrpc\" field",
+        ));
+    } else if version != "2.0" {
+        errors.push(ValidationError::new(
+            ViolationKind::InvalidVersion,
+            format!("\"jsonrpc\" is \"{}\", expected \"2.0\"", version),
+        ));
+    }
+
+    match msg {
+        JsonRpcMessage::Request(req) => {
+            if req.id.is_null() {
+                errors.push(ValidationError::new(
+                    ViolationKind::MissingId,
+                    format!("request \"{}\" has a null id", req.method),
+                ));
+            }
+            if is_notification_method(&req.method) {
+                errors.push(ValidationError::new(
+                    ViolationKind::NotificationWithId,
+                    format!("notification \"{}\" carries id {}", req.method, req.id),
+                ));
+            }
+        }
+        JsonRpcMessage::Response(resp) => {
+            if resp.result.is_some() == resp.error.is_some() {
+                errors.push(ValidationError::new(
+                    ViolationKind::InvalidResponse,
+                    format!(
+                        "response {} must have exactly one of \"result\" or \"error\"",
+                        resp.id
+                    ),
+                ));
+            }
+        }
+        JsonRpcMessage::Notification(notif) => {
+            if !is_notification_method(&notif.method) {
+                errors.push(ValidationError::new(
+                    ViolationKind::MissingId,
+                    format!("request \"{}\" has no id", notif.method),
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Parse `input` and validate it, explaining why it failed when it isn't JSON-RPC at all
+pub fn parse_and_validate(input: &str) -> (Option<JsonRpcMessage>, Vec<ValidationError>) {
+    match JsonRpcMessage::parse(input) {
+        Ok(msg) => {
+            let errors = validate_message(&msg);
+            (Some(msg), errors)
+        }
+        Err(e) => {
+            let error = match serde_json::from_str::<Value>(input) {
+                Ok(Value::Object(object))
+                    if !object.contains_key("id")
+                        && (object.contains_key("result") || object.contains_key("error")) =>
+                {
+                    ValidationError::new(ViolationKind::MissingId, "response has no id")
+                }
+                Ok(Value::Object(_)) => ValidationError::new(
+                    ViolationKind::InvalidMessage,
+                    "object is neither a request, response nor notification",
+                ),
+                Ok(_) => ValidationError::new(
+                    ViolationKind::InvalidMessage,
+                    "message is not a JSON object",
+                ),
+                Err(_) => ValidationError::new(
+                    ViolationKind::InvalidMessage,
+                    format!("invalid JSON: {}", e),
+                ),
+            };
+            (None, vec![error])
+        }
+    }
+}
+
+/// Validates the request/response entries of a session in order, pairing response ids
+/// with earlier requests sent in the opposite direction through the same proxy
+#[derive(Debug, Default)]
+pub struct SessionValidator {
+    pending: HashSet<(ProxyId, bool, String)>,
+}
+
+impl SessionValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Violations in `entry`; entries other than requests and responses are skipped
+    pub fn check_entry(&mut self, entry: &LogEntry) -> Vec<ValidationError> {
+        // Request-level entries flow client -> server, response-level ones server -> client
+        let from_client = match entry.level {
+            LogLevel::Request => true,
+            LogLevel::Response => false,
+            _ => return Vec::new(),
+        };
+
+        let message = strip_direction(&entry.message);
+        let (msg, mut errors) = parse_and_validate(message);
+
+        match msg {
+            Some(JsonRpcMessage::Request(req)) if !req.id.is_null() => {
+                self.pending
+                    .insert((entry.proxy_id.clone(), from_client, req.id.to_string()));
+            }
+            Some(JsonRpcMessage::Response(resp)) if !resp.id.is_null() => {
+                let key = (entry.proxy_id.clone(), !from_client, resp.id.to_string());
+                if !self.pending.remove(&key) {
+                    errors.push(ValidationError::new(
+                        ViolationKind::OrphanResponse,
+                        format!("response id {} matches no earlier request", resp.id),
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        errors
+    }
+}
+
+/// Drop the `→ `/`← ` direction marker the proxy prefixes logged messages with
+pub(crate) fn strip_direction(message: &str) -> &str {
+    let message = message.trim();
+    message
+        .strip_prefix('→')
+        .or_else(|| message.strip_prefix('←'))
+        .unwrap_or(message)
+        .trim()
+}
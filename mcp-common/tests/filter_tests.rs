@@ -0,0 +1,63 @@
+use chrono::{Duration, Utc};
+use mcp_common::*;
+
+#[test]
+fn test_log_filter_conditions_combine() {
+    let proxy_id = ProxyId::new();
+    let mut entry = LogEntry::new(
+        LogLevel::Request,
+        r#"→ {"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"Search"}}"#
+            .to_string(),
+        proxy_id,
+    );
+    entry.timestamp = Utc::now();
+    let json = message_json(&entry);
+    assert!(json.is_some());
+
+    assert!(LogFilter::default().matches(&entry, None));
+
+    let filter = LogFilter {
+        level: Some(LogLevel::Request),
+        method: Some("tools/call".to_string()),
+        from: Some(entry.timestamp - Duration::seconds(1)),
+        to: Some(entry.timestamp),
+        search: Some("search".to_string()),
+        ..Default::default()
+    };
+    assert!(filter.needs_json());
+    assert!(filter.matches(&entry, json.as_ref()));
+    // Without the parsed body the method can't match
+    assert!(!filter.matches(&entry, None));
+
+    let too_late = LogFilter {
+        from: Some(entry.timestamp + Duration::seconds(1)),
+        ..Default::default()
+    };
+    assert!(!too_late.matches(&entry, None));
+
+    let other_level = LogFilter {
+        level: Some(LogLevel::Response),
+        ..Default::default()
+    };
+    assert!(!other_level.needs_json());
+    assert!(!other_level.matches(&entry, None));
+
+    // Names are unknown until the proxy is seen
+    let mut by_name = LogFilter {
+        proxy_name: Some("github".to_string()),
+        ..Default::default()
+    };
+    assert!(!by_name.matches(&entry, None));
+    by_name.note_proxy(&ProxyInfo {
+        id: entry.proxy_id.clone(),
+        name: "github".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec![],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    });
+    assert!(by_name.matches(&entry, None));
+}
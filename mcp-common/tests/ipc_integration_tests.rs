@@ -119,6 +119,7 @@ async fn test_proxy_stats_updates() {
                 active_connections: 1,
                 uptime: Duration::from_secs(i * 60),
                 bytes_transferred: i * 1024,
+                ..Default::default()
             };
 
             client.send(IpcMessage::StatsUpdate(stats)).await.unwrap();
@@ -203,13 +204,11 @@ async fn test_concurrent_clients() {
 
     while total_received < expected_total {
         for connection in &mut all_connections {
-            match tokio::time::timeout(Duration::from_millis(50), connection.receive_message())
-                .await
+            // Timeout or error, continue
+            if let Ok(Ok(Some(_envelope))) =
+                tokio::time::timeout(Duration::from_millis(50), connection.receive_message()).await
             {
-                Ok(Ok(Some(_envelope))) => {
-                    total_received += 1;
-                }
-                _ => {} // Timeout or error, continue
+                total_received += 1;
             }
         }
         sleep(Duration::from_millis(1)).await;
@@ -255,13 +254,8 @@ async fn test_connection_recovery() {
     let mut connection1 = server.accept().await.unwrap();
     let mut first_messages = 0;
 
-    loop {
-        match connection1.receive_message().await {
-            Ok(Some(_envelope)) => {
-                first_messages += 1;
-            }
-            Ok(None) | Err(_) => break,
-        }
+    while let Ok(Some(_envelope)) = connection1.receive_message().await {
+        first_messages += 1;
     }
 
     assert_eq!(first_messages, 3);
@@ -314,6 +308,9 @@ async fn test_message_types() {
             name: "Test Proxy".to_string(),
             listen_address: "127.0.0.1:8080".to_string(),
             target_command: vec!["python".to_string(), "server.py".to_string()],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
             status: ProxyStatus::Running,
             stats: ProxyStats::default(),
         };
@@ -338,6 +335,7 @@ async fn test_message_types() {
             active_connections: 1,
             uptime: Duration::from_secs(60),
             bytes_transferred: 256,
+            ..Default::default()
         };
         client.send(IpcMessage::StatsUpdate(stats)).await.unwrap();
 
@@ -370,3 +368,149 @@ async fn test_message_types() {
     assert_eq!(message_types[2], "StatsUpdate");
     assert_eq!(message_types[3], "ProxyStopped");
 }
+
+#[tokio::test]
+async fn test_server_connection_limit() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("limit.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let server = IpcServer::bind(&socket_path)
+        .await
+        .unwrap()
+        .with_max_connections(2);
+
+    // Three clients connect at once
+    let mut clients = Vec::new();
+    for _ in 0..3 {
+        clients.push(IpcClient::connect(&socket_path).await.unwrap());
+    }
+
+    let first = server.accept().await.unwrap();
+    let _second = server.accept().await.unwrap();
+    assert_eq!(server.connection_count(), 2);
+
+    let error = server.accept().await.err().unwrap();
+    assert_eq!(
+        error.downcast_ref::<IpcError>(),
+        Some(&IpcError::ConnectionLimitReached)
+    );
+    assert_eq!(server.connection_count(), 2);
+
+    // The rejected client sees its connection closed
+    let mut rejected = clients.pop().unwrap();
+    assert!(rejected.receive().await.unwrap().is_none());
+
+    // Dropping a connection frees its slot
+    drop(first);
+    assert_eq!(server.connection_count(), 1);
+    let _late = IpcClient::connect(&socket_path).await.unwrap();
+    let _third = server.accept().await.unwrap();
+    assert_eq!(server.connection_count(), 2);
+}
+
+#[test]
+fn test_ipc_address_parse() {
+    assert_eq!(
+        IpcAddress::parse("tcp://127.0.0.1:4000"),
+        IpcAddress::Tcp("127.0.0.1:4000".to_string())
+    );
+    assert_eq!(
+        IpcAddress::parse("/tmp/mcp-monitor.sock"),
+        IpcAddress::Unix("/tmp/mcp-monitor.sock".into())
+    );
+    assert_eq!(
+        IpcAddress::parse("tcp://localhost:1").to_string(),
+        "tcp://localhost:1"
+    );
+}
+
+#[tokio::test]
+async fn test_tcp_ipc_communication() {
+    let server = IpcServer::bind("tcp://127.0.0.1:0").await.unwrap();
+    let address = server.address().to_string();
+    assert!(address.starts_with("tcp://127.0.0.1:"));
+    assert!(!address.ends_with(":0"));
+
+    let proxy_id = ProxyId::new();
+    let mut client = IpcClient::connect(&address).await.unwrap();
+    let mut connection = server.accept().await.unwrap();
+
+    client
+        .send(IpcMessage::ProxyStopped(proxy_id.clone()))
+        .await
+        .unwrap();
+    match connection.receive_message().await.unwrap().unwrap().message {
+        IpcMessage::ProxyStopped(id) => assert_eq!(id, proxy_id),
+        other => panic!("Expected ProxyStopped, got {:?}", other),
+    }
+
+    connection.send_message(IpcMessage::Ping).await.unwrap();
+    assert!(matches!(
+        client.receive().await.unwrap().unwrap().message,
+        IpcMessage::Ping
+    ));
+
+    drop(client);
+    assert!(connection.receive_message().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_tcp_server_requires_token() {
+    let server = IpcServer::bind("tcp://127.0.0.1:0")
+        .await
+        .unwrap()
+        .with_token(Some("s3cret".to_string()));
+    let address = server.address().to_string();
+    let unauthorized = |result: anyhow::Result<Option<IpcEnvelope>>| {
+        result.unwrap_err().downcast_ref::<IpcError>() == Some(&IpcError::Unauthorized)
+    };
+
+    let mut anonymous = IpcClient::connect(&address).await.unwrap();
+    let mut connection = server.accept().await.unwrap();
+    anonymous.send(IpcMessage::GetAllStats).await.unwrap();
+    assert!(unauthorized(connection.receive_message().await));
+
+    let mut wrong = IpcClient::connect_with_token(&address, Some("guess"))
+        .await
+        .unwrap();
+    let mut connection = server.accept().await.unwrap();
+    wrong.send(IpcMessage::GetAllStats).await.unwrap();
+    assert!(unauthorized(connection.receive_message().await));
+
+    // The Hello itself never reaches the server's caller
+    let mut trusted = IpcClient::connect_with_token(&address, Some("s3cret"))
+        .await
+        .unwrap();
+    let mut connection = server.accept().await.unwrap();
+    trusted.send(IpcMessage::GetAllStats).await.unwrap();
+    assert!(matches!(
+        connection.receive_message().await.unwrap().unwrap().message,
+        IpcMessage::GetAllStats
+    ));
+}
+
+#[tokio::test]
+async fn test_unix_server_skips_token_check() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("token.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path)
+        .await
+        .unwrap()
+        .with_token(Some("s3cret".to_string()));
+
+    let mut client = IpcClient::connect(&socket_path).await.unwrap();
+    let mut connection = server.accept().await.unwrap();
+    client.send(IpcMessage::GetAllStats).await.unwrap();
+    assert!(matches!(
+        connection.receive_message().await.unwrap().unwrap().message,
+        IpcMessage::GetAllStats
+    ));
+}
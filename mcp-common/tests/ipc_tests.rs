@@ -111,6 +111,7 @@ async fn test_ipc_client_wrapper() {
         active_connections: 1,
         uptime: std::time::Duration::from_secs(300),
         bytes_transferred: 1024,
+        ..Default::default()
     };
     let test_message = IpcMessage::StatsUpdate(stats.clone());
 
@@ -177,11 +178,18 @@ async fn test_multiple_messages_in_sequence() {
         }
     });
 
-    // Server receives all messages
+    // Server receives all messages, numbered in order within one session
     let mut server_connection = server.accept().await.unwrap();
-    for i in 0..messages.len() {
+    let mut session_id = None;
+    for (i, message) in messages.iter().enumerate() {
         let received_envelope = server_connection.receive_message().await.unwrap().unwrap();
-        match (&messages[i], &received_envelope.message) {
+        assert_eq!(received_envelope.sequence, i as u64);
+        assert!(received_envelope.session_id.is_some());
+        assert_eq!(
+            *session_id.get_or_insert(received_envelope.session_id),
+            received_envelope.session_id
+        );
+        match (message, &received_envelope.message) {
             (IpcMessage::LogEntry(sent), IpcMessage::LogEntry(received)) => {
                 assert_eq!(sent.message, received.message);
                 assert_eq!(sent.proxy_id, received.proxy_id);
@@ -189,10 +197,67 @@ async fn test_multiple_messages_in_sequence() {
             _ => panic!("Message type mismatch at index {}", i),
         }
     }
+    assert_eq!(server_connection.sequence_gaps(), 0);
 
     client_task.await.unwrap();
 }
 
+#[tokio::test]
+async fn test_sequence_gaps_are_counted_and_logged() {
+    use std::sync::{Arc, Mutex};
+    use tokio::io::AsyncWriteExt;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let _subscriber = tracing::subscriber::set_default(
+        tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish(),
+    );
+
+    let (mut peer, stream) = tokio::io::duplex(64 * 1024);
+    let mut connection = IpcConnection::new(stream);
+    let session = Some(uuid::Uuid::new_v4());
+    let envelope = |sequence: u64, session_id: Option<uuid::Uuid>| {
+        serde_json::to_string(&IpcEnvelope {
+            message: IpcMessage::Ping,
+            timestamp: chrono::Utc::now(),
+            correlation_id: None,
+            sequence,
+            session_id,
+        })
+        .unwrap()
+    };
+    let lines = [
+        envelope(0, session),
+        envelope(3, session),
+        // A reconnected peer starts over
+        envelope(0, Some(uuid::Uuid::new_v4())),
+    ];
+    peer.write_all((lines.join("\n") + "\n").as_bytes())
+        .await
+        .unwrap();
+    drop(peer);
+
+    while connection.receive_message().await.unwrap().is_some() {}
+    assert_eq!(connection.sequence_gaps(), 2);
+    let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    assert!(logs.contains("IPC sequence gap: expected 1, got 3 (2 missing)"));
+    assert_eq!(logs.matches("IPC sequence gap").count(), 1);
+}
+
 #[tokio::test]
 async fn test_connection_closed_handling() {
     let temp_dir = tempdir().unwrap();
@@ -274,6 +339,169 @@ async fn test_large_message_transmission() {
     client_task.await.unwrap();
 }
 
+#[tokio::test]
+async fn test_length_prefixed_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut client =
+        IpcClient::connect_with_framing(&socket_path, IpcFraming::LengthPrefixed, None)
+            .await
+            .unwrap();
+    let mut connection = server.accept().await.unwrap();
+    assert_eq!(connection.framing(), None);
+
+    let large_text = "x".repeat(10 * 1024 * 1024);
+    let multiline_text = "first line\nsecond line\r\n\nlast".to_string();
+    let sender = tokio::spawn(async move {
+        for text in [large_text, multiline_text] {
+            let entry = LogEntry::new(LogLevel::Response, text, ProxyId::new());
+            client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+        }
+        client
+    });
+
+    match connection.receive_message().await.unwrap().unwrap().message {
+        IpcMessage::LogEntry(entry) => assert_eq!(entry.message.len(), 10 * 1024 * 1024),
+        other => panic!("Expected LogEntry, got {:?}", other),
+    }
+    assert_eq!(connection.framing(), Some(IpcFraming::LengthPrefixed));
+    match connection.receive_message().await.unwrap().unwrap().message {
+        IpcMessage::LogEntry(entry) => {
+            assert_eq!(entry.message, "first line\nsecond line\r\n\nlast")
+        }
+        other => panic!("Expected LogEntry, got {:?}", other),
+    }
+
+    // The server answers in the framing it detected
+    let mut client = sender.await.unwrap();
+    connection.send_message(IpcMessage::Ping).await.unwrap();
+    assert!(matches!(
+        client.receive().await.unwrap().unwrap().message,
+        IpcMessage::Ping
+    ));
+    drop(client);
+    assert!(connection.receive_message().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_server_detects_line_framing() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut client = IpcClient::connect(&socket_path).await.unwrap();
+    let mut connection = server.accept().await.unwrap();
+    client.send(IpcMessage::GetAllStats).await.unwrap();
+    assert!(matches!(
+        connection.receive_message().await.unwrap().unwrap().message,
+        IpcMessage::GetAllStats
+    ));
+    assert_eq!(connection.framing(), Some(IpcFraming::Lines));
+}
+
+#[test]
+fn test_ipc_framing_parse() {
+    for framing in [IpcFraming::Lines, IpcFraming::LengthPrefixed] {
+        assert_eq!(framing.to_string().parse::<IpcFraming>(), Ok(framing));
+    }
+    assert!("binary".parse::<IpcFraming>().is_err());
+}
+
+#[tokio::test]
+async fn test_malformed_messages_are_skipped() {
+    use tokio::io::AsyncWriteExt;
+
+    let (mut peer, stream) = tokio::io::duplex(64 * 1024);
+    let mut connection = IpcConnection::new(stream);
+    let envelope = |message: IpcMessage| {
+        serde_json::to_string(&IpcEnvelope {
+            message,
+            timestamp: chrono::Utc::now(),
+            correlation_id: None,
+            sequence: 0,
+            session_id: None,
+        })
+        .unwrap()
+    };
+    let lines = [
+        "garbage".to_string(),
+        envelope(IpcMessage::GetAllStats),
+        "{\"message\": 42}".to_string(),
+        "{\"truncated\": ".to_string(),
+        envelope(IpcMessage::Ping),
+    ];
+    peer.write_all((lines.join("\n") + "\n").as_bytes())
+        .await
+        .unwrap();
+    drop(peer);
+
+    let invalid = |result: anyhow::Result<Option<IpcEnvelope>>| {
+        matches!(
+            result.unwrap_err().downcast_ref::<IpcError>(),
+            Some(IpcError::InvalidMessage(_))
+        )
+    };
+    assert!(invalid(connection.receive_message().await));
+    assert!(matches!(
+        connection.receive_message().await.unwrap().unwrap().message,
+        IpcMessage::GetAllStats
+    ));
+    assert!(invalid(connection.receive_message().await));
+    assert!(invalid(connection.receive_message().await));
+    assert!(matches!(
+        connection.receive_message().await.unwrap().unwrap().message,
+        IpcMessage::Ping
+    ));
+    assert!(connection.receive_message().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_message_size_limit() {
+    let (client_stream, server_stream) = tokio::io::duplex(64 * 1024);
+    let mut client = IpcConnection::new(client_stream);
+    let mut server =
+        IpcConnection::new(server_stream).with_max_message_bytes(DEFAULT_MAX_MESSAGE_BYTES);
+    let log = |size: usize| {
+        IpcMessage::LogEntry(LogEntry::new(
+            LogLevel::Response,
+            "x".repeat(size),
+            ProxyId::new(),
+        ))
+    };
+
+    let client_task = tokio::spawn(async move {
+        client.send_message(log(17 * 1024 * 1024)).await.unwrap();
+        client.send_message(log(15 * 1024 * 1024)).await.unwrap();
+    });
+
+    let error = server.receive_message().await.unwrap_err();
+    match error.downcast_ref::<IpcError>() {
+        Some(IpcError::MessageTooLarge { actual, limit }) => {
+            assert!(*actual > 17 * 1024 * 1024);
+            assert_eq!(*limit, 16 * 1024 * 1024);
+        }
+        other => panic!("Expected MessageTooLarge, got {:?}", other),
+    }
+
+    // The oversized message is skipped, not left to corrupt the next one
+    match server.receive_message().await.unwrap().unwrap().message {
+        IpcMessage::LogEntry(entry) => assert_eq!(entry.message.len(), 15 * 1024 * 1024),
+        other => panic!("Expected LogEntry, got {:?}", other),
+    }
+    client_task.await.unwrap();
+    assert!(server.receive_message().await.unwrap().is_none());
+}
+
 #[tokio::test]
 async fn test_concurrent_clients() {
     let temp_dir = tempdir().unwrap();
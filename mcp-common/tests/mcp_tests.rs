@@ -1,5 +1,4 @@
 use mcp_common::*;
-use serde_json;
 
 #[test]
 fn test_parse_valid_json_rpc_request() {
@@ -268,3 +267,20 @@ fn test_large_payload_handling() {
     let params = deserialized.params.unwrap();
     assert_eq!(params["items"].as_array().unwrap().len(), 1000);
 }
+
+#[test]
+fn test_parse_all_single_messages_and_batches() {
+    let single = JsonRpcMessage::parse_all(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#);
+    assert!(matches!(single[..], [JsonRpcMessage::Request(_)]));
+
+    let batch = JsonRpcMessage::parse_all(
+        r#"[{"jsonrpc":"2.0","id":1,"result":{}},"junk",{"jsonrpc":"2.0","method":"notifications/initialized"}]"#,
+    );
+    assert!(matches!(
+        batch[..],
+        [JsonRpcMessage::Response(_), JsonRpcMessage::Notification(_)]
+    ));
+
+    assert!(JsonRpcMessage::parse_all("not json").is_empty());
+    assert!(JsonRpcMessage::parse_all("[not json").is_empty());
+}
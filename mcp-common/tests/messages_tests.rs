@@ -1,6 +1,5 @@
 use chrono::Utc;
 use mcp_common::*;
-use serde_json;
 
 #[test]
 fn test_ipc_message_log_entry_serialization() {
@@ -36,6 +35,7 @@ fn test_ipc_message_stats_update_serialization() {
         active_connections: 2,
         uptime: std::time::Duration::from_secs(3600),
         bytes_transferred: 1024000,
+        ..Default::default()
     };
 
     let message = IpcMessage::StatsUpdate(stats.clone());
@@ -73,6 +73,9 @@ fn test_ipc_message_proxy_connected_serialization() {
         name: "Test Proxy".to_string(),
         listen_address: "127.0.0.1:8080".to_string(),
         target_command: vec!["python".to_string(), "server.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats::default(),
     };
@@ -119,6 +122,8 @@ fn test_ipc_envelope_creation() {
         message: message.clone(),
         timestamp: Utc::now(),
         correlation_id: Some(uuid::Uuid::new_v4()),
+        sequence: 0,
+        session_id: None,
     };
 
     assert!(envelope.correlation_id.is_some());
@@ -146,6 +151,8 @@ fn test_ipc_envelope_without_correlation_id() {
         message,
         timestamp: Utc::now(),
         correlation_id: None,
+        sequence: 0,
+        session_id: None,
     };
 
     assert!(envelope.correlation_id.is_none());
@@ -161,7 +168,7 @@ fn test_ipc_envelope_without_correlation_id() {
 fn test_all_ipc_message_variants() {
     let proxy_id = ProxyId::new();
 
-    let messages = vec![
+    let messages = [
         IpcMessage::LogEntry(LogEntry::new(
             LogLevel::Request,
             "Request message".to_string(),
@@ -175,12 +182,16 @@ fn test_all_ipc_message_variants() {
             active_connections: 1,
             uptime: std::time::Duration::from_secs(1800),
             bytes_transferred: 256000,
+            ..Default::default()
         }),
         IpcMessage::ProxyStarted(ProxyInfo {
             id: proxy_id.clone(),
             name: "Test Proxy".to_string(),
             listen_address: "localhost:9000".to_string(),
             target_command: vec!["node".to_string(), "server.js".to_string()],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
             status: ProxyStatus::Starting,
             stats: ProxyStats::default(),
         }),
@@ -256,6 +267,8 @@ fn test_envelope_ordering() {
             message: IpcMessage::LogEntry(log_entry),
             timestamp: Utc::now() + chrono::Duration::milliseconds(i),
             correlation_id: Some(uuid::Uuid::new_v4()),
+            sequence: 0,
+            session_id: None,
         };
         envelopes.push(envelope);
 
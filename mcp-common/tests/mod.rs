@@ -1,4 +1,6 @@
+pub mod filter_tests;
 pub mod ipc_tests;
 pub mod mcp_tests;
 pub mod messages_tests;
 pub mod types_tests;
+pub mod validation_tests;
@@ -1,6 +1,6 @@
 use chrono::Utc;
 use mcp_common::*;
-use serde_json;
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_proxy_id_creation() {
@@ -15,6 +15,20 @@ fn test_proxy_id_creation() {
     assert!(id2.0.get_version().is_some());
 }
 
+#[test]
+fn test_proxy_id_short_form_and_parsing() {
+    let id = ProxyId::new();
+
+    let short = id.to_string();
+    assert_eq!(short.len(), 8);
+    assert!(short.chars().all(|c| c.is_ascii_hexdigit()));
+    assert!(id.0.to_string().starts_with(&short));
+    assert_eq!(id.short(), short);
+
+    assert_eq!(id.0.to_string().parse::<ProxyId>().unwrap(), id);
+    assert!(short.parse::<ProxyId>().is_err());
+}
+
 #[test]
 fn test_proxy_id_default() {
     let id1 = ProxyId::default();
@@ -53,6 +67,32 @@ fn test_log_level_serialization() {
     }
 }
 
+#[test]
+fn test_log_level_order_display_and_parsing() {
+    assert!(LogLevel::Debug < LogLevel::Error);
+    assert!(LogLevel::Warning < LogLevel::Error);
+    assert!(LogLevel::Request < LogLevel::Response);
+    assert_eq!(
+        [LogLevel::Response, LogLevel::Info, LogLevel::Debug]
+            .iter()
+            .max(),
+        Some(&LogLevel::Response)
+    );
+
+    assert_eq!(LogLevel::Warning.to_string(), "warning");
+    assert_eq!(format!("{}", LogLevel::Response), "response");
+
+    assert_eq!(
+        LogLevel::from_str_case_insensitive("WARNING"),
+        Some(LogLevel::Warning)
+    );
+    assert_eq!(
+        LogLevel::from_str_case_insensitive("Request"),
+        Some(LogLevel::Request)
+    );
+    assert_eq!(LogLevel::from_str_case_insensitive("loud"), None);
+}
+
 #[test]
 fn test_log_entry_creation() {
     let proxy_id = ProxyId::new();
@@ -97,6 +137,27 @@ fn test_log_entry_with_metadata() {
     assert_eq!(entry.metadata, Some(metadata));
 }
 
+#[test]
+fn test_log_entry_truncated() {
+    let proxy_id = ProxyId::new();
+    let short = LogEntry::new(LogLevel::Response, "short".to_string(), proxy_id.clone());
+    assert_eq!(short.clone().truncated(5).message, "short");
+    assert_eq!(short.truncated(5).metadata, None);
+
+    // Cut before the 2-byte 'é' rather than through it, keeping earlier metadata
+    let entry = LogEntry::new(LogLevel::Response, "abcdé".to_string(), proxy_id)
+        .with_metadata(serde_json::json!({"method": "tools/call"}))
+        .truncated(5);
+    assert_eq!(entry.message, "abcd");
+    assert_eq!(
+        entry.metadata,
+        Some(serde_json::json!({
+            "method": "tools/call",
+            "truncated": {"original_bytes": 6}
+        }))
+    );
+}
+
 #[test]
 fn test_log_entry_chaining() {
     let proxy_id = ProxyId::new();
@@ -136,6 +197,9 @@ fn test_proxy_stats_serialization() {
         active_connections: 3,
         uptime: std::time::Duration::from_secs(3600),
         bytes_transferred: 1024000,
+        bytes_client_to_server: 24000,
+        bytes_server_to_client: 1000000,
+        ..Default::default()
     };
 
     let serialized = serde_json::to_string(&stats).unwrap();
@@ -147,10 +211,81 @@ fn test_proxy_stats_serialization() {
     assert_eq!(stats.failed_requests, deserialized.failed_requests);
     assert_eq!(stats.active_connections, deserialized.active_connections);
     assert_eq!(stats.bytes_transferred, deserialized.bytes_transferred);
+    assert_eq!(deserialized.bytes_client_to_server, 24000);
+    assert_eq!(deserialized.bytes_server_to_client, 1000000);
+
+    // Stats from proxies that only count the total still deserialize
+    let legacy = serialized
+        .replace(",\"bytes_client_to_server\":24000", "")
+        .replace(",\"bytes_server_to_client\":1000000", "");
+    assert!(!legacy.contains("bytes_client_to_server"));
+    let deserialized: ProxyStats = serde_json::from_str(&legacy).unwrap();
+    assert_eq!(deserialized.bytes_transferred, 1024000);
+    assert_eq!(deserialized.bytes_client_to_server, 0);
+    assert_eq!(deserialized.bytes_server_to_client, 0);
     // Note: Duration serialization might have slight differences, so we check within reasonable bounds
     assert!(deserialized.uptime.as_secs() >= 3599 && deserialized.uptime.as_secs() <= 3601);
 }
 
+#[test]
+fn test_proxy_stats_requests_per_second() {
+    let mut stats = ProxyStats::default();
+    let start = Instant::now();
+
+    // 50 requests, 10ms apart
+    for i in 0..50 {
+        stats.total_requests += 1;
+        stats.record_request(start + Duration::from_millis(i * 10));
+    }
+
+    let last = start + Duration::from_millis(490);
+    assert_eq!(stats.requests_per_second_at(last), 50.0);
+    assert_eq!(stats.current_rps, 50.0);
+    assert_eq!(stats.peak_rps, 50.0);
+
+    // Only the requests made after 300ms (310..=490) are still inside the window
+    let later = start + Duration::from_millis(1300);
+    assert_eq!(stats.requests_per_second_at(later), 19.0);
+
+    // Once the window has passed, the rate drops to zero but the peak is kept
+    let idle = start + Duration::from_millis(1600);
+    assert_eq!(stats.requests_per_second_at(idle), 0.0);
+    stats.update_rate(idle);
+    assert!(stats.request_timestamps.is_empty());
+    assert_eq!(stats.current_rps, 0.0);
+    assert_eq!(stats.peak_rps, 50.0);
+}
+
+#[test]
+fn test_proxy_stats_rate_serialization() {
+    let mut stats = ProxyStats::default();
+    let now = Instant::now();
+    stats.record_request(now);
+    stats.record_request(now);
+
+    let serialized = serde_json::to_string(&stats).unwrap();
+    assert!(!serialized.contains("request_timestamps"));
+
+    let deserialized: ProxyStats = serde_json::from_str(&serialized).unwrap();
+    assert!(deserialized.request_timestamps.is_empty());
+    assert_eq!(deserialized.current_rps, 2.0);
+    assert_eq!(deserialized.peak_rps, 2.0);
+
+    // Stats from proxies without rate fields still deserialize
+    let legacy = serialized
+        .replace(",\"current_rps\":2.0", "")
+        .replace(",\"peak_rps\":2.0", "");
+    let deserialized: ProxyStats = serde_json::from_str(&legacy).unwrap();
+    assert_eq!(deserialized.peak_rps, 0.0);
+
+    // and without IPC fields count as connected
+    let legacy = serialized.replace(",\"ipc_connected\":true", "");
+    assert!(!legacy.contains("ipc_connected"));
+    let deserialized: ProxyStats = serde_json::from_str(&legacy).unwrap();
+    assert!(deserialized.ipc_connected);
+    assert_eq!(deserialized.ipc_dropped, 0);
+}
+
 #[test]
 fn test_proxy_status_variants() {
     let statuses = vec![
@@ -243,6 +378,7 @@ fn test_proxy_info_complete() {
         active_connections: 1,
         uptime: std::time::Duration::from_secs(1800),
         bytes_transferred: 512000,
+        ..Default::default()
     };
 
     let info = ProxyInfo {
@@ -250,6 +386,9 @@ fn test_proxy_info_complete() {
         name: "Test Proxy".to_string(),
         listen_address: "127.0.0.1:8080".to_string(),
         target_command: vec!["python".to_string(), "server.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: stats.clone(),
     };
@@ -263,3 +402,62 @@ fn test_proxy_info_complete() {
     assert_eq!(info.target_command, deserialized.target_command);
     assert_eq!(info.stats.total_requests, deserialized.stats.total_requests);
 }
+
+#[test]
+fn test_log_entry_duration_serializes_as_nanoseconds() {
+    let mut entry = LogEntry::new(LogLevel::Response, "← {}".to_string(), ProxyId::new());
+    entry.duration = Some(Duration::from_micros(1500));
+
+    let json = serde_json::to_value(&entry).unwrap();
+    assert_eq!(json["duration"], 1_500_000);
+    let parsed: LogEntry = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.duration, entry.duration);
+
+    // Entries recorded before the field existed still load
+    let mut old = serde_json::to_value(&entry).unwrap();
+    old.as_object_mut().unwrap().remove("duration");
+    let parsed: LogEntry = serde_json::from_value(old).unwrap();
+    assert_eq!(parsed.duration, None);
+}
+
+#[test]
+fn test_proxy_stats_record_message() {
+    let mut stats = ProxyStats::default();
+    let now = Instant::now();
+    for line in [
+        r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/progress"}"#,
+        r#"{"jsonrpc":"2.0","id":1,"result":{}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32601,"message":"Method not found"}}"#,
+    ] {
+        stats.record_message(&JsonRpcMessage::parse(line).unwrap(), now);
+    }
+
+    assert_eq!(stats.total_requests, 1);
+    assert_eq!(stats.successful_requests, 1);
+    assert_eq!(stats.failed_requests, 1);
+    assert_eq!(stats.notifications, 1);
+    assert_eq!(stats.requests_per_second_at(now), 1.0);
+}
+
+#[test]
+fn test_log_entry_error_code() {
+    let proxy_id = ProxyId::new();
+    let messages = JsonRpcMessage::parse_all(
+        r#"[{"jsonrpc":"2.0","id":1,"result":{}},{"jsonrpc":"2.0","id":2,"error":{"code":-32601,"message":"Method not found"}}]"#,
+    );
+    let entry = LogEntry::new(LogLevel::Response, "← [...]".to_string(), proxy_id.clone())
+        .with_metadata(serde_json::json!({ "invalid_utf8": true }))
+        .with_error_code(&messages);
+    assert_eq!(entry.error_code(), Some(-32601));
+    assert_eq!(
+        entry.metadata,
+        Some(serde_json::json!({ "invalid_utf8": true, "error_code": -32601 }))
+    );
+
+    let result = JsonRpcMessage::parse_all(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#);
+    let entry =
+        LogEntry::new(LogLevel::Response, "← {}".to_string(), proxy_id).with_error_code(&result);
+    assert_eq!(entry.error_code(), None);
+    assert_eq!(entry.metadata, None);
+}
@@ -0,0 +1,104 @@
+use mcp_common::*;
+
+fn kinds(errors: &[ValidationError]) -> Vec<ViolationKind> {
+    errors.iter().map(|error| error.kind).collect()
+}
+
+fn entry(level: LogLevel, message: &str, proxy_id: &ProxyId) -> LogEntry {
+    LogEntry::new(level, message.to_string(), proxy_id.clone())
+}
+
+#[test]
+fn test_validate_compliant_messages() {
+    for json in [
+        r#"{"jsonrpc":"2.0","method":"tools/list","id":1}"#,
+        r#"{"jsonrpc":"2.0","result":{},"id":1}"#,
+        r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":"a"}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+    ] {
+        let msg = JsonRpcMessage::parse(json).unwrap();
+        assert!(msg.validate().is_empty(), "{}", json);
+    }
+}
+
+#[test]
+fn test_validate_missing_jsonrpc_field() {
+    let msg = JsonRpcMessage::parse(r#"{"method":"tools/list","id":1}"#).unwrap();
+    assert_eq!(kinds(&msg.validate()), vec![ViolationKind::InvalidVersion]);
+
+    let msg = JsonRpcMessage::parse(r#"{"jsonrpc":"1.0","result":{},"id":1}"#).unwrap();
+    assert_eq!(
+        kinds(&validate_message(&msg)),
+        vec![ViolationKind::InvalidVersion]
+    );
+}
+
+#[test]
+fn test_validate_notification_with_id() {
+    let msg =
+        JsonRpcMessage::parse(r#"{"jsonrpc":"2.0","method":"notifications/cancelled","id":7}"#)
+            .unwrap();
+    assert_eq!(
+        kinds(&msg.validate()),
+        vec![ViolationKind::NotificationWithId]
+    );
+}
+
+#[test]
+fn test_validate_request_and_response_shape() {
+    // A request method without an id
+    let msg = JsonRpcMessage::parse(r#"{"jsonrpc":"2.0","method":"tools/call"}"#).unwrap();
+    assert_eq!(kinds(&msg.validate()), vec![ViolationKind::MissingId]);
+
+    let (msg, errors) = parse_and_validate(r#"{"jsonrpc":"2.0","result":{}}"#);
+    assert!(msg.is_none());
+    assert_eq!(kinds(&errors), vec![ViolationKind::MissingId]);
+
+    let (_, errors) = parse_and_validate(r#"{"jsonrpc":"2.0","id":3}"#);
+    assert_eq!(kinds(&errors), vec![ViolationKind::InvalidResponse]);
+
+    let (_, errors) = parse_and_validate("not json");
+    assert_eq!(kinds(&errors), vec![ViolationKind::InvalidMessage]);
+}
+
+#[test]
+fn test_session_validator_orphan_response() {
+    let proxy_a = ProxyId::new();
+    let proxy_b = ProxyId::new();
+    let mut validator = SessionValidator::new();
+
+    let request = entry(
+        LogLevel::Request,
+        r#"→ {"jsonrpc":"2.0","method":"tools/list","id":1}"#,
+        &proxy_a,
+    );
+    assert!(validator.check_entry(&request).is_empty());
+
+    // Same id on another proxy has no matching request
+    let other_proxy = entry(
+        LogLevel::Response,
+        r#"← {"jsonrpc":"2.0","result":{},"id":1}"#,
+        &proxy_b,
+    );
+    assert_eq!(
+        kinds(&validator.check_entry(&other_proxy)),
+        vec![ViolationKind::OrphanResponse]
+    );
+
+    let response = entry(
+        LogLevel::Response,
+        r#"← {"jsonrpc":"2.0","result":{},"id":1}"#,
+        &proxy_a,
+    );
+    assert!(validator.check_entry(&response).is_empty());
+
+    // Each request is answered once
+    assert_eq!(
+        kinds(&validator.check_entry(&response)),
+        vec![ViolationKind::OrphanResponse]
+    );
+
+    // Non-message entries are ignored
+    let stderr = entry(LogLevel::Error, "stderr: boom", &proxy_a);
+    assert!(validator.check_entry(&stderr).is_empty());
+}
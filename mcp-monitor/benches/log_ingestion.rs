@@ -0,0 +1,69 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use mcp_common::{LogEntry, LogLevel, ProxyId};
+use mcp_monitor::{App, AppEvent, DEFAULT_MAX_LOGS};
+
+const MESSAGES: usize = 1000;
+
+fn entries(proxy_id: &ProxyId, count: usize) -> Vec<LogEntry> {
+    (0..count)
+        .map(|i| {
+            LogEntry::new(
+                LogLevel::Request,
+                format!(r#"→ {{"jsonrpc":"2.0","id":{},"method":"ping"}}"#, i),
+                proxy_id.clone(),
+            )
+        })
+        .collect()
+}
+
+/// An app already holding `DEFAULT_MAX_LOGS` entries, so every new one trims the oldest
+fn full_app(proxy_id: &ProxyId) -> App {
+    let mut app = App::new();
+    for entry in entries(proxy_id, DEFAULT_MAX_LOGS) {
+        app.handle_event(AppEvent::NewLogEntry(entry));
+    }
+    app
+}
+
+fn bench_ingestion_at_capacity(c: &mut Criterion) {
+    let proxy_id = ProxyId::new();
+    let incoming = entries(&proxy_id, MESSAGES);
+
+    let mut group = c.benchmark_group("1000 log entries into a full log");
+    group.bench_function("following", |b| {
+        b.iter_batched(
+            || (full_app(&proxy_id), incoming.clone()),
+            |(mut app, incoming)| {
+                for entry in incoming {
+                    app.handle_event(AppEvent::NewLogEntry(entry));
+                }
+                app
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("browsing search results", |b| {
+        b.iter_batched(
+            || {
+                let mut app = full_app(&proxy_id);
+                app.enter_search_mode();
+                for c in "ping".chars() {
+                    app.search_input_char(c);
+                }
+                app.confirm_search_results();
+                (app, incoming.clone())
+            },
+            |(mut app, incoming)| {
+                for entry in incoming {
+                    app.handle_event(AppEvent::NewLogEntry(entry));
+                }
+                app
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_ingestion_at_capacity);
+criterion_main!(benches);
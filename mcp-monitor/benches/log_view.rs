@@ -0,0 +1,61 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mcp_common::{LogEntry, LogLevel, ProxyId};
+use mcp_monitor::{App, AppEvent, DEFAULT_MAX_LOGS};
+
+const VIEWPORT_HEIGHT: usize = 50;
+
+/// An app holding `DEFAULT_MAX_LOGS` mixed entries, browsed in the middle of the list
+fn full_app() -> App {
+    let proxy_id = ProxyId::new();
+    let mut app = App::new();
+    for i in 0..DEFAULT_MAX_LOGS {
+        let level = match i % 4 {
+            0 => LogLevel::Request,
+            1 => LogLevel::Response,
+            2 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        };
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            level,
+            format!(r#"{{"jsonrpc":"2.0","id":{},"method":"tools/call"}}"#, i),
+            proxy_id.clone(),
+        )));
+    }
+    app.scroll_to_line(DEFAULT_MAX_LOGS / 4);
+    app
+}
+
+/// What `draw_logs` asks the app for on every frame
+fn prepare_frame(app: &mut App) -> usize {
+    app.prepare_viewport(VIEWPORT_HEIGHT);
+    let visible = app.get_visible_logs(VIEWPORT_HEIGHT).len();
+    let selection = app.get_relative_selection(VIEWPORT_HEIGHT);
+    visible + selection.unwrap_or(0) + app.get_search_filtered_count()
+}
+
+fn bench_frame_preparation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("draw_logs preparation at 10,000 entries");
+
+    let mut app = full_app();
+    group.bench_function("visible window", |b| {
+        b.iter(|| black_box(prepare_frame(&mut app)))
+    });
+    // What every frame used to do: collect the whole filtered list first
+    group.bench_function("full filtered list", |b| {
+        b.iter(|| black_box(app.get_search_filtered_logs().len()))
+    });
+
+    app.enter_search_mode();
+    for c in "tools".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_results();
+    app.scroll_to_line(DEFAULT_MAX_LOGS / 4);
+    group.bench_function("visible window of search results", |b| {
+        b.iter(|| black_box(prepare_frame(&mut app)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_frame_preparation);
+criterion_main!(benches);
@@ -1,5 +1,62 @@
-use mcp_common::{LogEntry, LogLevel, ProxyId, ProxyInfo, ProxyStats};
-use std::collections::HashMap;
+use crate::config::{Config, CustomTabConfig};
+use crate::diff::{compute_diff, DiffLine};
+use crate::json_tree::{tree_rows, JsonTreeRow};
+use crate::rate::RateHistory;
+use crate::replay::ReplayProgress;
+use crate::search::SearchQuery;
+use crate::time_range::TimeRange;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use mcp_common::{IpcMessage, LogEntry, LogLevel, ProxyId, ProxyInfo, ProxyStats, ProxyStatus};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Placeholder shown in the detail view while the background formatter runs
+pub const FORMATTING_PLACEHOLDER: &str = "Formatting…";
+
+/// Entries kept in the log list by default; older ones are dropped
+pub const DEFAULT_MAX_LOGS: usize = 10000;
+
+/// How often the UI redraws by default
+pub const DEFAULT_TICK_RATE_MS: u64 = 250;
+/// Shortest accepted tick, so a tiny value can't spin the UI loop
+pub const MIN_TICK_RATE_MS: u64 = 10;
+
+/// Latencies above this are shown in red while following, unless configured
+pub const DEFAULT_SLOW_THRESHOLD_MS: u64 = 1000;
+
+/// Earlier search queries kept for `Alt+Up`/`Alt+Down`
+pub const SEARCH_HISTORY_SIZE: usize = 50;
+
+/// A proxy that has sent nothing for this long is flagged as stale in the proxy list
+pub const PROXY_STALE_AFTER: Duration = Duration::from_secs(15);
+
+/// Error status of a proxy that stopped answering the monitor's pings
+const UNRESPONSIVE: &str = "unresponsive";
+
+/// Request timestamps kept for `requests_per_second`, dropping the oldest past this
+const MAX_REQUEST_TIMESTAMPS: usize = 10_000;
+
+/// Levels listed in the level filter dialog, in display order
+pub const LEVEL_FILTER_CHOICES: [LogLevel; 6] = [
+    LogLevel::Request,
+    LogLevel::Response,
+    LogLevel::Error,
+    LogLevel::Warning,
+    LogLevel::Info,
+    LogLevel::Debug,
+];
+
+/// A message for a proxy, delivered by the IPC server over that proxy's connection
+pub type ProxyCommand = (ProxyId, IpcMessage);
 
 #[derive(Debug)]
 pub enum AppEvent {
@@ -7,22 +64,129 @@ pub enum AppEvent {
     ProxyDisconnected(ProxyId),
     NewLogEntry(LogEntry),
     StatsUpdate(ProxyStats),
+    ProxyHeartbeat(ProxyId),        // A keepalive ping arrived from the proxy
+    ProxyUnresponsive(ProxyId),     // Nothing arrived from the proxy for a while
+    ReplayProgress(ReplayProgress), // A recorded session being replayed moved on
+    /// An IPC message was unreadable or too large and skipped; `proxy_id` if the
+    /// connection had announced one
+    MessageSkipped {
+        proxy_id: Option<ProxyId>,
+        reason: String,
+    },
+    /// The proxy's server answered `initialize` with this protocol version
+    ProxyMetadataUpdate {
+        proxy_id: ProxyId,
+        protocol_version: String,
+    },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TabType {
     All,
-    Messages, // Request + Response only
-    Errors,   // Error + Warning
-    System,   // Info + Debug + connection/disconnection logs
+    Messages,  // Request + Response only
+    Errors,    // Error + Warning
+    System,    // Info + Debug + connection/disconnection logs
+    Bookmarks, // Entries bookmarked with `b`
+    /// A tab from the config file's `custom_tabs`, listing entries at any of `levels`
+    Custom {
+        name: String,
+        levels: Vec<LogLevel>,
+    },
 }
 
+impl TabType {
+    /// The built-in tabs, which come before any custom ones
+    pub const ALL: [TabType; 5] = [
+        TabType::All,
+        TabType::Messages,
+        TabType::Errors,
+        TabType::System,
+        TabType::Bookmarks,
+    ];
+
+    /// The tab selected by its number key in the main view
+    pub fn from_shortcut(key: char) -> Option<Self> {
+        match key {
+            '1' => Some(TabType::All),
+            '2' => Some(TabType::Messages),
+            '3' => Some(TabType::Errors),
+            '4' => Some(TabType::System),
+            '5' => Some(TabType::Bookmarks),
+            _ => None,
+        }
+    }
+}
+
+/// First number key for custom tabs, after the built-in tabs' `1`-`5`
+const FIRST_CUSTOM_TAB_KEY: u32 = 6;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NavigationMode {
     Follow,        // Automatically follow latest log
     Navigate,      // Manual navigation with selection
     Search,        // Search mode with filtering
     SearchResults, // Navigating search results (no dialog)
+    SearchJump,    // Full view, n/N jump between search matches
+}
+
+/// How the log list shows when each entry arrived
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampMode {
+    #[default]
+    Absolute, // Wall-clock time, e.g. 12:03:15.123
+    Relative, // Age relative to now, e.g. -1.234s
+    Elapsed,  // Time since the first entry in the current view, e.g. +1.234s
+}
+
+impl TimestampMode {
+    pub fn next(self) -> Self {
+        match self {
+            TimestampMode::Absolute => TimestampMode::Relative,
+            TimestampMode::Relative => TimestampMode::Elapsed,
+            TimestampMode::Elapsed => TimestampMode::Absolute,
+        }
+    }
+}
+
+/// Displayed detail lines, each with the index of the unwrapped line it belongs to
+pub type DetailRows = Arc<Vec<(usize, String)>>;
+
+/// Everything the displayed rows of a detail log depend on, so wrapped rows are only
+/// rebuilt when one of them changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DetailRowsKey {
+    log_id: Uuid,
+    tab: DetailTab,
+    width: u16,
+    word_wrap: bool,
+    line_numbers: bool,
+    formatted: bool, // Whether the background formatter has delivered the content yet
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailTab {
+    Message,  // Pretty-printed message
+    Metadata, // Pretty-printed `LogEntry::metadata`
+    Raw,      // The message exactly as received
+}
+
+impl DetailTab {
+    pub const ALL: [DetailTab; 3] = [DetailTab::Message, DetailTab::Metadata, DetailTab::Raw];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            DetailTab::Message => "Message",
+            DetailTab::Metadata => "Metadata",
+            DetailTab::Raw => "Raw",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailPane {
+    Request,  // Left pane of the split detail view
+    Response, // Right pane of the split detail view
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -33,23 +197,111 @@ pub enum FocusArea {
 
 pub struct App {
     pub proxies: HashMap<ProxyId, ProxyInfo>,
-    pub logs: Vec<LogEntry>,
+    pub proxy_last_seen: HashMap<ProxyId, Instant>, // When each proxy last sent anything
+    pub logs: VecDeque<Arc<LogEntry>>,
+    pub first_log_seq: u64, // Sequence number of `logs[0]`; stored entries are numbered in order
+    pub max_logs: usize,    // Entries kept before the oldest are dropped; 0 keeps everything
+    pub paused: bool,       // New entries are held back instead of listed
+    pub pending_logs: VecDeque<LogEntry>, // Entries received while paused, in order
+    pub log_undo_buffer: Option<Vec<Arc<LogEntry>>>, // Entries the last `clear_logs` removed
+    log_undo_view: (usize, usize), // Selection and viewport when they were cleared
     pub selected_index: usize, // Currently selected item in the filtered list
     pub viewport_offset: usize, // First visible item in the viewport
     pub selected_proxy: Option<ProxyId>,
-    pub proxy_selected_index: usize, // Currently selected proxy in the list
-    pub focus_area: FocusArea,       // Which area has focus
+    pub follow_proxy_on_connect: bool, // Filter to each newly connected proxy
+    pub auto_followed_proxy: Option<ProxyId>, // Selected because it connected, cleared when it stops
+    pub error_responses_in_errors_tab: bool,  // Errors tab also lists responses with an error code
+    pub latency_column_visible: bool, // Any listed entry has a duration, updated on each draw
+    pub slow_threshold_ms: u64,       // Latencies above this are red in FOLLOW mode
+    pub proxy_selected_index: usize,  // Currently selected proxy in the list
+    pub focus_area: FocusArea,        // Which area has focus
+    pub proxy_panel_visible: bool,    // Whether the left proxy/stats panel is drawn
+    pub focus_before_panel_hidden: FocusArea, // Restored when the proxy panel is shown again
     pub active_tab: TabType,
+    pub tabs: Vec<TabType>, // In tab bar order: the built-in tabs, then the custom ones
     pub tab_states: HashMap<TabType, ListState>, // Store selection and viewport for each tab
+    pub tab_log_seqs: HashMap<TabType, VecDeque<u64>>, // Sequence numbers each tab lists, kept in step with the filters
     pub selected_log_index: Option<usize>,
     pub show_detail_view: bool,
+    pub second_selection: Option<usize>, // Entry marked with `v` to compare the next one with
+    pub show_diff_view: bool,
+    pub diff_lines: Vec<DiffLine>,
+    pub diff_contents: [String; 2], // Formatted entries being compared, older first
+    pub diff_scroll_offset: u16,
     pub detail_word_wrap: bool,
-    pub detail_scroll_offset: u16, // Vertical scroll offset for detail view
+    pub detail_syntax_highlight: bool, // Color JSON tokens in the detail view
+    pub detail_line_numbers: bool,     // Number the unwrapped content lines in a gutter
+    pub detail_tree_mode: bool,        // Show JSON content as a collapsible tree
+    pub detail_tree_expanded: HashSet<String>, // JSON pointers of expanded tree nodes
+    pub detail_tree_selected: usize,   // Selected row of the tree
+    pub detail_tree_value: Option<(Uuid, Option<Arc<serde_json::Value>>)>, // Parsed JSON by log id
+    pub detail_scroll_offset: u16,     // Vertical scroll offset for detail view
+    pub detail_content_width: u16,     // Text width of the detail pane, recorded on each draw
+    pub detail_pane_width: u16,        // Text width of each split pane, recorded on each draw
+    pub detail_viewport_height: u16, // Visible content rows of the detail view, recorded on each draw
+    pub detail_search_active: bool,  // Whether the inline detail search input is open
+    pub detail_search_query: String,
+    pub detail_search_match: Option<usize>, // Displayed line of the current detail match
+    pub show_paired_detail: bool,           // Split detail view: request left, response right
+    pub detail_pair_index: Option<usize>,   // The other pane's log, once there is one
+    pub detail_pane: DetailPane,            // Focused pane of the split detail view
+    pub detail_pane_scroll: [u16; 2],       // Scroll offsets of the request and response panes
+    pub detail_tab: DetailTab,              // Sub-tab of the single detail view
+    pub detail_tab_scroll: [u16; 3],        // Scroll offsets kept for the tabs not shown
+    pub show_save_dialog: bool,
+    pub save_raw: bool, // Save the original message instead of the formatted content
+    pub save_input: String,
+    pub save_cursor: usize, // Byte offset of the cursor in `save_input`
     pub navigation_mode: NavigationMode,
     pub search_query: String,
-    pub search_results: Vec<usize>, // Indices of matching logs in the main logs vector
-    pub search_cursor: usize,       // Current cursor position in search input
-    pub show_help_dialog: bool,     // Whether to show the help dialog
+    pub search_results: Vec<u64>, // Sequence numbers of matching logs, ascending
+    pub search_cursor: usize,     // Current cursor position in search input
+    pub search_history: VecDeque<String>, // Earlier queries, most recent first
+    pub search_history_index: Option<usize>, // History entry in the input, while browsing
+    pub show_help_dialog: bool,   // Whether to show the help dialog
+    pub help_scroll_offset: u16,  // First help line shown
+    pub help_max_scroll: u16,     // From the last draw of the help dialog
+    pub key_prefix: String,       // Repeat count typed before a navigation key, e.g. "10" of "10j"
+    pub tab_before_key_prefix: Option<TabType>, // Restored if the prefix's first digit switched tabs
+    pub timestamp_mode: TimestampMode,
+    pub config_path: Option<PathBuf>, // Where preferences are saved when they change
+    pub tick_rate: Duration,          // How often the UI redraws and ticks
+    pub last_tick_duration: Duration, // Time between the last two ticks
+    pub show_debug_overlay: bool,     // Tick timing in the corner, with `--verbose`
+    pub status_message: Option<String>, // Transient hint shown in the log view footer
+    pub skipped_messages: u64,        // IPC messages dropped as unreadable or too large
+    pub request_timestamps: VecDeque<chrono::DateTime<Utc>>, // Of recent Request entries, for the rate
+    pub peak_rps: f64,             // Highest rate `requests_per_second` has seen
+    pub request_rate: RateHistory, // Request entries per second, for the sparkline
+    pub proxy_request_rates: HashMap<ProxyId, RateHistory>, // The same for each proxy
+    rate_second_started: Instant,  // Start of the second the rates are counting
+    pub exclusions: Vec<String>,   // Method names/substrings hidden from every view
+    pub excluded_log_ids: HashSet<Uuid>, // Logs currently hidden by `exclusions`
+    log_ids: HashSet<Uuid>, // Of `logs` and `pending_logs`, so entries fetched again are skipped
+    pub bookmarks: HashSet<Uuid>, // Logs marked with `b`, listed in the Bookmarks tab
+    pub annotations: HashMap<Uuid, String>, // User notes added with `a`, by log id
+    pub show_annotation_dialog: bool,
+    pub annotation_input: String,
+    pub annotation_target: Option<Uuid>, // Log the open annotation dialog edits
+    pub show_exclusion_dialog: bool,
+    pub exclusion_input: String,
+    pub exclusion_selected: usize, // Highlighted entry in the exclusion dialog
+    pub time_range: Option<TimeRange>, // Only show logs inside this window
+    pub show_time_range_dialog: bool,
+    pub time_range_input: String,
+    pub time_range_error: Option<String>, // Parse error shown in the time range dialog
+    pub level_filter: Option<HashSet<LogLevel>>, // Only show these levels within the tab
+    pub show_level_filter_dialog: bool,
+    pub level_filter_selection: HashSet<LogLevel>, // Levels checked in the open dialog
+    pub level_filter_cursor: usize,                // Highlighted row of `LEVEL_FILTER_CHOICES`
+    pub log_counts: HashMap<(ProxyId, LogLevel), u64>, // Entries received since startup, for metrics
+    pub replay_progress: Option<ReplayProgress>,       // Set while a recorded session is replayed
+    pub proxy_commands: Option<mpsc::Sender<ProxyCommand>>, // To the IPC server, when running one
+    pub shutdown_dialog_proxy: Option<ProxyId>,        // Proxy whose shutdown awaits confirmation
+    pub formatted_cache: HashMap<Uuid, Arc<String>>,   // Pretty-printed detail content by log id
+    pub format_tx: mpsc::Sender<(Uuid, String)>,       // Handed to background formatting tasks
+    pub format_rx: mpsc::Receiver<(Uuid, String)>,     // Drained into formatted_cache each tick
+    detail_rows_cache: Vec<(DetailRowsKey, DetailRows)>, // Rows on screen at the last draw
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +311,12 @@ pub struct ListState {
     pub navigation_mode: NavigationMode,
 }
 
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl App {
     pub fn new() -> Self {
         let mut tab_states = HashMap::new();
@@ -94,82 +352,476 @@ impl App {
                 navigation_mode: NavigationMode::Follow,
             },
         );
+        tab_states.insert(
+            TabType::Bookmarks,
+            ListState {
+                selected_index: 0,
+                viewport_offset: 0,
+                navigation_mode: NavigationMode::Follow,
+            },
+        );
+
+        let (format_tx, format_rx) = mpsc::channel(64);
 
         Self {
             proxies: HashMap::new(),
-            logs: Vec::new(),
+            proxy_last_seen: HashMap::new(),
+            logs: VecDeque::new(),
+            first_log_seq: 0,
+            max_logs: DEFAULT_MAX_LOGS,
+            paused: false,
+            pending_logs: VecDeque::new(),
+            log_undo_buffer: None,
+            log_undo_view: (0, 0),
             selected_index: 0,
             viewport_offset: 0,
             selected_proxy: None,
+            follow_proxy_on_connect: false,
+            auto_followed_proxy: None,
+            error_responses_in_errors_tab: false,
+            latency_column_visible: false,
+            slow_threshold_ms: DEFAULT_SLOW_THRESHOLD_MS,
             proxy_selected_index: 0,
             focus_area: FocusArea::LogView, // Default focus on logs
-            active_tab: TabType::Messages,  // Default to Messages tab
+            proxy_panel_visible: true,
+            focus_before_panel_hidden: FocusArea::LogView,
+            active_tab: TabType::Messages, // Default to Messages tab
+            tabs: TabType::ALL.to_vec(),
             tab_states,
+            tab_log_seqs: TabType::ALL
+                .iter()
+                .map(|tab| (tab.clone(), VecDeque::new()))
+                .collect(),
             selected_log_index: None,
             show_detail_view: false,
+            second_selection: None,
+            show_diff_view: false,
+            diff_lines: Vec::new(),
+            diff_contents: Default::default(),
+            diff_scroll_offset: 0,
             detail_word_wrap: true,
+            detail_syntax_highlight: true,
+            detail_line_numbers: false,
+            detail_tree_mode: false,
+            detail_tree_expanded: HashSet::new(),
+            detail_tree_selected: 0,
+            detail_tree_value: None,
             detail_scroll_offset: 0,
+            detail_content_width: 0,
+            detail_pane_width: 0,
+            detail_viewport_height: 0,
+            detail_search_active: false,
+            detail_search_query: String::new(),
+            detail_search_match: None,
+            show_paired_detail: false,
+            detail_pair_index: None,
+            detail_pane: DetailPane::Request,
+            detail_pane_scroll: [0, 0],
+            detail_tab: DetailTab::Message,
+            detail_tab_scroll: [0; 3],
+            show_save_dialog: false,
+            save_raw: false,
+            save_input: String::new(),
+            save_cursor: 0,
             navigation_mode: NavigationMode::Follow,
             search_query: String::new(),
             search_results: Vec::new(),
             search_cursor: 0,
+            search_history: VecDeque::new(),
+            search_history_index: None,
             show_help_dialog: false,
+            help_scroll_offset: 0,
+            help_max_scroll: u16::MAX, // Unknown until the dialog is drawn
+            key_prefix: String::new(),
+            tab_before_key_prefix: None,
+            timestamp_mode: TimestampMode::Absolute,
+            config_path: None,
+            tick_rate: Duration::from_millis(DEFAULT_TICK_RATE_MS),
+            last_tick_duration: Duration::ZERO,
+            show_debug_overlay: false,
+            status_message: None,
+            skipped_messages: 0,
+            request_timestamps: VecDeque::new(),
+            peak_rps: 0.0,
+            request_rate: RateHistory::default(),
+            proxy_request_rates: HashMap::new(),
+            rate_second_started: Instant::now(),
+            exclusions: Vec::new(),
+            excluded_log_ids: HashSet::new(),
+            log_ids: HashSet::new(),
+            bookmarks: HashSet::new(),
+            annotations: HashMap::new(),
+            show_annotation_dialog: false,
+            annotation_input: String::new(),
+            annotation_target: None,
+            show_exclusion_dialog: false,
+            exclusion_input: String::new(),
+            exclusion_selected: 0,
+            time_range: None,
+            show_time_range_dialog: false,
+            time_range_input: String::new(),
+            time_range_error: None,
+            level_filter: None,
+            show_level_filter_dialog: false,
+            level_filter_selection: HashSet::new(),
+            level_filter_cursor: 0,
+            log_counts: HashMap::new(),
+            replay_progress: None,
+            proxy_commands: None,
+            shutdown_dialog_proxy: None,
+            formatted_cache: HashMap::new(),
+            detail_rows_cache: Vec::new(),
+            format_tx,
+            format_rx,
+        }
+    }
+
+    /// An app that keeps at most `max_logs` entries (0 for no limit)
+    pub fn with_capacity(max_logs: usize) -> Self {
+        let mut app = Self::new();
+        app.max_logs = max_logs;
+        // A full log then never reallocates: each new entry evicts the oldest. Capped so
+        // a huge limit doesn't claim memory up front
+        if max_logs > 0 {
+            app.logs.reserve(max_logs.min(1_000_000) + 1);
         }
+        app
     }
 
     pub fn handle_event(&mut self, event: AppEvent) {
+        let sender = match &event {
+            AppEvent::ProxyConnected(info) => Some(&info.id),
+            AppEvent::ProxyDisconnected(_) => None,
+            AppEvent::NewLogEntry(entry) => Some(&entry.proxy_id),
+            AppEvent::StatsUpdate(stats) => Some(&stats.proxy_id),
+            AppEvent::ProxyHeartbeat(id) => Some(id),
+            AppEvent::ProxyUnresponsive(_) | AppEvent::ReplayProgress(_) => None,
+            AppEvent::MessageSkipped { proxy_id, .. } => proxy_id.as_ref(),
+            AppEvent::ProxyMetadataUpdate { proxy_id, .. } => Some(proxy_id),
+        };
+        if let Some(id) = sender {
+            self.proxy_last_seen.insert(id.clone(), Instant::now());
+            // Hearing from an unresponsive proxy means it is back
+            if let Some(proxy) = self.proxies.get_mut(id) {
+                if matches!(&proxy.status, ProxyStatus::Error(reason) if reason == UNRESPONSIVE) {
+                    proxy.status = ProxyStatus::Running;
+                }
+            }
+        }
+
         match event {
-            AppEvent::ProxyConnected(info) => {
-                self.proxies.insert(info.id.clone(), info);
+            AppEvent::ProxyConnected(mut info) => {
+                // Running proxies re-announce themselves on status changes and reconnects
+                let connecting = !self
+                    .proxies
+                    .get(&info.id)
+                    .is_some_and(|proxy| !matches!(proxy.status, ProxyStatus::Stopped));
+                // A stopped proxy announcing itself again has reconnected, so it is up
+                if connecting && self.proxies.contains_key(&info.id) {
+                    info.status = ProxyStatus::Running;
+                }
+                // A proxy re-announcing itself doesn't repeat what it learned since
+                if info.protocol_version.is_none() {
+                    info.protocol_version = self
+                        .proxies
+                        .get(&info.id)
+                        .and_then(|proxy| proxy.protocol_version.clone());
+                }
+                let id = info.id.clone();
+                self.proxies.insert(id.clone(), info);
+                if connecting && self.follow_proxy_on_connect {
+                    self.set_proxy_filter(Some(id.clone()));
+                    self.auto_followed_proxy = Some(id);
+                }
             }
             AppEvent::ProxyDisconnected(id) => {
-                self.proxies.remove(&id);
-                if self.selected_proxy.as_ref() == Some(&id) {
-                    self.selected_proxy = None;
+                // Keep listing the proxy, so its logs keep their name, until `d` removes it
+                if let Some(proxy) = self.proxies.get_mut(&id) {
+                    proxy.status = ProxyStatus::Stopped;
+                }
+                self.proxy_last_seen.remove(&id);
+                // Unless the user has picked another proxy since
+                if self.auto_followed_proxy.as_ref() == Some(&id) {
+                    self.auto_followed_proxy = None;
+                    if self.selected_proxy.as_ref() == Some(&id) {
+                        self.clear_proxy_selection();
+                    }
                 }
             }
             AppEvent::NewLogEntry(entry) => {
-                // Store all logs without filtering (logs are added at the bottom)
-                self.logs.push(entry);
-
-                // Limit log size
-                const MAX_LOGS: usize = 10000;
-                if self.logs.len() > MAX_LOGS {
-                    self.logs.drain(0..self.logs.len() - MAX_LOGS);
-
-                    // Adjust selection if logs were removed
-                    for state in self.tab_states.values_mut() {
-                        if state.selected_index > 0 {
-                            state.selected_index = state
-                                .selected_index
-                                .saturating_sub(self.logs.len() - MAX_LOGS);
-                        }
-                        if state.viewport_offset > 0 {
-                            state.viewport_offset = state
-                                .viewport_offset
-                                .saturating_sub(self.logs.len() - MAX_LOGS);
-                        }
+                // Backfilled or fetched again with `L`, and already listed
+                if !self.log_ids.insert(entry.id) {
+                    return;
+                }
+                // Counted on arrival so metrics keep moving while paused
+                *self
+                    .log_counts
+                    .entry((entry.proxy_id.clone(), entry.level.clone()))
+                    .or_default() += 1;
+                if entry.level == LogLevel::Request {
+                    if self.request_timestamps.len() == MAX_REQUEST_TIMESTAMPS {
+                        self.request_timestamps.pop_front();
                     }
+                    self.request_timestamps.push_back(entry.timestamp);
+                    self.request_rate.record();
+                    self.proxy_request_rates
+                        .entry(entry.proxy_id.clone())
+                        .or_default()
+                        .record();
                 }
 
-                // In follow mode, automatically select the latest log
-                if self.navigation_mode == NavigationMode::Follow {
-                    let filtered_logs = self.get_search_filtered_logs();
-                    if !filtered_logs.is_empty() {
-                        self.selected_index = filtered_logs.len() - 1;
+                if self.paused {
+                    self.pending_logs.push_back(entry);
+                    // Older entries would be trimmed on resume anyway
+                    if self.max_logs > 0 && self.pending_logs.len() > self.max_logs {
+                        if let Some(dropped) = self.pending_logs.pop_front() {
+                            self.log_ids.remove(&dropped.id);
+                        }
                     }
+                    return;
                 }
+
+                self.push_log(entry);
+                self.follow_latest_log();
             }
             AppEvent::StatsUpdate(stats) => {
                 if let Some(proxy) = self.proxies.get_mut(&stats.proxy_id) {
+                    // Stats come more often than announcements, keeping this current
+                    proxy.monitor_connected = stats.ipc_connected;
                     proxy.stats = stats;
                 }
             }
+            AppEvent::ProxyHeartbeat(_) => {}
+            AppEvent::ProxyUnresponsive(id) => {
+                if let Some(proxy) = self.proxies.get_mut(&id) {
+                    if !matches!(proxy.status, ProxyStatus::Stopped) {
+                        proxy.status = ProxyStatus::Error(UNRESPONSIVE.to_string());
+                    }
+                }
+            }
+            AppEvent::ReplayProgress(progress) => self.replay_progress = Some(progress),
+            AppEvent::ProxyMetadataUpdate {
+                proxy_id,
+                protocol_version,
+            } => {
+                if let Some(proxy) = self.proxies.get_mut(&proxy_id) {
+                    proxy.protocol_version = Some(protocol_version);
+                }
+            }
+            AppEvent::MessageSkipped { proxy_id, reason } => {
+                self.skipped_messages += 1;
+                let entry = LogEntry::new(
+                    LogLevel::Warning,
+                    format!("Skipped an IPC message: {}", reason),
+                    proxy_id.unwrap_or_else(ProxyId::monitor),
+                );
+                self.handle_event(AppEvent::NewLogEntry(entry));
+            }
+        }
+    }
+
+    fn push_log(&mut self, entry: LogEntry) {
+        if self.is_excluded(&entry) {
+            self.excluded_log_ids.insert(entry.id);
+        }
+
+        // Store all logs without filtering (logs are added at the bottom), and list the
+        // new entry in every tab it passes the filters of
+        let seq = self.log_seq(self.logs.len());
+        let mut tab_log_seqs = std::mem::take(&mut self.tab_log_seqs);
+        for (tab, seqs) in tab_log_seqs.iter_mut() {
+            if self.is_log_in_tab(&entry, tab) {
+                seqs.push_back(seq);
+            }
+        }
+        self.tab_log_seqs = tab_log_seqs;
+
+        // A match for the active search joins its results (and n/N); a selection on the
+        // last listed result moves along with it, like follow mode does
+        let searching = matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults | NavigationMode::SearchJump
+        );
+        let listing_results = matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults
+        );
+        let new_match = searching
+            && self.is_log_in_tab(&entry, &self.active_tab)
+            && self.matches_search(&SearchQuery::parse(&self.search_query), &entry);
+        let at_last_result = self.selected_index + 1 >= self.search_results.len();
+        if new_match {
+            self.search_results.push(seq);
+        }
+
+        self.logs.push_back(Arc::new(entry));
+        // A response to the request split in the detail view fills its empty pane
+        if self.show_paired_detail && self.detail_pair_index.is_none() {
+            let index = self.logs.len() - 1;
+            let answers_selected = self
+                .selected_log_index
+                .and_then(|source| self.pair_matcher(source))
+                .is_some_and(|(forward, is_pair)| forward && is_pair(index));
+            if answers_selected {
+                self.detail_pair_index = Some(index);
+                self.request_formatting(index);
+            }
+        }
+        self.trim_logs();
+
+        if new_match && listing_results && at_last_result {
+            self.selected_index = self.search_results.len() - 1;
+            self.save_tab_state();
+        }
+    }
+
+    /// Recompute every tab's list from scratch, after a filter changed
+    fn rebuild_tab_log_seqs(&mut self) {
+        let tab_log_seqs = self
+            .tabs
+            .iter()
+            .map(|tab| {
+                let seqs = self
+                    .logs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, log)| self.is_log_in_tab(log, tab))
+                    .map(|(index, _)| self.log_seq(index))
+                    .collect();
+                (tab.clone(), seqs)
+            })
+            .collect();
+        self.tab_log_seqs = tab_log_seqs;
+    }
+
+    /// Sequence number of the log at `index`, stable while older entries are trimmed
+    pub fn log_seq(&self, index: usize) -> u64 {
+        self.first_log_seq + index as u64
+    }
+
+    /// Current index in `logs` of the entry numbered `seq`, if it is still stored
+    pub fn log_index(&self, seq: u64) -> Option<usize> {
+        let index = usize::try_from(seq.checked_sub(self.first_log_seq)?).ok()?;
+        (index < self.logs.len()).then_some(index)
+    }
+
+    /// Drop the oldest entries beyond `max_logs`, keeping every list's selection and
+    /// viewport on the same entries
+    fn trim_logs(&mut self) {
+        if self.max_logs == 0 || self.logs.len() <= self.max_logs {
+            return;
+        }
+        let removed = self.logs.len() - self.max_logs;
+        let first_kept_seq = self.log_seq(removed);
+        let stale_results = self
+            .search_results
+            .partition_point(|&seq| seq < first_kept_seq);
+
+        // How many of the dropped entries each list was showing, straight from the
+        // tab lists, which drop them the same way; the other tabs' saved positions move
+        // up past them right away
+        let mut removed_in_tab = 0;
+        for (tab, seqs) in self.tab_log_seqs.iter_mut() {
+            let count = seqs.partition_point(|&seq| seq < first_kept_seq);
+            seqs.drain(..count);
+            if *tab == self.active_tab {
+                removed_in_tab = count;
+            } else if let Some(state) = self.tab_states.get_mut(tab) {
+                state.selected_index = state.selected_index.saturating_sub(count);
+                state.viewport_offset = state.viewport_offset.saturating_sub(count);
+            }
+        }
+        let removed_in_view = if matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults
+        ) {
+            stale_results
+        } else {
+            removed_in_tab
+        };
+
+        // Popping the front of the deque is O(1) per entry; sequence numbers of the
+        // remaining entries, and so the search results, don't change
+        for log in self.logs.drain(..removed) {
+            self.log_ids.remove(&log.id);
+            self.formatted_cache.remove(&log.id);
+            self.excluded_log_ids.remove(&log.id);
+            self.bookmarks.remove(&log.id);
+            self.annotations.remove(&log.id);
+        }
+        self.first_log_seq = first_kept_seq;
+        self.search_results.drain(..stale_results);
+
+        // The detail view points into `logs` directly
+        self.detail_pair_index = self
+            .detail_pair_index
+            .and_then(|index| index.checked_sub(removed));
+        self.selected_log_index = self
+            .selected_log_index
+            .and_then(|index| index.checked_sub(removed));
+        self.second_selection = self
+            .second_selection
+            .and_then(|index| index.checked_sub(removed));
+        // A response whose request went has nothing left to show beside it
+        if self.show_paired_detail
+            && self.detail_pair_index.is_none()
+            && self.get_selected_log().map(|log| &log.level) != Some(&LogLevel::Request)
+        {
+            self.show_paired_detail = false;
+        }
+        let compared_entry_gone = self.show_diff_view && self.second_selection.is_none();
+        if self.show_detail_view && (self.selected_log_index.is_none() || compared_entry_gone) {
+            self.hide_detail_view();
+        }
+
+        self.selected_index = self.selected_index.saturating_sub(removed_in_view);
+        self.viewport_offset = self.viewport_offset.saturating_sub(removed_in_view);
+        self.save_tab_state();
+    }
+
+    fn follow_latest_log(&mut self) {
+        // In follow mode, automatically select the latest log
+        if self.navigation_mode == NavigationMode::Follow {
+            let count = self.get_search_filtered_count();
+            if count > 0 {
+                self.selected_index = count - 1;
+            }
+        }
+    }
+
+    /// Stop adding new entries to the list, or add everything held back while paused
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if !self.paused {
+            while let Some(entry) = self.pending_logs.pop_front() {
+                self.push_log(entry);
+            }
+            self.follow_latest_log();
         }
     }
 
+    /// Clear every entry; the last clear can be taken back with `undo_clear`
     pub fn clear_logs(&mut self) {
+        // Clearing an empty list again keeps the earlier entries restorable
+        if !self.logs.is_empty() {
+            self.log_undo_buffer = Some(self.logs.iter().cloned().collect());
+            self.log_undo_view = (self.selected_index, self.viewport_offset);
+            self.status_message = Some("Undo available (Ctrl+Z)".to_string());
+        }
+        // Keep numbering past the cleared entries so no old number is reused
+        self.first_log_seq += self.logs.len() as u64;
         self.logs.clear();
+        self.search_results.clear();
+        for seqs in self.tab_log_seqs.values_mut() {
+            seqs.clear();
+        }
+        self.pending_logs.clear();
+        self.log_ids.clear();
+        self.formatted_cache.clear();
+        self.excluded_log_ids.clear();
+        self.bookmarks.clear();
+        self.annotations.clear();
+        self.second_selection = None;
         self.selected_index = 0;
         self.viewport_offset = 0;
         self.navigation_mode = NavigationMode::Follow;
@@ -181,34 +833,207 @@ impl App {
         }
     }
 
+    /// Put the entries of the last `clear_logs` back before any received since, with the
+    /// selection they had. Returns how many were restored, or `None` with nothing to undo.
+    pub fn undo_clear(&mut self) -> Option<usize> {
+        let Some(restored) = self.log_undo_buffer.take() else {
+            self.status_message = Some("Nothing to undo".to_string());
+            return None;
+        };
+        // Less any fetched again since
+        let restored: Vec<_> = restored
+            .into_iter()
+            .filter(|log| self.log_ids.insert(log.id))
+            .collect();
+        let count = restored.len();
+
+        // Renumbered past every old number, like `clear_proxy_logs` does
+        let first_seq = self.log_seq(self.logs.len());
+        let newer = std::mem::take(&mut self.logs);
+        self.logs = restored.into_iter().chain(newer).collect();
+        self.first_log_seq = first_seq;
+        self.second_selection = None;
+        self.detail_pair_index = self.detail_pair_index.map(|index| index + count);
+        self.selected_log_index = self.selected_log_index.map(|index| index + count);
+        self.rebuild_tab_log_seqs();
+        if matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults
+        ) {
+            self.search_results = self.compute_search_results();
+        } else {
+            self.navigation_mode = NavigationMode::Navigate;
+        }
+        (self.selected_index, self.viewport_offset) = self.log_undo_view;
+        self.save_tab_state();
+        self.trim_logs();
+
+        self.status_message = Some(format!("Restored {} cleared entries", count));
+        Some(count)
+    }
+
+    /// The filtered proxy, or the highlighted one when the proxy list has focus
+    fn target_proxy(&self) -> Option<ProxyId> {
+        self.selected_proxy.clone().or_else(|| {
+            (self.focus_area == FocusArea::ProxyList)
+                .then(|| {
+                    self.get_proxy_list()
+                        .get(self.proxy_selected_index)
+                        .map(|p| p.id.clone())
+                })
+                .flatten()
+        })
+    }
+
+    /// Clear the filtered proxy's logs, or the highlighted proxy's when the proxy list
+    /// has focus. Clearing everything takes `C`, so without a proxy this only hints at it.
+    pub fn clear_selected_proxy_logs(&mut self) {
+        match self.target_proxy() {
+            Some(proxy_id) => self.clear_proxy_logs(&proxy_id),
+            None => {
+                self.status_message =
+                    Some("Select a proxy to clear its logs, or press C to clear all".to_string())
+            }
+        }
+    }
+
+    /// Remove only `proxy_id`'s entries, keeping the selection on the same remaining entry
+    pub fn clear_proxy_logs(&mut self, proxy_id: &ProxyId) {
+        let before = self.view_log_indices();
+        let selected = before.get(self.selected_index).copied();
+        let top = before.get(self.viewport_offset).copied();
+
+        // kept_before[i]: entries before old index i that survive, i.e. its new index
+        let mut kept_before = Vec::with_capacity(self.logs.len() + 1);
+        let mut kept = 0;
+        for log in self.logs.iter() {
+            kept_before.push(kept);
+            if &log.proxy_id != proxy_id {
+                kept += 1;
+            }
+        }
+        kept_before.push(kept);
+
+        let removed = self.logs.len() - kept;
+        for log in self.logs.iter().filter(|log| &log.proxy_id == proxy_id) {
+            self.log_ids.remove(&log.id);
+            self.formatted_cache.remove(&log.id);
+            self.excluded_log_ids.remove(&log.id);
+            self.bookmarks.remove(&log.id);
+            self.annotations.remove(&log.id);
+        }
+        // The remaining entries are renumbered past every old number
+        let first_seq = self.log_seq(self.logs.len());
+        let search_results = self
+            .search_results
+            .iter()
+            .filter_map(|&seq| self.log_index(seq))
+            .filter(|&index| &self.logs[index].proxy_id != proxy_id)
+            .map(|index| first_seq + kept_before[index] as u64)
+            .collect();
+        self.search_results = search_results;
+        self.second_selection = self
+            .second_selection
+            .filter(|&index| &self.logs[index].proxy_id != proxy_id)
+            .map(|index| kept_before[index]);
+        self.logs.retain(|log| &log.proxy_id != proxy_id);
+        self.first_log_seq = first_seq;
+        let log_ids = &mut self.log_ids;
+        self.pending_logs.retain(|log| {
+            let keep = &log.proxy_id != proxy_id;
+            if !keep {
+                log_ids.remove(&log.id);
+            }
+            keep
+        });
+        self.rebuild_tab_log_seqs();
+
+        let after = self.view_log_indices();
+        let last = after.len().saturating_sub(1);
+        if self.navigation_mode == NavigationMode::Follow {
+            self.selected_index = last;
+        } else if let Some(selected) = selected {
+            // Land on the same entry, or the next remaining one if it was removed
+            let selected = kept_before[selected];
+            self.selected_index = after.partition_point(|&i| i < selected).min(last);
+        } else {
+            self.selected_index = self.selected_index.min(last);
+        }
+        self.viewport_offset = match top {
+            Some(top) => {
+                let top = kept_before[top];
+                after.partition_point(|&i| i < top).min(last)
+            }
+            None => self.viewport_offset.min(last),
+        };
+        self.save_tab_state();
+
+        // The other tabs only need to stay within their shorter lists
+        for (tab, state) in self.tab_states.iter_mut() {
+            if *tab == self.active_tab {
+                continue;
+            }
+            let last = self
+                .tab_log_seqs
+                .get(tab)
+                .map_or(0, VecDeque::len)
+                .saturating_sub(1);
+            state.selected_index = state.selected_index.min(last);
+            state.viewport_offset = state.viewport_offset.min(last);
+        }
+
+        let name = self
+            .proxies
+            .get(proxy_id)
+            .map_or_else(|| proxy_id.to_string(), |proxy| proxy.name.clone());
+        self.status_message = Some(format!("Cleared {} entries from {}", removed, name));
+    }
+
     pub fn refresh(&mut self) {
         // Force refresh - in a real implementation, this might
         // send requests to proxies for updated stats
     }
 
     pub fn scroll_up(&mut self) {
+        self.scroll_up_by(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll_down_by(1);
+    }
+
+    pub fn scroll_up_by(&mut self, count: usize) {
         if self.navigation_mode == NavigationMode::Follow {
             self.navigation_mode = NavigationMode::Navigate;
         }
         if self.selected_index > 0 {
-            self.selected_index -= 1;
+            self.selected_index = self.selected_index.saturating_sub(count);
             self.ensure_selection_visible();
             self.save_tab_state();
         }
     }
 
-    pub fn scroll_down(&mut self) {
+    pub fn scroll_down_by(&mut self, count: usize) {
         if self.navigation_mode == NavigationMode::Follow {
             self.navigation_mode = NavigationMode::Navigate;
         }
-        let filtered_count = self.get_search_filtered_logs().len();
+        let filtered_count = self.get_search_filtered_count();
         if filtered_count > 0 && self.selected_index < filtered_count - 1 {
-            self.selected_index += 1;
+            self.selected_index = self
+                .selected_index
+                .saturating_add(count)
+                .min(filtered_count - 1);
             self.ensure_selection_visible();
             self.save_tab_state();
         }
     }
 
+    /// Select the `line`-th (1-based) entry of the current list, like vim's `{count}G`
+    pub fn scroll_to_line(&mut self, line: usize) {
+        self.scroll_to_top();
+        self.scroll_down_by(line.saturating_sub(1));
+    }
+
     pub fn page_up(&mut self) {
         if self.navigation_mode == NavigationMode::Follow {
             self.navigation_mode = NavigationMode::Navigate;
@@ -224,7 +1049,7 @@ impl App {
             self.navigation_mode = NavigationMode::Navigate;
         }
         let page_size = 10;
-        let filtered_count = self.get_search_filtered_logs().len();
+        let filtered_count = self.get_search_filtered_count();
         if filtered_count > 0 {
             self.selected_index = (self.selected_index + page_size).min(filtered_count - 1);
             self.ensure_selection_visible();
@@ -245,25 +1070,30 @@ impl App {
         if self.navigation_mode == NavigationMode::Follow {
             self.navigation_mode = NavigationMode::Navigate;
         }
-        let filtered_logs = self.get_search_filtered_logs();
-        if !filtered_logs.is_empty() {
-            self.selected_index = filtered_logs.len() - 1;
+        let filtered_count = self.get_search_filtered_count();
+        if filtered_count > 0 {
+            self.selected_index = filtered_count - 1;
             self.ensure_selection_visible();
             self.save_tab_state();
         }
     }
 
     pub fn exit_navigation_mode(&mut self) {
-        if self.navigation_mode == NavigationMode::Search
-            || self.navigation_mode == NavigationMode::SearchResults
-        {
+        if matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults | NavigationMode::SearchJump
+        ) {
             self.exit_search_mode();
+        } else if self.time_range.is_some() {
+            self.clear_time_range();
+        } else if self.level_filter.is_some() {
+            self.clear_level_filter();
         } else {
             self.navigation_mode = NavigationMode::Follow;
             // Go to the latest log
-            let filtered_logs = self.get_search_filtered_logs();
-            if !filtered_logs.is_empty() {
-                self.selected_index = filtered_logs.len() - 1;
+            let filtered_count = self.get_search_filtered_count();
+            if filtered_count > 0 {
+                self.selected_index = filtered_count - 1;
                 self.ensure_selection_visible();
                 self.save_tab_state();
             }
@@ -284,8 +1114,49 @@ impl App {
         }
     }
 
+    // Repeat count methods
+    /// Add a digit to the repeat count. Each digit naming a tab still switches to it
+    /// right away, so `2` then `3` ends on tab 3; the switches are undone if the count
+    /// ends up used by a navigation key.
+    pub fn push_key_prefix(&mut self, digit: char) {
+        if self.key_prefix.is_empty() {
+            self.tab_before_key_prefix = Some(self.active_tab.clone());
+        }
+        if let Some(tab) = self.tab_for_shortcut(digit) {
+            self.switch_tab(tab);
+        }
+        self.key_prefix.push(digit);
+    }
+
+    /// Consume the typed repeat count for a navigation key, 1 when none was typed
+    pub fn take_key_count(&mut self) -> usize {
+        if let Some(tab) = self.tab_before_key_prefix.take() {
+            if tab != self.active_tab {
+                self.switch_tab(tab);
+            }
+        }
+        // Counts too large for usize still mean "as far as possible"
+        let count = match self.key_prefix.parse::<usize>() {
+            Ok(count) => count.max(1),
+            Err(_) if self.key_prefix.is_empty() => 1,
+            Err(_) => usize::MAX,
+        };
+        self.key_prefix.clear();
+        count
+    }
+
+    /// Forget the repeat count after a key that doesn't use it
+    pub fn clear_key_prefix(&mut self) {
+        self.key_prefix.clear();
+        self.tab_before_key_prefix = None;
+    }
+
     // Focus and proxy selection methods
     pub fn switch_focus_to_proxy_list(&mut self) {
+        if !self.proxy_panel_visible {
+            self.status_message = Some("Press P to show proxies".to_string());
+            return;
+        }
         self.focus_area = FocusArea::ProxyList;
     }
 
@@ -293,6 +1164,19 @@ impl App {
         self.focus_area = FocusArea::LogView;
     }
 
+    /// Hide or show the proxy panel; the log view takes its width while hidden
+    pub fn toggle_proxy_panel(&mut self) {
+        if self.proxy_panel_visible {
+            self.focus_before_panel_hidden = self.focus_area;
+            self.focus_area = FocusArea::LogView;
+            self.proxy_panel_visible = false;
+        } else {
+            self.proxy_panel_visible = true;
+            self.focus_area = self.focus_before_panel_hidden;
+            self.status_message = None;
+        }
+    }
+
     pub fn proxy_scroll_up(&mut self) {
         if self.proxy_selected_index > 0 {
             self.proxy_selected_index -= 1;
@@ -310,42 +1194,141 @@ impl App {
         let proxy_list = self.get_proxy_list();
         if self.proxy_selected_index < proxy_list.len() {
             let selected_proxy_id = proxy_list[self.proxy_selected_index].id.clone();
-            self.selected_proxy = Some(selected_proxy_id);
-
-            // Reset log selection to latest when changing proxy filter
-            self.navigation_mode = NavigationMode::Follow;
-            let filtered_logs = self.get_filtered_logs();
-            if !filtered_logs.is_empty() {
-                self.selected_index = filtered_logs.len() - 1;
-            } else {
-                self.selected_index = 0;
-            }
-            self.viewport_offset = 0;
-            self.save_tab_state();
+            self.set_proxy_filter(Some(selected_proxy_id));
         }
     }
 
     pub fn clear_proxy_selection(&mut self) {
-        self.selected_proxy = None;
+        self.set_proxy_filter(None);
+    }
 
-        // Reset log selection to latest when clearing proxy filter
-        self.navigation_mode = NavigationMode::Follow;
-        let filtered_logs = self.get_filtered_logs();
-        if !filtered_logs.is_empty() {
-            self.selected_index = filtered_logs.len() - 1;
-        } else {
-            self.selected_index = 0;
+    /// Remove the highlighted proxy from the list once it has stopped; its logs stay
+    pub fn remove_stopped_proxy(&mut self) {
+        let Some(proxy) = self
+            .get_proxy_list()
+            .get(self.proxy_selected_index)
+            .copied()
+        else {
+            return;
+        };
+        if !matches!(proxy.status, ProxyStatus::Stopped) {
+            self.status_message = Some("Only stopped proxies can be removed".to_string());
+            return;
+        }
+
+        let id = proxy.id.clone();
+        self.proxies.remove(&id);
+        self.proxy_request_rates.remove(&id);
+        if self.selected_proxy.as_ref() == Some(&id) {
+            self.set_proxy_filter(None);
         }
+        self.proxy_selected_index = self
+            .proxy_selected_index
+            .min(self.proxies.len().saturating_sub(1));
+    }
+
+    /// Ask to confirm shutting down the highlighted proxy's MCP server
+    pub fn open_shutdown_dialog(&mut self) {
+        let Some(proxy) = self
+            .get_proxy_list()
+            .get(self.proxy_selected_index)
+            .copied()
+        else {
+            return;
+        };
+        if matches!(proxy.status, ProxyStatus::Stopped) {
+            self.status_message = Some(format!("{} is already stopped", proxy.name));
+            return;
+        }
+        self.shutdown_dialog_proxy = Some(proxy.id.clone());
+    }
+
+    pub fn close_shutdown_dialog(&mut self) {
+        self.shutdown_dialog_proxy = None;
+    }
+
+    /// Send `Shutdown` to the proxy in the confirmation dialog; it answers with
+    /// `ProxyStopped` once its server is gone
+    pub fn confirm_proxy_shutdown(&mut self) {
+        let Some(id) = self.shutdown_dialog_proxy.take() else {
+            return;
+        };
+        let name = self
+            .proxies
+            .get(&id)
+            .map_or_else(|| id.to_string(), |proxy| proxy.name.clone());
+        let sent = self.proxy_commands.as_ref().is_some_and(|commands| {
+            commands
+                .try_send((id.clone(), IpcMessage::Shutdown(id)))
+                .is_ok()
+        });
+        self.status_message = Some(if sent {
+            format!("Asked {} to shut down", name)
+        } else {
+            format!("Could not reach {}", name)
+        });
+    }
+
+    /// Ask the filtered or highlighted proxy for its latest entries again, e.g. after
+    /// clearing them; the ones still listed are skipped as they arrive
+    pub fn fetch_selected_proxy_logs(&mut self) {
+        let Some(id) = self.target_proxy() else {
+            self.status_message = Some("Select a proxy to fetch its logs".to_string());
+            return;
+        };
+        let name = self
+            .proxies
+            .get(&id)
+            .map_or_else(|| id.to_string(), |proxy| proxy.name.clone());
+        let request = IpcMessage::GetLogs {
+            proxy_id: id.clone(),
+            limit: None,
+        };
+        let sent = self
+            .proxy_commands
+            .as_ref()
+            .is_some_and(|commands| commands.try_send((id, request)).is_ok());
+        self.status_message = Some(if sent {
+            format!("Asked {} for its recent logs", name)
+        } else {
+            format!("Could not reach {}", name)
+        });
+    }
+
+    /// Only list `proxy`'s logs, or every proxy's with `None`
+    pub fn set_proxy_filter(&mut self, proxy: Option<ProxyId>) {
+        self.selected_proxy = proxy;
+        self.rebuild_tab_log_seqs();
+
+        // Reset log selection to latest when changing proxy filter
+        self.navigation_mode = NavigationMode::Follow;
+        self.selected_index = self.get_tab_log_count(&self.active_tab).saturating_sub(1);
         self.viewport_offset = 0;
         self.save_tab_state();
     }
 
     pub fn tick(&mut self) {
         // Called periodically for any time-based updates
+        self.advance_request_rates(Instant::now());
+    }
+
+    /// Move the request rates on by each whole second passed by `now`
+    pub fn advance_request_rates(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.rate_second_started)
+            .as_secs();
+        if elapsed == 0 {
+            return;
+        }
+        self.request_rate.advance(elapsed);
+        for rate in self.proxy_request_rates.values_mut() {
+            rate.advance(elapsed);
+        }
+        self.rate_second_started += Duration::from_secs(elapsed);
     }
 
     pub fn prepare_viewport(&mut self, height: usize) {
-        let filtered_count = self.get_search_filtered_logs().len();
+        let filtered_count = self.get_search_filtered_count();
 
         if filtered_count == 0 {
             self.selected_index = 0;
@@ -372,29 +1355,29 @@ impl App {
     }
 
     pub fn get_visible_logs(&self, height: usize) -> Vec<&LogEntry> {
-        let filtered_logs = self.get_search_filtered_logs();
+        let filtered_count = self.get_search_filtered_count();
 
-        if filtered_logs.is_empty() || height == 0 {
+        if filtered_count == 0 || height == 0 {
             return vec![];
         }
 
         // Ensure viewport_offset is valid
-        let start = self
-            .viewport_offset
-            .min(filtered_logs.len().saturating_sub(1));
+        let start = self.viewport_offset.min(filtered_count - 1);
 
-        // Get visible range, limited by height
-        let end = (start + height).min(filtered_logs.len());
-        filtered_logs[start..end].to_vec()
+        // Only look up the visible range, limited by height
+        let end = (start + height).min(filtered_count);
+        (start..end)
+            .filter_map(|position| self.get_search_filtered_log(position))
+            .collect()
     }
 
     pub fn get_relative_selection(&self, height: usize) -> Option<usize> {
-        let filtered_logs = self.get_search_filtered_logs();
-        if filtered_logs.is_empty() {
+        let filtered_count = self.get_search_filtered_count();
+        if filtered_count == 0 {
             return None;
         }
 
-        let end = (self.viewport_offset + height).min(filtered_logs.len());
+        let end = (self.viewport_offset + height).min(filtered_count);
 
         // Calculate relative selection position within viewport
         if self.selected_index >= self.viewport_offset && self.selected_index < end {
@@ -405,252 +1388,1632 @@ impl App {
     }
 
     pub fn get_filtered_logs(&self) -> Vec<&LogEntry> {
-        self.logs
-            .iter()
-            .filter(|log| {
-                // First apply proxy filter if any
-                if let Some(ref selected_proxy) = self.selected_proxy {
-                    if &log.proxy_id != selected_proxy {
-                        return false;
-                    }
-                }
-
-                // Then apply tab filter
-                match self.active_tab {
-                    TabType::All => true,
-                    TabType::Messages => {
-                        matches!(log.level, LogLevel::Request | LogLevel::Response)
-                    }
-                    TabType::Errors => matches!(log.level, LogLevel::Error | LogLevel::Warning),
-                    TabType::System => matches!(log.level, LogLevel::Info | LogLevel::Debug),
-                }
-            })
+        self.tab_log_seqs
+            .get(&self.active_tab)
+            .into_iter()
+            .flatten()
+            .filter_map(|&seq| self.logs.get(self.log_index(seq)?))
+            .map(|log| log.as_ref())
             .collect()
     }
 
-    pub fn switch_tab(&mut self, tab: TabType) {
-        // Save current state
-        self.save_tab_state();
+    /// Whether a log passes the proxy, `tab`, time range, level and exclusion filters
+    fn is_log_in_tab(&self, log: &LogEntry, tab: &TabType) -> bool {
+        self.matches_filters_in_tab(log, tab) && !self.excluded_log_ids.contains(&log.id)
+    }
 
-        // Switch to new tab
-        self.active_tab = tab;
+    fn matches_view_filters(&self, log: &LogEntry) -> bool {
+        self.matches_filters_in_tab(log, &self.active_tab)
+    }
 
-        // Restore state for new tab
-        if let Some(state) = self.tab_states.get(&tab) {
-            self.selected_index = state.selected_index;
-            self.viewport_offset = state.viewport_offset;
-            self.navigation_mode = state.navigation_mode;
+    /// Whether a log passes the proxy, time range, level and `tab` filters
+    fn matches_filters_in_tab(&self, log: &LogEntry, tab: &TabType) -> bool {
+        // First apply proxy filter if any
+        if let Some(ref selected_proxy) = self.selected_proxy {
+            if &log.proxy_id != selected_proxy {
+                return false;
+            }
         }
 
-        // Ensure indices are valid for the filtered logs
-        let filtered_count = self.get_filtered_logs().len();
-        if filtered_count == 0 {
-            self.selected_index = 0;
-            self.viewport_offset = 0;
-        } else if self.selected_index >= filtered_count {
-            self.selected_index = filtered_count - 1;
+        if let Some(ref time_range) = self.time_range {
+            if !time_range.contains(&log.timestamp) {
+                return false;
+            }
         }
-    }
 
-    pub fn next_tab(&mut self) {
-        let next_tab = match self.active_tab {
-            TabType::All => TabType::Messages,
-            TabType::Messages => TabType::Errors,
-            TabType::Errors => TabType::System,
-            TabType::System => TabType::All,
-        };
-        self.switch_tab(next_tab);
+        // Then apply tab filter
+        self.matches_tab(log, tab) && self.matches_level_filter(log)
     }
 
-    pub fn prev_tab(&mut self) {
-        let prev_tab = match self.active_tab {
-            TabType::All => TabType::System,
-            TabType::Messages => TabType::All,
-            TabType::Errors => TabType::Messages,
-            TabType::System => TabType::Errors,
-        };
-        self.switch_tab(prev_tab);
+    fn matches_level_filter(&self, log: &LogEntry) -> bool {
+        match self.level_filter {
+            Some(ref levels) => levels.contains(&log.level),
+            None => true,
+        }
     }
 
-    pub fn get_tab_log_count(&self, tab: TabType) -> usize {
-        self.logs
+    fn matches_tab(&self, log: &LogEntry, tab: &TabType) -> bool {
+        match tab {
+            TabType::All => true,
+            TabType::Messages => matches!(log.level, LogLevel::Request | LogLevel::Response),
+            TabType::Errors => match log.level {
+                LogLevel::Error | LogLevel::Warning => true,
+                LogLevel::Response => {
+                    self.error_responses_in_errors_tab && log.error_code().is_some()
+                }
+                _ => false,
+            },
+            TabType::System => matches!(log.level, LogLevel::Info | LogLevel::Debug),
+            TabType::Bookmarks => self.bookmarks.contains(&log.id),
+            TabType::Custom { levels, .. } => levels.contains(&log.level),
+        }
+    }
+
+    /// Add the config file's custom tabs after the built-in ones, replacing any added before
+    pub fn set_custom_tabs(&mut self, custom_tabs: &[CustomTabConfig]) {
+        if matches!(self.active_tab, TabType::Custom { .. }) {
+            self.switch_tab(TabType::Messages);
+        }
+        for tab in self.tabs.drain(TabType::ALL.len()..) {
+            self.tab_states.remove(&tab);
+            self.tab_log_seqs.remove(&tab);
+        }
+        for custom_tab in custom_tabs {
+            let tab = TabType::Custom {
+                name: custom_tab.name.clone(),
+                levels: custom_tab.levels.clone(),
+            };
+            // Two tabs with the same name and levels would share one list
+            if self.tabs.contains(&tab) {
+                continue;
+            }
+            self.tab_states.insert(
+                tab.clone(),
+                ListState {
+                    selected_index: 0,
+                    viewport_offset: 0,
+                    navigation_mode: NavigationMode::Follow,
+                },
+            );
+            self.tabs.push(tab);
+        }
+        self.rebuild_tab_log_seqs();
+    }
+
+    /// The tab selected by its number key in the main view; custom tabs take the keys
+    /// from `FIRST_CUSTOM_TAB_KEY` to `9`, in the order they are configured
+    pub fn tab_for_shortcut(&self, key: char) -> Option<TabType> {
+        if let Some(tab) = TabType::from_shortcut(key) {
+            return Some(tab);
+        }
+        let index = key.to_digit(10)?.checked_sub(FIRST_CUSTOM_TAB_KEY)?;
+        self.tabs.get(TabType::ALL.len() + index as usize).cloned()
+    }
+
+    pub fn switch_tab(&mut self, tab: TabType) {
+        // Save current state
+        self.save_tab_state();
+
+        // Switch to new tab
+        self.active_tab = tab;
+
+        // Restore state for new tab
+        if let Some(state) = self.tab_states.get(&self.active_tab) {
+            self.selected_index = state.selected_index;
+            self.viewport_offset = state.viewport_offset;
+            self.navigation_mode = state.navigation_mode;
+        }
+
+        // Ensure indices are valid for the filtered logs
+        let filtered_count = self.get_tab_log_count(&self.active_tab);
+        if filtered_count == 0 {
+            self.selected_index = 0;
+            self.viewport_offset = 0;
+        } else if self.selected_index >= filtered_count {
+            self.selected_index = filtered_count - 1;
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        let next = (self.active_tab_position() + 1) % self.tabs.len();
+        self.switch_tab(self.tabs[next].clone());
+    }
+
+    pub fn prev_tab(&mut self) {
+        let prev = (self.active_tab_position() + self.tabs.len() - 1) % self.tabs.len();
+        self.switch_tab(self.tabs[prev].clone());
+    }
+
+    /// Where the active tab is in the tab bar
+    pub fn active_tab_position(&self) -> usize {
+        self.tabs
             .iter()
-            .filter(|log| {
-                // Apply proxy filter if any
-                if let Some(ref selected_proxy) = self.selected_proxy {
-                    if &log.proxy_id != selected_proxy {
-                        return false;
-                    }
+            .position(|tab| *tab == self.active_tab)
+            .unwrap_or(0)
+    }
+
+    /// Number of entries `tab` lists under the current filters
+    pub fn get_tab_log_count(&self, tab: &TabType) -> usize {
+        self.tab_log_seqs.get(tab).map_or(0, VecDeque::len)
+    }
+
+    /// Whether a proxy has been silent (no logs, stats or pings) for `PROXY_STALE_AFTER`
+    pub fn is_proxy_stale(&self, id: &ProxyId) -> bool {
+        self.proxy_last_seen
+            .get(id)
+            .is_some_and(|seen| seen.elapsed() > PROXY_STALE_AFTER)
+    }
+
+    pub fn get_proxy_list(&self) -> Vec<&ProxyInfo> {
+        let mut proxies: Vec<_> = self.proxies.values().collect();
+        proxies.sort_by(|a, b| a.name.cmp(&b.name));
+        proxies
+    }
+
+    pub fn total_stats(&self) -> ProxyStats {
+        let mut total = ProxyStats::default();
+
+        for proxy in self.proxies.values() {
+            total.total_requests += proxy.stats.total_requests;
+            total.successful_requests += proxy.stats.successful_requests;
+            total.failed_requests += proxy.stats.failed_requests;
+            total.notifications += proxy.stats.notifications;
+            // A stopped proxy's last reported connections and requests are gone
+            if !matches!(proxy.status, ProxyStatus::Stopped) {
+                total.active_connections += proxy.stats.active_connections;
+                total.in_flight_requests += proxy.stats.in_flight_requests;
+                total.uptime = total.uptime.max(proxy.stats.uptime);
+            }
+            total.bytes_transferred += proxy.stats.bytes_transferred;
+            total.bytes_client_to_server += proxy.stats.bytes_client_to_server;
+            total.bytes_server_to_client += proxy.stats.bytes_server_to_client;
+            total.current_rps += proxy.stats.current_rps;
+            total.peak_rps = total.peak_rps.max(proxy.stats.peak_rps);
+            total.max_request_bytes = total.max_request_bytes.max(proxy.stats.max_request_bytes);
+            total.max_response_bytes = total.max_response_bytes.max(proxy.stats.max_response_bytes);
+            // Connected only while every running proxy is
+            if !matches!(proxy.status, ProxyStatus::Stopped) {
+                total.ipc_connected &= proxy.stats.ipc_connected;
+            }
+            total.ipc_buffered += proxy.stats.ipc_buffered;
+            total.ipc_dropped += proxy.stats.ipc_dropped;
+            total.ipc_reconnects += proxy.stats.ipc_reconnects;
+        }
+
+        total
+    }
+
+    /// Request entries timestamped within the last second, across all proxies; older
+    /// timestamps are pruned and `peak_rps` raised along the way
+    pub fn requests_per_second(&mut self) -> f64 {
+        let window_start = Utc::now() - chrono::Duration::seconds(1);
+        self.request_timestamps
+            .retain(|timestamp| *timestamp > window_start);
+        let rps = self.request_timestamps.len() as f64;
+        if rps > self.peak_rps {
+            self.peak_rps = rps;
+        }
+        rps
+    }
+
+    /// Current proxy stats and log counts in the Prometheus text exposition format
+    pub fn prometheus_snapshot(&self) -> String {
+        let mut proxies: Vec<&ProxyInfo> = self.proxies.values().collect();
+        proxies.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut out = String::new();
+        let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, u64)>| {
+            out.push_str(&format!(
+                "# HELP {} {}\n# TYPE {} {}\n",
+                name, help, name, kind
+            ));
+            for (labels, value) in samples {
+                out.push_str(&format!("{}{{{}}} {}\n", name, labels, value));
+            }
+        };
+
+        family(
+            "mcp_requests_total",
+            "counter",
+            "Requests completed by each proxy, by outcome.",
+            proxies
+                .iter()
+                .flat_map(|proxy| {
+                    let name = escape_label_value(&proxy.name);
+                    [
+                        (
+                            format!("proxy=\"{}\",status=\"success\"", name),
+                            proxy.stats.successful_requests,
+                        ),
+                        (
+                            format!("proxy=\"{}\",status=\"failed\"", name),
+                            proxy.stats.failed_requests,
+                        ),
+                    ]
+                })
+                .collect(),
+        );
+        family(
+            "mcp_bytes_transferred_total",
+            "counter",
+            "Bytes forwarded by each proxy in both directions.",
+            proxies
+                .iter()
+                .map(|proxy| {
+                    (
+                        format!("proxy=\"{}\"", escape_label_value(&proxy.name)),
+                        proxy.stats.bytes_transferred,
+                    )
+                })
+                .collect(),
+        );
+        family(
+            "mcp_active_connections",
+            "gauge",
+            "Open client connections of each proxy.",
+            proxies
+                .iter()
+                .map(|proxy| {
+                    (
+                        format!("proxy=\"{}\"", escape_label_value(&proxy.name)),
+                        proxy.stats.active_connections as u64,
+                    )
+                })
+                .collect(),
+        );
+        family(
+            "mcp_in_flight_requests",
+            "gauge",
+            "Requests each proxy has sent to its server that are still unanswered.",
+            proxies
+                .iter()
+                .map(|proxy| {
+                    (
+                        format!("proxy=\"{}\"", escape_label_value(&proxy.name)),
+                        proxy.stats.in_flight_requests as u64,
+                    )
+                })
+                .collect(),
+        );
+
+        let mut log_counts: Vec<(String, u64)> = self
+            .log_counts
+            .iter()
+            .filter_map(|((proxy_id, level), count)| {
+                let proxy = self.proxies.get(proxy_id)?;
+                let level = level.to_string();
+                Some((
+                    format!(
+                        "proxy=\"{}\",level=\"{}\"",
+                        escape_label_value(&proxy.name),
+                        level
+                    ),
+                    *count,
+                ))
+            })
+            .collect();
+        log_counts.sort();
+        family(
+            "mcp_log_entries_total",
+            "counter",
+            "Log entries received from each proxy, by level.",
+            log_counts,
+        );
+
+        out
+    }
+
+    // Log selection methods
+    pub fn select_log_at_cursor(&mut self) {
+        // The view already knows where each listed entry sits in `logs`
+        if let Some(&index) = self.view_log_indices().get(self.selected_index) {
+            self.selected_log_index = Some(index);
+        }
+    }
+
+    pub fn show_selected_log_detail(&mut self) {
+        if let Some(index) = self.selected_log_index {
+            if index < self.logs.len() {
+                let log = &self.logs[index];
+                // Only show detail for Request/Response logs that have meaningful content
+                if matches!(log.level, LogLevel::Request | LogLevel::Response) {
+                    self.show_detail_view = true;
+                    self.request_formatting(index);
                 }
+            }
+        }
+    }
 
-                // Apply tab filter
-                match tab {
-                    TabType::All => true,
-                    TabType::Messages => {
-                        matches!(log.level, LogLevel::Request | LogLevel::Response)
-                    }
-                    TabType::Errors => matches!(log.level, LogLevel::Error | LogLevel::Warning),
-                    TabType::System => matches!(log.level, LogLevel::Info | LogLevel::Debug),
+    /// Mark the entry under the cursor to compare with the next one opened, or unmark it
+    pub fn toggle_second_selection(&mut self) {
+        let Some(&index) = self.view_log_indices().get(self.selected_index) else {
+            return;
+        };
+        if self.second_selection == Some(index) {
+            self.second_selection = None;
+            self.status_message = Some("Comparison mark cleared".to_string());
+        } else {
+            self.second_selection = Some(index);
+            self.status_message =
+                Some("Marked for comparison: press Enter on another entry to diff".to_string());
+        }
+    }
+
+    pub fn is_second_selection(&self, log: &LogEntry) -> bool {
+        self.second_selection
+            .and_then(|index| self.logs.get(index))
+            .is_some_and(|marked| marked.id == log.id)
+    }
+
+    /// Diff the marked entry against the selected one, older entry first
+    pub fn show_diff_detail(&mut self) {
+        let (Some(first), Some(second)) = (self.second_selection, self.selected_log_index) else {
+            return;
+        };
+        if first == second || first.max(second) >= self.logs.len() {
+            return;
+        }
+        let (older, newer) = (first.min(second), first.max(second));
+
+        // Formatted here rather than in the background: the diff needs both right away
+        self.diff_contents = [
+            format_message_content(&self.logs[older].message),
+            format_message_content(&self.logs[newer].message),
+        ];
+        self.diff_lines = compute_diff(&self.diff_contents[0], &self.diff_contents[1]);
+        self.diff_scroll_offset = 0;
+        self.show_diff_view = true;
+        self.show_detail_view = true;
+    }
+
+    /// The compared entries, older first
+    pub fn diff_entries(&self) -> Option<(&LogEntry, &LogEntry)> {
+        let (first, second) = (self.second_selection?, self.selected_log_index?);
+        let older = self.logs.get(first.min(second))?;
+        let newer = self.logs.get(first.max(second))?;
+        Some((older, newer))
+    }
+
+    pub fn diff_scroll_up(&mut self, lines: u16) {
+        self.diff_scroll_offset = self.diff_scroll_offset.saturating_sub(lines);
+    }
+
+    pub fn diff_scroll_down(&mut self, lines: u16) {
+        let longest = self
+            .diff_contents
+            .iter()
+            .map(|content| content.lines().count())
+            .chain([self.diff_lines.len()])
+            .max()
+            .unwrap_or(0);
+        let max = u16::try_from(longest.saturating_sub(1)).unwrap_or(u16::MAX);
+        self.diff_scroll_offset = self.diff_scroll_offset.saturating_add(lines).min(max);
+    }
+
+    /// Format the log at `index` off the render path unless it is already cached
+    fn request_formatting(&mut self, index: usize) {
+        let log = &self.logs[index];
+        if self.formatted_cache.contains_key(&log.id) {
+            return;
+        }
+
+        // The formatter shares the entry rather than copying its message
+        let log = log.clone();
+        let tx = self.format_tx.clone();
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(move || {
+                    let content = format_message_content(&log.message);
+                    let _ = tx.blocking_send((log.id, content));
+                });
+            }
+            Err(_) => {
+                // No runtime (e.g. synchronous tests): format inline, still delivered via the channel
+                let content = format_message_content(&log.message);
+                let _ = tx.try_send((log.id, content));
+            }
+        }
+    }
+
+    /// Move finished background formatting results into the cache
+    pub fn drain_formatted_content(&mut self) {
+        while let Ok((id, content)) = self.format_rx.try_recv() {
+            self.formatted_cache.insert(id, Arc::new(content));
+        }
+    }
+
+    pub fn hide_detail_view(&mut self) {
+        self.show_detail_view = false;
+        if self.show_diff_view {
+            self.show_diff_view = false;
+            self.second_selection = None;
+            self.diff_lines.clear();
+            self.diff_contents = Default::default();
+        }
+        self.close_save_dialog();
+        self.selected_log_index = None;
+        self.detail_scroll_offset = 0; // Reset scroll when hiding
+        self.show_paired_detail = false;
+        self.detail_pair_index = None;
+        self.detail_tab = DetailTab::Message;
+        self.detail_tab_scroll = [0; 3];
+        self.exit_detail_search();
+    }
+
+    /// Show `tab` in the single detail view, keeping the scroll position of the one left
+    pub fn switch_detail_tab(&mut self, tab: DetailTab) {
+        if tab == self.detail_tab {
+            return;
+        }
+        self.detail_tab_scroll[self.detail_tab as usize] = self.detail_scroll_offset;
+        self.detail_tab = tab;
+        self.detail_scroll_offset = self.detail_tab_scroll[tab as usize];
+        self.detail_search_match = None; // Matches are per tab content
+    }
+
+    pub fn next_detail_tab(&mut self) {
+        let next = (self.detail_tab as usize + 1) % DetailTab::ALL.len();
+        self.switch_detail_tab(DetailTab::ALL[next]);
+    }
+
+    pub fn prev_detail_tab(&mut self) {
+        let count = DetailTab::ALL.len();
+        let prev = (self.detail_tab as usize + count - 1) % count;
+        self.switch_detail_tab(DetailTab::ALL[prev]);
+    }
+
+    /// Text of `log` for the active detail tab; the Metadata tab of an entry without
+    /// metadata says so rather than disappearing
+    pub fn detail_content<'a>(&'a self, log: &'a LogEntry) -> Cow<'a, str> {
+        self.tab_content(log, self.detail_tab)
+    }
+
+    fn tab_content<'a>(&'a self, log: &'a LogEntry, tab: DetailTab) -> Cow<'a, str> {
+        match tab {
+            DetailTab::Message => Cow::Borrowed(self.format_log_content(log)),
+            DetailTab::Metadata => format_metadata(log)
+                .map(Cow::Owned)
+                .unwrap_or(Cow::Borrowed("No metadata for this entry")),
+            DetailTab::Raw => Cow::Borrowed(&log.message),
+        }
+    }
+
+    pub fn toggle_word_wrap(&mut self) {
+        self.detail_word_wrap = !self.detail_word_wrap;
+        self.detail_scroll_offset = 0; // Reset scroll when toggling wrap
+        self.detail_search_match = None; // Line numbers change with wrapping
+    }
+
+    // Detail tree methods
+    pub fn toggle_detail_tree(&mut self) {
+        self.detail_tree_mode = !self.detail_tree_mode;
+        if self.detail_tree_mode {
+            // Search works on the text view
+            self.exit_detail_search();
+        }
+    }
+
+    /// Parsed JSON of the detail log, cached while the same log stays open. Opening a
+    /// different log starts again with only the root expanded.
+    fn detail_tree_json(&mut self) -> Option<Arc<serde_json::Value>> {
+        let log = self.get_selected_log()?;
+        if let Some((id, ref value)) = self.detail_tree_value {
+            if id == log.id {
+                return value.clone();
+            }
+        }
+
+        let id = log.id;
+        let value = parse_message_json(&log.message).map(Arc::new);
+        self.detail_tree_value = Some((id, value.clone()));
+        self.detail_tree_expanded = HashSet::from([String::new()]);
+        self.detail_tree_selected = 0;
+        value
+    }
+
+    /// Visible tree rows, or `None` when the text view is shown instead: tree mode is off,
+    /// the view is split, another tab than Message is shown, or the content isn't JSON
+    pub fn detail_tree_rows(&mut self) -> Option<Vec<JsonTreeRow>> {
+        if !self.detail_tree_mode
+            || self.show_paired_detail
+            || self.detail_tab != DetailTab::Message
+        {
+            return None;
+        }
+        let value = self.detail_tree_json()?;
+        let rows = tree_rows(&value, &self.detail_tree_expanded);
+        self.detail_tree_selected = self.detail_tree_selected.min(rows.len() - 1);
+        Some(rows)
+    }
+
+    /// Move the tree selection by `delta` rows, staying within the tree
+    pub fn detail_tree_move(&mut self, delta: isize) {
+        let Some(rows) = self.detail_tree_rows() else {
+            return;
+        };
+        let last = rows.len().saturating_sub(1);
+        self.detail_tree_selected = self
+            .detail_tree_selected
+            .saturating_add_signed(delta)
+            .min(last);
+    }
+
+    /// Expand or collapse the selected object or array
+    pub fn detail_tree_toggle_selected(&mut self) {
+        let Some(rows) = self.detail_tree_rows() else {
+            return;
+        };
+        let Some(row) = rows.get(self.detail_tree_selected) else {
+            return;
+        };
+        if row.is_expandable() && !self.detail_tree_expanded.remove(&row.pointer) {
+            self.detail_tree_expanded.insert(row.pointer.clone());
+        }
+    }
+
+    pub fn toggle_syntax_highlight(&mut self) {
+        self.detail_syntax_highlight = !self.detail_syntax_highlight;
+    }
+
+    pub fn toggle_line_numbers(&mut self) {
+        self.detail_line_numbers = !self.detail_line_numbers;
+        self.detail_search_match = None; // The gutter narrows the wrap width
+    }
+
+    /// Whether `log` is shown with JSON syntax colors: coloring is on and its formatted
+    /// message is JSON
+    pub fn detail_is_highlighted(&self, log: &LogEntry) -> bool {
+        if !self.detail_syntax_highlight {
+            return false;
+        }
+        let Some(content) = self.formatted_cache.get(&log.id) else {
+            return false;
+        };
+        serde_json::from_str::<serde::de::IgnoredAny>(content).is_ok()
+    }
+
+    /// Whether the active tab of the single detail view is colored; raw messages are
+    /// shown exactly as received
+    pub fn detail_tab_is_highlighted(&self, log: &LogEntry) -> bool {
+        match self.detail_tab {
+            DetailTab::Message => self.detail_is_highlighted(log),
+            DetailTab::Metadata => self.detail_syntax_highlight && log.metadata.is_some(),
+            DetailTab::Raw => false,
+        }
+    }
+
+    pub fn detail_scroll_up(&mut self) {
+        let offset = self.detail_scroll_mut();
+        *offset = offset.saturating_sub(3);
+    }
+
+    pub fn detail_scroll_down(&mut self) {
+        let max = self.detail_max_scroll();
+        let offset = self.detail_scroll_mut();
+        *offset = offset.saturating_add(3).min(max);
+    }
+
+    pub fn detail_scroll_to_top(&mut self) {
+        *self.detail_scroll_mut() = 0;
+    }
+
+    pub fn detail_scroll_to_bottom(&mut self) {
+        *self.detail_scroll_mut() = self.detail_max_scroll();
+    }
+
+    /// Record the visible height of the detail content from the last draw and pull
+    /// scroll offsets that now point past the end of the content back into range
+    pub fn prepare_detail_viewport(&mut self, height: u16) {
+        self.detail_viewport_height = height;
+        self.refresh_detail_rows_cache();
+
+        if let Some((request, response)) = self.detail_split_logs() {
+            let max = [Some(request), response].map(|log| {
+                self.max_scroll_for(log.map_or(1, |log| self.detail_pane_rows(log).len()))
+            });
+            for (offset, max) in self.detail_pane_scroll.iter_mut().zip(max) {
+                *offset = (*offset).min(max);
+            }
+        } else {
+            let max = self.max_scroll_for(self.detail_rows().len());
+            self.detail_scroll_offset = self.detail_scroll_offset.min(max);
+        }
+    }
+
+    /// Keep the wrapped rows of the logs now on screen, reusing those still valid and
+    /// dropping the rest
+    fn refresh_detail_rows_cache(&mut self) {
+        let shown = match self.detail_split_logs() {
+            Some((request, response)) => [Some(request), response]
+                .into_iter()
+                .flatten()
+                .map(|log| (self.pane_detail_rows_key(log), self.detail_pane_rows(log)))
+                .collect(),
+            None => self
+                .get_selected_log()
+                .map(|log| (self.main_detail_rows_key(log), self.detail_rows()))
+                .into_iter()
+                .collect(),
+        };
+        self.detail_rows_cache = shown;
+    }
+
+    /// Largest scroll offset of the focused pane: the one showing its last line at the bottom
+    fn detail_max_scroll(&self) -> u16 {
+        let line_count = match self.detail_split_logs() {
+            Some(_) => self
+                .detail_focused_log()
+                .map_or(0, |log| self.detail_pane_rows(log).len()),
+            None => self.detail_rows().len(),
+        };
+        self.max_scroll_for(line_count)
+    }
+
+    fn max_scroll_for(&self, line_count: usize) -> u16 {
+        line_count
+            .saturating_sub(self.detail_viewport_height as usize)
+            .min(u16::MAX as usize) as u16
+    }
+
+    /// Lines of the detail content exactly as displayed, wrapped to the pane width when
+    /// word wrap is on so search matches and scroll offsets refer to the same lines
+    pub fn detail_lines(&self) -> Vec<String> {
+        self.detail_rows()
+            .iter()
+            .map(|(_, line)| line.clone())
+            .collect()
+    }
+
+    /// Displayed detail lines paired with the index of the unwrapped line they belong
+    /// to; the line number gutter, when shown, is taken out of the wrap width
+    pub fn detail_rows(&self) -> DetailRows {
+        match self.get_selected_log() {
+            Some(log) => self.wrapped_rows(log, self.main_detail_rows_key(log)),
+            None => Arc::default(),
+        }
+    }
+
+    /// Rows of `log` as displayed in one pane of the split detail view
+    pub fn detail_pane_rows(&self, log: &LogEntry) -> DetailRows {
+        self.wrapped_rows(log, self.pane_detail_rows_key(log))
+    }
+
+    /// Panes always show the Message tab, without line numbers
+    fn pane_detail_rows_key(&self, log: &LogEntry) -> DetailRowsKey {
+        self.detail_rows_key(log, DetailTab::Message, self.detail_pane_width, false)
+    }
+
+    fn main_detail_rows_key(&self, log: &LogEntry) -> DetailRowsKey {
+        self.detail_rows_key(
+            log,
+            self.detail_tab,
+            self.detail_content_width,
+            self.detail_line_numbers,
+        )
+    }
+
+    fn detail_rows_key(
+        &self,
+        log: &LogEntry,
+        tab: DetailTab,
+        width: u16,
+        line_numbers: bool,
+    ) -> DetailRowsKey {
+        DetailRowsKey {
+            log_id: log.id,
+            tab,
+            width,
+            word_wrap: self.detail_word_wrap,
+            line_numbers,
+            formatted: self.formatted_cache.contains_key(&log.id),
+        }
+    }
+
+    /// Rows of `log` for `key`, from the cache when the last draw wrapped them the same way
+    fn wrapped_rows(&self, log: &LogEntry, key: DetailRowsKey) -> DetailRows {
+        if let Some((_, rows)) = self
+            .detail_rows_cache
+            .iter()
+            .find(|(cached, _)| *cached == key)
+        {
+            return Arc::clone(rows);
+        }
+
+        let content = self.tab_content(log, key.tab);
+        let gutter = if key.line_numbers {
+            line_number_gutter_width(content.lines().count())
+        } else {
+            0
+        };
+        Arc::new(self.display_rows(&content, key.width.saturating_sub(gutter)))
+    }
+
+    fn display_rows(&self, content: &str, width: u16) -> Vec<(usize, String)> {
+        if self.detail_word_wrap {
+            content
+                .lines()
+                .enumerate()
+                .flat_map(|(index, line)| {
+                    wrap_line(line, width as usize)
+                        .into_iter()
+                        .map(move |row| (index, row))
+                })
+                .collect()
+        } else {
+            content.lines().map(str::to_string).enumerate().collect()
+        }
+    }
+
+    /// Displayed line numbers containing the detail search query (case-insensitive)
+    pub fn detail_match_lines(&self) -> Vec<usize> {
+        if self.detail_search_query.is_empty() {
+            return Vec::new();
+        }
+
+        let query = self.detail_search_query.to_lowercase();
+        self.detail_rows()
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, line))| line.to_lowercase().contains(&query))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // Detail view search methods
+    pub fn enter_detail_search(&mut self) {
+        // Search works on the single text view
+        self.show_paired_detail = false;
+        self.detail_pair_index = None;
+        self.detail_tree_mode = false;
+        self.detail_search_active = true;
+        self.detail_search_query.clear();
+        self.detail_search_match = None;
+    }
+
+    pub fn has_detail_search(&self) -> bool {
+        self.detail_search_active || !self.detail_search_query.is_empty()
+    }
+
+    pub fn exit_detail_search(&mut self) {
+        self.detail_search_active = false;
+        self.detail_search_query.clear();
+        self.detail_search_match = None;
+    }
+
+    pub fn detail_search_input_char(&mut self, c: char) {
+        if self.detail_search_active {
+            self.detail_search_query.push(c);
+        }
+    }
+
+    pub fn detail_search_backspace(&mut self) {
+        if self.detail_search_active {
+            self.detail_search_query.pop();
+        }
+    }
+
+    /// Close the input and scroll to the first match
+    pub fn confirm_detail_search(&mut self) {
+        self.detail_search_active = false;
+        self.detail_search_match = None;
+        self.jump_to_detail_match(self.detail_match_lines().first().copied());
+    }
+
+    pub fn next_detail_match(&mut self) {
+        let matches = self.detail_match_lines();
+        let next = match self.detail_search_match {
+            Some(current) => matches.iter().find(|&&line| line > current),
+            None => matches
+                .iter()
+                .find(|&&line| line >= self.detail_scroll_offset as usize),
+        };
+        self.jump_to_detail_match(next.or(matches.first()).copied());
+    }
+
+    pub fn prev_detail_match(&mut self) {
+        let matches = self.detail_match_lines();
+        let current = self
+            .detail_search_match
+            .unwrap_or(self.detail_scroll_offset as usize);
+        let prev = matches.iter().rev().find(|&&line| line < current);
+        self.jump_to_detail_match(prev.or(matches.last()).copied());
+    }
+
+    fn jump_to_detail_match(&mut self, line: Option<usize>) {
+        if let Some(line) = line {
+            self.detail_search_match = Some(line);
+            // Leave a couple of lines of context above the match
+            self.detail_scroll_offset = line.saturating_sub(2).min(u16::MAX as usize) as u16;
+        }
+    }
+
+    // Preference methods
+    pub fn apply_config(&mut self, config: &Config) {
+        self.timestamp_mode = config.timestamp_mode;
+        self.follow_proxy_on_connect = config.follow_proxy_on_connect;
+        if self.error_responses_in_errors_tab != config.error_responses_in_errors_tab {
+            self.error_responses_in_errors_tab = config.error_responses_in_errors_tab;
+            self.rebuild_tab_log_seqs();
+        }
+        self.set_custom_tabs(&config.custom_tabs);
+        if let Some(tick_rate_ms) = config.tick_rate_ms {
+            self.set_tick_rate_ms(tick_rate_ms);
+        }
+        if let Some(slow_threshold_ms) = config.slow_threshold_ms {
+            self.slow_threshold_ms = slow_threshold_ms;
+        }
+    }
+
+    /// Redraw every `tick_rate_ms`, at least `MIN_TICK_RATE_MS`
+    pub fn set_tick_rate_ms(&mut self, tick_rate_ms: u64) {
+        self.tick_rate = Duration::from_millis(tick_rate_ms.max(MIN_TICK_RATE_MS));
+    }
+
+    /// Switch to the next timestamp format and remember it for the next session
+    pub fn cycle_timestamp_mode(&mut self) {
+        self.timestamp_mode = self.timestamp_mode.next();
+        let timestamp_mode = self.timestamp_mode;
+        self.save_preference(|config| config.timestamp_mode = timestamp_mode);
+    }
+
+    /// Start or stop filtering the logs to each proxy as it connects, and remember it for
+    /// the next session
+    pub fn toggle_follow_proxy_on_connect(&mut self) {
+        self.follow_proxy_on_connect = !self.follow_proxy_on_connect;
+        let follow = self.follow_proxy_on_connect;
+        self.status_message = Some(if follow {
+            "Following proxies as they connect".to_string()
+        } else {
+            "No longer following proxies as they connect".to_string()
+        });
+        self.save_preference(|config| config.follow_proxy_on_connect = follow);
+    }
+
+    /// List JSON-RPC error responses in the Errors tab too, or stop, and remember it for the
+    /// next session
+    pub fn toggle_error_responses_in_errors_tab(&mut self) {
+        self.error_responses_in_errors_tab = !self.error_responses_in_errors_tab;
+        self.reset_view_after_filter_change();
+        let include = self.error_responses_in_errors_tab;
+        self.status_message = Some(if include {
+            "Errors tab includes error responses".to_string()
+        } else {
+            "Errors tab leaves out error responses".to_string()
+        });
+        self.save_preference(|config| config.error_responses_in_errors_tab = include);
+    }
+
+    /// Change one setting in the config file, if there is one
+    fn save_preference(&mut self, change: impl FnOnce(&mut Config)) {
+        if let Some(ref path) = self.config_path {
+            // Settings only set in the file, like the tick rate, are kept as they are
+            let mut config = Config::load(path).unwrap_or_default();
+            change(&mut config);
+            if let Err(e) = config.save(path) {
+                self.status_message = Some(format!("Failed to save preferences: {:#}", e));
+            }
+        }
+    }
+
+    // Bookmark methods
+    /// Bookmark the log under the cursor, or remove its bookmark
+    pub fn toggle_bookmark(&mut self) {
+        let Some(seq) = self.view_seq(self.selected_index) else {
+            return;
+        };
+        let Some(log) = self.log_index(seq).map(|index| self.logs[index].clone()) else {
+            return;
+        };
+
+        let bookmarked = !self.bookmarks.remove(&log.id);
+        if bookmarked {
+            self.bookmarks.insert(log.id);
+        }
+        let listed = self.is_log_in_tab(&log, &TabType::Bookmarks);
+        if let Some(seqs) = self.tab_log_seqs.get_mut(&TabType::Bookmarks) {
+            match seqs.binary_search(&seq) {
+                Ok(position) if !listed => {
+                    seqs.remove(position);
                 }
+                Err(position) if listed => seqs.insert(position, seq),
+                _ => {}
+            }
+        }
+
+        if !bookmarked && self.active_tab == TabType::Bookmarks {
+            // The entry just left this view
+            let remaining = self.get_search_filtered_count();
+            self.selected_index = self.selected_index.min(remaining.saturating_sub(1));
+            self.save_tab_state();
+        }
+    }
+
+    pub fn is_bookmarked(&self, log: &LogEntry) -> bool {
+        self.bookmarks.contains(&log.id)
+    }
+
+    /// Write the bookmarked entries to `path` as JSON lines, returning how many were written.
+    /// Annotated entries carry their note in an extra `annotation` field.
+    pub fn export_bookmarked_logs(&self, path: &Path) -> Result<usize> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let mut count = 0;
+        for log in self.logs.iter().filter(|log| self.is_bookmarked(log)) {
+            let mut value = serde_json::to_value(log.as_ref())?;
+            if let (Some(note), Some(fields)) = (self.annotation(log), value.as_object_mut()) {
+                fields.insert("annotation".to_string(), note.into());
+            }
+            serde_json::to_writer(&mut out, &value)?;
+            out.write_all(b"\n")?;
+            count += 1;
+        }
+        out.flush()?;
+        Ok(count)
+    }
+
+    // Annotation methods
+    pub fn annotation(&self, log: &LogEntry) -> Option<&str> {
+        self.annotations.get(&log.id).map(String::as_str)
+    }
+
+    /// Open the note editor for the detail log, or the log under the cursor, prefilled
+    /// with its current note
+    pub fn open_annotation_dialog(&mut self) {
+        let target = if self.show_detail_view {
+            self.get_selected_log()
+        } else {
+            self.get_search_filtered_log(self.selected_index)
+        };
+        let Some(id) = target.map(|log| log.id) else {
+            return;
+        };
+
+        self.annotation_input = self.annotations.get(&id).cloned().unwrap_or_default();
+        self.annotation_target = Some(id);
+        self.show_annotation_dialog = true;
+    }
+
+    pub fn close_annotation_dialog(&mut self) {
+        self.show_annotation_dialog = false;
+        self.annotation_input.clear();
+        self.annotation_target = None;
+    }
+
+    pub fn annotation_input_char(&mut self, c: char) {
+        self.annotation_input.push(c);
+    }
+
+    pub fn annotation_backspace(&mut self) {
+        self.annotation_input.pop();
+    }
+
+    /// Save the typed note; an empty note removes the annotation
+    pub fn confirm_annotation(&mut self) {
+        if let Some(id) = self.annotation_target {
+            let note = self.annotation_input.trim();
+            if note.is_empty() {
+                self.annotations.remove(&id);
+            } else {
+                self.annotations.insert(id, note.to_string());
+            }
+        }
+        self.close_annotation_dialog();
+    }
+
+    /// Remove the note of the log shown in the detail view
+    pub fn clear_annotation(&mut self) {
+        if let Some(id) = self.get_selected_log().map(|log| log.id) {
+            self.annotations.remove(&id);
+        }
+    }
+
+    /// Jump between a request and its response (same JSON-RPC id and proxy) and show it in detail
+    pub fn jump_to_paired_message(&mut self) {
+        if !self.show_detail_view {
+            self.select_log_at_cursor();
+        }
+        let Some(source_index) = self.selected_log_index else {
+            return;
+        };
+
+        let view = self.view_log_indices();
+        let Some(position) = self.paired_position(source_index, &view) else {
+            self.status_message = Some("No paired message".to_string());
+            return;
+        };
+
+        self.status_message = None;
+        if self.navigation_mode == NavigationMode::Follow {
+            self.navigation_mode = NavigationMode::Navigate;
+        }
+        self.selected_index = position;
+        self.save_tab_state();
+
+        self.hide_detail_view();
+        self.selected_log_index = Some(view[position]);
+        self.show_selected_log_detail();
+    }
+
+    /// Position in `candidates` (indices into `logs`) of the request/response paired with
+    /// the log at `source_index`: same JSON-RPC id, same proxy, opposite direction
+    fn paired_position(&self, source_index: usize, candidates: &[usize]) -> Option<usize> {
+        let (forward, is_pair) = self.pair_matcher(source_index)?;
+        if forward {
+            candidates
+                .iter()
+                .position(|&index| index > source_index && is_pair(index))
+        } else {
+            candidates
+                .iter()
+                .rposition(|&index| index < source_index && is_pair(index))
+        }
+    }
+
+    /// Index in `logs` of the request/response paired with the log at `source_index`
+    fn paired_index(&self, source_index: usize) -> Option<usize> {
+        let (forward, is_pair) = self.pair_matcher(source_index)?;
+        if forward {
+            (source_index + 1..self.logs.len()).find(|&index| is_pair(index))
+        } else {
+            (0..source_index).rev().find(|&index| is_pair(index))
+        }
+    }
+
+    /// Whether to look after the log at `source_index` for its pair (a request's response
+    /// follows it) or before it, and a test for the pair's index in `logs`
+    fn pair_matcher(&self, source_index: usize) -> Option<(bool, impl Fn(usize) -> bool + '_)> {
+        let source = self.logs.get(source_index)?;
+        let paired_level = match source.level {
+            LogLevel::Request => LogLevel::Response,
+            LogLevel::Response => LogLevel::Request,
+            _ => return None,
+        };
+        let id = jsonrpc_id(source)?;
+        let forward = paired_level == LogLevel::Response;
+        let is_pair = move |index: usize| {
+            let log = &self.logs[index];
+            log.level == paired_level
+                && log.proxy_id == source.proxy_id
+                && jsonrpc_id(log).as_deref() == Some(id.as_str())
+        };
+        Some((forward, is_pair))
+    }
+
+    /// Show the selected log next to its pair (request left, response right), or go back
+    /// to the single view. A request still waiting for its response gets an empty
+    /// response pane, filled in when the response arrives; other logs without a pair
+    /// stay in the single view.
+    pub fn toggle_detail_split(&mut self) {
+        if self.show_paired_detail {
+            self.show_paired_detail = false;
+            self.detail_pair_index = None;
+            return;
+        }
+
+        let Some(source_index) = self.selected_log_index else {
+            return;
+        };
+        let is_request = self.logs[source_index].level == LogLevel::Request;
+        let pair_index = self.paired_index(source_index);
+        if pair_index.is_none() && !is_request {
+            self.status_message = Some("No paired message".to_string());
+            return;
+        }
+
+        self.status_message = None;
+        self.exit_detail_search();
+        self.show_paired_detail = true;
+        self.detail_pair_index = pair_index;
+        self.detail_pane_scroll = [0, 0];
+        self.detail_pane = if is_request {
+            DetailPane::Request
+        } else {
+            DetailPane::Response
+        };
+        if let Some(pair_index) = pair_index {
+            self.request_formatting(pair_index);
+        }
+    }
+
+    /// The response to the request shown in the detail view: the next `Response` from the
+    /// same proxy with the same request id. The split view keeps the one it found.
+    pub fn get_paired_response(&self) -> Option<&LogEntry> {
+        let source_index = self.selected_log_index?;
+        if self.logs.get(source_index)?.level != LogLevel::Request {
+            return None;
+        }
+        let index = if self.show_paired_detail {
+            self.detail_pair_index?
+        } else {
+            self.paired_index(source_index)?
+        };
+        self.logs.get(index).map(|log| log.as_ref())
+    }
+
+    /// The request and, once there is one, the response shown in the split detail view
+    pub fn detail_split_logs(&self) -> Option<(&LogEntry, Option<&LogEntry>)> {
+        if !self.show_paired_detail {
+            return None;
+        }
+        let selected = self.get_selected_log()?;
+        if selected.level == LogLevel::Request {
+            Some((selected, self.get_paired_response()))
+        } else {
+            let request = self.logs.get(self.detail_pair_index?)?;
+            Some((request, Some(selected)))
+        }
+    }
+
+    pub fn switch_detail_pane(&mut self) {
+        self.detail_pane = match self.detail_pane {
+            DetailPane::Request => DetailPane::Response,
+            DetailPane::Response => DetailPane::Request,
+        };
+    }
+
+    /// Scroll offset of the focused pane in split view, or of the single view
+    fn detail_scroll_mut(&mut self) -> &mut u16 {
+        if self.show_paired_detail {
+            &mut self.detail_pane_scroll[self.detail_pane as usize]
+        } else {
+            &mut self.detail_scroll_offset
+        }
+    }
+
+    pub fn get_selected_log(&self) -> Option<&LogEntry> {
+        if let Some(index) = self.selected_log_index {
+            self.logs.get(index).map(|log| log.as_ref())
+        } else {
+            None
+        }
+    }
+
+    pub fn format_log_content(&self, log: &LogEntry) -> &str {
+        match self.formatted_cache.get(&log.id) {
+            Some(content) => content,
+            None => FORMATTING_PLACEHOLDER,
+        }
+    }
+
+    // Save detail methods
+    /// The log in the focused pane of the split detail view, or the single detail log
+    pub fn detail_focused_log(&self) -> Option<&LogEntry> {
+        match self.detail_split_logs() {
+            Some((request, _)) if self.detail_pane == DetailPane::Request => Some(request),
+            Some((_, response)) => response,
+            None => self.get_selected_log(),
+        }
+    }
+
+    /// Prompt for a file to save the detail log to, prefilled with its method and timestamp
+    pub fn open_save_dialog(&mut self, raw: bool) {
+        let Some(log) = self.detail_focused_log() else {
+            return;
+        };
+
+        let method = parse_message_json(&log.message)
+            .and_then(|json| json.get("method")?.as_str().map(str::to_string))
+            .or_else(|| {
+                // Name a response after the method of its request
+                let index = self.logs.iter().position(|entry| entry.id == log.id)?;
+                let request = &self.logs[self.paired_index(index)?];
+                parse_message_json(&request.message)?
+                    .get("method")?
+                    .as_str()
+                    .map(str::to_string)
             })
-            .count()
+            .unwrap_or_else(|| log.level.to_string());
+
+        self.save_input = format!(
+            "{}-{}.{}",
+            method.replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_"),
+            log.timestamp.format("%Y%m%d-%H%M%S"),
+            if raw { "raw.txt" } else { "json" }
+        );
+        self.save_cursor = self.save_input.len();
+        self.save_raw = raw;
+        self.show_save_dialog = true;
+        self.status_message = None;
+    }
+
+    pub fn close_save_dialog(&mut self) {
+        self.show_save_dialog = false;
+        self.save_input.clear();
+        self.save_cursor = 0;
+    }
+
+    pub fn save_input_char(&mut self, c: char) {
+        self.save_input.insert(self.save_cursor, c);
+        self.save_cursor += c.len_utf8();
+    }
+
+    pub fn save_backspace(&mut self) {
+        if let Some(c) = self.save_input[..self.save_cursor].chars().next_back() {
+            self.save_cursor -= c.len_utf8();
+            self.save_input.remove(self.save_cursor);
+        }
     }
 
-    pub fn get_proxy_list(&self) -> Vec<&ProxyInfo> {
-        let mut proxies: Vec<_> = self.proxies.values().collect();
-        proxies.sort_by(|a, b| a.name.cmp(&b.name));
-        proxies
+    pub fn save_delete(&mut self) {
+        if self.save_cursor < self.save_input.len() {
+            self.save_input.remove(self.save_cursor);
+        }
     }
 
-    pub fn total_stats(&self) -> ProxyStats {
-        let mut total = ProxyStats::default();
+    pub fn save_cursor_left(&mut self) {
+        if let Some(c) = self.save_input[..self.save_cursor].chars().next_back() {
+            self.save_cursor -= c.len_utf8();
+        }
+    }
 
-        for proxy in self.proxies.values() {
-            total.total_requests += proxy.stats.total_requests;
-            total.successful_requests += proxy.stats.successful_requests;
-            total.failed_requests += proxy.stats.failed_requests;
-            total.active_connections += proxy.stats.active_connections;
-            total.bytes_transferred += proxy.stats.bytes_transferred;
+    pub fn save_cursor_right(&mut self) {
+        if let Some(c) = self.save_input[self.save_cursor..].chars().next() {
+            self.save_cursor += c.len_utf8();
         }
+    }
 
-        total
+    pub fn save_cursor_home(&mut self) {
+        self.save_cursor = 0;
     }
 
-    // Log selection methods
-    pub fn select_log_at_cursor(&mut self) {
-        let filtered_logs = self.get_search_filtered_logs();
-        if !filtered_logs.is_empty() && self.selected_index < filtered_logs.len() {
-            // Find the index of the selected log in the full logs vector
-            let selected_log = filtered_logs[self.selected_index];
-            if let Some(index) = self
-                .logs
-                .iter()
-                .position(|log| std::ptr::eq(log, selected_log))
-            {
-                self.selected_log_index = Some(index);
+    pub fn save_cursor_end(&mut self) {
+        self.save_cursor = self.save_input.len();
+    }
+
+    /// Write the file named in the prompt; on failure the prompt stays open for another try
+    pub fn confirm_save_dialog(&mut self) {
+        let file_name = self.save_input.trim().to_string();
+        if file_name.is_empty() {
+            self.status_message = Some("Enter a file name".to_string());
+            return;
+        }
+
+        match self.save_detail_to_file(Path::new(&file_name), self.save_raw) {
+            Ok(bytes) => {
+                self.status_message = Some(format!("Saved {} bytes to {}", bytes, file_name));
+                self.close_save_dialog();
             }
+            Err(e) => self.status_message = Some(format!("Save failed: {:#}", e)),
         }
     }
 
-    pub fn show_selected_log_detail(&mut self) {
-        if let Some(index) = self.selected_log_index {
-            if index < self.logs.len() {
-                let log = &self.logs[index];
-                // Only show detail for Request/Response logs that have meaningful content
-                if matches!(log.level, LogLevel::Request | LogLevel::Response) {
-                    self.show_detail_view = true;
-                }
+    /// Write the focused detail log to `path` as shown in the detail view (the active
+    /// tab's content) or, with `raw`, exactly as received. Returns the number of bytes written.
+    pub fn save_detail_to_file(&self, path: &Path, raw: bool) -> Result<usize> {
+        let Some(log) = self.detail_focused_log() else {
+            anyhow::bail!("no log is open in the detail view");
+        };
+
+        let tab = if self.show_paired_detail {
+            DetailTab::Message // Split panes always show the message
+        } else {
+            self.detail_tab
+        };
+        let content = match tab {
+            _ if raw => log.message.clone(),
+            DetailTab::Message => format_message_content(&log.message),
+            DetailTab::Metadata => format_metadata(log).unwrap_or_default(),
+            DetailTab::Raw => log.message.clone(),
+        };
+        std::fs::write(path, &content)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(content.len())
+    }
+
+    // Exclusion methods
+    pub fn set_exclusions(&mut self, exclusions: Vec<String>) {
+        self.exclusions.clear();
+        for pattern in exclusions {
+            let pattern = pattern.trim().to_string();
+            if !pattern.is_empty() && !self.exclusions.contains(&pattern) {
+                self.exclusions.push(pattern);
             }
         }
+        self.refresh_exclusions();
     }
 
-    pub fn hide_detail_view(&mut self) {
-        self.show_detail_view = false;
-        self.selected_log_index = None;
-        self.detail_scroll_offset = 0; // Reset scroll when hiding
+    pub fn add_exclusion(&mut self, pattern: &str) {
+        let pattern = pattern.trim();
+        if pattern.is_empty() || self.exclusions.iter().any(|p| p == pattern) {
+            return;
+        }
+        self.exclusions.push(pattern.to_string());
+        self.refresh_exclusions();
     }
 
-    pub fn toggle_word_wrap(&mut self) {
-        self.detail_word_wrap = !self.detail_word_wrap;
-        self.detail_scroll_offset = 0; // Reset scroll when toggling wrap
+    pub fn remove_exclusion(&mut self, index: usize) {
+        if index < self.exclusions.len() {
+            self.exclusions.remove(index);
+            self.exclusion_selected = self
+                .exclusion_selected
+                .min(self.exclusions.len().saturating_sub(1));
+            self.refresh_exclusions();
+        }
     }
 
-    pub fn detail_scroll_up(&mut self) {
-        self.detail_scroll_offset = self.detail_scroll_offset.saturating_sub(3);
+    pub fn clear_exclusions(&mut self) {
+        self.exclusions.clear();
+        self.exclusion_selected = 0;
+        self.refresh_exclusions();
     }
 
-    pub fn detail_scroll_down(&mut self) {
-        self.detail_scroll_offset = self.detail_scroll_offset.saturating_add(3);
+    /// Number of logs in the current proxy/tab view hidden by exclusions
+    pub fn hidden_log_count(&self) -> usize {
+        if self.excluded_log_ids.is_empty() {
+            return 0;
+        }
+        self.logs
+            .iter()
+            .filter(|log| self.matches_view_filters(log) && self.excluded_log_ids.contains(&log.id))
+            .count()
     }
 
-    pub fn get_selected_log(&self) -> Option<&LogEntry> {
-        if let Some(index) = self.selected_log_index {
-            self.logs.get(index)
+    /// Show the help dialog from its first line
+    pub fn open_help_dialog(&mut self) {
+        self.show_help_dialog = true;
+        self.help_scroll_offset = 0;
+    }
+
+    pub fn help_scroll_up(&mut self, lines: u16) {
+        self.help_scroll_offset = self.help_scroll_offset.saturating_sub(lines);
+    }
+
+    pub fn help_scroll_down(&mut self, lines: u16) {
+        self.help_scroll_offset = self
+            .help_scroll_offset
+            .saturating_add(lines)
+            .min(self.help_max_scroll);
+    }
+
+    /// Record how far the help content can scroll at its drawn size, pulling the
+    /// offset back into range after a resize
+    pub fn set_help_max_scroll(&mut self, max: u16) {
+        self.help_max_scroll = max;
+        self.help_scroll_offset = self.help_scroll_offset.min(max);
+    }
+
+    pub fn open_exclusion_dialog(&mut self) {
+        self.show_exclusion_dialog = true;
+        self.exclusion_input.clear();
+    }
+
+    pub fn close_exclusion_dialog(&mut self) {
+        self.show_exclusion_dialog = false;
+        self.exclusion_input.clear();
+    }
+
+    pub fn exclusion_input_char(&mut self, c: char) {
+        self.exclusion_input.push(c);
+    }
+
+    pub fn exclusion_backspace(&mut self) {
+        self.exclusion_input.pop();
+    }
+
+    pub fn confirm_exclusion_input(&mut self) {
+        let pattern = std::mem::take(&mut self.exclusion_input);
+        self.add_exclusion(&pattern);
+        self.exclusion_selected = self.exclusions.len().saturating_sub(1);
+    }
+
+    pub fn exclusion_select_up(&mut self) {
+        self.exclusion_selected = self.exclusion_selected.saturating_sub(1);
+    }
+
+    pub fn exclusion_select_down(&mut self) {
+        if self.exclusion_selected + 1 < self.exclusions.len() {
+            self.exclusion_selected += 1;
+        }
+    }
+
+    pub fn remove_selected_exclusion(&mut self) {
+        self.remove_exclusion(self.exclusion_selected);
+    }
+
+    // Time range methods
+    pub fn set_time_range(&mut self, time_range: Option<TimeRange>) {
+        self.time_range = time_range;
+        self.reset_view_after_filter_change();
+    }
+
+    /// Recompute search results and move the selection to the latest entry of the
+    /// re-filtered view, like the proxy filter does
+    fn reset_view_after_filter_change(&mut self) {
+        self.rebuild_tab_log_seqs();
+        if matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults
+        ) {
+            self.search_results = self.compute_search_results();
         } else {
-            None
+            self.navigation_mode = NavigationMode::Follow;
         }
+
+        self.selected_index = self.view_log_indices().len().saturating_sub(1);
+        self.viewport_offset = 0;
+        self.save_tab_state();
     }
 
-    pub fn format_log_content(&self, log: &LogEntry) -> String {
-        // First priority: format metadata as pretty JSON if available
-        if let Some(ref metadata) = log.metadata {
-            match serde_json::to_string_pretty(metadata) {
-                Ok(formatted) => {
-                    return format!(
-                        "=== METADATA ===\n{}\n\n=== MESSAGE ===\n{}",
-                        formatted,
-                        self.format_message_content(&log.message)
-                    );
-                }
-                Err(_) => {}
+    pub fn clear_time_range(&mut self) {
+        self.set_time_range(None);
+    }
+
+    pub fn open_time_range_dialog(&mut self) {
+        self.show_time_range_dialog = true;
+        self.time_range_input.clear();
+        self.time_range_error = None;
+    }
+
+    pub fn close_time_range_dialog(&mut self) {
+        self.show_time_range_dialog = false;
+        self.time_range_input.clear();
+        self.time_range_error = None;
+    }
+
+    pub fn time_range_input_char(&mut self, c: char) {
+        self.time_range_input.push(c);
+        self.time_range_error = None;
+    }
+
+    pub fn time_range_backspace(&mut self) {
+        self.time_range_input.pop();
+        self.time_range_error = None;
+    }
+
+    /// Apply the typed range, keeping the dialog open with an error if it doesn't parse
+    pub fn confirm_time_range_input(&mut self) {
+        match TimeRange::parse(&self.time_range_input, Utc::now()) {
+            Ok(time_range) => {
+                self.set_time_range(Some(time_range));
+                self.close_time_range_dialog();
             }
+            Err(e) => self.time_range_error = Some(e),
         }
+    }
 
-        // Second priority: just format the message
-        self.format_message_content(&log.message)
+    // Level filter methods
+    /// Only show `levels` within each tab; an empty list removes the filter
+    pub fn set_level_filter(&mut self, levels: Vec<LogLevel>) {
+        self.level_filter = if levels.is_empty() {
+            None
+        } else {
+            Some(levels.into_iter().collect())
+        };
+        self.reset_view_after_filter_change();
     }
 
-    fn format_message_content(&self, message: &str) -> String {
-        let trimmed = message.trim();
+    pub fn clear_level_filter(&mut self) {
+        self.level_filter = None;
+        self.reset_view_after_filter_change();
+    }
 
-        // Clean up the message by removing common prefixes and control characters
-        let cleaned = self.clean_json_message(trimmed);
+    /// Open the level picker with the current filter checked, or every level without one
+    pub fn open_level_filter_dialog(&mut self) {
+        self.show_level_filter_dialog = true;
+        self.level_filter_cursor = 0;
+        self.level_filter_selection = self
+            .level_filter
+            .clone()
+            .unwrap_or_else(|| LEVEL_FILTER_CHOICES.iter().cloned().collect());
+    }
 
-        // Try to parse the cleaned message as JSON and format it
-        match serde_json::from_str::<serde_json::Value>(&cleaned) {
-            Ok(json_value) => {
-                match serde_json::to_string_pretty(&json_value) {
-                    Ok(formatted) => {
-                        return formatted; // Return just the formatted JSON
-                    }
-                    Err(_) => {
-                        return format!("JSON parse success but format failed:\n{}", cleaned);
-                    }
-                }
-            }
-            Err(_) => {
-                // If cleaning didn't work, try parsing the original
-                if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(trimmed) {
-                    if let Ok(formatted) = serde_json::to_string_pretty(&json_value) {
-                        return formatted;
-                    }
-                }
-            }
+    pub fn close_level_filter_dialog(&mut self) {
+        self.show_level_filter_dialog = false;
+        self.level_filter_selection.clear();
+    }
+
+    pub fn level_filter_cursor_up(&mut self) {
+        self.level_filter_cursor = self.level_filter_cursor.saturating_sub(1);
+    }
+
+    pub fn level_filter_cursor_down(&mut self) {
+        if self.level_filter_cursor + 1 < LEVEL_FILTER_CHOICES.len() {
+            self.level_filter_cursor += 1;
         }
+    }
 
-        // If it's not JSON, return the original message
-        trimmed.to_string()
+    /// Check or uncheck the highlighted level
+    pub fn toggle_level_filter_choice(&mut self) {
+        let level = &LEVEL_FILTER_CHOICES[self.level_filter_cursor];
+        if !self.level_filter_selection.remove(level) {
+            self.level_filter_selection.insert(level.clone());
+        }
     }
 
-    fn clean_json_message(&self, message: &str) -> String {
-        let mut cleaned = message.to_string();
+    /// Apply the checked levels; checking all of them or none removes the filter
+    pub fn confirm_level_filter(&mut self) {
+        let selection = std::mem::take(&mut self.level_filter_selection);
+        if selection.len() == LEVEL_FILTER_CHOICES.len() {
+            self.clear_level_filter();
+        } else {
+            self.set_level_filter(selection.into_iter().collect());
+        }
+        self.close_level_filter_dialog();
+    }
 
-        // Remove common prefixes that might interfere with JSON parsing
-        let prefixes_to_remove = ["<-", "->", "<<", ">>", "IN:", "OUT:", "REQ:", "RESP:"];
+    /// A log is excluded when its JSON `method` contains any exclusion pattern
+    fn is_excluded(&self, log: &LogEntry) -> bool {
+        if self.exclusions.is_empty() {
+            return false;
+        }
 
-        for prefix in &prefixes_to_remove {
-            if cleaned.trim_start().starts_with(prefix) {
-                cleaned = cleaned
-                    .trim_start()
-                    .strip_prefix(prefix)
-                    .unwrap_or(&cleaned)
-                    .to_string();
-                cleaned = cleaned.trim_start().to_string(); // Remove any remaining whitespace
-                break;
-            }
+        let Some(method) = parse_message_json(&log.message)
+            .and_then(|json| json.get("method")?.as_str().map(str::to_lowercase))
+        else {
+            return false;
+        };
+        self.exclusions
+            .iter()
+            .any(|pattern| method.contains(&pattern.to_lowercase()))
+    }
+
+    /// Recompute hidden logs, keeping the selection and viewport on the same entries
+    fn refresh_exclusions(&mut self) {
+        let before = self.view_log_indices();
+        let selected = before.get(self.selected_index).copied();
+        let top = before.get(self.viewport_offset).copied();
+
+        self.excluded_log_ids = self
+            .logs
+            .iter()
+            .filter(|log| self.is_excluded(log))
+            .map(|log| log.id)
+            .collect();
+        self.rebuild_tab_log_seqs();
+        if matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults
+        ) {
+            self.search_results = self.compute_search_results();
         }
 
-        // Remove any leading/trailing whitespace and control characters
-        cleaned = cleaned.trim().to_string();
+        let after = self.view_log_indices();
+        let last = after.len().saturating_sub(1);
+        if self.navigation_mode == NavigationMode::Follow {
+            self.selected_index = last;
+        } else if let Some(selected) = selected {
+            // Land on the same entry, or the next visible one if it was just hidden
+            self.selected_index = after.partition_point(|&i| i < selected).min(last);
+        }
+        if let Some(top) = top {
+            self.viewport_offset = after.partition_point(|&i| i < top).min(last);
+        }
+        self.save_tab_state();
+    }
 
-        // Remove any remaining non-printable characters at the start
-        while cleaned.chars().next().map_or(false, |c| {
-            c.is_control() && c != '\n' && c != '\r' && c != '\t'
-        }) {
-            cleaned = cleaned.chars().skip(1).collect();
+    /// Indices into `logs` of the entries listed in the current view
+    fn view_log_indices(&self) -> Vec<usize> {
+        if matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults
+        ) {
+            self.search_results
+                .iter()
+                .filter_map(|&seq| self.log_index(seq))
+                .collect()
+        } else {
+            self.tab_log_seqs
+                .get(&self.active_tab)
+                .into_iter()
+                .flatten()
+                .filter_map(|&seq| self.log_index(seq))
+                .collect()
         }
+    }
 
-        cleaned
+    /// Sequence number of the entry at `position` in the current view
+    fn view_seq(&self, position: usize) -> Option<u64> {
+        if matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults
+        ) {
+            self.search_results.get(position).copied()
+        } else {
+            self.tab_log_seqs
+                .get(&self.active_tab)?
+                .get(position)
+                .copied()
+        }
     }
 
     // Search mode methods
@@ -659,26 +3022,30 @@ impl App {
         self.search_query.clear();
         self.search_results.clear();
         self.search_cursor = 0;
+        self.search_history_index = None;
         self.selected_index = 0;
         self.viewport_offset = 0;
     }
 
     pub fn exit_search_mode(&mut self) {
+        self.remember_search_query();
         self.navigation_mode = NavigationMode::Navigate;
         self.search_query.clear();
         self.search_results.clear();
         self.search_cursor = 0;
+        self.status_message = None;
 
         // Return to regular filtered view
-        let filtered_logs = self.get_filtered_logs();
-        if !filtered_logs.is_empty() {
-            self.selected_index = filtered_logs.len() - 1;
+        let filtered_count = self.get_tab_log_count(&self.active_tab);
+        if filtered_count > 0 {
+            self.selected_index = filtered_count - 1;
             self.ensure_selection_visible();
         }
         self.save_tab_state();
     }
 
     pub fn confirm_search_results(&mut self) {
+        self.remember_search_query();
         // Switch to SearchResults mode to keep the search results visible
         self.navigation_mode = NavigationMode::SearchResults;
         self.search_cursor = 0;
@@ -687,8 +3054,115 @@ impl App {
         self.save_tab_state();
     }
 
+    /// Keep the full log view and jump between matches with `n`/`N` instead of filtering
+    pub fn confirm_search_jump(&mut self) {
+        self.navigation_mode = NavigationMode::SearchJump;
+        self.search_cursor = 0;
+        self.status_message = None;
+
+        match self.search_match_positions().first() {
+            Some(&position) => self.selected_index = position,
+            None => self.status_message = Some(format!("Pattern not found: {}", self.search_query)),
+        }
+        self.save_tab_state();
+    }
+
+    pub fn next_search_match(&mut self) {
+        if self.navigation_mode != NavigationMode::SearchJump {
+            return;
+        }
+
+        let positions = self.search_match_positions();
+        let next = positions.iter().find(|&&p| p > self.selected_index);
+        self.jump_to_search_match(next, positions.first(), "BOTTOM", "TOP");
+    }
+
+    pub fn prev_search_match(&mut self) {
+        if self.navigation_mode != NavigationMode::SearchJump {
+            return;
+        }
+
+        let positions = self.search_match_positions();
+        let prev = positions.iter().rev().find(|&&p| p < self.selected_index);
+        self.jump_to_search_match(prev, positions.last(), "TOP", "BOTTOM");
+    }
+
+    fn jump_to_search_match(
+        &mut self,
+        target: Option<&usize>,
+        wrapped: Option<&usize>,
+        hit: &str,
+        continuing_at: &str,
+    ) {
+        self.status_message = None;
+        match (target, wrapped) {
+            (Some(&position), _) => self.selected_index = position,
+            (None, Some(&position)) => {
+                self.selected_index = position;
+                self.status_message = Some(format!(
+                    "Search hit {}, continuing at {}",
+                    hit, continuing_at
+                ));
+            }
+            (None, None) => {
+                self.status_message = Some(format!("Pattern not found: {}", self.search_query));
+            }
+        }
+        // prepare_viewport scrolls the jump target into view on the next draw
+        self.save_tab_state();
+    }
+
+    /// Positions of the search matches within the current (unfiltered by search) view
+    pub fn search_match_positions(&self) -> Vec<usize> {
+        self.tab_log_seqs
+            .get(&self.active_tab)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, seq)| self.search_results.binary_search(seq).is_ok())
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Put the current query at the front of the history, unless it is already there
+    fn remember_search_query(&mut self) {
+        self.search_history_index = None;
+        if self.search_query.is_empty() || self.search_history.front() == Some(&self.search_query) {
+            return;
+        }
+        self.search_history.push_front(self.search_query.clone());
+        self.search_history.truncate(SEARCH_HISTORY_SIZE);
+    }
+
+    /// Load the next older query from the history into the search input
+    pub fn search_history_older(&mut self) {
+        let index = self.search_history_index.map_or(0, |index| index + 1);
+        if self.navigation_mode == NavigationMode::Search && index < self.search_history.len() {
+            self.load_search_history(Some(index));
+        }
+    }
+
+    /// Load the next newer query, back to an empty input past the most recent one
+    pub fn search_history_newer(&mut self) {
+        if self.navigation_mode == NavigationMode::Search {
+            if let Some(index) = self.search_history_index {
+                self.load_search_history(index.checked_sub(1));
+            }
+        }
+    }
+
+    fn load_search_history(&mut self, index: Option<usize>) {
+        self.search_history_index = index;
+        self.search_query = index
+            .and_then(|index| self.search_history.get(index).cloned())
+            .unwrap_or_default();
+        self.search_cursor = self.search_query.len();
+        self.update_search_results();
+    }
+
     pub fn search_input_char(&mut self, c: char) {
         if self.navigation_mode == NavigationMode::Search {
+            self.search_history_index = None;
             self.search_query.insert(self.search_cursor, c);
             self.search_cursor += 1;
             self.update_search_results();
@@ -739,56 +3213,43 @@ impl App {
     }
 
     fn update_search_results(&mut self) {
-        self.search_results.clear();
-
-        if self.search_query.is_empty() {
-            self.selected_index = 0;
-            self.viewport_offset = 0;
-            return;
-        }
-
-        let query_lower = self.search_query.to_lowercase();
+        self.search_results = self.compute_search_results();
 
-        // Find matching log indices
-        for (index, log) in self.logs.iter().enumerate() {
-            // Apply proxy filter if any
-            if let Some(ref selected_proxy) = self.selected_proxy {
-                if &log.proxy_id != selected_proxy {
-                    continue;
-                }
-            }
+        // Reset selection to first result
+        self.selected_index = 0;
+        self.viewport_offset = 0;
+    }
 
-            // Apply tab filter
-            let matches_tab = match self.active_tab {
-                TabType::All => true,
-                TabType::Messages => matches!(log.level, LogLevel::Request | LogLevel::Response),
-                TabType::Errors => matches!(log.level, LogLevel::Error | LogLevel::Warning),
-                TabType::System => matches!(log.level, LogLevel::Info | LogLevel::Debug),
-            };
+    fn compute_search_results(&self) -> Vec<u64> {
+        let query = SearchQuery::parse(&self.search_query);
+        if query.is_empty() {
+            return Vec::new();
+        }
 
-            if !matches_tab {
+        // Find matching log sequence numbers among those the proxy, tab and exclusion
+        // filters already let through
+        let mut results = Vec::new();
+        for &seq in self
+            .tab_log_seqs
+            .get(&self.active_tab)
+            .into_iter()
+            .flatten()
+        {
+            let Some(log) = self.log_index(seq).map(|index| &self.logs[index]) else {
                 continue;
-            }
-
-            // Check if log matches search query (case-insensitive)
-            let message_matches = log.message.to_lowercase().contains(&query_lower);
-            let proxy_name_matches = self
-                .proxies
-                .get(&log.proxy_id)
-                .map(|p| p.name.to_lowercase().contains(&query_lower))
-                .unwrap_or(false);
-            let level_matches = format!("{:?}", log.level)
-                .to_lowercase()
-                .contains(&query_lower);
-
-            if message_matches || proxy_name_matches || level_matches {
-                self.search_results.push(index);
+            };
+            if self.matches_search(&query, log) {
+                results.push(seq);
             }
         }
+        results
+    }
 
-        // Reset selection to first result
-        self.selected_index = 0;
-        self.viewport_offset = 0;
+    /// Every term has to match (case-insensitive unless it is a field value); an empty
+    /// query matches nothing
+    fn matches_search(&self, query: &SearchQuery, log: &LogEntry) -> bool {
+        let proxy_name = self.proxies.get(&log.proxy_id).map(|p| p.name.as_str());
+        !query.is_empty() && query.matches(log, proxy_name)
     }
 
     pub fn get_search_filtered_logs(&self) -> Vec<&LogEntry> {
@@ -797,10 +3258,157 @@ impl App {
         {
             self.search_results
                 .iter()
-                .filter_map(|&index| self.logs.get(index))
+                .filter_map(|&seq| self.logs.get(self.log_index(seq)?))
+                .map(|log| log.as_ref())
                 .collect()
         } else {
             self.get_filtered_logs()
         }
     }
+
+    /// Length of `get_search_filtered_logs`, without collecting the entries
+    pub fn get_search_filtered_count(&self) -> usize {
+        if matches!(
+            self.navigation_mode,
+            NavigationMode::Search | NavigationMode::SearchResults
+        ) {
+            self.search_results.len()
+        } else {
+            self.get_tab_log_count(&self.active_tab)
+        }
+    }
+
+    /// Entry at `position` of `get_search_filtered_logs`, looked up on its own
+    pub fn get_search_filtered_log(&self, position: usize) -> Option<&LogEntry> {
+        let index = self.log_index(self.view_seq(position)?)?;
+        self.logs.get(index).map(|log| log.as_ref())
+    }
+}
+
+/// Columns taken by the detail view's line numbers: the digits of the largest number
+/// plus a " │ " separator
+pub fn line_number_gutter_width(line_count: usize) -> u16 {
+    line_count.max(1).to_string().len() as u16 + 3
+}
+
+/// Greedy word wrap of a single line to `width` columns (0 disables wrapping). Leading
+/// indentation is kept on the first row; words longer than `width` are split.
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut rest = line;
+
+    while width > 0 && rest.chars().count() > width {
+        let limit = rest
+            .char_indices()
+            .nth(width)
+            .map_or(rest.len(), |(byte, _)| byte);
+        let split = rest[..limit]
+            .rfind(' ')
+            .filter(|&space| !rest[..space].trim().is_empty())
+            .map_or(limit, |space| space + 1);
+
+        rows.push(rest[..split].trim_end().to_string());
+        rest = rest[split..].trim_start();
+    }
+
+    if !rest.is_empty() || rows.is_empty() {
+        rows.push(rest.to_string());
+    }
+    rows
+}
+
+fn format_metadata(log: &LogEntry) -> Option<String> {
+    serde_json::to_string_pretty(log.metadata.as_ref()?).ok()
+}
+
+fn format_message_content(message: &str) -> String {
+    let trimmed = message.trim();
+
+    // Clean up the message by removing common prefixes and control characters
+    let cleaned = clean_json_message(trimmed);
+
+    // Try to parse the cleaned message as JSON and format it
+    match serde_json::from_str::<serde_json::Value>(&cleaned) {
+        Ok(json_value) => {
+            match serde_json::to_string_pretty(&json_value) {
+                Ok(formatted) => {
+                    return formatted; // Return just the formatted JSON
+                }
+                Err(_) => {
+                    return format!("JSON parse success but format failed:\n{}", cleaned);
+                }
+            }
+        }
+        Err(_) => {
+            // If cleaning didn't work, try parsing the original
+            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(trimmed) {
+                if let Ok(formatted) = serde_json::to_string_pretty(&json_value) {
+                    return formatted;
+                }
+            }
+        }
+    }
+
+    // If it's not JSON, return the original message
+    trimmed.to_string()
+}
+
+/// Escape a Prometheus label value (backslash, double quote and newline)
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// JSON-RPC id of a log, from `request_id` or the message body
+fn jsonrpc_id(log: &LogEntry) -> Option<String> {
+    if let Some(ref request_id) = log.request_id {
+        return Some(request_id.clone());
+    }
+    match parse_message_json(&log.message)?.get("id")? {
+        serde_json::Value::Null => None,
+        // Keep the JSON form so the string "1" and the number 1 stay distinct
+        id => Some(id.to_string()),
+    }
+}
+
+/// Parse the JSON payload of a log message, ignoring direction prefixes like `→`
+pub(crate) fn parse_message_json(message: &str) -> Option<serde_json::Value> {
+    serde_json::from_str(&clean_json_message(message.trim())).ok()
+}
+
+fn clean_json_message(message: &str) -> String {
+    let mut cleaned = message.to_string();
+
+    // Remove common prefixes that might interfere with JSON parsing
+    let prefixes_to_remove = [
+        "→", "←", "<-", "->", "<<", ">>", "IN:", "OUT:", "REQ:", "RESP:",
+    ];
+
+    for prefix in &prefixes_to_remove {
+        if cleaned.trim_start().starts_with(prefix) {
+            cleaned = cleaned
+                .trim_start()
+                .strip_prefix(prefix)
+                .unwrap_or(&cleaned)
+                .to_string();
+            cleaned = cleaned.trim_start().to_string(); // Remove any remaining whitespace
+            break;
+        }
+    }
+
+    // Remove any leading/trailing whitespace and control characters
+    cleaned = cleaned.trim().to_string();
+
+    // Remove any remaining non-printable characters at the start
+    while cleaned
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_control() && c != '\n' && c != '\r' && c != '\t')
+    {
+        cleaned = cleaned.chars().skip(1).collect();
+    }
+
+    cleaned
 }
@@ -0,0 +1,60 @@
+use crate::app::TimestampMode;
+use anyhow::{Context, Result};
+use mcp_common::LogLevel;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Monitor preferences persisted between sessions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub timestamp_mode: TimestampMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tick_rate_ms: Option<u64>, // UI redraw interval, `--tick-rate-ms` takes precedence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slow_threshold_ms: Option<u64>, // Latencies above this are red while following
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom_tabs: Vec<CustomTabConfig>, // Tabs after the built-in ones, on keys 6-9
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub follow_proxy_on_connect: bool, // Filter the logs to each proxy that connects, `F`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub error_responses_in_errors_tab: bool, // List JSON-RPC error responses as errors, `E`
+}
+
+/// A tab listing only entries at `levels`, e.g. errors and warnings but not debug
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomTabConfig {
+    pub name: String,
+    pub levels: Vec<LogLevel>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/mcp-trace/monitor.toml`, falling back to `~/.config`
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("mcp-trace").join("monitor.toml"))
+    }
+
+    /// Read the config at `path`; a missing file gives the defaults
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("invalid config file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
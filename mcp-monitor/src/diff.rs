@@ -0,0 +1,119 @@
+/// One line of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Added(String),
+    Removed(String),
+    Context(String),
+}
+
+/// Edit distance past which the diff gives up on matching lines in the changed middle,
+/// keeping the search's memory (quadratic in the distance) small
+const MAX_EDIT_DISTANCE: usize = 1000;
+
+/// Line diff turning `a` into `b`, by Myers' algorithm
+pub fn compute_diff(a: &str, b: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = a.lines().collect();
+    let b: Vec<&str> = b.lines().collect();
+
+    // Lines shared at either end need no search
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_middle, b_middle) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let context = |lines: &[&str]| -> Vec<DiffLine> {
+        lines
+            .iter()
+            .map(|line| DiffLine::Context(line.to_string()))
+            .collect()
+    };
+    let mut diff = context(&a[..prefix]);
+    match myers(a_middle, b_middle) {
+        Some(middle) => diff.extend(middle),
+        None => {
+            diff.extend(
+                a_middle
+                    .iter()
+                    .map(|line| DiffLine::Removed(line.to_string())),
+            );
+            diff.extend(
+                b_middle
+                    .iter()
+                    .map(|line| DiffLine::Added(line.to_string())),
+            );
+        }
+    }
+    diff.extend(context(&a[a.len() - suffix..]));
+    diff
+}
+
+/// Shortest edit script from `a` to `b`, or `None` if it is over `MAX_EDIT_DISTANCE`
+fn myers(a: &[&str], b: &[&str]) -> Option<Vec<DiffLine>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (a.len() + b.len()).min(MAX_EDIT_DISTANCE) as isize;
+    let offset = max + 1;
+    // v[k + offset]: furthest x reached on diagonal k = x - y
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    let mut trace = Vec::new();
+
+    let mut distance = None;
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1] // Down: insert from b
+            } else {
+                v[index - 1] + 1 // Right: delete from a
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                distance = Some(d);
+                break 'search;
+            }
+        }
+    }
+    let distance = distance?;
+
+    // Walk back from the end through the furthest points of each round
+    let mut diff = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..=distance).rev() {
+        let v = &trace[d as usize];
+        let k = x - y;
+        let previous_k =
+            if k == -d || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize]) {
+                k + 1
+            } else {
+                k - 1
+            };
+        let previous_x = v[(previous_k + offset) as usize];
+        let previous_y = previous_x - previous_k;
+
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            diff.push(DiffLine::Context(a[x as usize].to_string()));
+        }
+        if d > 0 {
+            if x == previous_x {
+                diff.push(DiffLine::Added(b[previous_y as usize].to_string()));
+            } else {
+                diff.push(DiffLine::Removed(a[previous_x as usize].to_string()));
+            }
+        }
+        x = previous_x;
+        y = previous_y;
+    }
+    diff.reverse();
+    Some(diff)
+}
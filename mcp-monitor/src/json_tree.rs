@@ -0,0 +1,92 @@
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// One visible row of the detail view's JSON tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonTreeRow {
+    pub pointer: String, // JSON pointer of the node, "" for the root
+    pub depth: usize,
+    pub label: Option<String>, // Object key or `[index]`; `None` for the root
+    pub value: JsonTreeValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonTreeValue {
+    Object {
+        len: usize,
+        expanded: bool,
+    },
+    Array {
+        len: usize,
+        expanded: bool,
+    },
+    /// A scalar, as compact JSON
+    Leaf(Value),
+}
+
+impl JsonTreeRow {
+    pub fn is_expandable(&self) -> bool {
+        !matches!(self.value, JsonTreeValue::Leaf(_))
+    }
+}
+
+/// Rows for `value` with the containers whose pointers are in `expanded` opened
+pub fn tree_rows(value: &Value, expanded: &HashSet<String>) -> Vec<JsonTreeRow> {
+    let mut rows = Vec::new();
+    push_rows(value, String::new(), None, 0, expanded, &mut rows);
+    rows
+}
+
+fn push_rows(
+    value: &Value,
+    pointer: String,
+    label: Option<String>,
+    depth: usize,
+    expanded: &HashSet<String>,
+    rows: &mut Vec<JsonTreeRow>,
+) {
+    let is_expanded = expanded.contains(&pointer);
+    let tree_value = match value {
+        Value::Object(map) => JsonTreeValue::Object {
+            len: map.len(),
+            expanded: is_expanded,
+        },
+        Value::Array(items) => JsonTreeValue::Array {
+            len: items.len(),
+            expanded: is_expanded,
+        },
+        scalar => JsonTreeValue::Leaf(scalar.clone()),
+    };
+    rows.push(JsonTreeRow {
+        pointer: pointer.clone(),
+        depth,
+        label,
+        value: tree_value,
+    });
+
+    if !is_expanded {
+        return;
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_pointer = format!("{}/{}", pointer, escape_pointer_token(key));
+                let label = Some(key.clone());
+                push_rows(child, child_pointer, label, depth + 1, expanded, rows);
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                let child_pointer = format!("{}/{}", pointer, index);
+                let label = Some(format!("[{}]", index));
+                push_rows(child, child_pointer, label, depth + 1, expanded, rows);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Escape an object key for use in a JSON pointer (RFC 6901)
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::{
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
@@ -6,22 +6,82 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use mcp_common::IpcServer;
+use mcp_common::{IpcConnection, IpcError, IpcMessage, IpcServer, LogEntry, LogLevel, ProxyId};
 use ratatui::prelude::*;
-use std::io;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc;
+use subscribers::SubscriberHub;
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{interval_at, Instant};
 // Remove unused tracing imports that interfere with TUI
 
 mod app;
+mod config;
+mod diff;
+mod json_tree;
+mod metrics;
+mod rate;
+mod replay;
+mod search;
+mod subscribers;
+mod time_range;
 mod ui;
 
 // Export for testing and internal use
-pub use app::{App, AppEvent, FocusArea, NavigationMode, TabType};
+pub use app::{
+    line_number_gutter_width, App, AppEvent, DetailPane, DetailRows, DetailTab, FocusArea,
+    NavigationMode, ProxyCommand, TabType, TimestampMode, DEFAULT_MAX_LOGS,
+    DEFAULT_SLOW_THRESHOLD_MS, DEFAULT_TICK_RATE_MS, FORMATTING_PLACEHOLDER, LEVEL_FILTER_CHOICES,
+    MIN_TICK_RATE_MS, PROXY_STALE_AFTER,
+};
+pub use config::{Config, CustomTabConfig};
+pub use diff::{compute_diff, DiffLine};
+pub use json_tree::{tree_rows, JsonTreeRow, JsonTreeValue};
+pub use metrics::serve_metrics;
+pub use rate::{RateHistory, RATE_HISTORY_SECONDS};
+pub use replay::{
+    load_recording, run_replay, step_replay_speed, ReplayControl, ReplayProgress, MAX_REPLAY_SPEED,
+    MIN_REPLAY_SPEED,
+};
+pub use search::{SearchQuery, SearchSyntax};
+pub use time_range::TimeRange;
+pub use ui::{
+    draw, format_line_position, format_timestamp, format_uptime, highlight_json_lines,
+    highlight_spans,
+};
+
+/// Entries a new proxy is asked for, as many as a proxy keeps; the ones this monitor
+/// already has are skipped
+const BACKFILL_LIMIT: usize = 500;
+
+/// Proxy and client connections the IPC server keeps open at once by default
+pub const DEFAULT_MAX_CONNECTIONS: usize = 32;
+
+/// How often the monitor pings each proxy
+pub const MONITOR_PING_INTERVAL: Duration = Duration::from_secs(5);
+/// Silence after which a proxy is shown as unresponsive
+pub const PROXY_UNRESPONSIVE_AFTER: Duration = Duration::from_secs(15);
 
 pub struct MonitorArgs {
     pub ipc_socket: String,
+    pub ipc_token: Option<String>, // Required from proxies and clients over TCP
     pub verbose: bool,
+    pub exclude: Vec<String>,
+    pub metrics_port: Option<u16>,
+    pub max_logs: usize,
+    pub max_connections: usize,    // 0 for no limit
+    pub tick_rate_ms: Option<u64>, // None for the config file's, else DEFAULT_TICK_RATE_MS
+}
+
+pub struct ReplayArgs {
+    pub file: PathBuf,
+    pub speed: f64, // Starting speed factor, 1.0 replays in recorded time
+    pub max_logs: usize,
 }
 
 pub async fn run_monitor_app(args: MonitorArgs) -> Result<()> {
@@ -47,28 +107,103 @@ pub async fn run_monitor_app(args: MonitorArgs) -> Result<()> {
             .init();
     }
 
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // Bind the metrics endpoint before taking over the terminal so errors stay readable
+    let metrics_listener = match args.metrics_port {
+        Some(port) => Some(TcpListener::bind(("127.0.0.1", port)).await?),
+        None => None,
+    };
+
+    // Channel for commands from the UI to proxies
+    let (command_tx, command_rx) = mpsc::channel(16);
 
-    // Create app
-    let app = App::new();
+    // Create app, shared with the metrics exporter
+    let mut app = monitor_app(&args);
+    app.set_exclusions(args.exclude);
+    app.proxy_commands = Some(command_tx);
+    let app = Arc::new(RwLock::new(app));
+
+    if let Some(listener) = metrics_listener {
+        let app = app.clone();
+        tokio::spawn(async move {
+            let _ = serve_metrics(listener, app).await;
+        });
+    }
 
     // Channel for IPC events
     let (event_tx, event_rx) = mpsc::channel(100);
 
     // Start IPC server in background
     let ipc_socket_path = args.ipc_socket.clone();
+    let ipc_token = args.ipc_token.clone();
+    let max_connections = args.max_connections;
     tokio::spawn(async move {
-        let _ = run_ipc_server(&ipc_socket_path, event_tx).await;
+        let _ = run_ipc_server(
+            &ipc_socket_path,
+            ipc_token,
+            max_connections,
+            event_tx,
+            command_rx,
+        )
+        .await;
         // Remove error logging to avoid TUI interference
     });
 
+    run_terminal(app, event_rx, None).await
+}
+
+/// Replay a recorded session (NDJSON of IPC envelopes) in the monitor UI, with `Space`
+/// to pause and `+`/`-` to change the speed
+pub async fn run_replay_app(args: ReplayArgs) -> Result<()> {
+    // Read the whole recording before taking over the terminal so errors stay readable
+    let file = File::open(&args.file)
+        .with_context(|| format!("failed to open {}", args.file.display()))?;
+    let envelopes = load_recording(BufReader::new(file))?;
+
+    let app = Arc::new(RwLock::new(configured_app(args.max_logs)));
+    let (event_tx, event_rx) = mpsc::channel(100);
+    let (control_tx, control_rx) = mpsc::channel(16);
+    tokio::spawn(run_replay(envelopes, args.speed, event_tx, control_rx));
+
+    run_terminal(app, event_rx, Some(control_tx)).await
+}
+
+/// The app for a live monitor: the saved preferences with the command line's on top
+pub fn monitor_app(args: &MonitorArgs) -> App {
+    let mut app = configured_app(args.max_logs);
+    if let Some(tick_rate_ms) = args.tick_rate_ms {
+        app.set_tick_rate_ms(tick_rate_ms);
+    }
+    app.show_debug_overlay = args.verbose;
+    app
+}
+
+/// An app keeping `max_logs` entries, with the saved preferences applied
+fn configured_app(max_logs: usize) -> App {
+    let mut app = App::with_capacity(max_logs);
+    // An unreadable config file falls back to the defaults rather than blocking startup
+    let config_path = Config::default_path();
+    if let Some(ref path) = config_path {
+        app.apply_config(&Config::load(path).unwrap_or_default());
+    }
+    app.config_path = config_path;
+    app
+}
+
+/// Run the UI in the alternate screen until the user quits, restoring the terminal after
+async fn run_terminal(
+    app: Arc<RwLock<App>>,
+    event_rx: mpsc::Receiver<AppEvent>,
+    replay: Option<mpsc::Sender<ReplayControl>>,
+) -> Result<()> {
+    // Setup terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
     // Run the app
-    let result = run_app(&mut terminal, app, event_rx).await;
+    let result = run_app(&mut terminal, app, event_rx, replay).await;
 
     // Restore terminal
     disable_raw_mode()?;
@@ -82,52 +217,64 @@ pub async fn run_monitor_app(args: MonitorArgs) -> Result<()> {
     result
 }
 
-async fn run_ipc_server(socket_path: &str, event_tx: mpsc::Sender<AppEvent>) -> Result<()> {
-    let server = IpcServer::bind(socket_path).await?;
+/// Accept proxy and client connections on `socket_path`, turning proxy messages into
+/// app events. A proxy heard from for the first time is asked for the entries it sent
+/// before, so a restarted monitor backfills them. `commands` are delivered over the
+/// connections of the proxy they name. Over TCP, a `token` is required from every
+/// connection and ones without it are dropped with a note in the System tab.
+pub async fn run_ipc_server(
+    socket_path: &str,
+    token: Option<String>,
+    max_connections: usize,
+    event_tx: mpsc::Sender<AppEvent>,
+    commands: mpsc::Receiver<ProxyCommand>,
+) -> Result<()> {
+    run_ipc_server_with_keepalive(
+        socket_path,
+        token,
+        max_connections,
+        MONITOR_PING_INTERVAL,
+        PROXY_UNRESPONSIVE_AFTER,
+        event_tx,
+        commands,
+    )
+    .await
+}
+
+/// `run_ipc_server`, pinging each proxy every `ping_interval` and reporting it
+/// unresponsive once nothing has arrived from it for `unresponsive_after`
+pub async fn run_ipc_server_with_keepalive(
+    socket_path: &str,
+    token: Option<String>,
+    max_connections: usize,
+    ping_interval: Duration,
+    unresponsive_after: Duration,
+    event_tx: mpsc::Sender<AppEvent>,
+    commands: mpsc::Receiver<ProxyCommand>,
+) -> Result<()> {
+    let mut server = IpcServer::bind(socket_path).await?.with_token(token);
+    if max_connections > 0 {
+        server = server.with_max_connections(max_connections);
+    }
+    let hub = Arc::new(SubscriberHub::new());
+    let live_proxies = LiveProxies::default();
+    tokio::spawn(route_commands(commands, live_proxies.clone()));
     // Remove logging that interferes with TUI
 
     loop {
         match server.accept().await {
-            Ok(mut connection) => {
+            Ok(connection) => {
                 // Remove "New proxy connected" log
-                let tx = event_tx.clone();
-
-                tokio::spawn(async move {
-                    loop {
-                        match connection.receive_message().await {
-                            Ok(Some(envelope)) => {
-                                let event = match envelope.message {
-                                    mcp_common::IpcMessage::ProxyStarted(info) => {
-                                        AppEvent::ProxyConnected(info)
-                                    }
-                                    mcp_common::IpcMessage::ProxyStopped(id) => {
-                                        AppEvent::ProxyDisconnected(id)
-                                    }
-                                    mcp_common::IpcMessage::LogEntry(entry) => {
-                                        AppEvent::NewLogEntry(entry)
-                                    }
-                                    mcp_common::IpcMessage::StatsUpdate(stats) => {
-                                        AppEvent::StatsUpdate(stats)
-                                    }
-                                    _ => continue,
-                                };
-
-                                if tx.send(event).await.is_err() {
-                                    // Remove error logging
-                                    break;
-                                }
-                            }
-                            Ok(None) => {
-                                // Remove "Proxy disconnected" log
-                                break;
-                            }
-                            Err(_e) => {
-                                // Remove error logging
-                                break;
-                            }
-                        }
-                    }
-                });
+                tokio::spawn(serve_connection(
+                    connection,
+                    event_tx.clone(),
+                    hub.clone(),
+                    live_proxies.clone(),
+                    Keepalive {
+                        ping_interval,
+                        unresponsive_after,
+                    },
+                ));
             }
             Err(_e) => {
                 // Remove error logging
@@ -137,138 +284,561 @@ async fn run_ipc_server(socket_path: &str, event_tx: mpsc::Sender<AppEvent>) ->
     }
 }
 
+struct Keepalive {
+    ping_interval: Duration,
+    unresponsive_after: Duration,
+}
+
+/// Every proxy this monitor has heard from, with the outbox of each connection it has open
+type LiveProxies = Arc<std::sync::Mutex<HashMap<ProxyId, Vec<mpsc::Sender<IpcMessage>>>>>;
+
+/// Hand each command to the connections of its proxy; dropped if it has none
+async fn route_commands(mut commands: mpsc::Receiver<ProxyCommand>, live_proxies: LiveProxies) {
+    while let Some((proxy_id, message)) = commands.recv().await {
+        let outboxes = live_proxies
+            .lock()
+            .unwrap()
+            .get(&proxy_id)
+            .cloned()
+            .unwrap_or_default();
+        for outbox in outboxes {
+            let _ = outbox.send(message.clone()).await;
+        }
+    }
+}
+
+async fn serve_connection(
+    mut connection: IpcConnection,
+    tx: mpsc::Sender<AppEvent>,
+    hub: Arc<SubscriberHub>,
+    live_proxies: LiveProxies,
+    keepalive: Keepalive,
+) {
+    // Learned from the proxy's announcement, to attribute its pings
+    let mut proxy_id: Option<ProxyId> = None;
+    let mut backfill = None; // A new proxy to ask for its earlier entries
+    let mut ping_timer = interval_at(
+        Instant::now() + keepalive.ping_interval,
+        keepalive.ping_interval,
+    );
+    let mut last_heard = Instant::now();
+    let mut unresponsive = false;
+    let (outbox_tx, mut outbox) = mpsc::channel(16); // Commands for this proxy
+
+    loop {
+        let received = tokio::select! {
+            received = connection.receive_message() => received,
+            Some(message) = outbox.recv() => {
+                if connection.send_message(message).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            _ = ping_timer.tick() => {
+                let Some(ref id) = proxy_id else {
+                    continue;
+                };
+                if !unresponsive && last_heard.elapsed() >= keepalive.unresponsive_after {
+                    unresponsive = true;
+                    let _ = tx.send(AppEvent::ProxyUnresponsive(id.clone())).await;
+                }
+                if connection.send_message(IpcMessage::Ping).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+        // Anything at all shows the proxy is still there
+        last_heard = Instant::now();
+        unresponsive = false;
+
+        let envelope = match received {
+            Ok(Some(envelope)) => envelope,
+            Err(e) if e.downcast_ref() == Some(&IpcError::Unauthorized) => {
+                let entry = LogEntry::new(
+                    LogLevel::Info,
+                    "Rejected an IPC connection with a missing or wrong token".to_string(),
+                    ProxyId::monitor(),
+                );
+                let _ = tx.send(AppEvent::NewLogEntry(entry)).await;
+                break;
+            }
+            Err(e)
+                if matches!(
+                    e.downcast_ref(),
+                    Some(IpcError::MessageTooLarge { .. } | IpcError::InvalidMessage(_))
+                ) =>
+            {
+                // Skipped whole, so the connection carries on with the next message
+                tracing::warn!("Skipped an IPC message: {}", e);
+                let skipped = AppEvent::MessageSkipped {
+                    proxy_id: proxy_id.clone(),
+                    reason: e.to_string(),
+                };
+                let _ = tx.send(skipped).await;
+                continue;
+            }
+            // Remove "Proxy disconnected" and error logging
+            Ok(None) | Err(_) => break,
+        };
+        match envelope.message {
+            IpcMessage::ProxyStarted(ref info) if proxy_id.is_none() => {
+                proxy_id = Some(info.id.clone());
+                let mut live = live_proxies.lock().unwrap();
+                if !live.contains_key(&info.id) {
+                    backfill = Some(info.id.clone());
+                }
+                live.entry(info.id.clone())
+                    .or_default()
+                    .push(outbox_tx.clone());
+            }
+            IpcMessage::Ping | IpcMessage::Pong => {
+                if matches!(envelope.message, IpcMessage::Ping)
+                    && connection.send_message(IpcMessage::Pong).await.is_err()
+                {
+                    break;
+                }
+                if let Some(ref id) = proxy_id {
+                    let _ = tx.send(AppEvent::ProxyHeartbeat(id.clone())).await;
+                }
+                continue;
+            }
+            IpcMessage::Subscribe { follow } => {
+                let _ = hub.serve(connection, follow).await;
+                return;
+            }
+            IpcMessage::GetAllStats => {
+                if hub.send_stats(&mut connection).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        hub.publish(&envelope.message);
+
+        let mut closed = false;
+        for event in ipc_message_events(envelope.message) {
+            if tx.send(event).await.is_err() {
+                closed = true;
+                break;
+            }
+        }
+        if closed {
+            // Remove error logging
+            break;
+        }
+
+        // Asked once the proxy is listed, so its history has a home
+        if let Some(proxy_id) = backfill.take() {
+            let request = IpcMessage::GetLogs {
+                proxy_id,
+                limit: Some(BACKFILL_LIMIT),
+            };
+            if connection.send_message(request).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    // A proxy that went away without ProxyStopped (killed or crashed) shows as stopped
+    // once its last connection closes
+    if let Some(id) = proxy_id {
+        let closed_last = {
+            let mut live = live_proxies.lock().unwrap();
+            live.get_mut(&id).is_some_and(|outboxes| {
+                outboxes.retain(|outbox| !outbox.same_channel(&outbox_tx));
+                outboxes.is_empty()
+            })
+        };
+        if closed_last {
+            let _ = tx.send(AppEvent::ProxyDisconnected(id)).await;
+        }
+    }
+}
+
+/// App events for a message received from a proxy; a batch yields one event per entry
+pub fn ipc_message_events(message: IpcMessage) -> Vec<AppEvent> {
+    match message {
+        IpcMessage::ProxyStarted(info) => vec![AppEvent::ProxyConnected(info)],
+        IpcMessage::ProxyStopped(id) => vec![AppEvent::ProxyDisconnected(id)],
+        IpcMessage::LogEntry(entry) => vec![AppEvent::NewLogEntry(entry)],
+        IpcMessage::LogsBatch(entries) => entries.into_iter().map(AppEvent::NewLogEntry).collect(),
+        IpcMessage::StatsUpdate(stats) => vec![AppEvent::StatsUpdate(stats)],
+        IpcMessage::ProxyMetadata {
+            proxy_id,
+            protocol_version,
+        } => vec![AppEvent::ProxyMetadataUpdate {
+            proxy_id,
+            protocol_version,
+        }],
+        _ => Vec::new(),
+    }
+}
+
 async fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
-    mut app: App,
+    shared_app: Arc<RwLock<App>>,
     mut event_rx: mpsc::Receiver<AppEvent>,
+    replay: Option<mpsc::Sender<ReplayControl>>,
 ) -> Result<()> {
     let mut last_tick = std::time::Instant::now();
-    let tick_rate = Duration::from_millis(250);
+    let tick_rate = shared_app.read().await.tick_rate;
 
     loop {
-        // Draw UI
-        terminal.draw(|f| ui::draw(f, &mut app))?;
+        {
+            let mut app = shared_app.write().await;
+
+            // Pick up any detail content formatted in the background
+            app.drain_formatted_content();
+
+            // Draw UI
+            terminal.draw(|f| ui::draw(f, &mut app))?;
+        }
 
-        // Handle events
+        // Handle events (without holding the app lock while waiting for input)
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
 
-        if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    if app.show_help_dialog {
-                        // Handle help dialog keyboard events
-                        match key.code {
-                            KeyCode::Esc | KeyCode::Char('?') => app.show_help_dialog = false,
-                            _ => {}
+        let key_event = if event::poll(timeout)? {
+            Some(event::read()?)
+        } else {
+            None
+        };
+
+        let mut app = shared_app.write().await;
+
+        if let Some(Event::Key(key)) = key_event {
+            if key.kind == KeyEventKind::Press {
+                if app.show_help_dialog {
+                    // Handle help dialog keyboard events
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('?') => app.show_help_dialog = false,
+                        KeyCode::Up => app.help_scroll_up(1),
+                        KeyCode::Down => app.help_scroll_down(1),
+                        KeyCode::PageUp => app.help_scroll_up(10),
+                        KeyCode::PageDown => app.help_scroll_down(10),
+                        _ => {}
+                    }
+                } else if app.shutdown_dialog_proxy.is_some() {
+                    // Handle the proxy shutdown confirmation
+                    match key.code {
+                        KeyCode::Char('y') | KeyCode::Enter => app.confirm_proxy_shutdown(),
+                        KeyCode::Char('n') | KeyCode::Esc => app.close_shutdown_dialog(),
+                        _ => {}
+                    }
+                } else if app.show_exclusion_dialog {
+                    // Handle exclusion dialog keyboard events
+                    match key.code {
+                        KeyCode::Esc => app.close_exclusion_dialog(),
+                        KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.clear_exclusions()
                         }
-                    } else if app.show_detail_view {
-                        // Handle detail view keyboard events
-                        match key.code {
-                            KeyCode::Esc => app.hide_detail_view(),
-                            KeyCode::Char('w') | KeyCode::Char('W') => app.toggle_word_wrap(),
-                            KeyCode::Up => app.detail_scroll_up(),
-                            KeyCode::Down => app.detail_scroll_down(),
-                            KeyCode::PageUp => {
-                                for _ in 0..10 {
-                                    app.detail_scroll_up();
-                                }
-                            }
-                            KeyCode::PageDown => {
-                                for _ in 0..10 {
-                                    app.detail_scroll_down();
-                                }
-                            }
-                            KeyCode::Home => app.detail_scroll_offset = 0,
-                            KeyCode::End => app.detail_scroll_offset = 1000, // Large number to scroll to bottom
-                            _ => {}
+                        KeyCode::Char(c) => app.exclusion_input_char(c),
+                        KeyCode::Backspace => app.exclusion_backspace(),
+                        KeyCode::Enter => app.confirm_exclusion_input(),
+                        KeyCode::Up => app.exclusion_select_up(),
+                        KeyCode::Down => app.exclusion_select_down(),
+                        KeyCode::Delete => app.remove_selected_exclusion(),
+                        _ => {}
+                    }
+                } else if app.show_level_filter_dialog {
+                    // Handle level filter checkboxes
+                    match key.code {
+                        KeyCode::Esc => app.close_level_filter_dialog(),
+                        KeyCode::Up | KeyCode::Char('k') => app.level_filter_cursor_up(),
+                        KeyCode::Down | KeyCode::Char('j') => app.level_filter_cursor_down(),
+                        KeyCode::Char(' ') => app.toggle_level_filter_choice(),
+                        KeyCode::Enter => app.confirm_level_filter(),
+                        _ => {}
+                    }
+                } else if app.show_time_range_dialog {
+                    // Handle time range dialog keyboard events
+                    match key.code {
+                        KeyCode::Esc => app.close_time_range_dialog(),
+                        KeyCode::Char(c) => app.time_range_input_char(c),
+                        KeyCode::Backspace => app.time_range_backspace(),
+                        KeyCode::Enter => app.confirm_time_range_input(),
+                        _ => {}
+                    }
+                } else if app.show_annotation_dialog {
+                    // Handle annotation note input
+                    match key.code {
+                        KeyCode::Esc => app.close_annotation_dialog(),
+                        KeyCode::Char(c) => app.annotation_input_char(c),
+                        KeyCode::Backspace => app.annotation_backspace(),
+                        KeyCode::Enter => app.confirm_annotation(),
+                        _ => {}
+                    }
+                } else if app.show_diff_view {
+                    // Handle diff view keyboard events
+                    match key.code {
+                        KeyCode::Esc => app.hide_detail_view(),
+                        KeyCode::Up => app.diff_scroll_up(1),
+                        KeyCode::Down => app.diff_scroll_down(1),
+                        KeyCode::PageUp => app.diff_scroll_up(10),
+                        KeyCode::PageDown => app.diff_scroll_down(10),
+                        KeyCode::Home => app.diff_scroll_up(u16::MAX),
+                        KeyCode::End => app.diff_scroll_down(u16::MAX),
+                        _ => {}
+                    }
+                } else if app.show_detail_view && app.show_save_dialog {
+                    // Handle save-to-file prompt input
+                    match key.code {
+                        KeyCode::Esc => app.close_save_dialog(),
+                        KeyCode::Enter => app.confirm_save_dialog(),
+                        KeyCode::Char(c) => app.save_input_char(c),
+                        KeyCode::Backspace => app.save_backspace(),
+                        KeyCode::Delete => app.save_delete(),
+                        KeyCode::Left => app.save_cursor_left(),
+                        KeyCode::Right => app.save_cursor_right(),
+                        KeyCode::Home => app.save_cursor_home(),
+                        KeyCode::End => app.save_cursor_end(),
+                        _ => {}
+                    }
+                } else if app.show_detail_view && app.detail_search_active {
+                    // Handle detail view search input
+                    match key.code {
+                        KeyCode::Esc => app.exit_detail_search(),
+                        KeyCode::Enter => app.confirm_detail_search(),
+                        KeyCode::Backspace => app.detail_search_backspace(),
+                        KeyCode::Char(c) => app.detail_search_input_char(c),
+                        _ => {}
+                    }
+                } else if app.show_detail_view {
+                    // Handle detail view keyboard events
+                    let tree_shown = app.detail_tree_rows().is_some();
+                    match key.code {
+                        KeyCode::Char('t') => app.toggle_detail_tree(),
+                        KeyCode::Enter | KeyCode::Char(' ') if tree_shown => {
+                            app.detail_tree_toggle_selected()
+                        }
+                        KeyCode::Up if tree_shown => app.detail_tree_move(-1),
+                        KeyCode::Down if tree_shown => app.detail_tree_move(1),
+                        KeyCode::PageUp if tree_shown => app.detail_tree_move(-10),
+                        KeyCode::PageDown if tree_shown => app.detail_tree_move(10),
+                        KeyCode::Home if tree_shown => app.detail_tree_move(isize::MIN),
+                        KeyCode::End if tree_shown => app.detail_tree_move(isize::MAX),
+                        // Esc clears an active search before closing the view
+                        KeyCode::Esc if app.has_detail_search() => app.exit_detail_search(),
+                        KeyCode::Esc => app.hide_detail_view(),
+                        KeyCode::Char('/') => app.enter_detail_search(),
+                        KeyCode::Char('n') => app.next_detail_match(),
+                        KeyCode::Char('N') => app.prev_detail_match(),
+                        KeyCode::Char('s') => app.open_save_dialog(false),
+                        KeyCode::Char('S') => app.open_save_dialog(true),
+                        KeyCode::Char('p') => app.toggle_detail_split(),
+                        KeyCode::Tab if app.show_paired_detail => app.switch_detail_pane(),
+                        KeyCode::Tab => app.toggle_detail_split(),
+                        KeyCode::Left | KeyCode::Right if app.show_paired_detail => {
+                            app.switch_detail_pane()
                         }
-                    } else if app.navigation_mode == NavigationMode::Search {
-                        // Handle search mode keyboard events
-                        match key.code {
-                            KeyCode::Esc => app.exit_search_mode(),
-                            KeyCode::Char(c) => app.search_input_char(c),
-                            KeyCode::Backspace => app.search_backspace(),
-                            KeyCode::Delete => app.search_delete(),
-                            KeyCode::Left => app.search_cursor_left(),
-                            KeyCode::Right => app.search_cursor_right(),
-                            KeyCode::Home => app.search_cursor_home(),
-                            KeyCode::End => app.search_cursor_end(),
-                            KeyCode::Up => app.scroll_up(),
-                            KeyCode::Down => app.scroll_down(),
-                            KeyCode::PageUp => app.page_up(),
-                            KeyCode::PageDown => app.page_down(),
-                            KeyCode::Enter => {
-                                // Confirm search results and switch to navigate mode while keeping results
-                                app.confirm_search_results();
+                        KeyCode::Left => app.prev_detail_tab(),
+                        KeyCode::Right => app.next_detail_tab(),
+                        KeyCode::Char('1') => app.switch_detail_tab(DetailTab::Message),
+                        KeyCode::Char('2') => app.switch_detail_tab(DetailTab::Metadata),
+                        KeyCode::Char('3') => app.switch_detail_tab(DetailTab::Raw),
+                        KeyCode::Char('w') | KeyCode::Char('W') => app.toggle_word_wrap(),
+                        KeyCode::Char('c') | KeyCode::Char('C') => app.toggle_syntax_highlight(),
+                        KeyCode::Char('#') => app.toggle_line_numbers(),
+                        KeyCode::Char('a') => app.open_annotation_dialog(),
+                        KeyCode::Delete => app.clear_annotation(),
+                        KeyCode::Up => app.detail_scroll_up(),
+                        KeyCode::Down => app.detail_scroll_down(),
+                        KeyCode::PageUp => {
+                            for _ in 0..10 {
+                                app.detail_scroll_up();
                             }
-                            _ => {}
                         }
-                    } else {
-                        // Handle main view keyboard events
-                        match key.code {
-                            KeyCode::Char('q') => break,
-                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                break
+                        KeyCode::PageDown => {
+                            for _ in 0..10 {
+                                app.detail_scroll_down();
                             }
-                            KeyCode::Char('c') => app.clear_logs(),
-                            KeyCode::Char('r') => app.refresh(),
-                            KeyCode::Left => app.switch_focus_to_proxy_list(),
-                            KeyCode::Right => app.switch_focus_to_logs(),
-                            KeyCode::Up => match app.focus_area {
-                                FocusArea::ProxyList => app.proxy_scroll_up(),
-                                FocusArea::LogView => app.scroll_up(),
-                            },
-                            KeyCode::Down => match app.focus_area {
-                                FocusArea::ProxyList => app.proxy_scroll_down(),
-                                FocusArea::LogView => app.scroll_down(),
-                            },
-                            KeyCode::PageUp => {
-                                if app.focus_area == FocusArea::LogView {
-                                    app.page_up();
+                        }
+                        KeyCode::Home => app.detail_scroll_to_top(),
+                        KeyCode::End => app.detail_scroll_to_bottom(),
+                        _ => {}
+                    }
+                } else if app.navigation_mode == NavigationMode::Search {
+                    // Handle search mode keyboard events
+                    match key.code {
+                        KeyCode::Esc => app.exit_search_mode(),
+                        KeyCode::Char(c) => app.search_input_char(c),
+                        KeyCode::Backspace => app.search_backspace(),
+                        KeyCode::Delete => app.search_delete(),
+                        KeyCode::Left => app.search_cursor_left(),
+                        KeyCode::Right => app.search_cursor_right(),
+                        KeyCode::Home => app.search_cursor_home(),
+                        KeyCode::End => app.search_cursor_end(),
+                        KeyCode::Up if key.modifiers.contains(KeyModifiers::ALT) => {
+                            app.search_history_older()
+                        }
+                        KeyCode::Down if key.modifiers.contains(KeyModifiers::ALT) => {
+                            app.search_history_newer()
+                        }
+                        KeyCode::Up => app.scroll_up(),
+                        KeyCode::Down => app.scroll_down(),
+                        KeyCode::PageUp => app.page_up(),
+                        KeyCode::PageDown => app.page_down(),
+                        KeyCode::Enter => {
+                            // Confirm search results and switch to navigate mode while keeping results
+                            app.confirm_search_results();
+                        }
+                        KeyCode::Tab => {
+                            // Keep every log visible and hop between matches with n/N
+                            app.confirm_search_jump();
+                        }
+                        _ => {}
+                    }
+                } else {
+                    // Digits build a repeat count for the next navigation key; any
+                    // other key that doesn't use it drops it
+                    let digit = match key.code {
+                        KeyCode::Char(c) if c.is_ascii_digit() => Some(c),
+                        _ => None,
+                    };
+
+                    // Handle main view keyboard events
+                    match key.code {
+                        KeyCode::Char(c) if digit.is_some() => app.push_key_prefix(c),
+                        KeyCode::Char('q') => break,
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            break
+                        }
+                        KeyCode::Char('c') => app.clear_selected_proxy_logs(),
+                        KeyCode::Char('C') => app.clear_logs(),
+                        KeyCode::Char('z') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.undo_clear();
+                        }
+                        KeyCode::Char('r') => app.refresh(),
+                        KeyCode::Left => app.switch_focus_to_proxy_list(),
+                        KeyCode::Right => app.switch_focus_to_logs(),
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            let count = app.take_key_count();
+                            match app.focus_area {
+                                FocusArea::ProxyList => {
+                                    for _ in 0..count.min(app.proxies.len()) {
+                                        app.proxy_scroll_up();
+                                    }
                                 }
+                                FocusArea::LogView => app.scroll_up_by(count),
                             }
-                            KeyCode::PageDown => {
-                                if app.focus_area == FocusArea::LogView {
-                                    app.page_down();
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            let count = app.take_key_count();
+                            match app.focus_area {
+                                FocusArea::ProxyList => {
+                                    for _ in 0..count.min(app.proxies.len()) {
+                                        app.proxy_scroll_down();
+                                    }
                                 }
+                                FocusArea::LogView => app.scroll_down_by(count),
                             }
-                            KeyCode::Home => {
-                                if app.focus_area == FocusArea::LogView {
-                                    app.scroll_to_top();
-                                }
+                        }
+                        KeyCode::PageUp if app.focus_area == FocusArea::LogView => {
+                            app.page_up();
+                        }
+                        KeyCode::PageDown if app.focus_area == FocusArea::LogView => {
+                            app.page_down();
+                        }
+                        KeyCode::Home | KeyCode::Char('g')
+                            if app.focus_area == FocusArea::LogView =>
+                        {
+                            app.scroll_to_top();
+                        }
+                        KeyCode::End if app.focus_area == FocusArea::LogView => {
+                            app.scroll_to_bottom();
+                        }
+                        KeyCode::Char('G') if app.focus_area == FocusArea::LogView => {
+                            // With a count, G jumps to that entry as in vim
+                            if app.key_prefix.is_empty() {
+                                app.scroll_to_bottom();
+                            } else {
+                                let line = app.take_key_count();
+                                app.scroll_to_line(line);
                             }
-                            KeyCode::End => {
-                                if app.focus_area == FocusArea::LogView {
-                                    app.scroll_to_bottom();
+                        }
+                        KeyCode::Esc => match app.focus_area {
+                            FocusArea::ProxyList => app.clear_proxy_selection(),
+                            FocusArea::LogView => app.exit_navigation_mode(),
+                        },
+                        KeyCode::Tab => app.next_tab(),
+                        KeyCode::BackTab => app.prev_tab(),
+                        KeyCode::Char('b') if app.focus_area == FocusArea::LogView => {
+                            app.toggle_bookmark();
+                        }
+                        KeyCode::Char('v') if app.focus_area == FocusArea::LogView => {
+                            app.toggle_second_selection();
+                        }
+                        KeyCode::Char('a') if app.focus_area == FocusArea::LogView => {
+                            app.open_annotation_dialog();
+                        }
+                        KeyCode::Char('/') if app.focus_area == FocusArea::LogView => {
+                            app.enter_search_mode();
+                        }
+                        KeyCode::Char('n') if app.navigation_mode == NavigationMode::SearchJump => {
+                            app.next_search_match();
+                        }
+                        KeyCode::Char('N') if app.navigation_mode == NavigationMode::SearchJump => {
+                            app.prev_search_match();
+                        }
+                        KeyCode::Char('p') if app.focus_area == FocusArea::LogView => {
+                            app.jump_to_paired_message();
+                        }
+                        KeyCode::Enter => match app.focus_area {
+                            FocusArea::ProxyList => app.select_current_proxy(),
+                            FocusArea::LogView => {
+                                app.select_log_at_cursor();
+                                if app.second_selection.is_some()
+                                    && app.second_selection != app.selected_log_index
+                                {
+                                    app.show_diff_detail();
+                                } else {
+                                    app.show_selected_log_detail();
                                 }
                             }
-                            KeyCode::Esc => match app.focus_area {
-                                FocusArea::ProxyList => app.clear_proxy_selection(),
-                                FocusArea::LogView => app.exit_navigation_mode(),
-                            },
-                            KeyCode::Tab => app.next_tab(),
-                            KeyCode::BackTab => app.prev_tab(),
-                            KeyCode::Char('1') => app.switch_tab(TabType::All),
-                            KeyCode::Char('2') => app.switch_tab(TabType::Messages),
-                            KeyCode::Char('3') => app.switch_tab(TabType::Errors),
-                            KeyCode::Char('4') => app.switch_tab(TabType::System),
-                            KeyCode::Char('/') => {
-                                if app.focus_area == FocusArea::LogView {
-                                    app.enter_search_mode();
-                                }
+                        },
+                        KeyCode::Char('d') if app.focus_area == FocusArea::ProxyList => {
+                            app.remove_stopped_proxy();
+                        }
+                        KeyCode::Char('K') if app.focus_area == FocusArea::ProxyList => {
+                            app.open_shutdown_dialog();
+                        }
+                        KeyCode::Char('?') => app.open_help_dialog(),
+                        KeyCode::Char('x') => app.open_exclusion_dialog(),
+                        KeyCode::Char('t') => app.open_time_range_dialog(),
+                        KeyCode::Char('l') => app.open_level_filter_dialog(),
+                        KeyCode::Char('P') => app.toggle_proxy_panel(),
+                        KeyCode::Char(c @ (' ' | '+' | '-')) if replay.is_some() => {
+                            if let (Some(replay), Some(progress)) = (&replay, &app.replay_progress)
+                            {
+                                let control = match c {
+                                    ' ' if progress.paused => ReplayControl::Resume,
+                                    ' ' => ReplayControl::Pause,
+                                    '+' => ReplayControl::SetSpeed(step_replay_speed(
+                                        progress.speed,
+                                        true,
+                                    )),
+                                    _ => ReplayControl::SetSpeed(step_replay_speed(
+                                        progress.speed,
+                                        false,
+                                    )),
+                                };
+                                // Never wait on the replay task while holding the app
+                                let _ = replay.try_send(control);
                             }
-                            KeyCode::Enter => match app.focus_area {
-                                FocusArea::ProxyList => app.select_current_proxy(),
-                                FocusArea::LogView => {
-                                    app.select_log_at_cursor();
-                                    app.show_selected_log_detail();
-                                }
-                            },
-                            KeyCode::Char('?') => app.show_help_dialog = true,
-                            _ => {}
                         }
+                        KeyCode::Char(' ') => app.toggle_pause(),
+                        KeyCode::Char('T') => app.cycle_timestamp_mode(),
+                        KeyCode::Char('F') => app.toggle_follow_proxy_on_connect(),
+                        KeyCode::Char('E') => app.toggle_error_responses_in_errors_tab(),
+                        KeyCode::Char('L') => app.fetch_selected_proxy_logs(),
+                        _ => {}
+                    }
+                    if digit.is_none() {
+                        app.clear_key_prefix();
                     }
                 }
             }
@@ -281,6 +851,7 @@ async fn run_app<B: Backend>(
 
         // Tick
         if last_tick.elapsed() >= tick_rate {
+            app.last_tick_duration = last_tick.elapsed();
             app.tick();
             last_tick = std::time::Instant::now();
         }
@@ -6,13 +6,42 @@ use mcp_monitor::{run_monitor_app, MonitorArgs};
 #[command(name = "mcp-monitor")]
 #[command(about = "Monitor for MCP proxy servers")]
 pub struct Args {
-    /// IPC socket path for proxy communication
-    #[arg(short, long, default_value = "/tmp/mcp-monitor.sock")]
+    /// IPC socket path for proxy communication (or tcp://HOST:PORT)
+    #[arg(short, long, default_value = mcp_common::DEFAULT_IPC_SOCKET)]
     pub ipc_socket: String,
 
+    /// Shared token for a TCP IPC socket; proxies and clients must send the same one
+    #[arg(
+        long,
+        env = "MCP_TRACE_IPC_TOKEN",
+        value_name = "TOKEN",
+        hide_env_values = true
+    )]
+    pub ipc_token: Option<String>,
+
     /// Verbose logging
     #[arg(short, long)]
     pub verbose: bool,
+
+    /// Hide messages whose JSON-RPC method contains this text (repeatable)
+    #[arg(short = 'x', long)]
+    pub exclude: Vec<String>,
+
+    /// Serve Prometheus metrics on http://127.0.0.1:PORT/metrics
+    #[arg(long, value_name = "PORT")]
+    pub metrics_port: Option<u16>,
+
+    /// Log entries to keep before dropping the oldest (0 for no limit)
+    #[arg(long, value_name = "N", default_value_t = mcp_monitor::DEFAULT_MAX_LOGS)]
+    pub max_logs: usize,
+
+    /// Proxy connections to accept at once before rejecting new ones (0 for no limit)
+    #[arg(long, value_name = "N", default_value_t = mcp_monitor::DEFAULT_MAX_CONNECTIONS)]
+    pub max_connections: usize,
+
+    /// Milliseconds between UI redraws (default 250, or `tick_rate_ms` in the config file)
+    #[arg(long, value_name = "MS")]
+    pub tick_rate_ms: Option<u64>,
 }
 
 #[tokio::main]
@@ -21,7 +50,13 @@ async fn main() -> Result<()> {
 
     let monitor_args = MonitorArgs {
         ipc_socket: args.ipc_socket,
+        ipc_token: args.ipc_token,
         verbose: args.verbose,
+        exclude: args.exclude,
+        metrics_port: args.metrics_port,
+        max_logs: args.max_logs,
+        max_connections: args.max_connections,
+        tick_rate_ms: args.tick_rate_ms,
     };
 
     run_monitor_app(monitor_args).await
@@ -0,0 +1,61 @@
+use crate::app::App;
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+
+/// Largest request head we read before answering
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Answer `GET /metrics` with `App::prometheus_snapshot` until the listener fails
+pub async fn serve_metrics(listener: TcpListener, app: Arc<RwLock<App>>) -> Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let app = app.clone();
+        tokio::spawn(async move {
+            let _ = handle_connection(stream, app).await;
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, app: Arc<RwLock<App>>) -> Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_SIZE {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next().unwrap_or("").split_whitespace();
+    let (method, path) = (request_line.next(), request_line.next());
+
+    let (status, content_type, body) = match (method, path) {
+        (Some("GET"), Some("/metrics")) => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            app.read().await.prometheus_snapshot(),
+        ),
+        (Some("GET"), _) => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method Not Allowed\n".to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
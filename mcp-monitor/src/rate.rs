@@ -0,0 +1,47 @@
+use std::collections::VecDeque;
+
+/// Seconds of request counts kept, more than the widest stats panel shows
+pub const RATE_HISTORY_SECONDS: usize = 300;
+
+/// Requests counted per second, for the stats panel's sparklines
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateHistory {
+    seconds: VecDeque<u64>, // Completed seconds, oldest first
+    current: u64,           // Requests in the second still under way
+}
+
+impl RateHistory {
+    pub fn record(&mut self) {
+        self.current += 1;
+    }
+
+    /// Close the second under way, and `elapsed - 1` seconds without requests after it
+    pub fn advance(&mut self, elapsed: u64) {
+        if elapsed == 0 {
+            return;
+        }
+        self.seconds.push_back(std::mem::take(&mut self.current));
+        let quiet = (elapsed - 1).min(RATE_HISTORY_SECONDS as u64) as usize;
+        self.seconds.resize(self.seconds.len() + quiet, 0);
+        let excess = self.seconds.len().saturating_sub(RATE_HISTORY_SECONDS);
+        self.seconds.drain(..excess);
+    }
+
+    /// The last `width` completed seconds, oldest first, with zeros before the history
+    /// starts so the newest second is always at the right
+    pub fn recent(&self, width: usize) -> Vec<u64> {
+        let kept = self.seconds.len().min(width);
+        let mut counts = vec![0; width - kept];
+        counts.extend(self.seconds.range(self.seconds.len() - kept..));
+        counts
+    }
+
+    /// Average requests per second over the last `window` completed seconds
+    pub fn per_second(&self, window: usize) -> f64 {
+        if window == 0 {
+            return 0.0;
+        }
+        let total: u64 = self.seconds.iter().rev().take(window).sum();
+        total as f64 / window as f64
+    }
+}
@@ -0,0 +1,126 @@
+use crate::app::AppEvent;
+use crate::ipc_message_events;
+use anyhow::{Context, Result};
+use mcp_common::IpcEnvelope;
+use std::io::BufRead;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Slowest and fastest replay speed factors
+pub const MIN_REPLAY_SPEED: f64 = 0.1;
+pub const MAX_REPLAY_SPEED: f64 = 100.0;
+
+/// Commands from the UI to a running replay
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayControl {
+    Pause,
+    Resume,
+    SetSpeed(f64),
+    Stop,
+}
+
+/// Where a replay is, shown in the log view title
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayProgress {
+    pub current_index: usize, // Envelopes replayed so far
+    pub total_entries: usize,
+    pub speed: f64,
+    pub paused: bool,
+}
+
+/// Read an NDJSON recording of IPC envelopes, skipping blank lines
+pub fn load_recording<R: BufRead>(reader: R) -> Result<Vec<IpcEnvelope>> {
+    let mut envelopes = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let envelope = serde_json::from_str(&line)
+            .with_context(|| format!("line {} is not an IPC envelope", index + 1))?;
+        envelopes.push(envelope);
+    }
+    Ok(envelopes)
+}
+
+/// Send the recorded envelopes to the app as events, spaced like they were recorded
+/// divided by the speed factor, until the end or a `Stop`.
+pub async fn run_replay(
+    envelopes: Vec<IpcEnvelope>,
+    speed: f64,
+    event_tx: mpsc::Sender<AppEvent>,
+    mut control_rx: mpsc::Receiver<ReplayControl>,
+) {
+    let mut progress = ReplayProgress {
+        current_index: 0,
+        total_entries: envelopes.len(),
+        speed: speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED),
+        paused: false,
+    };
+    if event_tx
+        .send(AppEvent::ReplayProgress(progress.clone()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let mut previous: Option<chrono::DateTime<chrono::Utc>> = None;
+    for envelope in envelopes {
+        // Time to the previous envelope at 1x, shrunk or stretched by the speed
+        let gap = previous
+            .and_then(|previous| (envelope.timestamp - previous).to_std().ok())
+            .unwrap_or_default();
+        previous = Some(envelope.timestamp);
+        let mut wait = gap.div_f64(progress.speed);
+
+        loop {
+            let control = if progress.paused {
+                control_rx.recv().await
+            } else {
+                let started = Instant::now();
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => break,
+                    control = control_rx.recv() => {
+                        wait = wait.saturating_sub(started.elapsed());
+                        control
+                    }
+                }
+            };
+
+            match control {
+                Some(ReplayControl::Pause) => progress.paused = true,
+                Some(ReplayControl::Resume) => progress.paused = false,
+                Some(ReplayControl::SetSpeed(speed)) => {
+                    let speed = speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED);
+                    wait = wait.mul_f64(progress.speed / speed);
+                    progress.speed = speed;
+                }
+                // The UI is gone once the control channel closes
+                Some(ReplayControl::Stop) | None => return,
+            }
+            if event_tx
+                .send(AppEvent::ReplayProgress(progress.clone()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+
+        let mut events = ipc_message_events(envelope.message);
+        progress.current_index += 1;
+        events.push(AppEvent::ReplayProgress(progress.clone()));
+        for event in events {
+            if event_tx.send(event).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Speed factor one step faster (`faster`) or slower than `speed`, within range
+pub fn step_replay_speed(speed: f64, faster: bool) -> f64 {
+    let speed = if faster { speed * 2.0 } else { speed / 2.0 };
+    speed.clamp(MIN_REPLAY_SPEED, MAX_REPLAY_SPEED)
+}
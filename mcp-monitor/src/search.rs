@@ -0,0 +1,252 @@
+use crate::app::parse_message_json;
+use mcp_common::LogEntry;
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+
+/// A parsed search query: whitespace-separated terms that must all match
+///
+/// `level:error proxy:github method:tools/call timeout` keeps error logs from proxies
+/// named like "github" whose JSON `method` is `tools/call` and that mention "timeout".
+/// Values containing spaces can be quoted (`params.name:"my tool"`), and `/pattern/`
+/// matches a case-insensitive regular expression.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchQuery {
+    pub terms: Vec<SearchSyntax>,
+}
+
+/// How a single search term is matched against a log entry
+#[derive(Debug, Clone)]
+pub enum SearchSyntax {
+    /// Case-insensitive substring of the message, proxy name or level
+    Text(String),
+    /// `level:` prefix of the log level name (`level:warn`)
+    Level(String),
+    /// `proxy:` substring of the proxy name
+    Proxy(String),
+    /// `field:value` against a JSON message, with dot-separated paths (`params.name:x`)
+    FieldPath { field: String, value: String },
+    /// `/pattern/`, a case-insensitive regular expression over the message, proxy name
+    /// or level
+    Regex(Regex),
+}
+
+// Compiled patterns are equal when their sources are
+impl PartialEq for SearchSyntax {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SearchSyntax::Text(a), SearchSyntax::Text(b))
+            | (SearchSyntax::Level(a), SearchSyntax::Level(b))
+            | (SearchSyntax::Proxy(a), SearchSyntax::Proxy(b)) => a == b,
+            (
+                SearchSyntax::FieldPath { field, value },
+                SearchSyntax::FieldPath {
+                    field: other_field,
+                    value: other_value,
+                },
+            ) => field == other_field && value == other_value,
+            (SearchSyntax::Regex(a), SearchSyntax::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for SearchSyntax {}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Self {
+        Self {
+            terms: tokenize(query)
+                .iter()
+                .map(|term| SearchSyntax::parse(term))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, log: &LogEntry, proxy_name: Option<&str>) -> bool {
+        self.terms.iter().all(|term| term.matches(log, proxy_name))
+    }
+
+    /// Byte ranges of `text` worth highlighting: literal terms and field values matched
+    /// case-insensitively, and regex matches; sorted, with overlaps dropped
+    pub fn match_ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for term in &self.terms {
+            match term {
+                SearchSyntax::Text(literal) => ranges.extend(find_match_ranges(text, literal)),
+                SearchSyntax::FieldPath { value, .. } => {
+                    ranges.extend(find_match_ranges(text, value))
+                }
+                SearchSyntax::Regex(regex) => ranges.extend(
+                    regex
+                        .find_iter(text)
+                        .filter(|found| !found.is_empty())
+                        .map(|found| (found.start(), found.end())),
+                ),
+                SearchSyntax::Level(_) | SearchSyntax::Proxy(_) => {}
+            }
+        }
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last() {
+                Some(&(_, last_end)) if start < last_end => {}
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+    }
+}
+
+impl SearchSyntax {
+    pub fn parse(term: &str) -> Self {
+        // A pattern that doesn't compile is searched for as written
+        if let Some(pattern) = term
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+            .filter(|pattern| !pattern.is_empty())
+        {
+            if let Ok(regex) = RegexBuilder::new(pattern).case_insensitive(true).build() {
+                return SearchSyntax::Regex(regex);
+            }
+        }
+
+        if let Some((field, value)) = term.split_once(':') {
+            let is_path = !field.is_empty()
+                && field
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+            if is_path {
+                return match field.to_lowercase().as_str() {
+                    "level" => SearchSyntax::Level(value.to_string()),
+                    "proxy" => SearchSyntax::Proxy(value.to_string()),
+                    _ => SearchSyntax::FieldPath {
+                        field: field.to_string(),
+                        value: value.to_string(),
+                    },
+                };
+            }
+        }
+
+        SearchSyntax::Text(term.to_string())
+    }
+
+    pub fn matches(&self, log: &LogEntry, proxy_name: Option<&str>) -> bool {
+        match self {
+            SearchSyntax::Text(text) => {
+                self.matches_message(&log.message)
+                    || proxy_name.is_some_and(|name| contains_ignore_case(name, text))
+                    || contains_ignore_case(&log.level.to_string(), text)
+            }
+            SearchSyntax::Level(level) => log.level.to_string().starts_with(&level.to_lowercase()),
+            SearchSyntax::Proxy(name) => {
+                proxy_name.is_some_and(|proxy_name| contains_ignore_case(proxy_name, name))
+            }
+            SearchSyntax::FieldPath { .. } => self.matches_message(&log.message),
+            SearchSyntax::Regex(regex) => {
+                regex.is_match(&log.message)
+                    || proxy_name.is_some_and(|name| regex.is_match(name))
+                    || regex.is_match(&log.level.to_string())
+            }
+        }
+    }
+
+    /// Whether `message` matches. Field paths apply to JSON messages that have the
+    /// field; anything else falls back to a literal match on the whole term.
+    pub fn matches_message(&self, message: &str) -> bool {
+        match self {
+            SearchSyntax::Text(text) => contains_ignore_case(message, text),
+            SearchSyntax::Level(value) => {
+                contains_ignore_case(message, &format!("level:{}", value))
+            }
+            SearchSyntax::Proxy(value) => {
+                contains_ignore_case(message, &format!("proxy:{}", value))
+            }
+            SearchSyntax::FieldPath { field, value } => {
+                let pointer = format!("/{}", field.replace('.', "/"));
+                match parse_message_json(message)
+                    .as_ref()
+                    .and_then(|json| json.pointer(&pointer))
+                {
+                    Some(actual) => value_matches(actual, value),
+                    None => contains_ignore_case(message, &format!("{}:{}", field, value)),
+                }
+            }
+            SearchSyntax::Regex(regex) => regex.is_match(message),
+        }
+    }
+}
+
+/// Byte ranges of every non-overlapping, case-insensitive occurrence of `query` in `text`
+pub(crate) fn find_match_ranges(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let query: Vec<char> = query.chars().collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i + query.len() <= chars.len() {
+        let matched = query
+            .iter()
+            .zip(&chars[i..])
+            .all(|(q, (_, c))| c.to_lowercase().eq(q.to_lowercase()));
+
+        if matched {
+            let start = chars[i].0;
+            let end = chars
+                .get(i + query.len())
+                .map_or(text.len(), |(byte, _)| *byte);
+            ranges.push((start, end));
+            i += query.len();
+        } else {
+            i += 1;
+        }
+    }
+
+    ranges
+}
+
+/// Split on whitespace, keeping double-quoted sections (quotes removed) together
+fn tokenize(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    terms.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+
+    terms
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+fn value_matches(actual: &Value, expected: &str) -> bool {
+    match actual {
+        Value::String(actual) => actual == expected,
+        // Numbers, booleans and null compare as JSON (`id:1`, `result.isError:true`)
+        Value::Number(_) | Value::Bool(_) | Value::Null => {
+            serde_json::from_str::<Value>(expected).is_ok_and(|expected| &expected == actual)
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use mcp_common::{IpcConnection, IpcMessage, LogEntry, ProxyId, ProxyInfo, ProxyStatus};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// Number of recent log entries replayed to a new subscriber
+const SUBSCRIBER_HISTORY: usize = 1000;
+
+#[derive(Default)]
+struct HubState {
+    proxies: HashMap<ProxyId, ProxyInfo>,
+    history: VecDeque<LogEntry>,
+}
+
+/// Fans proxy traffic out to `IpcMessage::Subscribe` connections (e.g. `mcp-trace dump`)
+pub struct SubscriberHub {
+    state: Mutex<HubState>,
+    tx: broadcast::Sender<IpcMessage>,
+}
+
+impl SubscriberHub {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(1024);
+        Self {
+            state: Mutex::new(HubState::default()),
+            tx,
+        }
+    }
+
+    /// Record a message received from a proxy and forward it to live subscribers
+    pub fn publish(&self, message: &IpcMessage) {
+        // Subscribers see batched entries one by one, as older proxies send them
+        if let IpcMessage::LogsBatch(entries) = message {
+            for entry in entries {
+                self.publish(&IpcMessage::LogEntry(entry.clone()));
+            }
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        match message {
+            IpcMessage::ProxyStarted(info) => {
+                state.proxies.insert(info.id.clone(), info.clone());
+            }
+            IpcMessage::ProxyStopped(id) => {
+                if let Some(info) = state.proxies.get_mut(id) {
+                    info.status = ProxyStatus::Stopped;
+                }
+            }
+            IpcMessage::StatsUpdate(stats) => {
+                if let Some(info) = state.proxies.get_mut(&stats.proxy_id) {
+                    info.stats = stats.clone();
+                }
+            }
+            IpcMessage::ProxyMetadata {
+                proxy_id,
+                protocol_version,
+            } => {
+                if let Some(info) = state.proxies.get_mut(proxy_id) {
+                    info.protocol_version = Some(protocol_version.clone());
+                }
+            }
+            IpcMessage::LogEntry(entry) => {
+                state.history.push_back(entry.clone());
+                if state.history.len() > SUBSCRIBER_HISTORY {
+                    state.history.pop_front();
+                }
+            }
+            _ => {}
+        }
+
+        // Sending while holding the lock keeps snapshots and live traffic in order
+        let _ = self.tx.send(message.clone());
+    }
+
+    /// Replay known proxies and recent history, then stream live traffic if `follow` is set
+    pub async fn serve(&self, mut connection: IpcConnection, follow: bool) -> Result<()> {
+        let (snapshot, mut rx) = {
+            let state = self.state.lock().unwrap();
+            let mut snapshot: Vec<IpcMessage> = state
+                .proxies
+                .values()
+                .cloned()
+                .map(IpcMessage::ProxyStarted)
+                .collect();
+            snapshot.extend(state.history.iter().cloned().map(IpcMessage::LogEntry));
+            (snapshot, self.tx.subscribe())
+        };
+
+        for message in snapshot {
+            connection.send_message(message).await?;
+        }
+
+        if !follow {
+            return Ok(());
+        }
+
+        loop {
+            match rx.recv().await {
+                Ok(message) => connection.send_message(message).await?,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    /// Answer `IpcMessage::GetAllStats` with every known proxy and its latest stats
+    pub async fn send_stats(&self, connection: &mut IpcConnection) -> Result<()> {
+        let proxies: Vec<ProxyInfo> = {
+            let state = self.state.lock().unwrap();
+            state.proxies.values().cloned().collect()
+        };
+
+        let stats = proxies.iter().map(|info| info.stats.clone()).collect();
+        for info in proxies {
+            connection
+                .send_message(IpcMessage::ProxyStarted(info))
+                .await?;
+        }
+        connection
+            .send_message(IpcMessage::AllStatsResponse(stats))
+            .await
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+
+/// A window of log timestamps, resolved against the time it was entered
+///
+/// Accepted input:
+/// - `5m`, `30s`, `2h`: the last N units up to now
+/// - `12:03` or `12:03:15`: from that (UTC, as displayed) time to now
+/// - `12:03-12:05`, `12:03 to now`, `10m..5m`: explicit start and end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>, // Exclusive; `None` keeps the range open to new logs
+    pub label: String,
+}
+
+impl TimeRange {
+    pub fn parse(input: &str, now: DateTime<Utc>) -> Result<Self, String> {
+        let input = input.trim();
+        let input = input.strip_prefix("from ").unwrap_or(input).trim();
+        if input.is_empty() {
+            return Err("Enter a range like 5m, 12:03 or 12:03-12:05".to_string());
+        }
+
+        let (from, to) = split_bounds(input);
+        let start = parse_bound(from, now)?.map(|(start, _)| start);
+        let end = match to {
+            Some(to) => parse_bound(to, now)?.map(|(end, precision)| end + precision),
+            None => None,
+        };
+
+        if let (Some(start), Some(end)) = (start, end) {
+            if end <= start {
+                return Err(format!("Range ends before it starts: {}", input));
+            }
+        }
+
+        let label = match (from, to) {
+            (from, Some(to)) => format!("{} → {}", from, to),
+            (from, None) if is_duration(from) => format!("last {}", from),
+            (from, None) => format!("{} → now", from),
+        };
+
+        Ok(Self { start, end, label })
+    }
+
+    pub fn contains(&self, timestamp: &DateTime<Utc>) -> bool {
+        !matches!(self.start, Some(start) if *timestamp < start)
+            && !matches!(self.end, Some(end) if *timestamp >= end)
+    }
+}
+
+fn split_bounds(input: &str) -> (&str, Option<&str>) {
+    for separator in [" to ", "..", "-"] {
+        if let Some((from, to)) = input.split_once(separator) {
+            return (from.trim(), Some(to.trim()));
+        }
+    }
+    (input, None)
+}
+
+fn is_duration(bound: &str) -> bool {
+    bound.ends_with(['s', 'm', 'h']) && !bound.contains(':')
+}
+
+/// Resolve one side of a range to a timestamp and the precision it was given at,
+/// or `None` for the open-ended `now`
+fn parse_bound(
+    bound: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<(DateTime<Utc>, Duration)>, String> {
+    if bound.eq_ignore_ascii_case("now") {
+        return Ok(None);
+    }
+
+    if is_duration(bound) {
+        let (amount, unit) = bound.split_at(bound.len() - 1);
+        let amount: i64 = amount
+            .parse()
+            .map_err(|_| format!("Invalid duration: {}", bound))?;
+        let ago = match unit {
+            "s" => Duration::try_seconds(amount),
+            "m" => Duration::try_minutes(amount),
+            _ => Duration::try_hours(amount),
+        };
+        let start = ago
+            .and_then(|ago| now.checked_sub_signed(ago))
+            .ok_or_else(|| format!("Time range too large: {}", bound))?;
+        return Ok(Some((start, Duration::zero())));
+    }
+
+    let (time, precision) = if let Ok(time) = NaiveTime::parse_from_str(bound, "%H:%M:%S") {
+        (time, Duration::seconds(1))
+    } else if let Ok(time) = NaiveTime::parse_from_str(bound, "%H:%M") {
+        (time, Duration::minutes(1))
+    } else {
+        return Err(format!(
+            "Invalid time: {} (use 5m, 30s or HH:MM[:SS])",
+            bound
+        ));
+    };
+
+    // A clock time later than now refers to yesterday
+    let mut timestamp = now.date_naive().and_time(time).and_utc();
+    if timestamp > now {
+        timestamp -= Duration::days(1);
+    }
+    Ok(Some((timestamp, precision)))
+}
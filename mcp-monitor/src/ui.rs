@@ -1,25 +1,40 @@
-use mcp_common::{LogLevel, ProxyStatus};
+use chrono::{DateTime, Utc};
+use mcp_common::{LogEntry, LogLevel, ProxyId, ProxyStats, ProxyStatus};
 use ratatui::{
     prelude::*,
     symbols::border,
     widgets::{block::Title, *},
 };
 
-use crate::app::{App, FocusArea, NavigationMode, TabType};
+use crate::app::{
+    line_number_gutter_width, App, DetailPane, DetailTab, FocusArea, NavigationMode, TabType,
+    TimestampMode, LEVEL_FILTER_CHOICES,
+};
+use crate::diff::DiffLine;
+use crate::json_tree::{JsonTreeRow, JsonTreeValue};
+use crate::search::{find_match_ranges, SearchQuery};
+use std::borrow::Cow;
+
+/// Columns at the right of a log row kept for its latency, e.g. ` [150MS]`
+const LATENCY_COLUMN_WIDTH: usize = 8;
+
+/// Seconds the filtered proxy's request rate is averaged over
+const PROXY_RATE_SECONDS: usize = 10;
 
 pub fn draw(f: &mut Frame, app: &mut App) {
     let size = f.size();
 
-    // Create main layout
+    // Create main layout, collapsing the left panel when hidden
+    let left_width = if app.proxy_panel_visible { 30 } else { 0 };
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Length(30), Constraint::Min(0)])
+        .constraints([Constraint::Length(left_width), Constraint::Min(0)])
         .split(size);
 
     // Left panel: Proxy list and stats
     let left_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(0), Constraint::Length(8)])
+        .constraints([Constraint::Min(0), Constraint::Length(stats_height(app))])
         .split(chunks[0]);
 
     // Right panel: Tabs, Logs, Help
@@ -32,11 +47,13 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         ])
         .split(chunks[1]);
 
-    // Draw proxy list
-    draw_proxy_list(f, app, left_chunks[0]);
+    if app.proxy_panel_visible {
+        // Draw proxy list
+        draw_proxy_list(f, app, left_chunks[0]);
 
-    // Draw stats
-    draw_stats(f, app, left_chunks[1]);
+        // Draw stats
+        draw_stats(f, app, left_chunks[1]);
+    }
 
     // Draw tabs
     draw_tabs(f, app, right_chunks[0]);
@@ -52,15 +69,68 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         draw_detail_view(f, app, size);
     }
 
+    // Draw save prompt over the detail view
+    if app.show_detail_view && app.show_save_dialog {
+        draw_save_dialog(f, app, size);
+    }
+
+    // Draw annotation editor over the log list or the detail view
+    if app.show_annotation_dialog {
+        draw_annotation_dialog(f, app, size);
+    }
+
     // Draw search dialog overlay if in search mode
     if app.navigation_mode == NavigationMode::Search {
         draw_search_dialog(f, app, size);
     }
 
+    // Draw exclusion dialog overlay if active
+    if app.show_exclusion_dialog {
+        draw_exclusion_dialog(f, app, size);
+    }
+
+    // Draw time range dialog overlay if active
+    if app.show_time_range_dialog {
+        draw_time_range_dialog(f, app, size);
+    }
+
+    if app.show_level_filter_dialog {
+        draw_level_filter_dialog(f, app, size);
+    }
+
+    if app.shutdown_dialog_proxy.is_some() {
+        draw_shutdown_dialog(f, app, size);
+    }
+
     // Draw help dialog overlay if active
     if app.show_help_dialog {
         draw_help_dialog(f, app, size);
     }
+
+    if app.show_debug_overlay {
+        draw_debug_overlay(f, app, size);
+    }
+}
+
+/// Configured and measured tick interval, in the top right corner
+fn draw_debug_overlay(f: &mut Frame, app: &App, area: Rect) {
+    let text = format!(
+        " tick {}ms, last {}ms ",
+        app.tick_rate.as_millis(),
+        app.last_tick_duration.as_millis()
+    );
+    let width = (text.chars().count() as u16).min(area.width);
+    let corner = Rect::new(
+        area.right().saturating_sub(width),
+        area.y,
+        width,
+        1.min(area.height),
+    );
+    f.render_widget(Clear, corner);
+    f.render_widget(
+        Paragraph::new(text).style(Style::default().fg(Color::Black).bg(Color::Yellow)),
+        corner,
+    );
 }
 
 fn draw_proxy_list(f: &mut Frame, app: &App, area: Rect) {
@@ -68,8 +138,7 @@ fn draw_proxy_list(f: &mut Frame, app: &App, area: Rect) {
 
     let items: Vec<ListItem> = proxies
         .iter()
-        .enumerate()
-        .map(|(_index, proxy)| {
+        .map(|proxy| {
             let status_symbol = match proxy.status {
                 ProxyStatus::Running => "🟢",
                 ProxyStatus::Starting => "🟡",
@@ -84,18 +153,62 @@ fn draw_proxy_list(f: &mut Frame, app: &App, area: Rect) {
                 ""
             };
 
+            let stale = app.is_proxy_stale(&proxy.id);
+            let stale_indicator = match &proxy.status {
+                ProxyStatus::Error(reason) => format!(" ⚠ {}", reason),
+                _ if stale => " ⚠ stale".to_string(),
+                _ => String::new(),
+            };
+
+            // Stopped proxies keep their last report, which no longer grows
+            let uptime_indicator = match proxy.status {
+                ProxyStatus::Stopped => String::new(),
+                _ => format!(" {}", format_uptime(proxy.stats.uptime)),
+            };
+
+            let restart_indicator = match proxy.stats.restarts {
+                0 => String::new(),
+                restarts => format!(" ↻{}", restarts),
+            };
+
+            // Reported by a proxy that lost its monitor connection meanwhile
+            let monitor_indicator = if proxy.monitor_connected {
+                ""
+            } else {
+                " [no monitor]"
+            };
+
             let text = format!(
-                "{} {} ({}){}",
-                status_symbol, proxy.name, proxy.stats.total_requests, filter_indicator
+                "{} {} [{}] ({}){}{}{}{}{}",
+                status_symbol,
+                proxy.name,
+                proxy.id.short(),
+                proxy.stats.total_requests,
+                uptime_indicator,
+                restart_indicator,
+                monitor_indicator,
+                stale_indicator,
+                filter_indicator
             );
+            let mut spans = vec![Span::raw(text)];
+            if let Some(ref version) = proxy.protocol_version {
+                spans.push(Span::styled(
+                    format!(" (v{})", version),
+                    Style::default().add_modifier(Modifier::DIM),
+                ));
+            }
+            let text = Line::from(spans);
 
-            // Highlight the filtered proxy
+            // Highlight the filtered proxy, and grey out stopped and silent ones
             if app.selected_proxy.as_ref() == Some(&proxy.id) {
                 ListItem::new(text).style(
                     Style::default()
                         .fg(Color::Yellow)
                         .add_modifier(Modifier::BOLD),
                 )
+            } else if stale || !matches!(proxy.status, ProxyStatus::Running | ProxyStatus::Starting)
+            {
+                ListItem::new(text).style(Style::default().fg(Color::DarkGray))
             } else {
                 ListItem::new(text)
             }
@@ -103,10 +216,13 @@ fn draw_proxy_list(f: &mut Frame, app: &App, area: Rect) {
         .collect();
 
     // Create focus indicator for the title - keep it shorter
-    let (title_text, title_color) = match app.focus_area {
-        FocusArea::ProxyList => ("Proxies *", Color::Green),
-        FocusArea::LogView => ("Proxies", Color::Gray),
+    let (mut title_text, title_color) = match app.focus_area {
+        FocusArea::ProxyList => ("Proxies *".to_string(), Color::Green),
+        FocusArea::LogView => ("Proxies".to_string(), Color::Gray),
     };
+    if app.follow_proxy_on_connect {
+        title_text.push_str(" [AUTO-FOLLOW]");
+    }
 
     // Add concise instructions for the narrow panel
     let instructions = if app.focus_area == FocusArea::ProxyList {
@@ -148,84 +264,151 @@ fn draw_proxy_list(f: &mut Frame, app: &App, area: Rect) {
     f.render_stateful_widget(list, area, &mut state);
 }
 
-fn draw_stats(f: &mut Frame, app: &App, area: Rect) {
+fn draw_stats(f: &mut Frame, app: &mut App, area: Rect) {
     let total_stats = app.total_stats();
+    // Counted from the Request entries arriving here, so it also covers proxies whose
+    // stats updates lag behind
+    let rps = app.requests_per_second();
     let proxy_count = app.proxies.len();
 
-    let stats_text = vec![
+    let mut stats_text = vec![
         Line::from(format!("Proxies: {}", proxy_count)),
+        Line::from(format!("Uptime: {}", format_uptime(total_stats.uptime))),
         Line::from(format!("Total Requests: {}", total_stats.total_requests)),
         Line::from(format!("Successful: {}", total_stats.successful_requests)),
         Line::from(format!("Failed: {}", total_stats.failed_requests)),
+        Line::from(format!("Notifications: {}", total_stats.notifications)),
+        Line::from(format!("Req/s: {:.1} (peak {:.1})", rps, app.peak_rps)),
         Line::from(format!(
             "Active Connections: {}",
             total_stats.active_connections
         )),
+        Line::from(format!("In-flight: {}", total_stats.in_flight_requests)),
         Line::from(format!(
-            "Bytes Transferred: {}",
-            format_bytes(total_stats.bytes_transferred)
+            "Bytes →: {}",
+            format_bytes(total_stats.bytes_client_to_server)
+        )),
+        Line::from(format!(
+            "Bytes ←: {}",
+            format_bytes(total_stats.bytes_server_to_client)
+        )),
+        Line::from(format!(
+            "Max Req: {}",
+            format_bytes(total_stats.max_request_bytes)
+        )),
+        Line::from(format!(
+            "Max Resp: {}",
+            format_bytes(total_stats.max_response_bytes)
         )),
     ];
+    stats_text.push(ipc_stats_line(&total_stats));
+    // Only there once something is off, e.g. a proxy speaking another protocol version
+    if app.skipped_messages > 0 {
+        stats_text.push(Line::styled(
+            format!("Skipped IPC Msgs: {}", app.skipped_messages),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
 
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Statistics")
+        .border_set(border::ROUNDED);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // The counters, then a sparkline of the request rate per second (newest at the right),
+    // and one for the filtered proxy
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(stats_text.len() as u16),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(inner);
     let paragraph = Paragraph::new(stats_text)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Statistics")
-                .border_set(border::ROUNDED),
-        )
         .style(Style::default().fg(Color::White))
         .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, rows[0]);
+
+    let width = inner.width as usize;
+    f.render_widget(
+        Sparkline::default()
+            .data(&app.request_rate.recent(width))
+            .style(Style::default().fg(Color::Green)),
+        rows[1],
+    );
+
+    let Some(ref proxy_id) = app.selected_proxy else {
+        return;
+    };
+    let name = app
+        .proxies
+        .get(proxy_id)
+        .map_or("Filtered proxy", |proxy| proxy.name.as_str());
+    let rate = app.proxy_request_rates.get(proxy_id);
+    let per_second = rate.map_or(0.0, |rate| rate.per_second(PROXY_RATE_SECONDS));
+    f.render_widget(
+        Paragraph::new(format!("{}: {:.1} req/s", name, per_second))
+            .style(Style::default().fg(Color::Yellow)),
+        rows[2],
+    );
+    f.render_widget(
+        Sparkline::default()
+            .data(&rate.map_or_else(|| vec![0; width], |rate| rate.recent(width)))
+            .style(Style::default().fg(Color::Yellow)),
+        rows[3],
+    );
+}
 
-    f.render_widget(paragraph, area);
+/// Rows of the stats panel: its borders, the counters and the rate sparklines
+fn stats_height(app: &App) -> u16 {
+    let skipped = u16::from(app.skipped_messages > 0);
+    let proxy_rate = if app.selected_proxy.is_some() { 2 } else { 0 };
+    // Borders, the 14 counter lines `draw_stats` always has, the skipped line when
+    // there is one, the global sparkline, and the filtered proxy's label and sparkline
+    2 + 14 + skipped + 1 + proxy_rate
 }
 
 fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
-    let tabs: Vec<Line> = vec![
-        TabType::All,
-        TabType::Messages,
-        TabType::Errors,
-        TabType::System,
-    ]
-    .iter()
-    .map(|&tab| {
-        let (tab_name, emoji, fallback) = match tab {
-            TabType::All => ("All", "📊", "A"),
-            TabType::Messages => ("Messages", "💬", "M"),
-            TabType::Errors => ("Errors", "❗", "E"),
-            TabType::System => ("System", "⚡", "S"),
-        };
+    let tabs: Vec<Line> = app
+        .tabs
+        .iter()
+        .map(|tab| {
+            let (tab_name, emoji, fallback) = match tab {
+                TabType::All => ("All", "📊", "A"),
+                TabType::Messages => ("Messages", "💬", "M"),
+                TabType::Errors => ("Errors", "❗", "E"),
+                TabType::System => ("System", "⚡", "S"),
+                TabType::Bookmarks => ("Bookmarks", "★", "B"),
+                TabType::Custom { name, .. } => (name.as_str(), "🔖", "C"),
+            };
 
-        // Use emoji with fallback for limited terminals
-        let tab_icon = if std::env::var("TERM")
-            .unwrap_or_default()
-            .contains("256color")
-            || std::env::var("COLORTERM").is_ok()
-        {
-            emoji
-        } else {
-            fallback
-        };
+            // Use emoji with fallback for limited terminals
+            let tab_icon = if supports_emoji() { emoji } else { fallback };
 
-        let count = app.get_tab_log_count(tab);
-        let tab_text = format!("{} {} ({})", tab_icon, tab_name, count);
+            let count = app.get_tab_log_count(tab);
+            let tab_text = format!("{} {} ({})", tab_icon, tab_name, count);
 
-        if tab == app.active_tab {
-            Line::from(Span::styled(
-                format!(" {} ", tab_text),
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::LightBlue)
-                    .add_modifier(Modifier::BOLD),
-            ))
-        } else {
-            Line::from(Span::styled(
-                format!(" {} ", tab_text),
-                Style::default().fg(Color::Gray),
-            ))
-        }
-    })
-    .collect();
+            if *tab == app.active_tab {
+                Line::from(Span::styled(
+                    format!(" {} ", tab_text),
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::LightBlue)
+                        .add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Line::from(Span::styled(
+                    format!(" {} ", tab_text),
+                    Style::default().fg(Color::Gray),
+                ))
+            }
+        })
+        .collect();
 
     let tabs_widget = Tabs::new(tabs)
         .block(
@@ -236,31 +419,56 @@ fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
         )
         .style(Style::default())
         .highlight_style(Style::default().fg(Color::White))
-        .select(match app.active_tab {
-            TabType::All => 0,
-            TabType::Messages => 1,
-            TabType::Errors => 2,
-            TabType::System => 3,
-        });
+        .select(app.active_tab_position());
 
     f.render_widget(tabs_widget, area);
 }
 
+fn supports_emoji() -> bool {
+    std::env::var("TERM")
+        .unwrap_or_default()
+        .contains("256color")
+        || std::env::var("COLORTERM").is_ok()
+}
+
 fn draw_logs(f: &mut Frame, app: &mut App, area: Rect) {
     // Prepare viewport first
     let visible_height = area.height.saturating_sub(2) as usize;
     app.prepare_viewport(visible_height);
+    app.latency_column_visible = app
+        .get_visible_logs(visible_height)
+        .iter()
+        .any(|log| log.duration.is_some());
 
     // Get data for rendering
     let visible_logs = app.get_visible_logs(visible_height);
     let relative_selection = app.get_relative_selection(visible_height);
-    let filtered_count = app.get_search_filtered_logs().len();
+    let filtered_count = app.get_search_filtered_count();
     let display_position = if filtered_count > 0 {
         app.selected_index + 1
     } else {
         0
     };
 
+    // Only highlight matches once search results are being navigated
+    let highlight_query = if matches!(
+        app.navigation_mode,
+        NavigationMode::SearchResults | NavigationMode::SearchJump
+    ) {
+        SearchQuery::parse(&app.search_query)
+    } else {
+        SearchQuery::default()
+    };
+
+    let bookmark_symbol = if supports_emoji() { "★" } else { "*" };
+
+    let timestamp_reference = match app.timestamp_mode {
+        TimestampMode::Elapsed => app
+            .get_search_filtered_log(0)
+            .map_or_else(Utc::now, |log| log.timestamp),
+        _ => Utc::now(),
+    };
+
     let items: Vec<ListItem> = visible_logs
         .iter()
         .map(|log| {
@@ -281,15 +489,21 @@ fn draw_logs(f: &mut Frame, app: &mut App, area: Rect) {
                 LogLevel::Request => "📤",
                 LogLevel::Response => "📥",
             };
+            // Bookmarks take the level symbol's place; the message color still shows the level
+            let level_symbol = if app.is_bookmarked(log) {
+                bookmark_symbol
+            } else {
+                level_symbol
+            };
 
-            let timestamp = log.timestamp.format("%H:%M:%S%.3f");
-            let proxy_name = app
-                .proxies
-                .get(&log.proxy_id)
-                .map(|p| p.name.as_str())
-                .unwrap_or("unknown");
+            let timestamp = format_timestamp(log, app.timestamp_mode, timestamp_reference);
+            let proxy_name = match app.proxies.get(&log.proxy_id) {
+                Some(proxy) => proxy.name.as_str(),
+                None if log.proxy_id == ProxyId::monitor() => "monitor",
+                None => "unknown",
+            };
 
-            let text = vec![Line::from(vec![
+            let mut spans = vec![
                 Span::styled(
                     format!("{} [{}] ", level_symbol, timestamp),
                     Style::default().fg(Color::Gray),
@@ -298,10 +512,57 @@ fn draw_logs(f: &mut Frame, app: &mut App, area: Rect) {
                     format!("[{}] ", proxy_name),
                     Style::default().fg(Color::Magenta),
                 ),
-                Span::styled(&log.message, Style::default().fg(level_color)),
-            ])];
+            ];
+            // Badges before the message so long messages can't push them out of view
+            if app.is_second_selection(log) {
+                spans.push(Span::styled(
+                    "[v] ",
+                    Style::default()
+                        .fg(Color::LightBlue)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if app.annotation(log).is_some() {
+                spans.push(Span::styled(
+                    "[A] ",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            // Borders and the highlight symbol take 3 columns
+            let row_width = if app.latency_column_visible {
+                (area.width as usize).saturating_sub(3 + LATENCY_COLUMN_WIDTH)
+            } else {
+                (area.width as usize).saturating_sub(3)
+            };
+            let prefix_width: usize = spans.iter().map(|span| span.width()).sum();
+            spans.extend(highlight_spans(
+                &log.message,
+                &highlight_query,
+                row_width.saturating_sub(prefix_width),
+                Style::default().fg(level_color),
+            ));
+            if app.latency_column_visible {
+                // A fixed column, so latencies line up whatever the message length
+                spans = fit_spans(spans, row_width);
+                let slow = app.navigation_mode == NavigationMode::Follow
+                    && log.duration.is_some_and(|duration| {
+                        duration.as_millis() > u128::from(app.slow_threshold_ms)
+                    });
+                let latency_color = if slow { Color::Red } else { Color::Gray };
+                spans.push(Span::styled(
+                    format!(
+                        "{:>width$}",
+                        format_latency(log.duration),
+                        width = LATENCY_COLUMN_WIDTH
+                    ),
+                    Style::default().fg(latency_color),
+                ));
+            }
 
-            ListItem::new(text)
+            ListItem::new(vec![Line::from(spans)])
         })
         .collect();
 
@@ -311,6 +572,7 @@ fn draw_logs(f: &mut Frame, app: &mut App, area: Rect) {
         NavigationMode::Navigate => ("NAVIGATE", Color::Yellow),
         NavigationMode::Search => ("SEARCH", Color::Cyan),
         NavigationMode::SearchResults => ("SEARCH RESULTS", Color::Magenta),
+        NavigationMode::SearchJump => ("SEARCH n/N", Color::LightMagenta),
     };
 
     // Create focus indicator for logs
@@ -331,24 +593,112 @@ fn draw_logs(f: &mut Frame, app: &mut App, area: Rect) {
     };
 
     // Add search query to title if in search results mode
-    let search_text =
-        if app.navigation_mode == NavigationMode::SearchResults && !app.search_query.is_empty() {
-            format!(" | Search: \"{}\"", app.search_query)
-        } else {
-            String::new()
-        };
+    let search_text = if matches!(
+        app.navigation_mode,
+        NavigationMode::SearchResults | NavigationMode::SearchJump
+    ) && !app.search_query.is_empty()
+    {
+        format!(" | Search: \"{}\"", app.search_query)
+    } else {
+        String::new()
+    };
+
+    // Make it obvious that exclusions are hiding entries
+    let hidden_count = app.hidden_log_count();
+    let hidden_text = if hidden_count > 0 {
+        format!(" | {} hidden [x]", hidden_count)
+    } else {
+        String::new()
+    };
+
+    let time_range_text = match app.time_range {
+        Some(ref time_range) => format!(" | Time: {} [t]", time_range.label),
+        None => String::new(),
+    };
+
+    let level_filter_text = match app.level_filter {
+        Some(ref levels) => {
+            let names: Vec<&str> = LEVEL_FILTER_CHOICES
+                .iter()
+                .filter(|level| levels.contains(level))
+                .map(level_name)
+                .collect();
+            format!(" | Levels: {} [l]", names.join(", "))
+        }
+        None => String::new(),
+    };
+
+    // Show a repeat count while it is being typed
+    let key_prefix_text = if app.key_prefix.is_empty() {
+        String::new()
+    } else {
+        format!(" | Count: {}", app.key_prefix)
+    };
+
+    // Paused ingestion must be impossible to miss
+    let paused_span = if app.paused {
+        Span::styled(
+            format!("PAUSED ({} pending) ", app.pending_logs.len()),
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Span::raw("")
+    };
+
+    let replay_span = match &app.replay_progress {
+        Some(progress) => Span::styled(
+            format!(
+                "REPLAY {:.1}x {}/{}{} ",
+                progress.speed,
+                progress.current_index,
+                progress.total_entries,
+                if progress.paused { " [paused]" } else { "" }
+            ),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ),
+        None => Span::raw(""),
+    };
+
+    let status_text = app.status_message.as_deref().unwrap_or("");
 
     let logs_list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title(Title::from(logs_title).alignment(Alignment::Center))
+                .title(
+                    Title::from(Line::from(vec![
+                        replay_span,
+                        paused_span,
+                        Span::styled(
+                            format!(
+                                "[{}]{}{}{}{}{}{}",
+                                mode_text,
+                                proxy_filter_text,
+                                time_range_text,
+                                level_filter_text,
+                                search_text,
+                                hidden_text,
+                                key_prefix_text
+                            ),
+                            Style::default().fg(mode_color).add_modifier(Modifier::BOLD),
+                        ),
+                    ]))
+                    .alignment(Alignment::Left),
+                )
                 .title(
                     Title::from(Span::styled(
-                        format!("[{}]{}{}", mode_text, proxy_filter_text, search_text),
-                        Style::default().fg(mode_color).add_modifier(Modifier::BOLD),
+                        status_text,
+                        Style::default().fg(Color::Yellow),
                     ))
-                    .alignment(Alignment::Left),
+                    .alignment(Alignment::Left)
+                    .position(block::Position::Bottom),
                 )
                 .title(
                     Title::from(format!(
@@ -369,10 +719,58 @@ fn draw_logs(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_stateful_widget(logs_list, area, &mut state);
 }
 
+/// `[150MS]`, whole seconds from 10s so it fits the column, `[------]` without a duration
+fn format_latency(duration: Option<std::time::Duration>) -> String {
+    match duration {
+        Some(duration) if duration.as_millis() < 10_000 => {
+            format!("[{}MS]", duration.as_millis())
+        }
+        Some(duration) => format!("[{}S]", duration.as_secs().min(99_999)),
+        None => "[------]".to_string(),
+    }
+}
+
+/// `spans` cut or padded with spaces to exactly `width` columns
+fn fit_spans(spans: Vec<Span<'_>>, width: usize) -> Vec<Span<'_>> {
+    let mut fitted = Vec::with_capacity(spans.len() + 1);
+    let mut used = 0;
+    for span in spans {
+        let span_width = span.width();
+        if used + span_width <= width {
+            used += span_width;
+            fitted.push(span);
+            continue;
+        }
+
+        let mut end = 0;
+        for (index, c) in span.content.char_indices() {
+            let char_width = Span::raw(c.to_string()).width();
+            if used + char_width > width {
+                break;
+            }
+            used += char_width;
+            end = index + c.len_utf8();
+        }
+        let content = match span.content {
+            Cow::Borrowed(text) => Cow::Borrowed(&text[..end]),
+            Cow::Owned(mut text) => {
+                text.truncate(end);
+                Cow::Owned(text)
+            }
+        };
+        fitted.push(Span::styled(content, span.style));
+        break;
+    }
+    if used < width {
+        fitted.push(Span::raw(" ".repeat(width - used)));
+    }
+    fitted
+}
+
 fn draw_help(f: &mut Frame, area: Rect) {
     let help_text = vec![
-        Line::from("q/Ctrl+C: Quit | c: Clear logs | r: Refresh | ←→: Switch focus | ↑↓: Navigate | Esc: Follow/Clear filter | Enter: Select | /: Search"),
-        Line::from("Tab/Shift+Tab: Switch tabs | 1-4: Direct tab selection | PgUp/PgDn: Page | Home/End: Top/Bottom"),
+        Line::from("q/Ctrl+C: Quit | c: Clear proxy logs | C: Clear all | r: Refresh | ←→: Switch focus | ↑↓: Navigate | Esc: Follow/Clear filter | Enter: Select | /: Search | x: Exclude | t: Time range | l: Levels | p: Paired msg | P: Proxies"),
+        Line::from("Tab/Shift+Tab: Switch tabs | 1-9: Direct tab selection | b: Bookmark | a: Annotate | Space: Pause | T: Timestamps | F: Auto-follow | E: Error responses | PgUp/PgDn: Page | Home/End/g/G: Top/Bottom | j/k: Down/Up, 10j: repeat"),
     ];
 
     let paragraph = Paragraph::new(help_text)
@@ -388,6 +786,338 @@ fn draw_help(f: &mut Frame, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
+/// Split `text` into plain and highlighted spans around each match of `query`'s terms,
+/// regex ones included. When the first match would be cut off past `width` columns, the
+/// line is shifted left (with a leading ellipsis) so the match stays visible.
+pub fn highlight_spans<'a>(
+    text: &'a str,
+    query: &SearchQuery,
+    width: usize,
+    base_style: Style,
+) -> Vec<Span<'a>> {
+    let ranges = query.match_ranges(text);
+    if ranges.is_empty() {
+        return vec![Span::styled(text, base_style)];
+    }
+    highlighted_message_spans(text, &ranges, width, base_style)
+}
+
+/// Split `text` into spans with every range in `ranges` highlighted, shifted left for
+/// a first match past `width` columns
+fn highlighted_message_spans<'a>(
+    text: &'a str,
+    ranges: &[(usize, usize)],
+    width: usize,
+    base_style: Style,
+) -> Vec<Span<'a>> {
+    let match_style = base_style
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::REVERSED | Modifier::BOLD);
+
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    if let Some(&(first_start, first_end)) = ranges.first() {
+        if text[..first_end].chars().count() > width {
+            // Keep a little leading context before the match
+            let skip = text[..first_start]
+                .chars()
+                .count()
+                .saturating_sub(width / 4);
+            offset = text.char_indices().nth(skip).map_or(0, |(byte, _)| byte);
+            spans.push(Span::styled("…", base_style));
+        }
+    }
+
+    let mut pos = offset;
+    for &(start, end) in ranges.iter().filter(|(start, _)| *start >= offset) {
+        if start > pos {
+            spans.push(Span::styled(&text[pos..start], base_style));
+        }
+        spans.push(Span::styled(&text[start..end], match_style));
+        pos = end;
+    }
+    if pos < text.len() {
+        spans.push(Span::styled(&text[pos..], base_style));
+    }
+
+    spans
+}
+
+/// Lexer state carried from one line to the next, so a string wrapped across rows
+/// keeps its color
+#[derive(Debug, Default, Clone, Copy)]
+struct JsonLexState {
+    in_string: bool,
+    escaped: bool,
+    is_key: bool,
+}
+
+fn json_string_style(is_key: bool) -> Style {
+    if is_key {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default().fg(Color::Green)
+    }
+}
+
+/// Color JSON keys, strings, numbers, booleans and null in each line, treating the
+/// lines as one continuous document
+pub fn highlight_json_lines<S: AsRef<str>>(
+    lines: impl IntoIterator<Item = S>,
+) -> Vec<Line<'static>> {
+    let mut state = JsonLexState::default();
+    lines
+        .into_iter()
+        .map(|line| {
+            let line = line.as_ref();
+            let spans: Vec<Span<'static>> = json_line_styles(line, &mut state)
+                .into_iter()
+                .map(|(start, end, style)| Span::styled(line[start..end].to_string(), style))
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Styled byte ranges covering all of `line`. Text that isn't a JSON token, such as
+/// punctuation or plain words, keeps the default style.
+fn json_line_styles(line: &str, state: &mut JsonLexState) -> Vec<(usize, usize, Style)> {
+    let bytes = line.as_bytes();
+    let mut segments: Vec<(usize, usize, Style)> = Vec::new();
+    let mut push = |start: usize, end: usize, style: Style| match segments.last_mut() {
+        Some(last) if last.1 == start && last.2 == style => last.1 = end,
+        _ => segments.push((start, end, style)),
+    };
+
+    let mut i = 0;
+    if state.in_string {
+        i = scan_json_string(bytes, 0, state);
+        push(0, i, json_string_style(state.is_key));
+    }
+
+    while i < bytes.len() {
+        let at_word_start = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        let literal = ["true", "false", "null"].into_iter().find(|word| {
+            line[i..].starts_with(word)
+                && !bytes
+                    .get(i + word.len())
+                    .is_some_and(|b| b.is_ascii_alphanumeric())
+        });
+
+        match bytes[i] {
+            b'"' => {
+                state.in_string = true;
+                state.escaped = false;
+                state.is_key = is_json_key(bytes, i);
+                let end = scan_json_string(bytes, i + 1, state);
+                push(i, end, json_string_style(state.is_key));
+                i = end;
+            }
+            b'-' | b'0'..=b'9'
+                if at_word_start
+                    && bytes[i..]
+                        .iter()
+                        .find(|b| **b != b'-')
+                        .is_some_and(|b| b.is_ascii_digit()) =>
+            {
+                let end = bytes[i + 1..]
+                    .iter()
+                    .position(|b| !matches!(b, b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-'))
+                    .map_or(bytes.len(), |offset| i + 1 + offset);
+                push(i, end, Style::default().fg(Color::LightMagenta));
+                i = end;
+            }
+            _ if at_word_start && literal.is_some() => {
+                let word = literal.unwrap_or_default();
+                let color = if word == "null" {
+                    Color::LightRed
+                } else {
+                    Color::LightYellow
+                };
+                push(i, i + word.len(), Style::default().fg(color));
+                i += word.len();
+            }
+            _ => {
+                let end = i + line[i..].chars().next().map_or(1, char::len_utf8);
+                push(i, end, Style::default());
+                i = end;
+            }
+        }
+    }
+
+    segments
+}
+
+/// Byte offset just past the closing quote of a string whose content starts at `from`,
+/// or the end of the line when the string continues on the next one
+fn scan_json_string(bytes: &[u8], from: usize, state: &mut JsonLexState) -> usize {
+    for (offset, &byte) in bytes[from..].iter().enumerate() {
+        if state.escaped {
+            state.escaped = false;
+        } else if byte == b'\\' {
+            state.escaped = true;
+        } else if byte == b'"' {
+            state.in_string = false;
+            return from + offset + 1;
+        }
+    }
+    bytes.len()
+}
+
+/// Whether the string opening at `quote` is an object key, i.e. followed by `:`
+fn is_json_key(bytes: &[u8], quote: usize) -> bool {
+    let mut state = JsonLexState {
+        in_string: true,
+        ..Default::default()
+    };
+    let end = scan_json_string(bytes, quote + 1, &mut state);
+    !state.in_string
+        && bytes[end..]
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| *b == b':')
+}
+
+/// Spans for one detail line: syntax colors from `segments`, with `base_style` and the
+/// search `ranges` layered on top
+fn styled_detail_line(
+    line: &str,
+    segments: &[(usize, usize, Style)],
+    ranges: &[(usize, usize)],
+    base_style: Style,
+) -> Line<'static> {
+    let match_style = base_style
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::REVERSED | Modifier::BOLD);
+
+    let mut spans = Vec::new();
+    for &(start, end, style) in segments {
+        let mut cuts = vec![start, end];
+        for &(match_start, match_end) in ranges {
+            cuts.extend(
+                [match_start, match_end]
+                    .into_iter()
+                    .filter(|&cut| start < cut && cut < end),
+            );
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        for piece in cuts.windows(2) {
+            let (from, to) = (piece[0], piece[1]);
+            let in_match = ranges.iter().any(|&(s, e)| s <= from && to <= e);
+            let piece_style = if in_match {
+                style.patch(match_style)
+            } else {
+                style.patch(base_style)
+            };
+            spans.push(Span::styled(line[from..to].to_string(), piece_style));
+        }
+    }
+    Line::from(spans)
+}
+
+/// The log list's timestamp column, right-padded to a constant width. `reference` is
+/// now in `Relative` mode and the first entry of the view in `Elapsed` mode.
+pub fn format_timestamp(entry: &LogEntry, mode: TimestampMode, reference: DateTime<Utc>) -> String {
+    let text = match mode {
+        TimestampMode::Absolute => entry.timestamp.format("%H:%M:%S%.3f").to_string(),
+        TimestampMode::Relative => format!("-{}", format_offset(reference - entry.timestamp)),
+        TimestampMode::Elapsed => format!("+{}", format_offset(entry.timestamp - reference)),
+    };
+    format!("{:<10}", text)
+}
+
+/// `1.234s` below a minute, then `2m05s` and `1h02m`
+fn format_offset(offset: chrono::Duration) -> String {
+    let millis = offset.num_milliseconds().max(0);
+    let seconds = millis / 1000;
+    if seconds >= 3600 {
+        format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60)
+    } else if seconds >= 60 {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        let fraction = format!("{:03}", millis % 1000);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            format!("{}s", seconds)
+        } else {
+            format!("{}.{}s", seconds, fraction)
+        }
+    }
+}
+
+/// Detail view position such as "lines 180–220 of 1,432 (15%)"; the percentage is how
+/// far through the content the last visible line is
+pub fn format_line_position(first: usize, last: usize, total: usize) -> String {
+    let percent = (last * 100).checked_div(total).unwrap_or(100);
+    format!(
+        "lines {}–{} of {} ({}%)",
+        group_thousands(first),
+        group_thousands(last),
+        group_thousands(total),
+        percent
+    )
+}
+
+fn group_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let (head, rest) = digits.split_at(digits.len() % 3);
+    let mut grouped = head.to_string();
+    for group in rest.as_bytes().chunks(3) {
+        if !grouped.is_empty() {
+            grouped.push(',');
+        }
+        grouped.push_str(std::str::from_utf8(group).unwrap_or_default());
+    }
+    grouped
+}
+
+/// How the proxies' links to the monitor are doing, yellow once messages wait or are lost
+fn ipc_stats_line(stats: &ProxyStats) -> Line<'static> {
+    let mut text = format!(
+        "IPC: {}, {} buffered",
+        if stats.ipc_connected {
+            "connected"
+        } else {
+            "disconnected"
+        },
+        format_count(stats.ipc_buffered)
+    );
+    if stats.ipc_dropped > 0 {
+        text.push_str(&format!(", {} dropped", format_count(stats.ipc_dropped)));
+    }
+    let style = if stats.ipc_connected && stats.ipc_dropped == 0 {
+        Style::default()
+    } else {
+        Style::default().fg(Color::Yellow)
+    };
+    Line::styled(text, style)
+}
+
+/// `count` shortened past a thousand, e.g. `1.2k`
+fn format_count(count: u64) -> String {
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => format!("{:.1}k", count as f64 / 1_000.0),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
+    }
+}
+
+/// `45s`, `23m 45s` or `1h 23m 45s`
+pub fn format_uptime(uptime: std::time::Duration) -> String {
+    let secs = uptime.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
 fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     let mut size = bytes as f64;
@@ -405,25 +1135,131 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
-fn draw_detail_view(f: &mut Frame, app: &App, area: Rect) {
-    // Create a centered popup that covers most of the screen
-    let popup_area = centered_rect(90, 85, area);
+/// Two entries side by side above their unified diff, all scrolled together
+fn draw_diff_view(f: &mut Frame, app: &App, popup_area: Rect) {
+    let Some((older, newer)) = app.diff_entries() else {
+        return;
+    };
+    let inner_area = Rect {
+        x: popup_area.x + 1,
+        y: popup_area.y + 1,
+        width: popup_area.width.saturating_sub(2),
+        height: popup_area.height.saturating_sub(2),
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Percentage(40),
+            Constraint::Min(0),
+        ])
+        .split(inner_area);
+    let sides = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[1]);
 
-    // Clear the background completely first
-    let clear = Clear;
-    f.render_widget(clear, popup_area);
+    let (added, removed) =
+        app.diff_lines
+            .iter()
+            .fold((0, 0), |(added, removed), line| match line {
+                DiffLine::Added(_) => (added + 1, removed),
+                DiffLine::Removed(_) => (added, removed + 1),
+                DiffLine::Context(_) => (added, removed),
+            });
+    let header = Paragraph::new(Line::from(format!(
+        "Diff - {} → {} | +{} -{}",
+        older.timestamp.format("%H:%M:%S%.3f"),
+        newer.timestamp.format("%H:%M:%S%.3f"),
+        added,
+        removed
+    )))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Compare View")
+            .border_set(border::THICK)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Rgb(20, 20, 20))),
+    )
+    .style(Style::default().fg(Color::White))
+    .alignment(Alignment::Center);
+    f.render_widget(header, chunks[0]);
 
-    // Draw a solid background block to create visual separation
-    let background = Block::default()
-        .borders(Borders::ALL)
-        .border_set(border::DOUBLE)
-        .border_style(Style::default().fg(Color::White))
-        .style(Style::default().bg(Color::Black));
-    f.render_widget(background, popup_area);
+    for ((area, log), content) in sides
+        .iter()
+        .zip([older, newer])
+        .zip(app.diff_contents.iter())
+    {
+        let side = Paragraph::new(content.as_str())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        " {} {} ",
+                        log.level,
+                        log.timestamp.format("%H:%M:%S%.3f")
+                    ))
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+            .scroll((app.diff_scroll_offset, 0));
+        f.render_widget(side, *area);
+    }
 
-    if let Some(log) = app.get_selected_log() {
-        let content = app.format_log_content(log);
+    let lines: Vec<Line> = app
+        .diff_lines
+        .iter()
+        .map(|line| match line {
+            DiffLine::Added(text) => {
+                Line::styled(format!("+ {}", text), Style::default().fg(Color::Green))
+            }
+            DiffLine::Removed(text) => {
+                Line::styled(format!("- {}", text), Style::default().fg(Color::Red))
+            }
+            DiffLine::Context(text) => Line::raw(format!("  {}", text)),
+        })
+        .collect();
+    let diff = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Unified Diff | ↑↓ to scroll | Press ESC to close ")
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .scroll((app.diff_scroll_offset, 0));
+    f.render_widget(diff, chunks[2]);
+}
+
+fn draw_detail_view(f: &mut Frame, app: &mut App, area: Rect) {
+    // Create a centered popup that covers most of the screen
+    let popup_area = centered_rect(90, 85, area);
+
+    // Clear the background completely first
+    let clear = Clear;
+    f.render_widget(clear, popup_area);
+
+    // Draw a solid background block to create visual separation
+    let background = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::DOUBLE)
+        .border_style(Style::default().fg(Color::White))
+        .style(Style::default().bg(Color::Black));
+    f.render_widget(background, popup_area);
+
+    if app.show_diff_view {
+        draw_diff_view(f, app, popup_area);
+        return;
+    }
 
+    let tree_rows = app.detail_tree_rows();
+
+    if let Some(log) = app.get_selected_log() {
         // Create the main content area (with margin to avoid overlapping the border)
         let inner_area = Rect {
             x: popup_area.x + 1,
@@ -432,18 +1268,23 @@ fn draw_detail_view(f: &mut Frame, app: &App, area: Rect) {
             height: popup_area.height.saturating_sub(2),
         };
 
+        // The note of an annotated entry gets its own section below the content
+        let annotation = app.annotation(log).map(str::to_string);
+        let annotation_height = if annotation.is_some() { 3 } else { 0 };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
                 Constraint::Min(0),
+                Constraint::Length(annotation_height),
                 Constraint::Length(3),
             ])
             .split(inner_area);
 
-        // Header with log info
-        let header_text = vec![Line::from(format!(
-            "Log Details - {} | {} | {}",
+        // Header with log info; the split view names both panes
+        let level_text = if app.show_paired_detail {
+            "📤 Request │ 📥 Response"
+        } else {
             match log.level {
                 mcp_common::LogLevel::Request => "📤 Request",
                 mcp_common::LogLevel::Response => "📥 Response",
@@ -451,11 +1292,49 @@ fn draw_detail_view(f: &mut Frame, app: &App, area: Rect) {
                 mcp_common::LogLevel::Warning => "⚠️ Warning",
                 mcp_common::LogLevel::Info => "ℹ️ Info",
                 mcp_common::LogLevel::Debug => "🐛 Debug",
-            },
+            }
+        };
+        let header_text = vec![Line::from(format!(
+            "Log Details - {} | {} | {} [{}]",
+            level_text,
             log.timestamp.format("%H:%M:%S%.3f"),
-            log.request_id.as_deref().unwrap_or("N/A")
+            log.request_id.as_deref().unwrap_or("N/A"),
+            log.proxy_id.short()
         ))];
 
+        let highlight = app.detail_tab_is_highlighted(log);
+
+        // Sub-tabs; Metadata stays listed (dimmed) for entries without metadata
+        let mut tab_spans = Vec::new();
+        for (number, tab) in DetailTab::ALL.into_iter().enumerate() {
+            let style = if tab == app.detail_tab {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else if tab == DetailTab::Metadata && log.metadata.is_none() {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            tab_spans.push(Span::styled(
+                format!(" {} {} ", number + 1, tab.title()),
+                style,
+            ));
+            tab_spans.push(Span::raw("│"));
+        }
+        tab_spans.pop();
+
+        // Lines are wrapped by the app rather than the paragraph so search matches
+        // and scroll offsets line up with what is on screen
+        app.detail_content_width = chunks[1].width.saturating_sub(2);
+        app.detail_pane_width = split_pane_areas(chunks[1])
+            .iter()
+            .map(|pane| pane.width.saturating_sub(2))
+            .min()
+            .unwrap_or(0);
+        app.prepare_detail_viewport(chunks[1].height.saturating_sub(2));
+
         let header = Paragraph::new(header_text)
             .block(
                 Block::default()
@@ -475,27 +1354,102 @@ fn draw_detail_view(f: &mut Frame, app: &App, area: Rect) {
         // Content area with word wrap toggle
         let wrap_indicator = if app.detail_word_wrap { "ON" } else { "OFF" };
 
-        // Create text from content with proper line breaks
-        let text = if app.detail_word_wrap {
-            // When word wrap is on, create a single text block that will be wrapped
-            Text::from(content)
+        let query = app.detail_search_query.as_str();
+        let match_lines = app.detail_match_lines();
+        let rows = app.detail_rows();
+        let line_count = rows.last().map_or(0, |&(line, _)| line + 1);
+        let number_width = if app.detail_line_numbers {
+            line_number_gutter_width(line_count) as usize - 3
         } else {
-            // When word wrap is off, split into lines to preserve formatting
-            let lines: Vec<Line> = content
-                .lines()
-                .map(|line| Line::from(line.to_string()))
-                .collect();
-            Text::from(lines)
+            0
+        };
+
+        let mut lex_state = JsonLexState::default();
+        let mut previous_line = None;
+        let lines: Vec<Line> = rows
+            .iter()
+            .enumerate()
+            .map(|(index, (line_index, line))| {
+                let segments = if highlight {
+                    json_line_styles(line, &mut lex_state)
+                } else {
+                    vec![(0, line.len(), Style::default())]
+                };
+                let ranges = find_match_ranges(line, query);
+
+                let base_style = if !ranges.is_empty() && app.detail_search_match == Some(index) {
+                    Style::default().bg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                let mut styled = styled_detail_line(line, &segments, &ranges, base_style);
+
+                // Only the first row of a wrapped line carries its number
+                if app.detail_line_numbers {
+                    let number = if previous_line == Some(*line_index) {
+                        String::new()
+                    } else {
+                        (line_index + 1).to_string()
+                    };
+                    styled.spans.insert(
+                        0,
+                        Span::styled(
+                            format!("{:>width$} │ ", number, width = number_width),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    );
+                }
+                previous_line = Some(*line_index);
+                styled
+            })
+            .collect();
+
+        // Unwrapped line numbers of the first and last rows on screen
+        let first_row = app.detail_scroll_offset as usize;
+        let last_row = (first_row + app.detail_viewport_height.max(1) as usize)
+            .min(rows.len())
+            .saturating_sub(1);
+        let position_text = match (rows.get(first_row), rows.get(last_row)) {
+            (Some(&(first, _)), Some(&(last, _))) => {
+                format_line_position(first + 1, last + 1, line_count)
+            }
+            _ => String::new(),
+        };
+
+        let search_indicator = if query.is_empty() || app.detail_search_active {
+            String::new()
+        } else if match_lines.is_empty() {
+            format!(" [\"{}\": no matches]", query)
+        } else {
+            let current = app
+                .detail_search_match
+                .and_then(|line| match_lines.iter().position(|&m| m == line))
+                .map_or("-".to_string(), |position| (position + 1).to_string());
+            format!(
+                " [\"{}\": {}/{}] [n/N: Next/Prev]",
+                query,
+                current,
+                match_lines.len()
+            )
         };
 
-        let content_paragraph = Paragraph::new(text)
+        let content_paragraph = Paragraph::new(Text::from(lines))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!(
-                        "Content [Word Wrap: {}] [W: Toggle]",
-                        wrap_indicator
-                    ))
+                    .title(Title::from(Line::from(tab_spans)))
+                    .title(
+                        Title::from(format!(
+                            "[Word Wrap: {}] [W: Toggle]{}",
+                            wrap_indicator, search_indicator
+                        ))
+                        .alignment(Alignment::Right),
+                    )
+                    .title(
+                        Title::from(position_text)
+                            .alignment(Alignment::Right)
+                            .position(block::Position::Bottom),
+                    )
                     .border_set(border::THICK)
                     .border_style(
                         Style::default()
@@ -505,23 +1459,44 @@ fn draw_detail_view(f: &mut Frame, app: &App, area: Rect) {
                     .style(Style::default().bg(Color::Rgb(20, 20, 20))),
             )
             .style(Style::default().fg(Color::White))
-            .wrap(if app.detail_word_wrap {
-                Wrap { trim: true }
-            } else {
-                Wrap { trim: false }
-            })
             .scroll((app.detail_scroll_offset, 0)); // Use scroll offset
 
-        // Footer with controls
-        let footer_text = vec![
-            Line::from("ESC: Close | W: Toggle Word Wrap | ↑↓: Scroll | PgUp/PgDn: Page scroll | Home/End: Top/Bottom")
-        ];
+        // Footer with controls, replaced by the input while searching
+        let footer_text = if app.detail_search_active {
+            vec![Line::from(vec![
+                Span::styled("Find: ", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    format!("{}▏", app.detail_search_query),
+                    Style::default().fg(Color::White),
+                ),
+                Span::raw("   (Enter: Search | Esc: Cancel)"),
+            ])]
+        } else if app.show_paired_detail {
+            vec![Line::from(
+                "ESC: Close | P: Single view | Tab/←→: Switch pane | s/S: Save pane/raw | W: Toggle Word Wrap | C: Colors | ↑↓: Scroll | PgUp/PgDn: Page scroll | Home/End: Top/Bottom",
+            )]
+        } else if tree_rows.is_some() {
+            vec![Line::from(
+                "ESC: Close | T: Text view | Enter/Space: Expand/Collapse | ↑↓: Select | PgUp/PgDn: Page | Home/End: Top/Bottom | s/S: Save/raw",
+            )]
+        } else {
+            vec![Line::from(
+                "ESC: Close | 1-3/←→: Message/Metadata/Raw | W: Toggle Word Wrap | #: Line numbers | /: Find | a: Annotate | T: Tree | P/Tab: Side by side | s/S: Save/raw | C: Colors | ↑↓: Scroll | PgUp/PgDn: Page scroll | Home/End: Top/Bottom",
+            )]
+        };
 
         let footer = Paragraph::new(footer_text)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .title("Controls")
+                    .title(
+                        Title::from(Span::styled(
+                            app.status_message.as_deref().unwrap_or(""),
+                            Style::default().fg(Color::Yellow),
+                        ))
+                        .alignment(Alignment::Right),
+                    )
                     .border_set(border::THICK)
                     .border_style(
                         Style::default()
@@ -534,11 +1509,180 @@ fn draw_detail_view(f: &mut Frame, app: &App, area: Rect) {
             .alignment(Alignment::Center);
 
         f.render_widget(header, chunks[0]);
-        f.render_widget(content_paragraph, chunks[1]);
-        f.render_widget(footer, chunks[2]);
+        if app.show_paired_detail {
+            draw_detail_panes(f, app, chunks[1]);
+        } else if let Some(rows) = tree_rows {
+            draw_detail_tree(f, app, &rows, chunks[1]);
+        } else {
+            f.render_widget(content_paragraph, chunks[1]);
+        }
+        if let Some(note) = annotation {
+            let annotation_paragraph = Paragraph::new(note)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Annotation [a: Edit | Del: Remove]")
+                        .border_set(border::THICK)
+                        .border_style(Style::default().fg(Color::Yellow))
+                        .style(Style::default().bg(Color::Rgb(20, 20, 20))),
+                )
+                .style(Style::default().fg(Color::Yellow));
+            f.render_widget(annotation_paragraph, chunks[2]);
+        }
+        f.render_widget(footer, chunks[3]);
+    }
+}
+
+/// JSON content as a tree of expandable objects and arrays
+fn draw_detail_tree(f: &mut Frame, app: &App, rows: &[JsonTreeRow], area: Rect) {
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| ListItem::new(json_tree_line(row, app.detail_syntax_highlight)))
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Content [Tree: {}/{}] [T: Text view]",
+                    app.detail_tree_selected + 1,
+                    rows.len()
+                ))
+                .border_set(border::THICK)
+                .border_style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .style(Style::default().bg(Color::Rgb(20, 20, 20))),
+        )
+        .style(Style::default().fg(Color::White))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+    let mut state = ListState::default();
+    state.select(Some(app.detail_tree_selected));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn json_tree_line(row: &JsonTreeRow, colored: bool) -> Line<'static> {
+    let color = |style: Style| if colored { style } else { Style::default() };
+    let count =
+        |len: usize, unit: &str| format!("{} {}{}", len, unit, if len == 1 { "" } else { "s" });
+
+    let mut spans = vec![Span::raw("  ".repeat(row.depth))];
+    spans.push(Span::raw(match row.value {
+        JsonTreeValue::Object { expanded: true, .. }
+        | JsonTreeValue::Array { expanded: true, .. } => "▾ ",
+        JsonTreeValue::Object { .. } | JsonTreeValue::Array { .. } => "▸ ",
+        JsonTreeValue::Leaf(_) => "  ",
+    }));
+    if let Some(ref label) = row.label {
+        spans.push(Span::styled(label.clone(), color(json_string_style(true))));
+        spans.push(Span::raw(": "));
+    }
+
+    let summary_style = Style::default().fg(Color::DarkGray);
+    match row.value {
+        JsonTreeValue::Object { len, expanded } => {
+            spans.push(Span::raw("{"));
+            if !expanded {
+                spans.push(Span::styled(
+                    format!("… {}", count(len, "key")),
+                    summary_style,
+                ));
+                spans.push(Span::raw("}"));
+            }
+        }
+        JsonTreeValue::Array { len, expanded } => {
+            spans.push(Span::raw("["));
+            if !expanded {
+                spans.push(Span::styled(
+                    format!("… {}", count(len, "item")),
+                    summary_style,
+                ));
+                spans.push(Span::raw("]"));
+            }
+        }
+        JsonTreeValue::Leaf(ref value) => {
+            let text = value.to_string();
+            let mut state = JsonLexState::default();
+            for (start, end, style) in json_line_styles(&text, &mut state) {
+                spans.push(Span::styled(text[start..end].to_string(), color(style)));
+            }
+        }
+    }
+    Line::from(spans)
+}
+
+/// Request and response side by side, each pretty-printed and scrolled on its own; an
+/// unanswered request's response pane says so until the response arrives
+fn draw_detail_panes(f: &mut Frame, app: &App, area: Rect) {
+    let Some((request, response)) = app.detail_split_logs() else {
+        return;
+    };
+
+    let panes = split_pane_areas(area);
+
+    for (pane, log, title, rect) in [
+        (DetailPane::Request, Some(request), "📤 Request", panes[0]),
+        (DetailPane::Response, response, "📥 Response", panes[1]),
+    ] {
+        let focused = app.detail_pane == pane;
+        let border_style = if focused {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+
+        let rows = log.map(|log| app.detail_pane_rows(log));
+        let text = match (log, &rows) {
+            (Some(log), Some(rows)) if app.detail_is_highlighted(log) => {
+                Text::from(highlight_json_lines(rows.iter().map(|(_, line)| line)))
+            }
+            (_, Some(rows)) => Text::from(
+                rows.iter()
+                    .map(|(_, line)| Line::from(line.as_str()))
+                    .collect::<Vec<_>>(),
+            ),
+            (_, None) => Text::styled(
+                "No matching response found",
+                Style::default().fg(Color::DarkGray),
+            ),
+        };
+        let timestamp = log.map(|log| log.timestamp.format("%H:%M:%S%.3f").to_string());
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        "{} {}{}",
+                        title,
+                        timestamp.unwrap_or_default(),
+                        if focused { " [FOCUSED]" } else { "" }
+                    ))
+                    .border_set(border::THICK)
+                    .border_style(border_style)
+                    .style(Style::default().bg(Color::Rgb(20, 20, 20))),
+            )
+            .style(Style::default().fg(Color::White))
+            .scroll((app.detail_pane_scroll[pane as usize], 0));
+
+        f.render_widget(paragraph, rect);
     }
 }
 
+/// Request and response halves of the split detail view
+fn split_pane_areas(area: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area)
+}
+
 // Helper function to create a centered rectangle
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -562,7 +1706,7 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 
 fn draw_search_dialog(f: &mut Frame, app: &App, area: Rect) {
     // Create a smaller centered dialog for search
-    let dialog_area = centered_rect(60, 20, area);
+    let dialog_area = centered_rect(60, 30, area);
 
     // Clear the background completely first
     let clear = Clear;
@@ -624,11 +1768,19 @@ fn draw_search_dialog(f: &mut Frame, app: &App, area: Rect) {
         )
     };
 
+    let results_title = match app.search_history_index {
+        Some(index) => format!(
+            "Results [history {}/{}]",
+            index + 1,
+            app.search_history.len()
+        ),
+        None => "Results".to_string(),
+    };
     let results_paragraph = Paragraph::new(results_text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Results")
+                .title(results_title)
                 .border_set(border::THICK)
                 .border_style(
                     Style::default()
@@ -641,8 +1793,10 @@ fn draw_search_dialog(f: &mut Frame, app: &App, area: Rect) {
 
     // Instructions
     let instructions = vec![
-        Line::from("ESC: Exit search | Enter: Navigate to results | ↑↓: Navigate results"),
-        Line::from("Type to filter logs by message, proxy name, or log level"),
+        Line::from("ESC: Exit search | Enter: Navigate to results | Tab: Jump with n/N | ↑↓: Navigate results"),
+        Line::from("Alt+↑/Alt+↓: Earlier searches"),
+        Line::from("Type to filter logs by message, proxy name, or log level; all terms must match"),
+        Line::from("Filters: level:error proxy:github method:tools/call params.name:\"my tool\""),
     ];
 
     let instructions_paragraph = Paragraph::new(instructions)
@@ -678,15 +1832,11 @@ fn draw_search_dialog(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
-fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
-    // Create a centered dialog for help
-    let dialog_area = centered_rect(70, 80, area);
+fn draw_exclusion_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let dialog_area = centered_rect(50, 40, area);
 
-    // Clear the background
-    let clear = Clear;
-    f.render_widget(clear, dialog_area);
+    f.render_widget(Clear, dialog_area);
 
-    // Draw background block
     let background = Block::default()
         .borders(Borders::ALL)
         .border_set(border::DOUBLE)
@@ -694,7 +1844,6 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
         .style(Style::default().bg(Color::Black));
     f.render_widget(background, dialog_area);
 
-    // Create inner area with margin
     let inner_area = Rect {
         x: dialog_area.x + 1,
         y: dialog_area.y + 1,
@@ -702,126 +1851,641 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
         height: dialog_area.height.saturating_sub(2),
     };
 
-    // Build context-aware help content
-    let mut help_sections = vec![];
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(inner_area);
 
-    // Global shortcuts
-    help_sections.push(Line::from(Span::styled(
-        "━━━ Global Shortcuts ━━━",
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD),
-    )));
-    help_sections.push(Line::from(""));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "q/Ctrl+C",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("  Quit application"),
-    ]));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "?",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("         Show this help dialog"),
-    ]));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "c",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("         Clear all logs"),
-    ]));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "r",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("         Refresh proxy connections"),
-    ]));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "/",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("         Open search dialog"),
-    ]));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "←/→",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("       Switch focus between panels"),
-    ]));
-    help_sections.push(Line::from(""));
+    let dialog_block = |title: String| {
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_set(border::THICK)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Rgb(20, 20, 20)))
+    };
 
-    // Tab navigation
-    help_sections.push(Line::from(Span::styled(
-        "━━━ Tab Navigation ━━━",
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD),
-    )));
-    help_sections.push(Line::from(""));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "Tab",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("       Next tab"),
-    ]));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "Shift+Tab",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(" Previous tab"),
-    ]));
-    help_sections.push(Line::from(vec![
-        Span::styled(
-            "1-4",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw("       Direct tab selection (1=All, 2=Messages, 3=Errors, 4=System)"),
-    ]));
-    help_sections.push(Line::from(""));
+    let items: Vec<ListItem> = if app.exclusions.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "Nothing excluded",
+            Style::default().fg(Color::Gray),
+        )))]
+    } else {
+        app.exclusions
+            .iter()
+            .map(|pattern| ListItem::new(Line::from(pattern.as_str())))
+            .collect()
+    };
 
-    // Context-specific shortcuts
-    match app.focus_area {
-        FocusArea::ProxyList => {
-            help_sections.push(Line::from(Span::styled(
-                "━━━ Proxy List (Current Focus) ━━━",
+    let mut state = ListState::default();
+    if !app.exclusions.is_empty() {
+        state.select(Some(app.exclusion_selected));
+    }
+
+    let list = List::new(items)
+        .block(dialog_block(format!(
+            "Excluded Methods ({} hidden)",
+            app.hidden_log_count()
+        )))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">");
+
+    let input = Paragraph::new(format!("{}▏", app.exclusion_input))
+        .block(dialog_block("Add method or substring".to_string()))
+        .style(Style::default().fg(Color::White));
+
+    let instructions =
+        Paragraph::new("Enter: Add | ↑↓: Select | Del: Remove | Ctrl+X: Clear all | Esc: Close")
+            .block(dialog_block("Instructions".to_string()))
+            .style(Style::default().fg(Color::LightCyan))
+            .alignment(Alignment::Center);
+
+    f.render_stateful_widget(list, chunks[0], &mut state);
+    f.render_widget(input, chunks[1]);
+    f.render_widget(instructions, chunks[2]);
+}
+
+fn level_name(level: &LogLevel) -> &'static str {
+    match level {
+        LogLevel::Request => "Request",
+        LogLevel::Response => "Response",
+        LogLevel::Error => "Error",
+        LogLevel::Warning => "Warning",
+        LogLevel::Info => "Info",
+        LogLevel::Debug => "Debug",
+    }
+}
+
+fn draw_level_filter_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let dialog_area = centered_rect(40, 50, area);
+
+    f.render_widget(Clear, dialog_area);
+
+    let background = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::DOUBLE)
+        .border_style(Style::default().fg(Color::White))
+        .style(Style::default().bg(Color::Black));
+    f.render_widget(background, dialog_area);
+
+    let inner_area = Rect {
+        x: dialog_area.x + 1,
+        y: dialog_area.y + 1,
+        width: dialog_area.width.saturating_sub(2),
+        height: dialog_area.height.saturating_sub(2),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(inner_area);
+
+    let dialog_block = |title: String| {
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_set(border::THICK)
+            .border_style(
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
-            )));
-            help_sections.push(Line::from(""));
-            help_sections.push(Line::from(vec![
-                Span::styled(
-                    "↑/↓",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Rgb(20, 20, 20)))
+    };
+
+    let items: Vec<ListItem> = LEVEL_FILTER_CHOICES
+        .iter()
+        .map(|level| {
+            let checkbox = if app.level_filter_selection.contains(level) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            ListItem::new(Line::from(format!("{} {}", checkbox, level_name(level))))
+        })
+        .collect();
+
+    let mut state = ListState::default();
+    state.select(Some(app.level_filter_cursor));
+
+    let list = List::new(items)
+        .block(dialog_block("Show Levels".to_string()))
+        .style(Style::default().fg(Color::White))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">");
+
+    let instructions = Paragraph::new("Space: Toggle | ↑↓: Select | Enter: Apply | Esc: Cancel")
+        .block(dialog_block("Instructions".to_string()))
+        .style(Style::default().fg(Color::LightCyan))
+        .alignment(Alignment::Center);
+
+    f.render_stateful_widget(list, chunks[0], &mut state);
+    f.render_widget(instructions, chunks[1]);
+}
+
+fn draw_save_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let dialog_area = centered_rect(50, 30, area);
+
+    f.render_widget(Clear, dialog_area);
+
+    let background = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::DOUBLE)
+        .border_style(Style::default().fg(Color::White))
+        .style(Style::default().bg(Color::Black));
+    f.render_widget(background, dialog_area);
+
+    let inner_area = Rect {
+        x: dialog_area.x + 1,
+        y: dialog_area.y + 1,
+        width: dialog_area.width.saturating_sub(2),
+        height: dialog_area.height.saturating_sub(2),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(inner_area);
+
+    let dialog_block = |title: &str| {
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string())
+            .border_set(border::THICK)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Rgb(20, 20, 20)))
+    };
+
+    let title = if app.save_raw {
+        "Save Raw Message"
+    } else {
+        "Save Formatted Content"
+    };
+    let input = Paragraph::new(app.save_input.as_str())
+        .block(dialog_block(title))
+        .style(Style::default().fg(Color::White));
+
+    let hint = match app.status_message {
+        Some(ref message) => Line::from(Span::styled(
+            message.as_str(),
+            Style::default().fg(Color::Red),
+        )),
+        None => Line::from(Span::styled(
+            "Relative paths are saved under the directory the monitor was started from",
+            Style::default().fg(Color::Gray),
+        )),
+    };
+    let status = Paragraph::new(hint)
+        .block(dialog_block("File"))
+        .wrap(Wrap { trim: true });
+
+    let instructions = Paragraph::new("Enter: Save | Esc: Cancel | ←→/Home/End: Move cursor")
+        .block(dialog_block("Instructions"))
+        .style(Style::default().fg(Color::LightCyan))
+        .alignment(Alignment::Center);
+
+    f.render_widget(input, chunks[0]);
+    f.render_widget(status, chunks[1]);
+    f.render_widget(instructions, chunks[2]);
+
+    let cursor_offset = app.save_input[..app.save_cursor].chars().count() as u16;
+    f.set_cursor(chunks[0].x + 1 + cursor_offset, chunks[0].y + 1); // 1 for each border
+}
+
+fn draw_annotation_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let dialog_area = centered_rect(50, 30, area);
+
+    f.render_widget(Clear, dialog_area);
+
+    let background = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::DOUBLE)
+        .border_style(Style::default().fg(Color::White))
+        .style(Style::default().bg(Color::Black));
+    f.render_widget(background, dialog_area);
+
+    let inner_area = Rect {
+        x: dialog_area.x + 1,
+        y: dialog_area.y + 1,
+        width: dialog_area.width.saturating_sub(2),
+        height: dialog_area.height.saturating_sub(2),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(inner_area);
+
+    let dialog_block = |title: &str| {
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string())
+            .border_set(border::THICK)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Rgb(20, 20, 20)))
+    };
+
+    let input = Paragraph::new(format!("{}▏", app.annotation_input))
+        .block(dialog_block("Annotation"))
+        .style(Style::default().fg(Color::White));
+
+    let hint = Paragraph::new(Line::from(Span::styled(
+        "Annotated entries show [A] in the log list; the note is shown in the detail view",
+        Style::default().fg(Color::Gray),
+    )))
+    .block(dialog_block("Note"))
+    .wrap(Wrap { trim: true });
+
+    let instructions = Paragraph::new("Enter: Save (empty removes the note) | Esc: Cancel")
+        .block(dialog_block("Instructions"))
+        .style(Style::default().fg(Color::LightCyan))
+        .alignment(Alignment::Center);
+
+    f.render_widget(input, chunks[0]);
+    f.render_widget(hint, chunks[1]);
+    f.render_widget(instructions, chunks[2]);
+}
+
+fn draw_time_range_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let dialog_area = centered_rect(50, 30, area);
+
+    f.render_widget(Clear, dialog_area);
+
+    let background = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::DOUBLE)
+        .border_style(Style::default().fg(Color::White))
+        .style(Style::default().bg(Color::Black));
+    f.render_widget(background, dialog_area);
+
+    let inner_area = Rect {
+        x: dialog_area.x + 1,
+        y: dialog_area.y + 1,
+        width: dialog_area.width.saturating_sub(2),
+        height: dialog_area.height.saturating_sub(2),
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(inner_area);
+
+    let dialog_block = |title: String| {
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_set(border::THICK)
+            .border_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Rgb(20, 20, 20)))
+    };
+
+    let current = match app.time_range {
+        Some(ref time_range) => format!("Time Range (current: {})", time_range.label),
+        None => "Time Range".to_string(),
+    };
+    let input = Paragraph::new(format!("{}▏", app.time_range_input))
+        .block(dialog_block(current))
+        .style(Style::default().fg(Color::White));
+
+    let hint = match app.time_range_error {
+        Some(ref error) => Line::from(Span::styled(
+            error.as_str(),
+            Style::default().fg(Color::Red),
+        )),
+        None => Line::from(Span::styled(
+            "5m, 30s, 12:03, 12:03:15-12:05, 12:03 to now (times as shown in the log list)",
+            Style::default().fg(Color::Gray),
+        )),
+    };
+    let examples = Paragraph::new(hint)
+        .block(dialog_block("Examples".to_string()))
+        .wrap(Wrap { trim: true });
+
+    let instructions = Paragraph::new("Enter: Apply | Esc: Close | Esc in log view: Clear range")
+        .block(dialog_block("Instructions".to_string()))
+        .style(Style::default().fg(Color::LightCyan))
+        .alignment(Alignment::Center);
+
+    f.render_widget(input, chunks[0]);
+    f.render_widget(examples, chunks[1]);
+    f.render_widget(instructions, chunks[2]);
+}
+
+fn draw_shutdown_dialog(f: &mut Frame, app: &App, area: Rect) {
+    let Some(ref id) = app.shutdown_dialog_proxy else {
+        return;
+    };
+    let name = app
+        .proxies
+        .get(id)
+        .map_or_else(|| id.to_string(), |proxy| proxy.name.clone());
+    let dialog_area = centered_rect(50, 20, area);
+
+    f.render_widget(Clear, dialog_area);
+
+    let text = vec![
+        Line::from(""),
+        Line::from(format!("Shut down {} [{}]?", name, id.short())),
+        Line::from("Its MCP server process will be killed."),
+        Line::from(""),
+        Line::from(Span::styled(
+            "y/Enter: Shut down | n/Esc: Cancel",
+            Style::default().fg(Color::LightCyan),
+        )),
+    ];
+    let dialog = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Shut Down Proxy")
+                .border_set(border::DOUBLE)
+                .border_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+                .style(Style::default().bg(Color::Black)),
+        )
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(dialog, dialog_area);
+}
+
+fn draw_help_dialog(f: &mut Frame, app: &mut App, area: Rect) {
+    // Create a centered dialog for help
+    let dialog_area = centered_rect(70, 80, area);
+
+    // Clear the background
+    let clear = Clear;
+    f.render_widget(clear, dialog_area);
+
+    // Draw background block
+    let background = Block::default()
+        .borders(Borders::ALL)
+        .border_set(border::DOUBLE)
+        .border_style(Style::default().fg(Color::White))
+        .style(Style::default().bg(Color::Black));
+    f.render_widget(background, dialog_area);
+
+    // Create inner area with margin
+    let inner_area = Rect {
+        x: dialog_area.x + 1,
+        y: dialog_area.y + 1,
+        width: dialog_area.width.saturating_sub(2),
+        height: dialog_area.height.saturating_sub(2),
+    };
+
+    // Build context-aware help content
+    let mut help_sections = vec![
+        // Global shortcuts
+        Line::from(Span::styled(
+            "━━━ Global Shortcuts ━━━",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                "q/Ctrl+C",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  Quit application"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "?",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Show this help dialog"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "c",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Clear all logs"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "Ctrl+Z",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("    Bring back the last cleared logs"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "r",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Refresh proxy connections"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "/",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Open search dialog"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "x",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Edit excluded methods"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "p",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Jump between request and response"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "P",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Show/hide the proxy panel"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "t",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Filter logs by time range"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "T",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Cycle timestamps: clock / age / since first entry"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "F",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Filter to each proxy as it connects"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "E",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Show JSON-RPC error responses in the Errors tab"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "L",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Fetch the proxy's recent logs again"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "b",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Bookmark the selected log (see tab 5)"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "v",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Mark a log, then Enter on another to diff them"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "←/→",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("       Switch focus between panels"),
+        ]),
+        Line::from(""),
+        // Tab navigation
+        Line::from(Span::styled(
+            "━━━ Tab Navigation ━━━",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                "Tab",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("       Next tab"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "Shift+Tab",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" Previous tab"),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                "1-9",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(
+                "       Direct tab selection (1=All, 2=Messages, 3=Errors, 4=System, 5=Bookmarks, 6-9=custom)",
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    // Context-specific shortcuts
+    match app.focus_area {
+        FocusArea::ProxyList => {
+            help_sections.push(Line::from(Span::styled(
+                "━━━ Proxy List (Current Focus) ━━━",
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            help_sections.push(Line::from(""));
+            help_sections.push(Line::from(vec![
+                Span::styled(
+                    "↑/↓",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
                 ),
                 Span::raw("       Navigate proxy list"),
             ]));
@@ -843,6 +2507,24 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
                 ),
                 Span::raw("       Clear proxy filter"),
             ]));
+            help_sections.push(Line::from(vec![
+                Span::styled(
+                    "d",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("         Remove a stopped proxy from the list"),
+            ]));
+            help_sections.push(Line::from(vec![
+                Span::styled(
+                    "K",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("         Shut down the proxy's MCP server (asks first)"),
+            ]));
         }
         FocusArea::LogView => {
             help_sections.push(Line::from(Span::styled(
@@ -861,6 +2543,15 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
                 ),
                 Span::raw("       Navigate logs"),
             ]));
+            help_sections.push(Line::from(vec![
+                Span::styled(
+                    "j/k",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("       Navigate logs; a number first repeats (5j)"),
+            ]));
             help_sections.push(Line::from(vec![
                 Span::styled(
                     "PgUp/PgDn",
@@ -879,6 +2570,15 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
                 ),
                 Span::raw("  Jump to top/bottom"),
             ]));
+            help_sections.push(Line::from(vec![
+                Span::styled(
+                    "g/G",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("       Jump to top/bottom; 20G jumps to entry 20"),
+            ]));
             help_sections.push(Line::from(vec![
                 Span::styled(
                     "Enter",
@@ -938,6 +2638,8 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
             help_sections.push(Line::from(""));
             help_sections.push(Line::from("Type to filter logs"));
             help_sections.push(Line::from("Enter to navigate results, Esc to exit"));
+            help_sections.push(Line::from("Tab to keep all logs and jump with n/N"));
+            help_sections.push(Line::from("Alt+Up/Alt+Down for earlier searches"));
         }
         NavigationMode::SearchResults => {
             help_sections.push(Line::from(Span::styled(
@@ -950,6 +2652,44 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
             help_sections.push(Line::from("Navigating filtered search results"));
             help_sections.push(Line::from("Press / to search again, Esc to clear"));
         }
+        NavigationMode::SearchJump => {
+            help_sections.push(Line::from(Span::styled(
+                "━━━ Search Jump (Active) ━━━",
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            help_sections.push(Line::from(""));
+            help_sections.push(Line::from("All logs stay visible, matches are highlighted"));
+            help_sections.push(Line::from(
+                "Press n/N for next/previous match, Esc to clear",
+            ));
+        }
+    }
+
+    if app.replay_progress.is_some() {
+        help_sections.push(Line::from(""));
+        help_sections.push(Line::from(Span::styled(
+            "━━━ Replay ━━━",
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        )));
+        help_sections.push(Line::from(""));
+        for (key, description) in [
+            ("Space", "     Pause/resume the replay"),
+            ("+/-", "       Double/halve the replay speed"),
+        ] {
+            help_sections.push(Line::from(vec![
+                Span::styled(
+                    key,
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(description),
+            ]));
+        }
     }
 
     // Special view shortcuts
@@ -980,6 +2720,60 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
             ),
             Span::raw("       Scroll content"),
         ]));
+        help_sections.push(Line::from(vec![
+            Span::styled(
+                "/",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Find in content"),
+        ]));
+        help_sections.push(Line::from(vec![
+            Span::styled(
+                "n/N",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("       Next/previous match"),
+        ]));
+        help_sections.push(Line::from(vec![
+            Span::styled(
+                "P/Tab",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("     Request and response side by side (Tab/←→ switch pane)"),
+        ]));
+        help_sections.push(Line::from(vec![
+            Span::styled(
+                "s/S",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("       Save formatted content / raw message to a file"),
+        ]));
+        help_sections.push(Line::from(vec![
+            Span::styled(
+                "c",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Toggle JSON syntax colors"),
+        ]));
+        help_sections.push(Line::from(vec![
+            Span::styled(
+                "t",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("         Collapsible JSON tree (Enter/Space expand/collapse)"),
+        ]));
         help_sections.push(Line::from(vec![
             Span::styled(
                 "Esc",
@@ -987,18 +2781,22 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::raw("       Close detail view"),
+            Span::raw("       Clear search / close detail view"),
         ]));
     }
 
-    // Create scrollable paragraph
+    // Scrollable within the paragraph's borders
+    let visible_lines = inner_area.height.saturating_sub(2);
+    let content_lines = help_sections.len().min(u16::MAX as usize) as u16;
+    app.set_help_max_scroll(content_lines.saturating_sub(visible_lines));
+
     let help_paragraph = Paragraph::new(help_sections)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title(" Keyboard Shortcuts ")
                 .title(
-                    Title::from(" Press ESC or ? to close ")
+                    Title::from(" ↑↓ to scroll | Press ESC or ? to close ")
                         .alignment(Alignment::Right)
                         .position(block::Position::Bottom),
                 )
@@ -1011,7 +2809,8 @@ fn draw_help_dialog(f: &mut Frame, app: &App, area: Rect) {
                 .style(Style::default().bg(Color::Rgb(20, 20, 20))),
         )
         .style(Style::default().fg(Color::White))
-        .alignment(Alignment::Left);
+        .alignment(Alignment::Left)
+        .scroll((app.help_scroll_offset, 0));
 
     f.render_widget(help_paragraph, inner_area);
 }
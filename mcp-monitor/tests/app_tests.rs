@@ -1,5 +1,6 @@
 use mcp_common::*;
 use mcp_monitor::*;
+use std::sync::Arc;
 
 #[test]
 fn test_app_creation() {
@@ -33,6 +34,9 @@ fn test_app_handle_proxy_connected() {
         name: "Test Proxy".to_string(),
         listen_address: "127.0.0.1:8080".to_string(),
         target_command: vec!["python".to_string(), "server.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats::default(),
     };
@@ -54,6 +58,9 @@ fn test_app_handle_proxy_disconnected() {
         name: "Test Proxy".to_string(),
         listen_address: "127.0.0.1:8080".to_string(),
         target_command: vec!["python".to_string(), "server.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats::default(),
     };
@@ -63,13 +70,310 @@ fn test_app_handle_proxy_disconnected() {
     assert_eq!(app.proxies.len(), 1);
 
     // Set as selected proxy
-    app.selected_proxy = Some(proxy_id.clone());
+    app.set_proxy_filter(Some(proxy_id.clone()));
 
-    // Disconnect proxy
+    // Disconnect proxy: it stays listed as stopped, and can still be filtered by
     app.handle_event(AppEvent::ProxyDisconnected(proxy_id.clone()));
 
-    assert!(app.proxies.is_empty());
+    assert_eq!(app.proxies.len(), 1);
+    assert!(matches!(
+        app.proxies[&proxy_id].status,
+        ProxyStatus::Stopped
+    ));
+    assert_eq!(app.selected_proxy, Some(proxy_id));
+}
+
+#[test]
+fn test_app_follows_proxies_on_connect() {
+    let mut app = App::new();
+    let info = |id: &ProxyId, status: ProxyStatus| ProxyInfo {
+        id: id.clone(),
+        name: "server".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status,
+        stats: ProxyStats::default(),
+    };
+    let first = ProxyId::new();
+    let second = ProxyId::new();
+
+    // Off by default
+    app.handle_event(AppEvent::ProxyConnected(info(&first, ProxyStatus::Running)));
+    assert_eq!(app.selected_proxy, None);
+
+    app.follow_proxy_on_connect = true;
+    app.handle_event(AppEvent::ProxyConnected(info(
+        &second,
+        ProxyStatus::Starting,
+    )));
+    assert_eq!(app.selected_proxy, Some(second.clone()));
+    assert_eq!(app.navigation_mode, NavigationMode::Follow);
+
+    // A proxy already running announcing itself again isn't a new connection
+    app.handle_event(AppEvent::ProxyConnected(info(&first, ProxyStatus::Running)));
+    assert_eq!(app.selected_proxy, Some(second.clone()));
+
+    app.handle_event(AppEvent::ProxyDisconnected(second.clone()));
+    assert_eq!(app.selected_proxy, None);
+
+    // Still watching: a stopped proxy reconnecting is followed again
+    app.handle_event(AppEvent::ProxyConnected(info(
+        &second,
+        ProxyStatus::Running,
+    )));
+    assert_eq!(app.selected_proxy, Some(second.clone()));
+
+    // A proxy picked by hand stays selected when the followed one stops
+    app.set_proxy_filter(Some(first.clone()));
+    app.handle_event(AppEvent::ProxyDisconnected(second));
+    assert_eq!(app.selected_proxy, Some(first));
+}
+
+#[test]
+fn test_app_errors_tab_can_include_error_responses() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    let failed = JsonRpcMessage::parse_all(
+        r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"Invalid params"}}"#,
+    );
+    app.handle_event(AppEvent::NewLogEntry(
+        LogEntry::new(LogLevel::Response, "← failed".to_string(), proxy_id.clone())
+            .with_error_code(&failed),
+    ));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        "← ok".to_string(),
+        proxy_id.clone(),
+    )));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Error,
+        "boom".to_string(),
+        proxy_id,
+    )));
+
+    app.switch_tab(TabType::Errors);
+    assert_eq!(app.get_tab_log_count(&TabType::Errors), 1);
+
+    app.toggle_error_responses_in_errors_tab();
+    let messages: Vec<_> = app
+        .get_filtered_logs()
+        .iter()
+        .map(|log| log.message.clone())
+        .collect();
+    assert_eq!(messages, vec!["← failed", "boom"]);
+    assert_eq!(app.selected_index, 1);
+
+    app.toggle_error_responses_in_errors_tab();
+    assert_eq!(app.get_tab_log_count(&TabType::Errors), 1);
+}
+
+#[test]
+fn test_app_stopped_proxy_lifecycle() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    let other_id = ProxyId::new();
+    let info = |id: &ProxyId, name: &str| ProxyInfo {
+        id: id.clone(),
+        name: name.to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Starting,
+        stats: ProxyStats {
+            proxy_id: id.clone(),
+            active_connections: 1,
+            ..Default::default()
+        },
+    };
+    app.handle_event(AppEvent::ProxyConnected(info(&proxy_id, "alpha")));
+    app.handle_event(AppEvent::ProxyConnected(info(&other_id, "beta")));
+    assert_eq!(app.total_stats().active_connections, 2);
+
+    // A stopped proxy no longer counts as connected
+    app.handle_event(AppEvent::ProxyDisconnected(proxy_id.clone()));
+    assert!(matches!(
+        app.proxies[&proxy_id].status,
+        ProxyStatus::Stopped
+    ));
+    assert_eq!(app.total_stats().active_connections, 1);
+
+    // Reconnecting with the same id brings it back up
+    app.handle_event(AppEvent::ProxyConnected(info(&proxy_id, "alpha")));
+    assert!(matches!(
+        app.proxies[&proxy_id].status,
+        ProxyStatus::Running
+    ));
+    assert_eq!(app.total_stats().active_connections, 2);
+
+    // A running proxy restarting its server shows as starting until it is up again
+    app.handle_event(AppEvent::ProxyConnected(info(&proxy_id, "alpha")));
+    assert!(matches!(
+        app.proxies[&proxy_id].status,
+        ProxyStatus::Starting
+    ));
+
+    // Only a stopped proxy can be removed
+    app.switch_focus_to_proxy_list();
+    app.proxy_selected_index = 0; // "alpha"
+    app.remove_stopped_proxy();
+    assert_eq!(app.proxies.len(), 2);
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Only stopped proxies can be removed")
+    );
+
+    app.handle_event(AppEvent::ProxyDisconnected(proxy_id.clone()));
+    app.set_proxy_filter(Some(proxy_id.clone()));
+    app.remove_stopped_proxy();
+    assert!(!app.proxies.contains_key(&proxy_id));
     assert!(app.selected_proxy.is_none());
+    assert_eq!(app.proxy_selected_index, 0);
+}
+
+#[test]
+fn test_app_proxy_shutdown_needs_confirmation() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+        id: proxy_id.clone(),
+        name: "runaway".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    }));
+    let (command_tx, mut command_rx) = tokio::sync::mpsc::channel(4);
+    app.proxy_commands = Some(command_tx);
+    app.switch_focus_to_proxy_list();
+
+    // Cancelling sends nothing
+    app.open_shutdown_dialog();
+    assert_eq!(app.shutdown_dialog_proxy, Some(proxy_id.clone()));
+    app.close_shutdown_dialog();
+    assert!(command_rx.try_recv().is_err());
+
+    app.open_shutdown_dialog();
+    app.confirm_proxy_shutdown();
+    assert!(app.shutdown_dialog_proxy.is_none());
+    let (target, message) = command_rx.try_recv().unwrap();
+    assert_eq!(target, proxy_id);
+    assert!(matches!(message, IpcMessage::Shutdown(id) if id == proxy_id));
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Asked runaway to shut down")
+    );
+
+    // A stopped proxy has nothing left to shut down
+    app.handle_event(AppEvent::ProxyDisconnected(proxy_id));
+    app.open_shutdown_dialog();
+    assert!(app.shutdown_dialog_proxy.is_none());
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("runaway is already stopped")
+    );
+}
+
+#[test]
+fn test_app_fetches_proxy_logs_without_duplicates() {
+    let mut app = App::new();
+    app.switch_tab(TabType::All);
+    let proxy_id = ProxyId::new();
+    app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+        id: proxy_id.clone(),
+        name: "files".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    }));
+    let (command_tx, mut command_rx) = tokio::sync::mpsc::channel(4);
+    app.proxy_commands = Some(command_tx);
+
+    // Nothing filtered or highlighted yet
+    app.fetch_selected_proxy_logs();
+    assert!(command_rx.try_recv().is_err());
+
+    app.set_proxy_filter(Some(proxy_id.clone()));
+    app.fetch_selected_proxy_logs();
+    let (target, message) = command_rx.try_recv().unwrap();
+    assert_eq!(target, proxy_id);
+    assert!(matches!(
+        message,
+        IpcMessage::GetLogs { proxy_id: id, limit: None } if id == proxy_id
+    ));
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Asked files for its recent logs")
+    );
+
+    // The answer repeats entries still listed, which are skipped
+    let entries: Vec<_> = (0..3)
+        .map(|index| LogEntry::new(LogLevel::Info, format!("entry {}", index), proxy_id.clone()))
+        .collect();
+    app.handle_event(AppEvent::NewLogEntry(entries[0].clone()));
+    for entry in &entries {
+        app.handle_event(AppEvent::NewLogEntry(entry.clone()));
+    }
+    assert_eq!(app.logs.len(), 3);
+
+    // Cleared entries can be fetched again
+    app.clear_proxy_logs(&proxy_id);
+    for entry in &entries {
+        app.handle_event(AppEvent::NewLogEntry(entry.clone()));
+    }
+    let messages: Vec<_> = app.logs.iter().map(|log| log.message.clone()).collect();
+    assert_eq!(messages, ["entry 0", "entry 1", "entry 2"]);
+}
+
+#[test]
+fn test_app_proxy_last_seen_and_stale() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+        id: proxy_id.clone(),
+        name: "Test Proxy".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    }));
+    assert!(app.proxy_last_seen.contains_key(&proxy_id));
+    assert!(!app.is_proxy_stale(&proxy_id));
+
+    // Silent for longer than the threshold
+    let long_ago =
+        std::time::Instant::now() - PROXY_STALE_AFTER - std::time::Duration::from_secs(1);
+    app.proxy_last_seen.insert(proxy_id.clone(), long_ago);
+    assert!(app.is_proxy_stale(&proxy_id));
+
+    // Any message from the proxy, including a keepalive ping, refreshes it
+    app.handle_event(AppEvent::ProxyHeartbeat(proxy_id.clone()));
+    assert!(!app.is_proxy_stale(&proxy_id));
+    app.proxy_last_seen.insert(proxy_id.clone(), long_ago);
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Info,
+        "hello".to_string(),
+        proxy_id.clone(),
+    )));
+    assert!(!app.is_proxy_stale(&proxy_id));
+
+    app.handle_event(AppEvent::ProxyDisconnected(proxy_id.clone()));
+    assert!(app.proxy_last_seen.is_empty());
 }
 
 #[test]
@@ -102,6 +406,9 @@ fn test_app_handle_stats_update() {
         name: "Test Proxy".to_string(),
         listen_address: "127.0.0.1:8080".to_string(),
         target_command: vec!["python".to_string(), "server.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats::default(),
     };
@@ -116,6 +423,7 @@ fn test_app_handle_stats_update() {
         active_connections: 3,
         uptime: std::time::Duration::from_secs(3600),
         bytes_transferred: 1024000,
+        ..Default::default()
     };
 
     app.handle_event(AppEvent::StatsUpdate(updated_stats.clone()));
@@ -152,6 +460,241 @@ fn test_app_clear_logs() {
     assert_eq!(app.navigation_mode, NavigationMode::Follow);
 }
 
+#[test]
+fn test_app_undo_clear() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    let add = |app: &mut App, message: &str| {
+        let log_entry = LogEntry::new(LogLevel::Info, message.to_string(), proxy_id.clone());
+        app.handle_event(AppEvent::NewLogEntry(log_entry));
+    };
+
+    for i in 0..5 {
+        add(&mut app, &format!("Log entry {}", i));
+    }
+    app.selected_index = 2;
+    app.viewport_offset = 1;
+
+    app.clear_logs();
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Undo available (Ctrl+Z)")
+    );
+    add(&mut app, "After clear 0");
+    add(&mut app, "After clear 1");
+
+    assert_eq!(app.undo_clear(), Some(5));
+    let messages: Vec<&str> = app.logs.iter().map(|log| log.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Log entry 0",
+            "Log entry 1",
+            "Log entry 2",
+            "Log entry 3",
+            "Log entry 4",
+            "After clear 0",
+            "After clear 1",
+        ]
+    );
+    assert_eq!(app.selected_index, 2);
+    assert_eq!(app.viewport_offset, 1);
+    assert_eq!(app.get_tab_log_count(&TabType::All), 7);
+
+    // Only the last clear is kept
+    add(&mut app, "After undo");
+    assert_eq!(app.undo_clear(), None);
+    assert_eq!(app.logs.len(), 8);
+}
+
+#[test]
+fn test_app_clear_proxy_logs_in_search_results() {
+    let mut app = App::new();
+    let noisy = ProxyId::new();
+    let quiet = ProxyId::new();
+    app.switch_tab(TabType::All);
+    for (id, name) in [(&noisy, "noisy"), (&quiet, "quiet")] {
+        app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+            id: id.clone(),
+            name: name.to_string(),
+            listen_address: "stdio".to_string(),
+            target_command: vec![name.to_string()],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
+            status: ProxyStatus::Running,
+            stats: ProxyStats::default(),
+        }));
+    }
+
+    // Interleaved entries; every third one is a "user" match
+    for i in 0..12 {
+        let proxy_id = if i % 2 == 0 { &noisy } else { &quiet };
+        let message = if i % 3 == 0 {
+            format!("user lookup {}", i)
+        } else {
+            format!("heartbeat {}", i)
+        };
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Info,
+            message,
+            proxy_id.clone(),
+        )));
+    }
+    let noisy_id = app.logs[0].id;
+    app.bookmarks.insert(noisy_id);
+
+    app.enter_search_mode();
+    for c in "user".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_results();
+    assert_eq!(app.search_results, vec![0, 3, 6, 9]);
+
+    // Select "user lookup 9", a quiet entry after noisy ones
+    app.selected_index = 3;
+    app.viewport_offset = 2;
+    app.clear_proxy_logs(&noisy);
+
+    assert_eq!(app.logs.len(), 6);
+    assert!(app.logs.iter().all(|log| log.proxy_id == quiet));
+    assert!(!app.bookmarks.contains(&noisy_id));
+    assert_eq!(app.navigation_mode, NavigationMode::SearchResults);
+    // "user lookup 3" and "user lookup 9" remain, now at indices 1 and 4
+    let indices: Vec<usize> = app
+        .search_results
+        .iter()
+        .map(|&seq| app.log_index(seq).unwrap())
+        .collect();
+    assert_eq!(indices, vec![1, 4]);
+    let results: Vec<&str> = app
+        .get_search_filtered_logs()
+        .iter()
+        .map(|log| log.message.as_str())
+        .collect();
+    assert_eq!(results, vec!["user lookup 3", "user lookup 9"]);
+    assert_eq!(app.selected_index, 1);
+    assert_eq!(app.viewport_offset, 1);
+    assert!(app.status_message.as_deref().unwrap().contains("noisy"));
+
+    // Clearing the rest leaves nothing pointing past the end
+    app.clear_proxy_logs(&quiet);
+    assert!(app.logs.is_empty());
+    assert!(app.search_results.is_empty());
+    assert_eq!(app.selected_index, 0);
+    assert_eq!(app.viewport_offset, 0);
+}
+
+#[test]
+fn test_app_clear_selected_proxy_logs() {
+    let mut app = App::new();
+    let proxy_a = ProxyId::new();
+    let proxy_b = ProxyId::new();
+    app.switch_tab(TabType::All);
+    for (id, name) in [(&proxy_a, "a"), (&proxy_b, "b")] {
+        app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+            id: id.clone(),
+            name: name.to_string(),
+            listen_address: "stdio".to_string(),
+            target_command: vec![name.to_string()],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
+            status: ProxyStatus::Running,
+            stats: ProxyStats::default(),
+        }));
+        for i in 0..3 {
+            app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+                LogLevel::Info,
+                format!("{} {}", name, i),
+                id.clone(),
+            )));
+        }
+    }
+
+    // Without a proxy filter or proxy list focus nothing is cleared
+    app.clear_selected_proxy_logs();
+    assert_eq!(app.logs.len(), 6);
+    assert!(app.status_message.is_some());
+
+    // The highlighted proxy is cleared when the proxy list has focus
+    app.switch_focus_to_proxy_list();
+    app.proxy_selected_index = 1;
+    app.clear_selected_proxy_logs();
+    assert_eq!(app.logs.len(), 3);
+    assert!(app.logs.iter().all(|log| log.proxy_id == proxy_a));
+    assert_eq!(app.selected_index, 2);
+
+    // A proxy filter takes precedence
+    app.proxy_selected_index = 0;
+    app.select_current_proxy();
+    app.clear_selected_proxy_logs();
+    assert!(app.logs.is_empty());
+    assert_eq!(app.selected_index, 0);
+}
+
+#[test]
+fn test_app_level_filter() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::Errors);
+    app.set_level_filter(vec![LogLevel::Error]);
+
+    for level in [LogLevel::Error, LogLevel::Warning] {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            level,
+            "failure".to_string(),
+            proxy_id.clone(),
+        )));
+    }
+
+    assert_eq!(app.get_filtered_logs().len(), 1);
+    assert_eq!(app.get_filtered_logs()[0].level, LogLevel::Error);
+    assert_eq!(app.get_tab_log_count(&TabType::Errors), 1);
+    assert_eq!(app.get_tab_log_count(&TabType::All), 1);
+
+    // Clearing logs keeps the filter; Esc in the log view removes it
+    app.clear_logs();
+    assert!(app.level_filter.is_some());
+    app.exit_navigation_mode();
+    assert!(app.level_filter.is_none());
+}
+
+#[test]
+fn test_app_level_filter_dialog() {
+    let mut app = App::new();
+
+    // Every level starts checked; unchecking Response leaves only requests shown
+    app.open_level_filter_dialog();
+    assert_eq!(app.level_filter_selection.len(), LEVEL_FILTER_CHOICES.len());
+    app.level_filter_cursor_down();
+    app.toggle_level_filter_choice();
+    for _ in 0..4 {
+        app.level_filter_cursor_down();
+        app.toggle_level_filter_choice();
+    }
+    app.confirm_level_filter();
+    assert!(!app.show_level_filter_dialog);
+    assert_eq!(
+        app.level_filter,
+        Some([LogLevel::Request].into_iter().collect())
+    );
+
+    // Checking everything again removes the filter
+    app.open_level_filter_dialog();
+    assert_eq!(app.level_filter_selection.len(), 1);
+    for _ in 0..LEVEL_FILTER_CHOICES.len() {
+        app.level_filter_cursor_up();
+    }
+    for _ in 1..LEVEL_FILTER_CHOICES.len() {
+        app.level_filter_cursor_down();
+        app.toggle_level_filter_choice();
+    }
+    app.confirm_level_filter();
+    assert!(app.level_filter.is_none());
+}
+
 #[test]
 fn test_app_log_filtering_by_tab() {
     let mut app = App::new();
@@ -219,11 +762,11 @@ fn test_app_log_filtering_by_proxy() {
     assert_eq!(app.logs.len(), 6);
 
     // No proxy filter - should see all logs
-    app.selected_proxy = None;
+    app.set_proxy_filter(None);
     assert_eq!(app.get_filtered_logs().len(), 6);
 
     // Filter by proxy1
-    app.selected_proxy = Some(proxy_id1.clone());
+    app.set_proxy_filter(Some(proxy_id1.clone()));
     let filtered = app.get_filtered_logs();
     assert_eq!(filtered.len(), 3);
     for log in filtered {
@@ -231,7 +774,7 @@ fn test_app_log_filtering_by_proxy() {
     }
 
     // Filter by proxy2
-    app.selected_proxy = Some(proxy_id2.clone());
+    app.set_proxy_filter(Some(proxy_id2.clone()));
     let filtered = app.get_filtered_logs();
     assert_eq!(filtered.len(), 3);
     for log in filtered {
@@ -298,6 +841,9 @@ fn test_app_tab_switching() {
     app.next_tab();
     assert_eq!(app.active_tab, TabType::System);
 
+    app.next_tab();
+    assert_eq!(app.active_tab, TabType::Bookmarks);
+
     app.next_tab();
     assert_eq!(app.active_tab, TabType::All);
 
@@ -308,6 +854,9 @@ fn test_app_tab_switching() {
     app.prev_tab();
     assert_eq!(app.active_tab, TabType::All);
 
+    app.prev_tab();
+    assert_eq!(app.active_tab, TabType::Bookmarks);
+
     app.prev_tab();
     assert_eq!(app.active_tab, TabType::System);
 
@@ -316,6 +865,122 @@ fn test_app_tab_switching() {
     assert_eq!(app.active_tab, TabType::Errors);
 }
 
+#[test]
+fn test_app_custom_tabs() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    let req_only = TabType::Custom {
+        name: "Req only".to_string(),
+        levels: vec![LogLevel::Request],
+    };
+    app.apply_config(&Config {
+        custom_tabs: vec![CustomTabConfig {
+            name: "Req only".to_string(),
+            levels: vec![LogLevel::Request],
+        }],
+        ..Default::default()
+    });
+    assert_eq!(app.tabs.last(), Some(&req_only));
+
+    for level in [LogLevel::Request, LogLevel::Response] {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            level,
+            "message".to_string(),
+            proxy_id.clone(),
+        )));
+    }
+    assert_eq!(app.get_tab_log_count(&req_only), 1);
+    assert_eq!(app.get_tab_log_count(&TabType::Messages), 2);
+
+    // Custom tabs follow the built-in ones, on keys from 6
+    app.push_key_prefix('6');
+    app.clear_key_prefix();
+    assert_eq!(app.active_tab, req_only);
+    assert_eq!(app.get_filtered_logs()[0].level, LogLevel::Request);
+    app.push_key_prefix('7');
+    app.clear_key_prefix();
+    assert_eq!(app.active_tab, req_only);
+    app.next_tab();
+    assert_eq!(app.active_tab, TabType::All);
+    app.prev_tab();
+    assert_eq!(app.active_tab, req_only);
+
+    // Reloading the config replaces the custom tabs, leaving a removed one
+    app.apply_config(&Config::default());
+    assert_eq!(app.tabs, TabType::ALL.to_vec());
+    assert_eq!(app.active_tab, TabType::Messages);
+}
+
+#[test]
+fn test_app_key_prefix_repeat_count() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    for i in 0..30 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            format!("request {}", i),
+            proxy_id.clone(),
+        )));
+    }
+    app.scroll_to_top();
+
+    // "5j": the digit briefly shows tab 5, then the count moves down five entries
+    app.push_key_prefix('5');
+    assert_eq!(app.key_prefix, "5");
+    assert_eq!(app.active_tab, TabType::Bookmarks);
+    let count = app.take_key_count();
+    app.scroll_down_by(count);
+    assert_eq!(app.active_tab, TabType::Messages);
+    assert_eq!(app.selected_index, 5);
+    assert!(app.key_prefix.is_empty());
+
+    // Multi-digit counts, clamped to the list
+    app.push_key_prefix('1');
+    app.push_key_prefix('2');
+    let count = app.take_key_count();
+    app.scroll_down_by(count);
+    assert_eq!(app.selected_index, 17);
+    app.push_key_prefix('9');
+    app.push_key_prefix('9');
+    let count = app.take_key_count();
+    app.scroll_up_by(count);
+    assert_eq!(app.selected_index, 0);
+
+    // Without a prefix the count is 1; "20G" jumps to the 20th entry
+    let count = app.take_key_count();
+    app.scroll_down_by(count);
+    assert_eq!(app.selected_index, 1);
+    app.push_key_prefix('2');
+    app.push_key_prefix('0');
+    let count = app.take_key_count();
+    app.scroll_to_line(count);
+    assert_eq!(app.selected_index, 19);
+
+    // A digit followed by a key that takes no count keeps the tab switch
+    app.push_key_prefix('3');
+    app.clear_key_prefix();
+    assert_eq!(app.active_tab, TabType::Errors);
+    assert!(app.key_prefix.is_empty());
+    assert_eq!(app.take_key_count(), 1);
+    assert_eq!(app.active_tab, TabType::Errors);
+
+    // Digits typed one after another each select their tab, the last one winning
+    app.push_key_prefix('2');
+    app.push_key_prefix('3');
+    assert_eq!(app.active_tab, TabType::Errors);
+    app.clear_key_prefix();
+    assert_eq!(app.active_tab, TabType::Errors);
+
+    // ... unless a navigation key takes them as a count, back on the first tab
+    app.switch_tab(TabType::Messages);
+    app.push_key_prefix('1');
+    app.push_key_prefix('3');
+    assert_eq!(app.active_tab, TabType::Errors);
+    assert_eq!(app.take_key_count(), 13);
+    assert_eq!(app.active_tab, TabType::Messages);
+}
+
 #[test]
 fn test_app_focus_area_switching() {
     let mut app = App::new();
@@ -344,6 +1009,9 @@ fn test_app_proxy_selection() {
         name: "Proxy A".to_string(),
         listen_address: "127.0.0.1:8080".to_string(),
         target_command: vec!["python".to_string(), "server1.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats::default(),
     };
@@ -352,6 +1020,9 @@ fn test_app_proxy_selection() {
         name: "Proxy B".to_string(),
         listen_address: "127.0.0.1:8081".to_string(),
         target_command: vec!["python".to_string(), "server2.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats::default(),
     };
@@ -446,6 +1117,103 @@ fn test_app_search_functionality() {
     assert!(app.search_results.is_empty());
 }
 
+#[test]
+fn test_app_search_jump_between_matches() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    let other_proxy = ProxyId::new();
+
+    app.switch_tab(TabType::All);
+
+    let log_messages = vec![
+        "User login successful",
+        "Database connection established",
+        "Error: User not found",
+        "Processing request",
+        "Processing user request",
+    ];
+
+    for message in log_messages {
+        let log_entry = LogEntry::new(LogLevel::Info, message.to_string(), proxy_id.clone());
+        app.handle_event(AppEvent::NewLogEntry(log_entry));
+    }
+    // A match hidden by the proxy filter must not be jumped to
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Info,
+        "user from another proxy".to_string(),
+        other_proxy,
+    )));
+    app.set_proxy_filter(Some(proxy_id));
+
+    app.enter_search_mode();
+    for c in "user".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_jump();
+
+    // The full view stays visible while the selection lands on the first match
+    assert_eq!(app.navigation_mode, NavigationMode::SearchJump);
+    assert_eq!(app.get_search_filtered_logs().len(), 5);
+    assert_eq!(app.search_match_positions(), vec![0, 2, 4]);
+    assert_eq!(app.selected_index, 0);
+
+    app.next_search_match();
+    assert_eq!(app.selected_index, 2);
+    app.next_search_match();
+    assert_eq!(app.selected_index, 4);
+    assert!(app.status_message.is_none());
+
+    // Wrap around at the end with a hint
+    app.next_search_match();
+    assert_eq!(app.selected_index, 0);
+    assert!(app.status_message.as_deref().unwrap().contains("BOTTOM"));
+
+    // And backwards from the top
+    app.prev_search_match();
+    assert_eq!(app.selected_index, 4);
+    assert!(app.status_message.as_deref().unwrap().contains("TOP"));
+
+    app.prev_search_match();
+    assert_eq!(app.selected_index, 2);
+    assert!(app.status_message.is_none());
+
+    // Manual navigation continues from the current match
+    app.scroll_up();
+    app.next_search_match();
+    assert_eq!(app.selected_index, 2);
+
+    app.exit_navigation_mode();
+    assert_eq!(app.navigation_mode, NavigationMode::Navigate);
+    assert!(app.search_results.is_empty());
+    assert!(app.status_message.is_none());
+}
+
+#[test]
+fn test_app_search_jump_without_matches() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    app.switch_tab(TabType::All);
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Info,
+        "nothing to see".to_string(),
+        proxy_id,
+    )));
+
+    app.enter_search_mode();
+    for c in "missing".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_jump();
+    app.next_search_match();
+
+    assert_eq!(app.selected_index, 0);
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Pattern not found: missing")
+    );
+}
+
 #[test]
 fn test_app_log_detail_view() {
     let mut app = App::new();
@@ -473,7 +1241,9 @@ fn test_app_log_detail_view() {
     app.toggle_word_wrap();
     assert!(!app.detail_word_wrap);
 
-    // Test scrolling
+    // Test scrolling; the pretty-printed JSON is taller than a one-line viewport
+    app.drain_formatted_content();
+    app.detail_viewport_height = 1;
     assert_eq!(app.detail_scroll_offset, 0);
     app.detail_scroll_down();
     assert!(app.detail_scroll_offset > 0);
@@ -489,26 +1259,354 @@ fn test_app_log_detail_view() {
 }
 
 #[test]
-fn test_app_total_stats() {
+fn test_app_detail_view_search() {
     let mut app = App::new();
-    let proxy_id1 = ProxyId::new();
-    let proxy_id2 = ProxyId::new();
+    let proxy_id = ProxyId::new();
 
-    // Add two proxies with different stats
-    let proxy_info1 = ProxyInfo {
-        id: proxy_id1.clone(),
-        name: "Proxy 1".to_string(),
-        listen_address: "127.0.0.1:8080".to_string(),
-        target_command: vec!["python".to_string(), "server1.py".to_string()],
-        status: ProxyStatus::Running,
-        stats: ProxyStats {
-            proxy_id: proxy_id1.clone(),
-            total_requests: 100,
-            successful_requests: 95,
-            failed_requests: 5,
-            active_connections: 2,
-            uptime: std::time::Duration::from_secs(3600),
+    let json_content = r#"{"method": "tools/call", "params": {"name": "search", "arguments": {"query": "needle"}}, "id": 7}"#;
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        json_content.to_string(),
+        proxy_id,
+    )));
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.drain_formatted_content();
+    app.detail_word_wrap = false;
+
+    let lines = app.detail_lines();
+    assert!(lines.len() > 5);
+
+    app.enter_detail_search();
+    assert!(app.detail_search_active);
+    for c in "NAME".chars() {
+        app.detail_search_input_char(c);
+    }
+    app.detail_search_backspace();
+    app.detail_search_input_char('e');
+    app.confirm_detail_search();
+    assert!(!app.detail_search_active);
+
+    // Matches are case-insensitive; "name" appears in the params key only
+    let matches = app.detail_match_lines();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(app.detail_search_match, Some(matches[0]));
+    assert!(lines[matches[0]].contains("\"name\""));
+    assert_eq!(
+        app.detail_scroll_offset as usize,
+        matches[0].saturating_sub(2)
+    );
+
+    // A query with several hits wraps around with n/N
+    app.enter_detail_search();
+    for c in "e".chars() {
+        app.detail_search_input_char(c);
+    }
+    app.confirm_detail_search();
+    let matches = app.detail_match_lines();
+    assert!(matches.len() > 2);
+    assert_eq!(app.detail_search_match, Some(matches[0]));
+    app.next_detail_match();
+    assert_eq!(app.detail_search_match, Some(matches[1]));
+    app.prev_detail_match();
+    app.prev_detail_match();
+    assert_eq!(app.detail_search_match, matches.last().copied());
+    app.next_detail_match();
+    assert_eq!(app.detail_search_match, Some(matches[0]));
+
+    // Esc clears the search first, leaving the view open
+    assert!(app.has_detail_search());
+    app.exit_detail_search();
+    assert!(!app.has_detail_search());
+    assert!(app.show_detail_view);
+}
+
+#[test]
+fn test_app_detail_search_uses_wrapped_lines() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    let message = "alpha beta gamma delta epsilon needle zeta";
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        message.to_string(),
+        proxy_id,
+    )));
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.drain_formatted_content();
+    app.detail_content_width = 12;
+
+    // With wrap on, lines are broken at word boundaries within the pane width
+    assert!(app.detail_word_wrap);
+    let lines = app.detail_lines();
+    assert_eq!(
+        lines,
+        vec!["alpha beta", "gamma delta", "epsilon", "needle zeta"]
+    );
+
+    app.enter_detail_search();
+    for c in "needle".chars() {
+        app.detail_search_input_char(c);
+    }
+    app.confirm_detail_search();
+    assert_eq!(app.detail_search_match, Some(3));
+
+    // Without wrap the whole message is a single line
+    app.toggle_word_wrap();
+    assert_eq!(app.detail_lines().len(), 1);
+    app.next_detail_match();
+    assert_eq!(app.detail_search_match, Some(0));
+    assert_eq!(app.detail_scroll_offset, 0);
+
+    // Closing the view resets the search
+    app.hide_detail_view();
+    assert!(!app.has_detail_search());
+}
+
+#[test]
+fn test_app_detail_line_numbers_follow_unwrapped_lines() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    let message = "alpha beta gamma delta epsilon needle zeta";
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        message.to_string(),
+        proxy_id,
+    )));
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.drain_formatted_content();
+    app.detail_content_width = 17;
+
+    // Every wrapped row keeps the number of the line it came from
+    assert_eq!(
+        *app.detail_rows(),
+        vec![
+            (0, "alpha beta gamma".to_string()),
+            (0, "delta epsilon".to_string()),
+            (0, "needle zeta".to_string()),
+        ]
+    );
+
+    // The gutter ("1 │ ") comes out of the wrap width
+    assert_eq!(line_number_gutter_width(1), 4);
+    assert_eq!(line_number_gutter_width(1432), 7);
+    app.toggle_line_numbers();
+    assert!(app.detail_line_numbers);
+    let lines = app.detail_lines();
+    assert_eq!(
+        lines,
+        vec!["alpha beta", "gamma delta", "epsilon", "needle zeta"]
+    );
+    assert!(app.detail_rows().iter().all(|&(line, _)| line == 0));
+
+    app.toggle_word_wrap();
+    assert_eq!(*app.detail_rows(), vec![(0, message.to_string())]);
+}
+
+#[test]
+fn test_app_detail_rows_are_cached_until_their_inputs_change() {
+    let mut app = App::new();
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#.to_string(),
+        ProxyId::new(),
+    )));
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.detail_content_width = 40;
+
+    // Drawn before the formatter has delivered, then again once it has
+    app.prepare_detail_viewport(10);
+    assert_eq!(app.detail_lines(), vec![FORMATTING_PLACEHOLDER]);
+    app.drain_formatted_content();
+    assert_eq!(app.detail_rows().len(), 5);
+
+    // Between draws the same rows are handed out rather than wrapped again
+    app.prepare_detail_viewport(10);
+    let rows = app.detail_rows();
+    assert!(Arc::ptr_eq(&rows, &app.detail_rows()));
+    app.prepare_detail_viewport(10);
+    assert!(Arc::ptr_eq(&rows, &app.detail_rows()));
+
+    // A narrower pane wraps them again
+    app.detail_content_width = 12;
+    assert!(app.detail_rows().len() > rows.len());
+    app.toggle_word_wrap();
+    assert_eq!(*app.detail_rows(), *rows);
+}
+
+#[test]
+fn test_app_detail_tabs() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    let message = r#"→ {"jsonrpc":"2.0","method":"tools/call","id":1}"#;
+    let metadata = serde_json::json!({"transport": "stdio", "bytes": 48});
+    app.handle_event(AppEvent::NewLogEntry(
+        LogEntry::new(LogLevel::Request, message.to_string(), proxy_id.clone())
+            .with_metadata(metadata),
+    ));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        r#"{"jsonrpc":"2.0","result":{},"id":1}"#.to_string(),
+        proxy_id,
+    )));
+    app.scroll_to_top();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.drain_formatted_content();
+    app.detail_word_wrap = false;
+    app.prepare_detail_viewport(1);
+
+    // Message is pretty-printed without the metadata
+    assert_eq!(app.detail_tab, DetailTab::Message);
+    let lines = app.detail_lines();
+    assert_eq!(lines[0], "{");
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("\"method\": \"tools/call\"")));
+    assert!(!lines.iter().any(|line| line.contains("transport")));
+
+    // Each tab keeps its own scroll offset
+    app.detail_scroll_down();
+    assert_eq!(app.detail_scroll_offset, 3);
+    app.switch_detail_tab(DetailTab::Metadata);
+    assert_eq!(app.detail_scroll_offset, 0);
+    let lines = app.detail_lines();
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("\"transport\": \"stdio\"")));
+    assert!(app.detail_tab_is_highlighted(app.get_selected_log().unwrap()));
+
+    app.next_detail_tab();
+    assert_eq!(app.detail_tab, DetailTab::Raw);
+    assert_eq!(app.detail_lines(), vec![message]);
+    app.next_detail_tab();
+    assert_eq!(app.detail_tab, DetailTab::Message);
+    assert_eq!(app.detail_scroll_offset, 3);
+    app.prev_detail_tab();
+    assert_eq!(app.detail_tab, DetailTab::Raw);
+
+    // Saving follows the shown tab
+    app.switch_detail_tab(DetailTab::Metadata);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("metadata.json");
+    app.save_detail_to_file(&path, false).unwrap();
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(saved["transport"], "stdio");
+
+    // Entries without metadata keep the tab, with a note; reopening starts on Message
+    app.hide_detail_view();
+    assert_eq!(app.detail_tab, DetailTab::Message);
+    app.scroll_down();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.switch_detail_tab(DetailTab::Metadata);
+    assert_eq!(app.detail_lines(), vec!["No metadata for this entry"]);
+    assert!(!app.detail_tab_is_highlighted(app.get_selected_log().unwrap()));
+}
+
+#[test]
+fn test_app_detail_scroll_clamped_with_word_wrap() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    let message = "alpha beta gamma delta epsilon needle zeta";
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        message.to_string(),
+        proxy_id,
+    )));
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.drain_formatted_content();
+    app.detail_content_width = 12;
+    app.prepare_detail_viewport(2);
+
+    // Four wrapped lines in a two-line viewport: End shows the last two
+    assert_eq!(app.detail_lines().len(), 4);
+    app.detail_scroll_to_bottom();
+    assert_eq!(app.detail_scroll_offset, 2);
+    for _ in 0..5 {
+        app.detail_scroll_down();
+    }
+    assert_eq!(app.detail_scroll_offset, 2);
+    app.detail_scroll_to_top();
+    app.detail_scroll_down();
+    assert_eq!(app.detail_scroll_offset, 2);
+
+    // Unwrapped, the message fits on screen and never scrolls
+    app.toggle_word_wrap();
+    app.detail_scroll_to_bottom();
+    assert_eq!(app.detail_scroll_offset, 0);
+    app.detail_scroll_down();
+    assert_eq!(app.detail_scroll_offset, 0);
+
+    // A taller viewport pulls an offset past the end back into range
+    app.toggle_word_wrap();
+    app.detail_scroll_to_bottom();
+    app.prepare_detail_viewport(3);
+    assert_eq!(app.detail_scroll_offset, 1);
+}
+
+#[test]
+fn test_app_detail_scroll_to_bottom_of_long_content() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    // Pretty-printed, each array item is a line of its own
+    let items: Vec<String> = (0..1500).map(|i| i.to_string()).collect();
+    let message = format!(r#"{{"result":[{}]}}"#, items.join(","));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        message,
+        proxy_id,
+    )));
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.drain_formatted_content();
+    app.detail_word_wrap = false;
+    app.prepare_detail_viewport(20);
+
+    let line_count = app.detail_lines().len();
+    assert!(line_count > 1500);
+    app.detail_scroll_to_bottom();
+    assert_eq!(app.detail_scroll_offset as usize, line_count - 20);
+}
+
+#[test]
+fn test_app_total_stats() {
+    let mut app = App::new();
+    let proxy_id1 = ProxyId::new();
+    let proxy_id2 = ProxyId::new();
+
+    // Add two proxies with different stats
+    let proxy_info1 = ProxyInfo {
+        id: proxy_id1.clone(),
+        name: "Proxy 1".to_string(),
+        listen_address: "127.0.0.1:8080".to_string(),
+        target_command: vec!["python".to_string(), "server1.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats {
+            proxy_id: proxy_id1.clone(),
+            total_requests: 100,
+            successful_requests: 95,
+            failed_requests: 5,
+            active_connections: 2,
+            uptime: std::time::Duration::from_secs(3600),
             bytes_transferred: 1024000,
+            bytes_client_to_server: 24000,
+            bytes_server_to_client: 1000000,
+            max_request_bytes: 500,
+            max_response_bytes: 2000,
+            ..Default::default()
         },
     };
 
@@ -517,6 +1615,9 @@ fn test_app_total_stats() {
         name: "Proxy 2".to_string(),
         listen_address: "127.0.0.1:8081".to_string(),
         target_command: vec!["python".to_string(), "server2.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats {
             proxy_id: proxy_id2.clone(),
@@ -526,6 +1627,14 @@ fn test_app_total_stats() {
             active_connections: 1,
             uptime: std::time::Duration::from_secs(1800),
             bytes_transferred: 512000,
+            bytes_client_to_server: 500000,
+            bytes_server_to_client: 12000,
+            max_request_bytes: 800,
+            max_response_bytes: 100,
+            ipc_connected: false,
+            ipc_buffered: 1200,
+            ipc_dropped: 300,
+            ..Default::default()
         },
     };
 
@@ -538,28 +1647,1595 @@ fn test_app_total_stats() {
     assert_eq!(total_stats.failed_requests, 7);
     assert_eq!(total_stats.active_connections, 3);
     assert_eq!(total_stats.bytes_transferred, 1536000);
+    assert_eq!(total_stats.bytes_client_to_server, 524000);
+    assert_eq!(total_stats.bytes_server_to_client, 1012000);
+    // Maximums are taken across proxies, not summed
+    assert_eq!(total_stats.max_request_bytes, 800);
+    assert_eq!(total_stats.max_response_bytes, 2000);
+    // The longest-running proxy's uptime
+    assert_eq!(total_stats.uptime, std::time::Duration::from_secs(3600));
+    // One disconnected proxy makes the whole link count as down
+    assert!(!total_stats.ipc_connected);
+    assert_eq!(total_stats.ipc_buffered, 1200);
+    assert_eq!(total_stats.ipc_dropped, 300);
 }
 
 #[test]
 fn test_app_log_size_limit() {
     let mut app = App::new();
     let proxy_id = ProxyId::new();
+    assert_eq!(app.max_logs, DEFAULT_MAX_LOGS);
 
-    // Add more than the max log limit (10,000 entries)
-    for i in 0..10005 {
+    // Add more than the default limit
+    for i in 0..DEFAULT_MAX_LOGS + 5 {
         let log_entry = LogEntry::new(LogLevel::Info, format!("Log entry {}", i), proxy_id.clone());
         app.handle_event(AppEvent::NewLogEntry(log_entry));
     }
 
-    // Should be limited to 10,000 entries
-    assert_eq!(app.logs.len(), 10000);
+    assert_eq!(app.logs.len(), DEFAULT_MAX_LOGS);
 
     // The first 5 entries should have been removed, so log should start with "Log entry 5"
     assert!(app.logs[0].message.starts_with("Log entry 5"));
+    let last = format!("Log entry {}", DEFAULT_MAX_LOGS + 4);
+    assert_eq!(app.logs.back().unwrap().message, last);
+}
+
+#[test]
+fn test_app_full_log_evicts_one_entry_per_new_one() {
+    let mut app = App::with_capacity(10);
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    let push = |app: &mut App, index: usize| {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Info,
+            format!("entry {}", index),
+            proxy_id.clone(),
+        )));
+    };
+    for index in 0..10 {
+        push(&mut app, index);
+    }
+    assert!(app.logs.capacity() > 10);
+
+    app.scroll_up_by(4);
+    assert_eq!(app.navigation_mode, NavigationMode::Navigate);
+    assert_eq!(app.selected_index, 5);
+
+    // The oldest entry goes and the selection moves up one to stay on "entry 5"
+    push(&mut app, 10);
+    assert_eq!(app.logs.len(), 10);
+    assert_eq!(app.logs.front().unwrap().message, "entry 1");
+    assert_eq!(app.logs.back().unwrap().message, "entry 10");
+    assert_eq!(app.selected_index, 4);
+    assert_eq!(app.get_filtered_logs()[4].message, "entry 5");
+}
+
+#[test]
+fn test_app_small_log_cap_trims_bursts() {
+    let mut app = App::with_capacity(100);
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    let mut next = 0;
+    let mut burst = |app: &mut App, count: usize| {
+        for _ in 0..count {
+            let level = if next % 10 == 0 {
+                LogLevel::Error
+            } else {
+                LogLevel::Info
+            };
+            app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+                level,
+                format!("entry {}", next),
+                proxy_id.clone(),
+            )));
+            next += 1;
+        }
+    };
+
+    burst(&mut app, 150);
+    assert_eq!(app.logs.len(), 100);
+    assert_eq!(app.logs[0].message, "entry 50");
+    assert_eq!(app.selected_index, 99);
+
+    // A browsing selection stays on the same entry while older ones are dropped
+    app.scroll_up_by(9);
+    assert_eq!(
+        app.get_filtered_logs()[app.selected_index].message,
+        "entry 140"
+    );
+    app.viewport_offset = 80;
+    burst(&mut app, 30);
+    assert_eq!(app.logs.len(), 100);
+    assert_eq!(
+        app.get_filtered_logs()[app.selected_index].message,
+        "entry 140"
+    );
+    assert_eq!(
+        app.get_filtered_logs()[app.viewport_offset].message,
+        "entry 130"
+    );
+
+    // Search results drop the trimmed matches and gain the new ones
+    app.enter_search_mode();
+    for c in "entry 1".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_results();
+    burst(&mut app, 50);
+    let results: Vec<&str> = app
+        .get_search_filtered_logs()
+        .iter()
+        .map(|log| log.message.as_str())
+        .collect();
+    assert!(!results.contains(&"entry 100"));
+    assert!(results.contains(&"entry 219"));
+    assert!(results.iter().all(|message| message.contains('1')));
     assert!(app
-        .logs
-        .last()
-        .unwrap()
+        .search_results
+        .iter()
+        .all(|&seq| app.log_index(seq).is_some()));
+
+    // The Errors tab selection is kept within its shorter list
+    app.exit_search_mode();
+    app.switch_tab(TabType::Errors);
+    assert!(app.selected_index < app.get_filtered_logs().len());
+}
+
+#[test]
+fn test_app_high_volume_keeps_search_selection() {
+    let mut app = App::with_capacity(1000);
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    let push = |app: &mut App, i: usize| {
+        let message = match i % 100 {
+            0 => format!("checkpoint {}", i),
+            _ => format!("tick {}", i),
+        };
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Info,
+            message,
+            proxy_id.clone(),
+        )));
+    };
+    for i in 0..1000 {
+        push(&mut app, i);
+    }
+
+    app.enter_search_mode();
+    for c in "checkpoint".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_results();
+    app.selected_index = 8;
+    app.select_log_at_cursor();
+    assert_eq!(app.get_selected_log().unwrap().message, "checkpoint 800");
+
+    // Results keep naming the same entries while the front is trimmed, expire as their
+    // entries are dropped, and gain the new matches
+    for i in 1000..1500 {
+        push(&mut app, i);
+    }
+    assert_eq!(app.logs.len(), 1000);
+    assert_eq!(app.first_log_seq, 500);
+    let results: Vec<&str> = app
+        .get_search_filtered_logs()
+        .iter()
+        .map(|log| log.message.as_str())
+        .collect();
+    let expected: Vec<String> = (5..15).map(|i| format!("checkpoint {}", i * 100)).collect();
+    assert_eq!(results, expected);
+    assert_eq!(results[app.selected_index], "checkpoint 800");
+
+    // 50,000 more entries trim the whole log 50 times over
+    for i in 1500..51000 {
+        push(&mut app, i);
+    }
+    assert_eq!(app.logs.len(), 1000);
+    assert_eq!(app.logs[0].message, "checkpoint 50000");
+    let results: Vec<&str> = app
+        .get_search_filtered_logs()
+        .iter()
+        .map(|log| log.message.as_str())
+        .collect();
+    let expected: Vec<String> = (500..510)
+        .map(|i| format!("checkpoint {}", i * 100))
+        .collect();
+    assert_eq!(results, expected);
+    assert_eq!(app.selected_index, 0);
+}
+
+#[test]
+fn test_app_unlimited_log_cap() {
+    let mut app = App::with_capacity(0);
+    let proxy_id = ProxyId::new();
+    for i in 0..DEFAULT_MAX_LOGS + 1 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Info,
+            format!("entry {}", i),
+            proxy_id.clone(),
+        )));
+    }
+    assert_eq!(app.logs.len(), DEFAULT_MAX_LOGS + 1);
+}
+
+#[tokio::test]
+async fn test_app_detail_formatting_runs_in_background() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    let json_content = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"x"}}"#;
+    let log_entry = LogEntry::new(LogLevel::Request, json_content.to_string(), proxy_id);
+    let log_id = log_entry.id;
+    app.handle_event(AppEvent::NewLogEntry(log_entry));
+
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+
+    // Nothing is cached until the main loop drains the formatter channel
+    let log = app.get_selected_log().unwrap().clone();
+    assert_eq!(app.format_log_content(&log), FORMATTING_PLACEHOLDER);
+
+    // One tick's worth of draining should populate the cache
+    for _ in 0..50 {
+        app.drain_formatted_content();
+        if app.formatted_cache.contains_key(&log_id) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    let cached = app.formatted_cache.get(&log_id).cloned().unwrap();
+    assert!(cached.contains("\"method\": \"tools/call\""));
+    assert_eq!(app.format_log_content(&log), cached.as_str());
+
+    // Reopening the same entry reuses the cached value instead of reformatting
+    app.hide_detail_view();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    app.drain_formatted_content();
+    assert!(Arc::ptr_eq(&cached, &app.formatted_cache[&log_id]));
+}
+
+#[test]
+fn test_app_exclusions_hide_noisy_methods() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    for i in 0..10 {
+        let message = if i % 2 == 0 {
+            format!(r#"→ {{"jsonrpc":"2.0","method":"ping","id":{}}}"#, i)
+        } else {
+            format!(r#"→ {{"jsonrpc":"2.0","method":"tools/call","id":{}}}"#, i)
+        };
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            message,
+            proxy_id.clone(),
+        )));
+    }
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        r#"→ {"jsonrpc":"2.0","method":"notifications/progress"}"#.to_string(),
+        proxy_id.clone(),
+    )));
+    assert_eq!(app.get_filtered_logs().len(), 11);
+    assert_eq!(app.hidden_log_count(), 0);
+
+    // Pre-seeded exclusions are trimmed and de-duplicated
+    app.set_exclusions(vec![
+        "ping".to_string(),
+        " notifications/ ".to_string(),
+        "ping".to_string(),
+    ]);
+    assert_eq!(app.exclusions, vec!["ping", "notifications/"]);
+    assert_eq!(app.get_filtered_logs().len(), 5);
+    assert_eq!(app.hidden_log_count(), 6);
+    assert!(app
+        .get_filtered_logs()
+        .iter()
+        .all(|log| log.message.contains("tools/call")));
+
+    // New entries are checked as they arrive
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        r#"→ {"jsonrpc":"2.0","method":"ping","id":99}"#.to_string(),
+        proxy_id.clone(),
+    )));
+    assert_eq!(app.hidden_log_count(), 7);
+
+    // Hidden logs don't show up in search results either
+    app.enter_search_mode();
+    for c in "jsonrpc".chars() {
+        app.search_input_char(c);
+    }
+    assert_eq!(app.search_results.len(), 5);
+    app.exit_search_mode();
+
+    // Other tabs only count what they would show
+    app.switch_tab(TabType::Errors);
+    assert_eq!(app.hidden_log_count(), 0);
+    app.switch_tab(TabType::Messages);
+
+    app.remove_exclusion(1);
+    assert_eq!(app.exclusions, vec!["ping"]);
+    assert_eq!(app.hidden_log_count(), 6);
+
+    app.clear_exclusions();
+    assert!(app.exclusions.is_empty());
+    assert_eq!(app.get_filtered_logs().len(), 12);
+    assert_eq!(app.hidden_log_count(), 0);
+}
+
+#[test]
+fn test_app_exclusions_preserve_scroll_position() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    for i in 0..20 {
+        let method = if i % 4 == 0 { "ping" } else { "tools/call" };
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            format!(r#"{{"method":"{}","id":{}}}"#, method, i),
+            proxy_id.clone(),
+        )));
+    }
+
+    // Select entry id 10 (a tools/call) with the viewport starting at id 6
+    app.scroll_to_top();
+    for _ in 0..10 {
+        app.scroll_down();
+    }
+    app.viewport_offset = 6;
+    let selected_message = app.get_filtered_logs()[app.selected_index].message.clone();
+    let top_message = app.get_filtered_logs()[app.viewport_offset].message.clone();
+
+    app.add_exclusion("ping");
+    assert_eq!(app.get_filtered_logs().len(), 15);
+    assert_eq!(
+        app.get_filtered_logs()[app.selected_index].message,
+        selected_message
+    );
+    assert_eq!(
+        app.get_filtered_logs()[app.viewport_offset].message,
+        top_message
+    );
+
+    // Clearing restores everything and keeps the same entries in place
+    app.clear_exclusions();
+    assert_eq!(app.get_filtered_logs().len(), 20);
+    assert_eq!(app.selected_index, 10);
+    assert_eq!(app.viewport_offset, 6);
+
+    // A selected entry that gets hidden moves to the next visible one
+    app.selected_index = 8; // ping
+    app.add_exclusion("ping");
+    assert!(app.get_filtered_logs()[app.selected_index]
         .message
-        .starts_with("Log entry 10004"));
+        .contains(r#""id":9"#));
+}
+
+#[test]
+fn test_app_exclusion_dialog_editing() {
+    let mut app = App::new();
+
+    app.open_exclusion_dialog();
+    assert!(app.show_exclusion_dialog);
+
+    for c in "pinx".chars() {
+        app.exclusion_input_char(c);
+    }
+    app.exclusion_backspace();
+    app.exclusion_input_char('g');
+    app.confirm_exclusion_input();
+    assert_eq!(app.exclusions, vec!["ping"]);
+    assert!(app.exclusion_input.is_empty());
+
+    // Blank and duplicate entries are ignored
+    app.confirm_exclusion_input();
+    for c in "ping".chars() {
+        app.exclusion_input_char(c);
+    }
+    app.confirm_exclusion_input();
+    assert_eq!(app.exclusions, vec!["ping"]);
+
+    for c in "notifications/progress".chars() {
+        app.exclusion_input_char(c);
+    }
+    app.confirm_exclusion_input();
+    assert_eq!(app.exclusion_selected, 1);
+
+    app.exclusion_select_up();
+    app.remove_selected_exclusion();
+    assert_eq!(app.exclusions, vec!["notifications/progress"]);
+    assert_eq!(app.exclusion_selected, 0);
+
+    app.close_exclusion_dialog();
+    assert!(!app.show_exclusion_dialog);
+}
+
+#[test]
+fn test_app_time_range_filters_and_keeps_following() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    let now = chrono::Utc::now();
+
+    for minutes_ago in [10, 8, 4, 2, 1] {
+        let mut entry = LogEntry::new(
+            LogLevel::Request,
+            format!("{} minutes ago", minutes_ago),
+            proxy_id.clone(),
+        );
+        entry.timestamp = now - chrono::Duration::minutes(minutes_ago);
+        app.handle_event(AppEvent::NewLogEntry(entry));
+    }
+    assert_eq!(app.get_filtered_logs().len(), 5);
+
+    // Invalid input keeps the dialog open with an error
+    app.open_time_range_dialog();
+    for c in "later".chars() {
+        app.time_range_input_char(c);
+    }
+    app.confirm_time_range_input();
+    assert!(app.show_time_range_dialog);
+    assert!(app.time_range_error.is_some());
+    assert!(app.time_range.is_none());
+
+    app.open_time_range_dialog();
+    for c in "5m".chars() {
+        app.time_range_input_char(c);
+    }
+    app.confirm_time_range_input();
+    assert!(!app.show_time_range_dialog);
+    assert_eq!(app.time_range.as_ref().unwrap().label, "last 5m");
+    assert_eq!(app.get_filtered_logs().len(), 3);
+    assert_eq!(app.navigation_mode, NavigationMode::Follow);
+    assert_eq!(app.selected_index, 2);
+
+    // The open-ended range still picks up new logs and follow mode tracks them
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        "just now".to_string(),
+        proxy_id.clone(),
+    )));
+    let filtered = app.get_filtered_logs();
+    assert_eq!(filtered.len(), 4);
+    assert_eq!(filtered[app.selected_index].message, "just now");
+
+    // Esc in the log view clears the range like the proxy filter
+    app.scroll_up();
+    assert_eq!(app.navigation_mode, NavigationMode::Navigate);
+    app.exit_navigation_mode();
+    assert!(app.time_range.is_none());
+    assert_eq!(app.navigation_mode, NavigationMode::Follow);
+    assert_eq!(app.get_filtered_logs().len(), 6);
+    assert_eq!(app.selected_index, 5);
+}
+
+#[test]
+fn test_app_jump_to_paired_message() {
+    let mut app = App::new();
+    let proxy_a = ProxyId::new();
+    let proxy_b = ProxyId::new();
+
+    let entries = [
+        (
+            LogLevel::Request,
+            &proxy_a,
+            r#"→ {"jsonrpc":"2.0","method":"tools/list","id":1}"#,
+        ),
+        (
+            LogLevel::Request,
+            &proxy_b,
+            r#"→ {"jsonrpc":"2.0","method":"ping","id":1}"#,
+        ),
+        (
+            LogLevel::Response,
+            &proxy_b,
+            r#"← {"jsonrpc":"2.0","result":{},"id":1}"#,
+        ),
+        (
+            LogLevel::Request,
+            &proxy_a,
+            r#"→ {"jsonrpc":"2.0","method":"tools/call","id":2}"#,
+        ),
+        (
+            LogLevel::Response,
+            &proxy_a,
+            r#"← {"jsonrpc":"2.0","result":{"tools":[]},"id":1}"#,
+        ),
+    ];
+    for (level, proxy_id, message) in entries {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            level,
+            message.to_string(),
+            proxy_id.clone(),
+        )));
+    }
+
+    // From proxy A's response back to its request, skipping proxy B's id 1
+    assert_eq!(app.selected_index, 4);
+    app.jump_to_paired_message();
+    assert!(app.show_detail_view);
+    assert_eq!(app.selected_log_index, Some(0));
+    assert_eq!(app.selected_index, 0);
+    assert_eq!(app.navigation_mode, NavigationMode::Navigate);
+
+    // And forward again from inside the detail view
+    app.jump_to_paired_message();
+    assert_eq!(app.selected_log_index, Some(4));
+    assert_eq!(app.selected_index, 4);
+    assert!(app.status_message.is_none());
+
+    // A request still waiting for its response has no pair
+    app.hide_detail_view();
+    app.scroll_up();
+    assert_eq!(app.selected_index, 3);
+    app.jump_to_paired_message();
+    assert!(!app.show_detail_view);
+    assert_eq!(app.status_message.as_deref(), Some("No paired message"));
+
+    // With a proxy filter the pair is found within that proxy's view
+    app.set_proxy_filter(Some(proxy_b.clone()));
+    app.selected_index = 1;
+    app.jump_to_paired_message();
+    assert_eq!(app.selected_log_index, Some(1));
+    assert_eq!(app.selected_index, 0);
+}
+
+#[test]
+fn test_app_detail_split_view() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    for (level, message) in [
+        (
+            LogLevel::Request,
+            r#"→ {"jsonrpc":"2.0","method":"tools/list","id":1}"#,
+        ),
+        (
+            LogLevel::Request,
+            r#"→ {"jsonrpc":"2.0","method":"ping","id":2}"#,
+        ),
+        (
+            LogLevel::Response,
+            r#"← {"jsonrpc":"2.0","result":{},"id":1}"#,
+        ),
+    ] {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            level,
+            message.to_string(),
+            proxy_id.clone(),
+        )));
+    }
+
+    // Open the response and split: request on the left, response focused on the right
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.toggle_detail_split();
+    assert_eq!(app.detail_pair_index, Some(0));
+    assert_eq!(app.detail_pane, DetailPane::Response);
+    let (request, response) = app.detail_split_logs().unwrap();
+    assert!(request.message.contains("tools/list"));
+    assert!(response.unwrap().message.contains("result"));
+
+    // Each pane scrolls on its own, stopping at the end of its content
+    app.drain_formatted_content();
+    app.detail_viewport_height = 1;
+    app.detail_scroll_down();
+    app.switch_detail_pane();
+    assert_eq!(app.detail_pane, DetailPane::Request);
+    app.detail_scroll_down();
+    app.detail_scroll_down();
+    assert_eq!(app.detail_pane_scroll, [4, 3]);
+    assert_eq!(app.detail_scroll_offset, 0);
+
+    app.toggle_detail_split();
+    assert!(app.detail_split_logs().is_none());
+
+    // A request without a response splits with an empty response pane...
+    app.hide_detail_view();
+    app.scroll_up();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.toggle_detail_split();
+    assert!(app.show_paired_detail);
+    assert_eq!(app.detail_pane, DetailPane::Request);
+    let (request, response) = app.detail_split_logs().unwrap();
+    assert!(request.message.contains("ping"));
+    assert!(response.is_none());
+    assert!(app.get_paired_response().is_none());
+
+    // ...filled in once the response arrives, but not by responses to other requests
+    for id in [3, 2] {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Response,
+            format!(r#"← {{"jsonrpc":"2.0","result":{{}},"id":{}}}"#, id),
+            proxy_id.clone(),
+        )));
+    }
+    assert_eq!(app.detail_pair_index, Some(4));
+    let (_, response) = app.detail_split_logs().unwrap();
+    assert!(response.unwrap().message.contains(r#""id":2"#));
+
+    // A response without a request keeps the single view
+    app.hide_detail_view();
+    app.selected_log_index = Some(3);
+    app.show_detail_view = true;
+    app.toggle_detail_split();
+    assert!(!app.show_paired_detail);
+    assert_eq!(app.status_message.as_deref(), Some("No paired message"));
+}
+
+#[test]
+fn test_app_get_paired_response() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+
+    let entries = [
+        (LogLevel::Request, "call a", "req-1"),
+        (LogLevel::Request, "call b", "req-2"),
+        (LogLevel::Response, "result b", "req-2"),
+        (LogLevel::Response, "result a", "req-1"),
+    ];
+    for (level, message, request_id) in entries {
+        app.handle_event(AppEvent::NewLogEntry(
+            LogEntry::new(level, message.to_string(), proxy_id.clone())
+                .with_request_id(request_id.to_string()),
+        ));
+    }
+
+    // The request "call a" pairs with the later response carrying the same request id
+    app.selected_log_index = Some(0);
+    assert_eq!(app.get_paired_response().unwrap().message, "result a");
+    app.selected_log_index = Some(1);
+    assert_eq!(app.get_paired_response().unwrap().message, "result b");
+
+    // Responses have no paired response, and neither does an unanswered request
+    app.selected_log_index = Some(2);
+    assert!(app.get_paired_response().is_none());
+    app.handle_event(AppEvent::NewLogEntry(
+        LogEntry::new(LogLevel::Request, "call c".to_string(), proxy_id.clone())
+            .with_request_id("req-3".to_string()),
+    ));
+    app.selected_log_index = Some(4);
+    assert!(app.get_paired_response().is_none());
+}
+
+#[test]
+fn test_app_bookmarks_tab() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+
+    for i in 0..5 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Info,
+            format!("entry {}", i),
+            proxy_id.clone(),
+        )));
+    }
+
+    app.scroll_to_top();
+    app.scroll_down();
+    app.toggle_bookmark();
+    assert_eq!(app.bookmarks.len(), 1);
+    assert!(app.is_bookmarked(&app.logs[1]));
+
+    app.switch_tab(TabType::System);
+    app.switch_tab(TabType::Bookmarks);
+    let bookmarked = app.get_filtered_logs();
+    assert_eq!(bookmarked.len(), 1);
+    assert_eq!(bookmarked[0].message, "entry 1");
+    assert_eq!(app.get_tab_log_count(&TabType::Bookmarks), 1);
+
+    // Proxy filters still apply, but don't drop bookmarks
+    app.set_proxy_filter(Some(ProxyId::new()));
+    assert!(app.get_filtered_logs().is_empty());
+    app.clear_proxy_selection();
+    assert_eq!(app.get_filtered_logs().len(), 1);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bookmarks.ndjson");
+    assert_eq!(app.export_bookmarked_logs(&path).unwrap(), 1);
+    let exported = std::fs::read_to_string(&path).unwrap();
+    let entry: LogEntry = serde_json::from_str(exported.trim()).unwrap();
+    assert_eq!(entry.message, "entry 1");
+
+    // Removing the bookmark from the Bookmarks tab empties it
+    app.selected_index = 0;
+    app.toggle_bookmark();
+    assert!(app.bookmarks.is_empty());
+    assert!(app.get_filtered_logs().is_empty());
+    assert_eq!(app.selected_index, 0);
+
+    app.switch_tab(TabType::All);
+    app.toggle_bookmark();
+    app.clear_logs();
+    assert!(app.bookmarks.is_empty());
+}
+
+#[test]
+fn test_app_counts_skipped_messages() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+
+    app.handle_event(AppEvent::MessageSkipped {
+        proxy_id: Some(proxy_id.clone()),
+        reason: "IPC message is not a valid envelope: expected value".to_string(),
+    });
+    app.handle_event(AppEvent::MessageSkipped {
+        proxy_id: None,
+        reason: "IPC message of 20 bytes is over the 10 byte limit".to_string(),
+    });
+
+    assert_eq!(app.skipped_messages, 2);
+    assert_eq!(app.logs.len(), 2);
+    assert_eq!(app.logs[0].level, LogLevel::Warning);
+    assert_eq!(app.logs[0].proxy_id, proxy_id);
+    assert_eq!(
+        app.logs[0].message,
+        "Skipped an IPC message: IPC message is not a valid envelope: expected value"
+    );
+    assert_eq!(app.logs[1].proxy_id, ProxyId::monitor());
+}
+
+#[test]
+fn test_app_requests_per_second() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    let start = chrono::Utc::now() - chrono::Duration::milliseconds(980);
+
+    // Out of the window, and not a request
+    let mut stale = LogEntry::new(LogLevel::Request, "old".to_string(), proxy_id.clone());
+    stale.timestamp = start - chrono::Duration::seconds(5);
+    app.handle_event(AppEvent::NewLogEntry(stale));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        "response".to_string(),
+        proxy_id.clone(),
+    )));
+    for i in 0..100 {
+        let mut entry = LogEntry::new(
+            LogLevel::Request,
+            format!("request {}", i),
+            proxy_id.clone(),
+        );
+        entry.timestamp = start + chrono::Duration::milliseconds(10 * i);
+        app.handle_event(AppEvent::NewLogEntry(entry));
+    }
+
+    let rps = app.requests_per_second();
+    assert!((rps - 100.0).abs() <= 2.0, "got {} req/s", rps);
+    assert_eq!(app.peak_rps, rps);
+    // The stale timestamp was pruned
+    assert_eq!(app.request_timestamps.len(), rps as usize);
+}
+
+#[test]
+fn test_app_request_rate_history() {
+    let mut app = App::new();
+    let start = std::time::Instant::now();
+    let busy = ProxyId::new();
+    let quiet = ProxyId::new();
+
+    for i in 0..4 {
+        let proxy_id = if i == 0 { quiet.clone() } else { busy.clone() };
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            "tools/list".to_string(),
+            proxy_id,
+        )));
+    }
+    // Responses aren't requests
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        "result".to_string(),
+        busy.clone(),
+    )));
+
+    // Nothing to show until a second has passed
+    app.advance_request_rates(start);
+    assert_eq!(app.request_rate.recent(2), vec![0, 0]);
+
+    app.advance_request_rates(start + std::time::Duration::from_millis(1100));
+    assert_eq!(app.request_rate.recent(2), vec![0, 4]);
+    assert_eq!(app.proxy_request_rates[&busy].recent(2), vec![0, 3]);
+    assert_eq!(app.proxy_request_rates[&quiet].recent(2), vec![0, 1]);
+
+    // Quiet seconds go by for every proxy
+    app.advance_request_rates(start + std::time::Duration::from_millis(3100));
+    assert_eq!(app.request_rate.recent(3), vec![4, 0, 0]);
+    assert_eq!(app.proxy_request_rates[&busy].recent(3), vec![3, 0, 0]);
+}
+
+#[test]
+fn test_app_records_protocol_version() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    let info = ProxyInfo {
+        id: proxy_id.clone(),
+        name: "versioned".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    };
+    app.handle_event(AppEvent::ProxyConnected(info.clone()));
+
+    for event in ipc_message_events(IpcMessage::ProxyMetadata {
+        proxy_id: proxy_id.clone(),
+        protocol_version: "2024-11-05".to_string(),
+    }) {
+        app.handle_event(event);
+    }
+    assert_eq!(
+        app.proxies[&proxy_id].protocol_version,
+        Some("2024-11-05".to_string())
+    );
+
+    // Re-announcing after a reconnect keeps it
+    app.handle_event(AppEvent::ProxyConnected(info));
+    assert_eq!(
+        app.proxies[&proxy_id].protocol_version,
+        Some("2024-11-05".to_string())
+    );
+}
+
+#[test]
+fn test_app_tracks_proxy_monitor_connection() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+        id: proxy_id.clone(),
+        name: "flaky".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    }));
+
+    // Stats buffered while the proxy was cut off say so
+    app.handle_event(AppEvent::StatsUpdate(ProxyStats {
+        proxy_id: proxy_id.clone(),
+        ipc_connected: false,
+        ..Default::default()
+    }));
+    assert!(!app.proxies[&proxy_id].monitor_connected);
+
+    app.handle_event(AppEvent::StatsUpdate(ProxyStats {
+        proxy_id: proxy_id.clone(),
+        ..Default::default()
+    }));
+    assert!(app.proxies[&proxy_id].monitor_connected);
+}
+
+#[test]
+fn test_app_compare_logs() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    for value in 1..=3 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Response,
+            format!(r#"{{"jsonrpc":"2.0","id":1,"result":{{"a":{}}}}}"#, value),
+            proxy_id.clone(),
+        )));
+    }
+
+    // Mark the newest, then open the oldest: the older entry is diffed first
+    app.scroll_to_bottom();
+    app.toggle_second_selection();
+    assert_eq!(app.second_selection, Some(2));
+    assert!(app.is_second_selection(&app.logs[2]));
+    app.scroll_to_top();
+    app.select_log_at_cursor();
+    app.show_diff_detail();
+
+    assert!(app.show_diff_view);
+    assert!(app.show_detail_view);
+    let (older, newer) = app.diff_entries().unwrap();
+    assert_eq!((older.id, newer.id), (app.logs[0].id, app.logs[2].id));
+    let removed: Vec<_> = app
+        .diff_lines
+        .iter()
+        .filter(|line| matches!(line, DiffLine::Removed(_)))
+        .collect();
+    let added: Vec<_> = app
+        .diff_lines
+        .iter()
+        .filter(|line| matches!(line, DiffLine::Added(_)))
+        .collect();
+    assert_eq!(removed, [&DiffLine::Removed("    \"a\": 1".to_string())]);
+    assert_eq!(added, [&DiffLine::Added("    \"a\": 3".to_string())]);
+
+    app.diff_scroll_down(100);
+    assert_eq!(app.diff_scroll_offset as usize, app.diff_lines.len() - 1);
+
+    // Closing drops the mark too
+    app.hide_detail_view();
+    assert!(!app.show_diff_view);
+    assert_eq!(app.second_selection, None);
+
+    // Marking the same entry twice clears it
+    app.toggle_second_selection();
+    app.toggle_second_selection();
+    assert_eq!(app.second_selection, None);
+}
+
+#[test]
+fn test_app_bookmarks_pruned_with_old_logs() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+
+    for i in 0..10 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Info,
+            format!("entry {}", i),
+            proxy_id.clone(),
+        )));
+    }
+    app.scroll_to_top();
+    app.toggle_bookmark();
+    let old_bookmark = app.logs[0].id;
+    app.scroll_to_bottom();
+    app.toggle_bookmark();
+    let recent_bookmark = app.logs[9].id;
+    assert_eq!(app.bookmarks.len(), 2);
+
+    // Push the first entry past the log size limit
+    for i in 10..10_001 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Info,
+            format!("entry {}", i),
+            proxy_id.clone(),
+        )));
+    }
+
+    assert!(!app.bookmarks.contains(&old_bookmark));
+    assert!(app.bookmarks.contains(&recent_bookmark));
+    assert_eq!(app.get_tab_log_count(&TabType::Bookmarks), 1);
+}
+
+#[test]
+fn test_app_pause_holds_new_entries() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+        id: proxy_id.clone(),
+        name: "server".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    }));
+    let request = |i: usize| {
+        AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            format!("request {}", i),
+            proxy_id.clone(),
+        ))
+    };
+
+    app.handle_event(request(0));
+    app.toggle_pause();
+    assert!(app.paused);
+    for i in 1..4 {
+        app.handle_event(request(i));
+    }
+    assert_eq!(app.logs.len(), 1);
+    assert_eq!(app.pending_logs.len(), 3);
+
+    // Stats keep applying while paused
+    app.handle_event(AppEvent::StatsUpdate(ProxyStats {
+        proxy_id: proxy_id.clone(),
+        total_requests: 4,
+        ..ProxyStats::default()
+    }));
+    assert_eq!(app.proxies[&proxy_id].stats.total_requests, 4);
+
+    // Resuming adds the held entries in order and follows the newest
+    app.toggle_pause();
+    assert!(!app.paused);
+    assert!(app.pending_logs.is_empty());
+    let messages: Vec<&str> = app.logs.iter().map(|log| log.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec!["request 0", "request 1", "request 2", "request 3"]
+    );
+    assert_eq!(app.selected_index, 3);
+
+    // Clearing while paused drops the held entries too
+    app.toggle_pause();
+    app.handle_event(request(4));
+    app.clear_logs();
+    assert!(app.pending_logs.is_empty());
+    app.toggle_pause();
+    assert!(app.logs.is_empty());
+}
+
+#[test]
+fn test_app_annotations() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    for i in 0..3 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            format!(r#"{{"jsonrpc":"2.0","method":"tools/call","id":{}}}"#, i),
+            proxy_id.clone(),
+        )));
+    }
+
+    // Annotate the entry under the cursor
+    app.scroll_to_top();
+    app.open_annotation_dialog();
+    assert!(app.show_annotation_dialog);
+    for c in "crashed prod ".chars() {
+        app.annotation_input_char(c);
+    }
+    app.annotation_backspace();
+    app.confirm_annotation();
+    assert!(!app.show_annotation_dialog);
+    let first = app.logs[0].clone();
+    assert_eq!(
+        app.annotations.get(&first.id).map(String::as_str),
+        Some("crashed prod")
+    );
+    assert_eq!(app.annotation(&first), Some("crashed prod"));
+    assert_eq!(app.annotation(&app.logs[1]), None);
+
+    // Reopening prefills the note; Esc leaves it unchanged
+    app.open_annotation_dialog();
+    assert_eq!(app.annotation_input, "crashed prod");
+    app.annotation_input_char('x');
+    app.close_annotation_dialog();
+    assert_eq!(app.annotation(&first), Some("crashed prod"));
+
+    // Bookmarked exports carry the note
+    app.toggle_bookmark();
+    app.scroll_down();
+    app.toggle_bookmark();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bookmarks.ndjson");
+    assert_eq!(app.export_bookmarked_logs(&path).unwrap(), 2);
+    let exported: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(exported[0]["annotation"], "crashed prod");
+    assert!(exported[1].get("annotation").is_none());
+
+    // In the detail view, the note belongs to the shown entry and Delete removes it
+    app.scroll_to_top();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.clear_annotation();
+    assert_eq!(app.annotation(&first), None);
+
+    // An empty note removes the annotation too
+    app.open_annotation_dialog();
+    app.annotation_input_char('n');
+    app.confirm_annotation();
+    assert_eq!(app.annotations.len(), 1);
+    app.open_annotation_dialog();
+    app.annotation_backspace();
+    app.confirm_annotation();
+    assert!(app.annotations.is_empty());
+}
+
+#[test]
+fn test_app_toggle_proxy_panel() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    for i in 0..3 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            format!("request {}", i),
+            proxy_id.clone(),
+        )));
+    }
+    let visible_logs = app.get_filtered_logs().len();
+    assert!(app.proxy_panel_visible);
+
+    app.switch_focus_to_proxy_list();
+    app.toggle_proxy_panel();
+    assert!(!app.proxy_panel_visible);
+    assert_eq!(app.focus_area, FocusArea::LogView);
+    assert_eq!(app.get_filtered_logs().len(), visible_logs);
+
+    // Focus can't move to the hidden panel
+    app.switch_focus_to_proxy_list();
+    assert_eq!(app.focus_area, FocusArea::LogView);
+    assert_eq!(
+        app.status_message.as_deref(),
+        Some("Press P to show proxies")
+    );
+
+    app.toggle_proxy_panel();
+    assert!(app.proxy_panel_visible);
+    assert_eq!(app.focus_area, FocusArea::ProxyList);
+    assert_eq!(app.status_message, None);
+    assert_eq!(app.get_filtered_logs().len(), visible_logs);
+}
+
+#[test]
+fn test_app_save_detail_to_file() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        r#"→ {"jsonrpc":"2.0","id":7,"method":"tools/call","params":{}}"#.to_string(),
+        proxy_id.clone(),
+    )));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        r#"← {"jsonrpc":"2.0","id":7,"result":{"ok":true}}"#.to_string(),
+        proxy_id.clone(),
+    )));
+
+    // Responses are named after their request's method
+    app.scroll_to_bottom();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.open_save_dialog(false);
+    assert!(app.show_save_dialog);
+    let timestamp = app.logs[1].timestamp.format("%Y%m%d-%H%M%S");
+    assert_eq!(app.save_input, format!("tools_call-{}.json", timestamp));
+    assert_eq!(app.save_cursor, app.save_input.len());
+
+    // Point the prompt at a temporary directory using the editing keys
+    let dir = tempfile::tempdir().unwrap();
+    app.save_cursor_home();
+    for c in format!("{}/", dir.path().display()).chars() {
+        app.save_input_char(c);
+    }
+    app.confirm_save_dialog();
+    assert!(!app.show_save_dialog);
+    let saved =
+        std::fs::read_to_string(dir.path().join(format!("tools_call-{}.json", timestamp))).unwrap();
+    assert!(
+        saved.contains("\n    \"ok\": true"),
+        "content is pretty-printed"
+    );
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&saved).unwrap(),
+        serde_json::json!({"jsonrpc": "2.0", "id": 7, "result": {"ok": true}})
+    );
+    assert!(app.status_message.as_deref().unwrap().starts_with("Saved"));
+
+    // Raw mode writes the message exactly as received
+    let raw_path = dir.path().join("raw.txt");
+    let bytes = app.save_detail_to_file(&raw_path, true).unwrap();
+    assert_eq!(bytes, app.logs[1].message.len());
+    assert_eq!(
+        std::fs::read_to_string(&raw_path).unwrap(),
+        app.logs[1].message
+    );
+
+    // Failures keep the prompt open and report the error
+    app.open_save_dialog(true);
+    assert!(app.save_input.ends_with(".raw.txt"));
+    app.save_cursor_end();
+    while !app.save_input.is_empty() {
+        app.save_backspace();
+    }
+    for c in dir
+        .path()
+        .join("missing/out.txt")
+        .display()
+        .to_string()
+        .chars()
+    {
+        app.save_input_char(c);
+    }
+    app.confirm_save_dialog();
+    assert!(app.show_save_dialog);
+    assert!(app
+        .status_message
+        .as_deref()
+        .unwrap()
+        .starts_with("Save failed: failed to write"));
+
+    app.hide_detail_view();
+    assert!(!app.show_save_dialog);
+}
+
+#[test]
+fn test_app_detail_syntax_highlight_only_for_json() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        r#"→ {"jsonrpc":"2.0","id":1,"method":"ping"}"#.to_string(),
+        proxy_id.clone(),
+    )));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        "← not json at all".to_string(),
+        proxy_id.clone(),
+    )));
+
+    app.scroll_to_top();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.drain_formatted_content();
+    let json_log = app.logs[0].clone();
+    assert!(app.detail_is_highlighted(&json_log));
+
+    app.toggle_syntax_highlight();
+    assert!(!app.detail_is_highlighted(&json_log));
+    app.toggle_syntax_highlight();
+
+    app.hide_detail_view();
+    app.scroll_to_bottom();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.drain_formatted_content();
+    let text_log = app.logs[1].clone();
+    assert!(!app.detail_is_highlighted(&text_log));
+}
+
+#[test]
+fn test_app_detail_tree_expand_collapse() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        r#"← {"jsonrpc":"2.0","id":1,"result":{"tools":[{"name":"a"},{"name":"b"}]}}"#.to_string(),
+        proxy_id.clone(),
+    )));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Response,
+        "← plain text".to_string(),
+        proxy_id.clone(),
+    )));
+
+    app.scroll_to_top();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    assert!(app.detail_tree_rows().is_none(), "text view by default");
+
+    app.toggle_detail_tree();
+    // Only the root starts expanded
+    let rows = app.detail_tree_rows().unwrap();
+    let pointers: Vec<&str> = rows.iter().map(|row| row.pointer.as_str()).collect();
+    assert_eq!(pointers, vec!["", "/id", "/jsonrpc", "/result"]);
+
+    app.detail_tree_move(isize::MAX);
+    assert_eq!(app.detail_tree_selected, 3);
+    app.detail_tree_toggle_selected();
+    assert_eq!(app.detail_tree_rows().unwrap().len(), 5);
+    app.detail_tree_move(1);
+    app.detail_tree_toggle_selected();
+    assert_eq!(app.detail_tree_rows().unwrap().len(), 7);
+
+    // Collapsing a parent hides its children but remembers their state
+    app.detail_tree_move(-1);
+    app.detail_tree_toggle_selected();
+    assert_eq!(app.detail_tree_rows().unwrap().len(), 4);
+    app.detail_tree_toggle_selected();
+    assert_eq!(app.detail_tree_rows().unwrap().len(), 7);
+
+    // Opening another log resets the expansion; non-JSON keeps the text view
+    app.hide_detail_view();
+    app.scroll_to_bottom();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    assert!(app.detail_tree_rows().is_none());
+
+    app.hide_detail_view();
+    app.scroll_to_top();
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    assert_eq!(app.detail_tree_rows().unwrap().len(), 4);
+    assert_eq!(app.detail_tree_selected, 0);
+
+    // Search switches back to the text view
+    app.enter_detail_search();
+    assert!(!app.detail_tree_mode);
+}
+
+#[test]
+fn test_app_cycle_timestamp_mode_saves_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("monitor.toml");
+
+    let mut app = App::new();
+    assert_eq!(app.timestamp_mode, TimestampMode::Absolute);
+    app.cycle_timestamp_mode();
+    assert_eq!(app.timestamp_mode, TimestampMode::Relative);
+    assert!(!path.exists(), "nothing is saved without a config path");
+
+    app.config_path = Some(path.clone());
+    app.cycle_timestamp_mode();
+    assert_eq!(app.timestamp_mode, TimestampMode::Elapsed);
+
+    let mut restored = App::new();
+    restored.apply_config(&Config::load(&path).unwrap());
+    assert_eq!(restored.timestamp_mode, TimestampMode::Elapsed);
+
+    app.cycle_timestamp_mode();
+    assert_eq!(app.timestamp_mode, TimestampMode::Absolute);
+}
+
+#[test]
+fn test_app_logs_batch_adds_every_entry() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+
+    let batch: Vec<LogEntry> = (0..5)
+        .map(|i| {
+            LogEntry::new(
+                LogLevel::Request,
+                format!("request {}", i),
+                proxy_id.clone(),
+            )
+        })
+        .collect();
+    let events = ipc_message_events(IpcMessage::LogsBatch(batch.clone()));
+    assert_eq!(events.len(), 5);
+    for event in events {
+        app.handle_event(event);
+    }
+
+    let messages: Vec<&str> = app.logs.iter().map(|log| log.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "request 0",
+            "request 1",
+            "request 2",
+            "request 3",
+            "request 4"
+        ]
+    );
+    assert_eq!(app.logs[4].id, batch[4].id);
+
+    // Single entries and other messages still map as before
+    let single = LogEntry::new(LogLevel::Info, "single".to_string(), proxy_id.clone());
+    assert_eq!(ipc_message_events(IpcMessage::LogEntry(single)).len(), 1);
+    assert!(ipc_message_events(IpcMessage::Ping).is_empty());
+}
+
+/// Entries `tab` should list, filtered from scratch
+fn expected_tab_messages(app: &App, tab: &TabType) -> Vec<String> {
+    app.logs
+        .iter()
+        .filter(|log| {
+            let proxy_ok = match app.selected_proxy {
+                Some(ref proxy_id) => &log.proxy_id == proxy_id,
+                None => true,
+            };
+            let time_ok = match app.time_range {
+                Some(ref range) => range.contains(&log.timestamp),
+                None => true,
+            };
+            let level_ok = match app.level_filter {
+                Some(ref levels) => levels.contains(&log.level),
+                None => true,
+            };
+            let tab_ok = match tab {
+                TabType::All => true,
+                TabType::Messages => matches!(log.level, LogLevel::Request | LogLevel::Response),
+                TabType::Errors => matches!(log.level, LogLevel::Error | LogLevel::Warning),
+                TabType::System => matches!(log.level, LogLevel::Info | LogLevel::Debug),
+                TabType::Bookmarks => app.bookmarks.contains(&log.id),
+                TabType::Custom { levels, .. } => levels.contains(&log.level),
+            };
+            proxy_ok && time_ok && level_ok && tab_ok && !app.excluded_log_ids.contains(&log.id)
+        })
+        .map(|log| log.message.clone())
+        .collect()
+}
+
+#[test]
+fn test_app_cached_tab_lists_match_fresh_filter() {
+    // A small LCG keeps the sequence reproducible without a rand dependency
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = |n: usize| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as usize % n
+    };
+
+    let mut app = App::with_capacity(60);
+    app.set_custom_tabs(&[CustomTabConfig {
+        name: "Problems".to_string(),
+        levels: vec![LogLevel::Error, LogLevel::Warning, LogLevel::Request],
+    }]);
+    let proxies = [ProxyId::new(), ProxyId::new(), ProxyId::new()];
+    let methods = ["ping", "tools/list", "noise/heartbeat"];
+
+    for step in 0..3000 {
+        match next(20) {
+            0..=9 => {
+                let level = LEVEL_FILTER_CHOICES[next(LEVEL_FILTER_CHOICES.len())].clone();
+                let message = format!(
+                    r#"{{"jsonrpc":"2.0","id":{},"method":"{}"}}"#,
+                    step,
+                    methods[next(methods.len())]
+                );
+                let proxy_id = proxies[next(proxies.len())].clone();
+                app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+                    level, message, proxy_id,
+                )));
+            }
+            10 => app.toggle_pause(),
+            11 => app.switch_tab(app.tabs[next(app.tabs.len())].clone()),
+            12 => match next(2) {
+                0 => app.set_proxy_filter(Some(proxies[next(proxies.len())].clone())),
+                _ => app.clear_proxy_selection(),
+            },
+            13 => {
+                let levels = LEVEL_FILTER_CHOICES
+                    .iter()
+                    .filter(|_| next(2) == 0)
+                    .cloned()
+                    .collect();
+                app.set_level_filter(levels);
+            }
+            14 => match next(2) {
+                0 => app.add_exclusion("noise"),
+                _ => app.clear_exclusions(),
+            },
+            15 => {
+                let count = app.get_search_filtered_logs().len();
+                if count > 0 {
+                    app.selected_index = next(count);
+                    app.toggle_bookmark();
+                }
+            }
+            16 => match next(3) {
+                0 if !app.logs.is_empty() => {
+                    let start = app.logs[next(app.logs.len())].timestamp;
+                    app.set_time_range(Some(TimeRange {
+                        start: Some(start),
+                        end: None,
+                        label: "test".to_string(),
+                    }));
+                }
+                _ => app.clear_time_range(),
+            },
+            17 => {
+                app.enter_search_mode();
+                for c in "ping".chars() {
+                    app.search_input_char(c);
+                }
+                app.confirm_search_results();
+            }
+            18 => app.exit_navigation_mode(),
+            _ => match next(10) {
+                0 => app.clear_logs(),
+                _ => app.clear_proxy_logs(&proxies[next(proxies.len())]),
+            },
+        }
+
+        for tab in &app.tabs {
+            let cached: Vec<String> = app.tab_log_seqs[tab]
+                .iter()
+                .map(|&seq| app.logs[app.log_index(seq).unwrap()].message.clone())
+                .collect();
+            assert_eq!(cached, expected_tab_messages(&app, tab), "step {}", step);
+            assert_eq!(app.get_tab_log_count(tab), cached.len());
+        }
+        let filtered: Vec<String> = app
+            .get_filtered_logs()
+            .iter()
+            .map(|log| log.message.clone())
+            .collect();
+        assert_eq!(filtered, expected_tab_messages(&app, &app.active_tab));
+    }
+}
+
+#[test]
+fn test_app_visible_window_matches_full_list() {
+    let mut app = App::with_capacity(10_000);
+    let proxy_id = ProxyId::new();
+    for i in 0..12_000 {
+        let level = if i % 3 == 0 {
+            LogLevel::Info
+        } else {
+            LogLevel::Request
+        };
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            level,
+            format!("entry {}", i),
+            proxy_id.clone(),
+        )));
+    }
+
+    let check = |app: &mut App| {
+        let height = 40;
+        app.prepare_viewport(height);
+        let full: Vec<String> = app
+            .get_search_filtered_logs()
+            .iter()
+            .map(|log| log.message.clone())
+            .collect();
+        assert_eq!(app.get_search_filtered_count(), full.len());
+        let start = app.viewport_offset.min(full.len().saturating_sub(1));
+        let end = (start + height).min(full.len());
+        let visible: Vec<String> = app
+            .get_visible_logs(height)
+            .iter()
+            .map(|log| log.message.clone())
+            .collect();
+        assert_eq!(visible, full[start..end].to_vec());
+        for position in [0, start, full.len().saturating_sub(1), full.len()] {
+            assert_eq!(
+                app.get_search_filtered_log(position)
+                    .map(|log| log.message.clone()),
+                full.get(position).cloned()
+            );
+        }
+    };
+
+    // Following, browsing the middle, and at the top of each tab
+    check(&mut app);
+    app.scroll_to_line(3000);
+    check(&mut app);
+    app.switch_tab(TabType::All);
+    app.scroll_to_top();
+    check(&mut app);
+
+    // Search results, before and after more entries trim the front
+    app.enter_search_mode();
+    for c in "entry 11".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_results();
+    app.scroll_to_line(50);
+    check(&mut app);
+    for i in 12_000..13_000 {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            format!("entry {}", i),
+            proxy_id.clone(),
+        )));
+    }
+    check(&mut app);
+
+    app.switch_tab(TabType::Errors);
+    check(&mut app);
+}
+
+#[test]
+fn test_app_help_dialog_scrolls_and_resets_on_open() {
+    let mut app = App::new();
+    app.open_help_dialog();
+    assert!(app.show_help_dialog);
+
+    app.help_scroll_down(1);
+    app.help_scroll_down(1);
+    assert_eq!(app.help_scroll_offset, 2);
+    app.help_scroll_up(1);
+    assert_eq!(app.help_scroll_offset, 1);
+
+    // A draw at a size that leaves 5 lines hidden caps the offset
+    app.help_scroll_down(10);
+    app.set_help_max_scroll(5);
+    assert_eq!(app.help_scroll_offset, 5);
+    app.help_scroll_down(1);
+    assert_eq!(app.help_scroll_offset, 5);
+
+    app.show_help_dialog = false;
+    app.open_help_dialog();
+    assert_eq!(app.help_scroll_offset, 0);
 }
@@ -0,0 +1,126 @@
+use mcp_common::LogLevel;
+use mcp_monitor::{
+    monitor_app, App, Config, CustomTabConfig, MonitorArgs, TimestampMode, MIN_TICK_RATE_MS,
+};
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn test_config_round_trip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("nested").join("monitor.toml");
+
+    let config = Config {
+        timestamp_mode: TimestampMode::Elapsed,
+        tick_rate_ms: None,
+        slow_threshold_ms: None,
+        custom_tabs: Vec::new(),
+        follow_proxy_on_connect: false,
+        error_responses_in_errors_tab: false,
+    };
+    config.save(&path).unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap().trim(),
+        "timestamp_mode = \"elapsed\""
+    );
+    assert_eq!(Config::load(&path).unwrap(), config);
+}
+
+#[test]
+fn test_config_missing_file_and_keys_use_defaults() {
+    let dir = tempdir().unwrap();
+    assert_eq!(
+        Config::load(&dir.path().join("missing.toml")).unwrap(),
+        Config::default()
+    );
+
+    let path = dir.path().join("empty.toml");
+    std::fs::write(&path, "").unwrap();
+    assert_eq!(
+        Config::load(&path).unwrap().timestamp_mode,
+        TimestampMode::Absolute
+    );
+
+    std::fs::write(&path, "timestamp_mode = \"sometimes\"").unwrap();
+    assert!(Config::load(&path).is_err());
+}
+
+#[test]
+fn test_tick_rate_from_config_and_command_line() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("monitor.toml");
+    std::fs::write(&path, "tick_rate_ms = 500").unwrap();
+
+    let mut app = App::new();
+    assert_eq!(app.tick_rate, Duration::from_millis(250));
+    app.apply_config(&Config::load(&path).unwrap());
+    assert_eq!(app.tick_rate, Duration::from_millis(500));
+
+    // Cycling the timestamp format keeps the tick rate in the file
+    app.config_path = Some(path.clone());
+    app.cycle_timestamp_mode();
+    assert_eq!(Config::load(&path).unwrap().tick_rate_ms, Some(500));
+
+    app.set_tick_rate_ms(0);
+    assert_eq!(app.tick_rate, Duration::from_millis(MIN_TICK_RATE_MS));
+
+    let args = MonitorArgs {
+        ipc_socket: "/tmp/unused.sock".to_string(),
+        ipc_token: None,
+        verbose: false,
+        exclude: Vec::new(),
+        metrics_port: None,
+        max_logs: 100,
+        max_connections: 0,
+        tick_rate_ms: Some(100),
+    };
+    let app = monitor_app(&args);
+    assert_eq!(app.tick_rate, Duration::from_millis(100));
+    assert!(!app.show_debug_overlay);
+}
+
+#[test]
+fn test_config_custom_tabs() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("monitor.toml");
+    std::fs::write(
+        &path,
+        "[[custom_tabs]]\nname = \"Problems\"\nlevels = [\"Error\", \"Warning\"]\n",
+    )
+    .unwrap();
+
+    let config = Config::load(&path).unwrap();
+    assert_eq!(
+        config.custom_tabs,
+        vec![CustomTabConfig {
+            name: "Problems".to_string(),
+            levels: vec![LogLevel::Error, LogLevel::Warning],
+        }]
+    );
+
+    std::fs::write(
+        &path,
+        "[[custom_tabs]]\nname = \"Bad\"\nlevels = [\"Loud\"]\n",
+    )
+    .unwrap();
+    assert!(Config::load(&path).is_err());
+}
+
+#[test]
+fn test_follow_proxy_on_connect_is_saved() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("monitor.toml");
+    std::fs::write(&path, "follow_proxy_on_connect = true").unwrap();
+
+    let mut app = App::new();
+    app.apply_config(&Config::load(&path).unwrap());
+    assert!(app.follow_proxy_on_connect);
+
+    app.config_path = Some(path.clone());
+    app.toggle_follow_proxy_on_connect();
+    assert!(!app.follow_proxy_on_connect);
+    assert!(!Config::load(&path).unwrap().follow_proxy_on_connect);
+    app.toggle_follow_proxy_on_connect();
+    assert!(Config::load(&path).unwrap().follow_proxy_on_connect);
+}
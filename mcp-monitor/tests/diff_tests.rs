@@ -0,0 +1,61 @@
+use mcp_monitor::{compute_diff, DiffLine};
+
+fn context(line: &str) -> DiffLine {
+    DiffLine::Context(line.to_string())
+}
+
+fn added(line: &str) -> DiffLine {
+    DiffLine::Added(line.to_string())
+}
+
+fn removed(line: &str) -> DiffLine {
+    DiffLine::Removed(line.to_string())
+}
+
+#[test]
+fn test_compute_diff_changed_value() {
+    assert_eq!(
+        compute_diff(r#"{"a":1}"#, r#"{"a":2}"#),
+        vec![removed(r#"{"a":1}"#), added(r#"{"a":2}"#)]
+    );
+    assert_eq!(
+        compute_diff("{\n  \"a\": 1\n}", "{\n  \"a\": 2\n}"),
+        vec![
+            context("{"),
+            removed("  \"a\": 1"),
+            added("  \"a\": 2"),
+            context("}")
+        ]
+    );
+}
+
+#[test]
+fn test_compute_diff_keeps_common_lines() {
+    let diff = compute_diff("a\nb\nc\nd\ne", "a\nc\nd\nx\ne\nf");
+    assert_eq!(
+        diff,
+        vec![
+            context("a"),
+            removed("b"),
+            context("c"),
+            context("d"),
+            added("x"),
+            context("e"),
+            added("f"),
+        ]
+    );
+
+    assert_eq!(compute_diff("same\n", "same"), vec![context("same")]);
+    assert_eq!(compute_diff("", "new"), vec![added("new")]);
+}
+
+#[test]
+fn test_compute_diff_very_different_inputs() {
+    // Past the search limit the changed middle is replaced wholesale
+    let a: Vec<String> = (0..1500).map(|i| format!("a{}", i)).collect();
+    let b: Vec<String> = (0..1500).map(|i| format!("b{}", i)).collect();
+    let diff = compute_diff(&a.join("\n"), &b.join("\n"));
+    assert_eq!(diff.len(), 3000);
+    assert_eq!(diff[0], removed("a0"));
+    assert_eq!(diff[1500], added("b0"));
+}
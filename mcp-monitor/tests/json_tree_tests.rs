@@ -0,0 +1,55 @@
+use mcp_monitor::{tree_rows, JsonTreeValue};
+use serde_json::json;
+use std::collections::HashSet;
+
+#[test]
+fn test_tree_rows_collapsed_root_shows_child_count() {
+    let value = json!({"jsonrpc": "2.0", "id": 1, "result": {"tools": []}});
+    let rows = tree_rows(&value, &HashSet::new());
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].pointer, "");
+    assert_eq!(rows[0].label, None);
+    assert_eq!(
+        rows[0].value,
+        JsonTreeValue::Object {
+            len: 3,
+            expanded: false
+        }
+    );
+}
+
+#[test]
+fn test_tree_rows_expand_by_json_pointer() {
+    let value = json!({"result": {"tools": [{"name": "read"}, {"name": "write"}]}, "a/b": 1});
+    let expanded: HashSet<String> = ["", "/result", "/result/tools", "/result/tools/1"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let rows = tree_rows(&value, &expanded);
+
+    let pointers: Vec<&str> = rows.iter().map(|row| row.pointer.as_str()).collect();
+    assert_eq!(
+        pointers,
+        vec![
+            "",
+            "/a~1b",
+            "/result",
+            "/result/tools",
+            "/result/tools/0",
+            "/result/tools/1",
+            "/result/tools/1/name",
+        ]
+    );
+    assert_eq!(rows[4].label.as_deref(), Some("[0]"));
+    assert_eq!(
+        rows[4].value,
+        JsonTreeValue::Object {
+            len: 1,
+            expanded: false
+        }
+    );
+    assert_eq!(rows[6].depth, 4);
+    assert_eq!(rows[6].value, JsonTreeValue::Leaf(json!("write")));
+    assert!(!rows[6].is_expandable());
+}
@@ -0,0 +1,99 @@
+use mcp_common::*;
+use mcp_monitor::*;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+
+fn app_with_traffic() -> App {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+        id: proxy_id.clone(),
+        name: "files \"prod\"".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    }));
+    app.handle_event(AppEvent::StatsUpdate(ProxyStats {
+        proxy_id: proxy_id.clone(),
+        total_requests: 10,
+        successful_requests: 8,
+        failed_requests: 2,
+        active_connections: 1,
+        in_flight_requests: 3,
+        bytes_transferred: 4096,
+        ..Default::default()
+    }));
+    for level in [LogLevel::Request, LogLevel::Request, LogLevel::Error] {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            level,
+            "message".to_string(),
+            proxy_id.clone(),
+        )));
+    }
+    app
+}
+
+#[test]
+fn test_prometheus_snapshot_format() {
+    let snapshot = app_with_traffic().prometheus_snapshot();
+
+    assert!(snapshot.contains("# TYPE mcp_requests_total counter\n"));
+    assert!(snapshot.contains(r#"mcp_requests_total{proxy="files \"prod\"",status="success"} 8"#));
+    assert!(snapshot.contains(r#"mcp_requests_total{proxy="files \"prod\"",status="failed"} 2"#));
+    assert!(snapshot.contains(r#"mcp_bytes_transferred_total{proxy="files \"prod\""} 4096"#));
+    assert!(snapshot.contains("# TYPE mcp_active_connections gauge\n"));
+    assert!(snapshot.contains(r#"mcp_active_connections{proxy="files \"prod\""} 1"#));
+    assert!(snapshot.contains("# TYPE mcp_in_flight_requests gauge\n"));
+    assert!(snapshot.contains(r#"mcp_in_flight_requests{proxy="files \"prod\""} 3"#));
+    assert!(snapshot.contains(r#"mcp_log_entries_total{proxy="files \"prod\"",level="request"} 2"#));
+    assert!(snapshot.contains(r#"mcp_log_entries_total{proxy="files \"prod\"",level="error"} 1"#));
+}
+
+#[test]
+fn test_log_entry_counts_survive_clearing_logs() {
+    let mut app = app_with_traffic();
+    app.clear_logs();
+
+    assert!(app.prometheus_snapshot().contains(r#"level="request"} 2"#));
+}
+
+async fn http_get(addr: std::net::SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_metrics_endpoint_serves_snapshot() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Arc::new(RwLock::new(app_with_traffic()));
+    tokio::spawn(serve_metrics(listener, app.clone()));
+
+    let response = http_get(addr, "/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+    for name in [
+        "mcp_requests_total",
+        "mcp_bytes_transferred_total",
+        "mcp_active_connections",
+        "mcp_log_entries_total",
+    ] {
+        assert!(response.contains(name), "missing {}", name);
+    }
+
+    let response = http_get(addr, "/").await;
+    assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+}
@@ -0,0 +1,38 @@
+use mcp_monitor::{RateHistory, RATE_HISTORY_SECONDS};
+
+#[test]
+fn test_rate_history_counts_per_second() {
+    let mut rate = RateHistory::default();
+    for _ in 0..3 {
+        rate.record();
+    }
+    rate.advance(1);
+    rate.record();
+    // Two seconds pass, the second without requests
+    rate.advance(2);
+
+    assert_eq!(rate.recent(3), vec![3, 1, 0]);
+    // Padded before the history starts, so the newest second stays at the right
+    assert_eq!(rate.recent(5), vec![0, 0, 3, 1, 0]);
+    assert_eq!(rate.recent(2), vec![1, 0]);
+    assert!((rate.per_second(2) - 0.5).abs() < f64::EPSILON);
+    assert!((rate.per_second(4) - 1.0).abs() < f64::EPSILON);
+
+    // The second under way isn't shown until it's over
+    rate.record();
+    assert_eq!(rate.recent(1), vec![0]);
+    rate.advance(0);
+    assert_eq!(rate.recent(1), vec![0]);
+}
+
+#[test]
+fn test_rate_history_is_bounded() {
+    let mut rate = RateHistory::default();
+    rate.record();
+    rate.advance(1);
+    rate.advance(10 * RATE_HISTORY_SECONDS as u64);
+
+    let recent = rate.recent(RATE_HISTORY_SECONDS + 1);
+    assert_eq!(recent.len(), RATE_HISTORY_SECONDS + 1);
+    assert!(recent.iter().all(|&count| count == 0));
+}
@@ -0,0 +1,155 @@
+use mcp_common::*;
+use mcp_monitor::*;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+/// `count` log envelopes recorded `gap_ms` apart
+fn recording(count: usize, gap_ms: i64) -> Vec<IpcEnvelope> {
+    let proxy_id = ProxyId::new();
+    let start = chrono::Utc::now();
+    (0..count)
+        .map(|index| IpcEnvelope {
+            message: IpcMessage::LogEntry(LogEntry::new(
+                LogLevel::Info,
+                format!("entry {}", index),
+                proxy_id.clone(),
+            )),
+            timestamp: start + chrono::Duration::milliseconds(gap_ms * index as i64),
+            correlation_id: None,
+            sequence: 0,
+            session_id: None,
+        })
+        .collect()
+}
+
+/// Next progress report, skipping log entries
+async fn next_progress(rx: &mut mpsc::Receiver<AppEvent>) -> ReplayProgress {
+    loop {
+        match timeout(Duration::from_secs(5), rx.recv()).await {
+            Ok(Some(AppEvent::ReplayProgress(progress))) => return progress,
+            Ok(Some(_)) => continue,
+            other => panic!("no replay progress: {:?}", other.is_ok()),
+        }
+    }
+}
+
+/// Next log entry message, skipping progress reports
+async fn next_log(rx: &mut mpsc::Receiver<AppEvent>, wait: Duration) -> Option<String> {
+    loop {
+        match timeout(wait, rx.recv()).await {
+            Ok(Some(AppEvent::NewLogEntry(entry))) => return Some(entry.message),
+            Ok(Some(_)) => continue,
+            _ => return None,
+        }
+    }
+}
+
+#[test]
+fn test_load_recording_skips_blank_lines() {
+    let envelopes = recording(2, 10);
+    let mut text = String::new();
+    for envelope in &envelopes {
+        text.push_str(&serde_json::to_string(envelope).unwrap());
+        text.push_str("\n\n");
+    }
+
+    let loaded = load_recording(text.as_bytes()).unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded[1].timestamp, envelopes[1].timestamp);
+
+    let error = load_recording("{\"not\": 1}\n".as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("line 1"));
+}
+
+#[test]
+fn test_step_replay_speed_stays_in_range() {
+    assert_eq!(step_replay_speed(1.0, true), 2.0);
+    assert_eq!(step_replay_speed(1.0, false), 0.5);
+    assert_eq!(step_replay_speed(80.0, true), MAX_REPLAY_SPEED);
+    assert_eq!(step_replay_speed(0.15, false), MIN_REPLAY_SPEED);
+}
+
+#[tokio::test]
+async fn test_replay_pause_and_resume() {
+    let (event_tx, mut event_rx) = mpsc::channel(100);
+    let (control_tx, control_rx) = mpsc::channel(16);
+    let replay = tokio::spawn(run_replay(recording(50, 20), 1.0, event_tx, control_rx));
+
+    let first = next_progress(&mut event_rx).await;
+    assert_eq!(first.total_entries, 50);
+    assert!(!first.paused);
+    assert_eq!(
+        next_log(&mut event_rx, Duration::from_secs(5))
+            .await
+            .as_deref(),
+        Some("entry 0")
+    );
+    assert_eq!(
+        next_log(&mut event_rx, Duration::from_secs(5))
+            .await
+            .as_deref(),
+        Some("entry 1")
+    );
+
+    // Once the pause is reported nothing more is replayed
+    control_tx.send(ReplayControl::Pause).await.unwrap();
+    let mut seen = 2;
+    loop {
+        match timeout(Duration::from_secs(5), event_rx.recv()).await {
+            Ok(Some(AppEvent::ReplayProgress(progress))) if progress.paused => break,
+            Ok(Some(AppEvent::NewLogEntry(_))) => seen += 1,
+            Ok(Some(_)) => {}
+            _ => panic!("pause was not reported"),
+        }
+    }
+    assert_eq!(
+        next_log(&mut event_rx, Duration::from_millis(200)).await,
+        None
+    );
+
+    control_tx.send(ReplayControl::Resume).await.unwrap();
+    assert_eq!(
+        next_log(&mut event_rx, Duration::from_secs(5)).await,
+        Some(format!("entry {}", seen))
+    );
+
+    control_tx.send(ReplayControl::Stop).await.unwrap();
+    timeout(Duration::from_secs(5), replay)
+        .await
+        .expect("replay did not stop")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_replay_speed_is_clamped_and_reported() {
+    let (event_tx, mut event_rx) = mpsc::channel(100);
+    let (control_tx, control_rx) = mpsc::channel(16);
+    // An hour between entries: only a speed change could bring the second one early
+    let replay = tokio::spawn(run_replay(
+        recording(2, 3_600_000),
+        1000.0,
+        event_tx,
+        control_rx,
+    ));
+
+    assert_eq!(next_progress(&mut event_rx).await.speed, MAX_REPLAY_SPEED);
+    control_tx
+        .send(ReplayControl::SetSpeed(0.01))
+        .await
+        .unwrap();
+    let progress = next_progress(&mut event_rx).await;
+    let progress = if progress.current_index == 1 {
+        next_progress(&mut event_rx).await
+    } else {
+        progress
+    };
+    assert_eq!(progress.speed, MIN_REPLAY_SPEED);
+
+    // Dropping the controls ends the replay like quitting the UI would
+    drop(control_tx);
+    timeout(Duration::from_secs(5), replay)
+        .await
+        .expect("replay did not stop")
+        .unwrap();
+}
@@ -0,0 +1,396 @@
+use mcp_common::*;
+use mcp_monitor::*;
+
+#[test]
+fn test_search_syntax_parse() {
+    assert_eq!(
+        SearchSyntax::parse("method:tools/call"),
+        SearchSyntax::FieldPath {
+            field: "method".to_string(),
+            value: "tools/call".to_string(),
+        }
+    );
+    assert_eq!(
+        SearchSyntax::parse("params.name:file_reader"),
+        SearchSyntax::FieldPath {
+            field: "params.name".to_string(),
+            value: "file_reader".to_string(),
+        }
+    );
+
+    // Anything that isn't a bare path before the colon stays a text search
+    assert_eq!(
+        SearchSyntax::parse("\"method\":"),
+        SearchSyntax::Text("\"method\":".to_string())
+    );
+    assert_eq!(
+        SearchSyntax::parse(":value"),
+        SearchSyntax::Text(":value".to_string())
+    );
+    assert_eq!(
+        SearchSyntax::parse("tools/call"),
+        SearchSyntax::Text("tools/call".to_string())
+    );
+}
+
+#[test]
+fn test_search_syntax_field_path_matching() {
+    let syntax = SearchSyntax::parse("method:tools/call");
+
+    assert!(syntax.matches_message(r#"{"method":"tools/call","id":1}"#));
+    assert!(!syntax.matches_message(r#"{"method":"tools/list"}"#));
+    assert!(!syntax.matches_message(r#"{"params":{"method":"tools/call"}}"#));
+
+    // Proxy direction prefixes are ignored
+    assert!(syntax.matches_message(r#"→ {"method":"tools/call","id":1}"#));
+
+    let nested = SearchSyntax::parse("params.name:file_reader");
+    assert!(nested.matches_message(r#"{"method":"tools/call","params":{"name":"file_reader"}}"#));
+    assert!(!nested.matches_message(r#"{"method":"tools/call","params":{"name":"other"}}"#));
+
+    // Non-string scalars compare by their JSON text
+    assert!(SearchSyntax::parse("id:1").matches_message(r#"{"id":1}"#));
+    assert!(SearchSyntax::parse("result.isError:true")
+        .matches_message(r#"{"result":{"isError":true}}"#));
+}
+
+#[test]
+fn test_search_syntax_non_json_fallback() {
+    let syntax = SearchSyntax::parse("method:tools/call");
+
+    assert!(syntax.matches_message("stderr: unknown METHOD:TOOLS/CALL"));
+    assert!(!syntax.matches_message("stderr: something else"));
+    assert!(!syntax.matches_message("{ not json"));
+
+    // Prose with a colon still finds plain-text logs
+    let prose = SearchSyntax::parse("error: timeout");
+    assert!(prose.matches_message("stderr: Error: Timeout after 30s"));
+}
+
+#[test]
+fn test_search_syntax_regex() {
+    let syntax = SearchSyntax::parse(r"/tools/(call|list)/");
+    assert!(matches!(syntax, SearchSyntax::Regex(_)));
+    assert!(syntax.matches_message(r#"{"method":"TOOLS/LIST"}"#));
+    assert!(!syntax.matches_message(r#"{"method":"tools/get"}"#));
+
+    let log = LogEntry::new(LogLevel::Error, "boom".to_string(), ProxyId::new());
+    assert!(SearchSyntax::parse("/^err/").matches(&log, None));
+    assert!(SearchSyntax::parse("/hub$/").matches(&log, Some("github")));
+
+    // A pattern that doesn't compile, or a lone slash, is plain text
+    assert_eq!(
+        SearchSyntax::parse("/(/"),
+        SearchSyntax::Text("/(/".to_string())
+    );
+    assert_eq!(
+        SearchSyntax::parse("//"),
+        SearchSyntax::Text("//".to_string())
+    );
+}
+
+#[test]
+fn test_app_field_path_search() {
+    let mut app = App::new();
+    let proxy_id = ProxyId::new();
+
+    app.switch_tab(TabType::All);
+    for message in [
+        r#"→ {"jsonrpc":"2.0","method":"tools/call","id":1}"#,
+        r#"→ {"jsonrpc":"2.0","method":"tools/list","id":2}"#,
+        "plain text mentioning method:tools/call",
+        "plain text without a match",
+    ] {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Request,
+            message.to_string(),
+            proxy_id.clone(),
+        )));
+    }
+
+    app.enter_search_mode();
+    for c in "method:tools/call".chars() {
+        app.search_input_char(c);
+    }
+
+    assert_eq!(app.search_results, vec![0, 2]);
+}
+
+#[test]
+fn test_search_query_parse() {
+    let query = SearchQuery::parse(
+        r#"level:error  proxy:github method:tools/call params.name:"my tool" timeout"#,
+    );
+
+    assert_eq!(
+        query.terms,
+        vec![
+            SearchSyntax::Level("error".to_string()),
+            SearchSyntax::Proxy("github".to_string()),
+            SearchSyntax::FieldPath {
+                field: "method".to_string(),
+                value: "tools/call".to_string(),
+            },
+            SearchSyntax::FieldPath {
+                field: "params.name".to_string(),
+                value: "my tool".to_string(),
+            },
+            SearchSyntax::Text("timeout".to_string()),
+        ]
+    );
+    let text = r#"{"method":"tools/call","params":{"name":"my tool"}} timeout"#;
+    let highlighted: Vec<&str> = query
+        .match_ranges(text)
+        .into_iter()
+        .map(|(start, end)| &text[start..end])
+        .collect();
+    assert_eq!(highlighted, vec!["tools/call", "my tool", "timeout"]);
+
+    assert!(SearchQuery::parse("   ").is_empty());
+}
+
+#[test]
+fn test_search_query_terms_are_anded() {
+    let proxy_id = ProxyId::new();
+    let log = LogEntry::new(
+        LogLevel::Error,
+        r#"← {"method":"tools/call","error":{"message":"request timeout"}}"#.to_string(),
+        proxy_id,
+    );
+
+    let matches = |query: &str| SearchQuery::parse(query).matches(&log, Some("GitHub Server"));
+
+    assert!(matches(
+        "level:error proxy:github method:tools/call timeout"
+    ));
+    assert!(matches("LEVEL:Err proxy:GITHUB"));
+    assert!(!matches("level:error proxy:filesystem"));
+    assert!(!matches("level:request timeout"));
+    assert!(!matches("method:tools/list timeout"));
+    assert!(!matches("method:tools/call cancelled"));
+
+    // Bare terms still match the proxy name and level like a plain search
+    assert!(matches("github"));
+    assert!(matches("error"));
+
+    // Unknown prefixes are matched literally against the message
+    let plain = LogEntry::new(
+        LogLevel::Info,
+        "fetching https://example.com/schema".to_string(),
+        ProxyId::new(),
+    );
+    assert!(SearchQuery::parse("https://example.com").matches(&plain, None));
+    assert!(!SearchQuery::parse("proxy:github").matches(&plain, None));
+}
+
+#[test]
+fn test_app_structured_search_with_filters() {
+    let mut app = App::new();
+    let github = ProxyId::new();
+    let filesystem = ProxyId::new();
+
+    for (id, name) in [(&github, "github"), (&filesystem, "filesystem")] {
+        app.handle_event(AppEvent::ProxyConnected(ProxyInfo {
+            id: id.clone(),
+            name: name.to_string(),
+            listen_address: "stdio".to_string(),
+            target_command: vec![name.to_string()],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
+            status: ProxyStatus::Running,
+            stats: ProxyStats::default(),
+        }));
+    }
+
+    let logs = [
+        (
+            &github,
+            LogLevel::Request,
+            r#"→ {"method":"tools/call","id":1}"#,
+        ),
+        (
+            &github,
+            LogLevel::Response,
+            r#"← {"id":1,"result":{"content":"error count: 0"}}"#,
+        ),
+        (&github, LogLevel::Error, "stderr: tools/call timeout"),
+        (
+            &filesystem,
+            LogLevel::Request,
+            r#"→ {"method":"tools/call","id":2}"#,
+        ),
+        (&filesystem, LogLevel::Error, "stderr: error reading file"),
+    ];
+    for (proxy_id, level, message) in logs {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            level,
+            message.to_string(),
+            proxy_id.clone(),
+        )));
+    }
+
+    let search = |app: &mut App, query: &str| {
+        app.enter_search_mode();
+        for c in query.chars() {
+            app.search_input_char(c);
+        }
+        let results = app.search_results.clone();
+        app.exit_search_mode();
+        results
+    };
+
+    // All tab: structured terms pick out exact entries
+    app.switch_tab(TabType::All);
+    assert_eq!(search(&mut app, "method:tools/call"), vec![0, 3]);
+    assert_eq!(search(&mut app, "proxy:github method:tools/call"), vec![0]);
+    assert_eq!(search(&mut app, "level:error"), vec![2, 4]);
+    assert_eq!(search(&mut app, "level:error proxy:file"), vec![4]);
+    // A plain "error" also hits payloads that merely mention it
+    assert_eq!(search(&mut app, "error"), vec![1, 2, 4]);
+
+    // The tab filter still applies on top of the query
+    app.switch_tab(TabType::Messages);
+    assert_eq!(search(&mut app, "level:error"), Vec::<u64>::new());
+    assert_eq!(search(&mut app, "error"), vec![1]);
+
+    // And so does the proxy filter
+    app.switch_tab(TabType::All);
+    app.set_proxy_filter(Some(filesystem.clone()));
+    assert_eq!(search(&mut app, "method:tools/call"), vec![3]);
+    assert_eq!(search(&mut app, "level:error reading"), vec![4]);
+}
+
+#[test]
+fn test_app_search_results_grow_as_logs_arrive() {
+    let mut app = App::with_capacity(20);
+    let proxy_id = ProxyId::new();
+    app.switch_tab(TabType::All);
+    let push = |app: &mut App, message: &str| {
+        app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+            LogLevel::Info,
+            message.to_string(),
+            proxy_id.clone(),
+        )));
+    };
+    let results = |app: &App| -> Vec<String> {
+        app.get_search_filtered_logs()
+            .iter()
+            .map(|log| log.message.clone())
+            .collect()
+    };
+
+    push(&mut app, "user 1");
+    push(&mut app, "other");
+    push(&mut app, "user 2");
+    app.enter_search_mode();
+    for c in "user".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_results();
+    app.scroll_to_bottom();
+    assert_eq!(results(&app), vec!["user 1", "user 2"]);
+
+    // At the last result the selection follows new matches; others don't join
+    push(&mut app, "other");
+    push(&mut app, "user 3");
+    assert_eq!(results(&app), vec!["user 1", "user 2", "user 3"]);
+    assert_eq!(app.selected_index, 2);
+
+    // Away from the bottom the selection stays on its entry
+    app.scroll_to_top();
+    push(&mut app, "user 4");
+    assert_eq!(results(&app).len(), 4);
+    assert_eq!(app.selected_index, 0);
+
+    // Matches hidden by the tab filter stay out
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Error,
+        "user error".to_string(),
+        proxy_id.clone(),
+    )));
+    app.switch_tab(TabType::System);
+    app.enter_search_mode();
+    for c in "user".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_results();
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Error,
+        "user error 2".to_string(),
+        proxy_id.clone(),
+    )));
+    assert_eq!(results(&app), vec!["user 1", "user 2", "user 3", "user 4"]);
+
+    // Trimming at the cap drops results along with their entries
+    app.scroll_to_bottom();
+    for i in 5..30 {
+        push(&mut app, &format!("user {}", i));
+    }
+    let expected: Vec<String> = (10..30).map(|i| format!("user {}", i)).collect();
+    assert_eq!(app.logs.len(), 20);
+    assert_eq!(results(&app), expected);
+    assert_eq!(app.selected_index, expected.len() - 1);
+    assert!(app
+        .search_results
+        .iter()
+        .all(|&seq| app.log_index(seq).is_some()));
+
+    // n/N reach matches that arrived after the jump search started
+    app.exit_search_mode();
+    app.enter_search_mode();
+    for c in "late".chars() {
+        app.search_input_char(c);
+    }
+    app.confirm_search_jump();
+    push(&mut app, "late arrival");
+    app.scroll_to_top();
+    app.next_search_match();
+    assert_eq!(
+        app.get_search_filtered_log(app.selected_index)
+            .map(|log| log.message.as_str()),
+        Some("late arrival")
+    );
+}
+
+#[test]
+fn test_app_search_history_steps_back_through_queries() {
+    let mut app = App::new();
+
+    for query in ["alpha", "beta", "beta", "gamma"] {
+        app.enter_search_mode();
+        for c in query.chars() {
+            app.search_input_char(c);
+        }
+        app.confirm_search_results();
+        app.exit_search_mode();
+    }
+    // The repeated "beta" is kept once
+    assert_eq!(app.search_history, ["gamma", "beta", "alpha"]);
+
+    app.enter_search_mode();
+    let mut seen = Vec::new();
+    for _ in 0..3 {
+        app.search_history_older();
+        seen.push(app.search_query.clone());
+    }
+    assert_eq!(seen, ["gamma", "beta", "alpha"]);
+    assert_eq!(app.search_history_index, Some(2));
+
+    // Past the oldest query nothing changes
+    app.search_history_older();
+    assert_eq!(app.search_query, "alpha");
+
+    app.search_history_newer();
+    assert_eq!(app.search_query, "beta");
+    assert_eq!(app.search_cursor, "beta".len());
+
+    // Typing leaves the history
+    app.search_input_char('x');
+    assert_eq!(app.search_history_index, None);
+    assert_eq!(app.search_query, "betax");
+
+    app.search_history_older();
+    app.search_history_newer();
+    assert_eq!(app.search_query, "");
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use mcp_monitor::TimeRange;
+
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 5, 1, 12, 10, 0).unwrap()
+}
+
+fn at(hour: u32, min: u32, sec: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 5, 1, hour, min, sec).unwrap()
+}
+
+#[test]
+fn test_time_range_relative() {
+    let range = TimeRange::parse("5m", now()).unwrap();
+    assert_eq!(range.start, Some(now() - Duration::minutes(5)));
+    assert_eq!(range.end, None);
+    assert_eq!(range.label, "last 5m");
+
+    assert!(range.contains(&now()));
+    assert!(range.contains(&(now() + Duration::hours(1))));
+    assert!(!range.contains(&at(12, 4, 59)));
+
+    let range = TimeRange::parse("30s", now()).unwrap();
+    assert_eq!(range.start, Some(at(12, 9, 30)));
+}
+
+#[test]
+fn test_time_range_absolute_open_ended() {
+    for input in ["12:03", "12:03-now", "from 12:03 to now"] {
+        let range = TimeRange::parse(input, now()).unwrap();
+        assert_eq!(range.start, Some(at(12, 3, 0)), "{}", input);
+        assert_eq!(range.end, None, "{}", input);
+        assert_eq!(range.label, "12:03 → now", "{}", input);
+    }
+}
+
+#[test]
+fn test_time_range_absolute_bounds_include_end_unit() {
+    let range = TimeRange::parse("12:03:15-12:05", now()).unwrap();
+    assert_eq!(range.start, Some(at(12, 3, 15)));
+    assert_eq!(range.end, Some(at(12, 6, 0)));
+
+    assert!(!range.contains(&at(12, 3, 14)));
+    assert!(range.contains(&at(12, 3, 15)));
+    assert!(range.contains(&at(12, 5, 59)));
+    assert!(!range.contains(&at(12, 6, 0)));
+}
+
+#[test]
+fn test_time_range_future_clock_time_means_yesterday() {
+    let range = TimeRange::parse("23:50", now()).unwrap();
+    assert_eq!(
+        range.start,
+        Some(Utc.with_ymd_and_hms(2024, 4, 30, 23, 50, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_time_range_rejects_invalid_input() {
+    assert!(TimeRange::parse("", now()).is_err());
+    assert!(TimeRange::parse("soon", now()).is_err());
+    assert!(TimeRange::parse("25:00", now()).is_err());
+    assert!(TimeRange::parse("xm", now()).is_err());
+    assert!(TimeRange::parse("12:05-12:03", now()).is_err());
+}
+
+#[test]
+fn test_time_range_rejects_out_of_range_durations() {
+    // Too many hours for a chrono Duration, and too far back for a timestamp
+    for input in ["99999999999h", "9223372036854775807s", "10000000000h..5m"] {
+        let error = TimeRange::parse(input, now()).unwrap_err();
+        assert!(error.contains("too large"), "{}: {}", input, error);
+    }
+}
@@ -0,0 +1,340 @@
+use chrono::{Duration, Utc};
+use mcp_common::{LogEntry, LogLevel, ProxyId};
+use mcp_monitor::{
+    draw, format_line_position, format_timestamp, format_uptime, highlight_json_lines,
+    highlight_spans, App, AppEvent, SearchQuery, TimestampMode,
+};
+use ratatui::backend::TestBackend;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::Terminal;
+
+/// Spans of `text` with `query`'s matches highlighted, on an unlimited width
+fn spans_for<'a>(text: &'a str, query: &str) -> Vec<ratatui::text::Span<'a>> {
+    highlight_spans(
+        text,
+        &SearchQuery::parse(query),
+        usize::MAX,
+        Style::default(),
+    )
+}
+
+#[test]
+fn test_highlight_spans_splits_around_match() {
+    let spans = spans_for("hello world again", "world");
+
+    assert_eq!(spans.len(), 3);
+    assert_eq!(spans[0].content, "hello ");
+    assert_eq!(spans[1].content, "world");
+    assert_eq!(spans[2].content, " again");
+
+    let match_style = spans[1].style;
+    assert!(match_style.add_modifier.contains(Modifier::REVERSED));
+    assert!(match_style.add_modifier.contains(Modifier::BOLD));
+    assert_eq!(match_style.fg, Some(Color::Yellow));
+    assert!(spans[0].style.add_modifier.is_empty());
+}
+
+#[test]
+fn test_highlight_spans_is_case_insensitive_and_finds_all_matches() {
+    let spans = spans_for("Tool call: TOOLS/list", "tool");
+    let highlighted: Vec<&str> = spans
+        .iter()
+        .filter(|span| span.style.add_modifier.contains(Modifier::BOLD))
+        .map(|span| span.content.as_ref())
+        .collect();
+
+    assert_eq!(highlighted, vec!["Tool", "TOOL"]);
+}
+
+#[test]
+fn test_highlight_spans_without_match_is_plain() {
+    let spans = spans_for("hello world", "xyz");
+
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].content, "hello world");
+
+    // Nor is a match at the very end followed by an empty span
+    let spans = spans_for("hello world", "world");
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[1].content, "world");
+}
+
+#[test]
+fn test_highlight_spans_uses_regex_matches() {
+    let spans = spans_for(
+        r#"{"method":"tools/call","id":12}"#,
+        r"/TOOLS/(call|list)/ /\d+/",
+    );
+    let highlighted: Vec<&str> = spans
+        .iter()
+        .filter(|span| span.style.add_modifier.contains(Modifier::BOLD))
+        .map(|span| span.content.as_ref())
+        .collect();
+
+    assert_eq!(highlighted, vec!["tools/call", "12"]);
+}
+
+#[test]
+fn test_highlight_spans_alternates_over_several_matches_in_a_line() {
+    let spans = spans_for("ping, ping and ping again", "ping");
+    let contents: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+
+    assert_eq!(
+        contents,
+        vec!["ping", ", ", "ping", " and ", "ping", " again"]
+    );
+    for (i, span) in spans.iter().enumerate() {
+        assert_eq!(
+            span.style.add_modifier.contains(Modifier::BOLD),
+            i % 2 == 0,
+            "span {:?}",
+            span.content
+        );
+    }
+}
+
+#[test]
+fn test_highlight_spans_shifts_left_to_a_match_past_the_width() {
+    // Multi-byte padding, so the shift has to land on a character boundary
+    let text = format!("{}needle tail", "é".repeat(40));
+    let query = SearchQuery::parse("needle");
+
+    let spans = highlight_spans(&text, &query, 20, Style::default());
+    let contents: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+
+    // A quarter of the width is kept as context before the match
+    assert_eq!(contents, vec!["…", "ééééé", "needle", " tail"]);
+    assert!(spans[2].style.add_modifier.contains(Modifier::BOLD));
+
+    // A match that fits isn't shifted
+    let spans = highlight_spans(&text, &query, 60, Style::default());
+    assert_eq!(spans[0].content, "é".repeat(40));
+}
+
+fn colored(line: &ratatui::text::Line) -> Vec<(String, Option<Color>)> {
+    line.spans
+        .iter()
+        .filter(|span| span.style.fg.is_some())
+        .map(|span| (span.content.to_string(), span.style.fg))
+        .collect()
+}
+
+#[test]
+fn test_highlight_json_lines_colors_each_token_kind() {
+    let lines = vec![
+        "{".to_string(),
+        r#"  "name": "read_file","#.to_string(),
+        r#"  "size": -12.5e3,"#.to_string(),
+        r#"  "ok": true,"#.to_string(),
+        r#"  "cursor": null"#.to_string(),
+        "}".to_string(),
+    ];
+    let highlighted = highlight_json_lines(&lines);
+
+    assert_eq!(highlighted.len(), lines.len());
+    assert!(colored(&highlighted[0]).is_empty());
+    assert_eq!(
+        colored(&highlighted[1]),
+        vec![
+            ("\"name\"".to_string(), Some(Color::Cyan)),
+            ("\"read_file\"".to_string(), Some(Color::Green)),
+        ]
+    );
+    assert_eq!(
+        colored(&highlighted[2])[1],
+        ("-12.5e3".to_string(), Some(Color::LightMagenta))
+    );
+    assert_eq!(
+        colored(&highlighted[3])[1],
+        ("true".to_string(), Some(Color::LightYellow))
+    );
+    assert_eq!(
+        colored(&highlighted[4])[1],
+        ("null".to_string(), Some(Color::LightRed))
+    );
+
+    // Every byte of the line is kept
+    for (line, spans) in lines.iter().zip(&highlighted) {
+        let text: String = spans
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(&text, line);
+    }
+}
+
+#[test]
+fn test_highlight_json_lines_carries_strings_across_wrapped_rows() {
+    // A long string value wrapped over three rows, with an escaped quote at a row end
+    let lines = vec![
+        r#"  "text": "a long value with \"#.to_string(),
+        r#""quotes\" that keeps"#.to_string(),
+        r#"going", "n": 1"#.to_string(),
+    ];
+    let highlighted = highlight_json_lines(&lines);
+
+    assert_eq!(
+        colored(&highlighted[1]),
+        vec![(r#""quotes\" that keeps"#.to_string(), Some(Color::Green))]
+    );
+    assert_eq!(
+        colored(&highlighted[2]),
+        vec![
+            ("going\"".to_string(), Some(Color::Green)),
+            ("\"n\"".to_string(), Some(Color::Cyan)),
+            ("1".to_string(), Some(Color::LightMagenta)),
+        ]
+    );
+}
+
+#[test]
+fn test_highlight_json_lines_leaves_words_plain() {
+    let lines = vec![
+        "=== METADATA ===".to_string(),
+        "nullable v2 true1".to_string(),
+    ];
+    let highlighted = highlight_json_lines(&lines);
+
+    assert!(colored(&highlighted[0]).is_empty());
+    assert!(colored(&highlighted[1]).is_empty());
+}
+
+fn entry_at(timestamp: chrono::DateTime<Utc>) -> LogEntry {
+    let mut entry = LogEntry::new(LogLevel::Info, "tick".to_string(), ProxyId::new());
+    entry.timestamp = timestamp;
+    entry
+}
+
+#[test]
+fn test_format_timestamp_modes() {
+    let now = Utc::now();
+    let entry = entry_at(now - Duration::milliseconds(2500));
+
+    let relative = format_timestamp(&entry, TimestampMode::Relative, now);
+    assert_eq!(relative.trim_end(), "-2.5s");
+    assert_eq!(relative.len(), 10);
+
+    let first = now - Duration::seconds(130);
+    let elapsed = format_timestamp(&entry, TimestampMode::Elapsed, first);
+    assert_eq!(elapsed.trim_end(), "+2m07s");
+
+    let absolute = format_timestamp(&entry, TimestampMode::Absolute, now);
+    assert_eq!(absolute, entry.timestamp.format("%H:%M:%S%.3f").to_string());
+
+    let old = entry_at(now - Duration::minutes(62) - Duration::seconds(5));
+    assert_eq!(
+        format_timestamp(&old, TimestampMode::Relative, now).trim_end(),
+        "-1h02m"
+    );
+    let same = format_timestamp(&entry_at(now), TimestampMode::Relative, now);
+    assert_eq!(same.trim_end(), "-0s");
+}
+
+#[test]
+fn test_format_uptime() {
+    use std::time::Duration;
+
+    assert_eq!(format_uptime(Duration::from_millis(45_900)), "45s");
+    assert_eq!(format_uptime(Duration::from_secs(23 * 60 + 5)), "23m 5s");
+    assert_eq!(format_uptime(Duration::from_secs(5025)), "1h 23m 45s");
+}
+
+#[test]
+fn test_format_line_position() {
+    assert_eq!(
+        format_line_position(180, 220, 1432),
+        "lines 180–220 of 1,432 (15%)"
+    );
+    assert_eq!(format_line_position(1, 4, 4), "lines 1–4 of 4 (100%)");
+    assert_eq!(
+        format_line_position(999_990, 1_000_000, 2_000_000),
+        "lines 999,990–1,000,000 of 2,000,000 (50%)"
+    );
+}
+
+#[test]
+fn test_log_rows_show_latency_column() {
+    let mut app = App::new();
+    app.slow_threshold_ms = 100;
+    let proxy_id = ProxyId::new();
+    let mut response = LogEntry::new(
+        LogLevel::Response,
+        "slow response".to_string(),
+        proxy_id.clone(),
+    );
+    response.duration = Some(std::time::Duration::from_millis(150));
+    app.handle_event(AppEvent::NewLogEntry(response));
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        "no duration".to_string(),
+        proxy_id,
+    )));
+
+    let mut terminal = Terminal::new(TestBackend::new(160, 30)).unwrap();
+    terminal.draw(|f| draw(f, &mut app)).unwrap();
+    assert!(app.latency_column_visible);
+
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol.as_str())
+                .collect()
+        })
+        .collect();
+    let slow_row = rows
+        .iter()
+        .position(|row| row.contains("slow response"))
+        .unwrap();
+    let other_row = rows
+        .iter()
+        .position(|row| row.contains("no duration"))
+        .unwrap();
+    assert!(rows[slow_row].contains("[150MS]"));
+    assert!(rows[other_row].contains("[------]"));
+    // Both latencies end in the same column
+    assert_eq!(
+        rows[slow_row].find("MS]").unwrap() + 3,
+        rows[other_row].find("---]").unwrap() + 4
+    );
+
+    // Over the threshold while following, so it's red
+    let digit = (0..buffer.area.width)
+        .rev()
+        .find(|&x| buffer.get(x, slow_row as u16).symbol == "1")
+        .unwrap();
+    assert_eq!(buffer.get(digit, slow_row as u16).fg, Color::Red);
+}
+
+#[test]
+fn test_split_detail_view_shows_missing_response() {
+    let mut app = App::new();
+    app.handle_event(AppEvent::NewLogEntry(LogEntry::new(
+        LogLevel::Request,
+        r#"→ {"jsonrpc":"2.0","method":"tools/list","id":1}"#.to_string(),
+        ProxyId::new(),
+    )));
+    app.select_log_at_cursor();
+    app.show_selected_log_detail();
+    app.toggle_detail_split();
+
+    let mut terminal = Terminal::new(TestBackend::new(160, 40)).unwrap();
+    terminal.draw(|f| draw(f, &mut app)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol.as_str())
+                .collect()
+        })
+        .collect();
+    let placeholder = rows
+        .iter()
+        .find(|row| row.contains("No matching response found"))
+        .expect("the response pane should say there is no response");
+    // In the right-hand pane
+    assert!(placeholder.find("No matching").unwrap() > 80);
+    assert!(rows.iter().any(|row| row.contains("Request │")));
+}
@@ -1,79 +1,340 @@
 use anyhow::Result;
-use mcp_common::{IpcClient, IpcMessage};
+use mcp_common::{IpcClient, IpcEnvelope, IpcFraming, IpcMessage, LogEntry, ProxyId, ProxyStats};
 use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
-use tokio::time::{sleep, Duration, Instant};
+use tokio::sync::{broadcast, mpsc, watch, Mutex};
+use tokio::time::{interval, interval_at, Duration, Instant};
 use tracing::{debug, error, info, warn};
 
 const MAX_BUFFER_SIZE: usize = 10_000; // Maximum number of messages to buffer
+const SPILL_BATCH_SIZE: usize = 1000; // Spilled messages read back per batch
+const HISTORY_SIZE: usize = 500; // Delivered entries kept to answer GetLogs, e.g. after a reconnect
+const DEFAULT_GET_LOGS_LIMIT: usize = 100; // Entries a GetLogs without a limit gets
 const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
 const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
 const RECONNECT_BACKOFF_FACTOR: u32 = 2;
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+const PONG_TIMEOUT: Duration = Duration::from_secs(10); // Unanswered pings mark the connection stale
+/// Longest log message sent as is; longer ones are cut, leaving room under the monitor's
+/// message limit for the envelope and JSON escaping
+pub const MAX_LOG_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+/// Most message bytes sent in one LogsBatch, so a batch fits under the monitor's limit
+/// just as a single entry does
+const MAX_BATCH_BYTES: usize = MAX_LOG_MESSAGE_BYTES;
+
+/// A JSONL file taking the messages that don't fit the in-memory buffer, up to `max_bytes`
+#[derive(Debug, Clone)]
+pub struct DiskSpill {
+    pub path: PathBuf,
+    pub max_bytes: u64,
+}
+
+/// A snapshot of how the client is getting on with the monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferMetrics {
+    pub buffered: u64,   // Waiting in memory or on disk for the monitor
+    pub dropped: u64,    // Lost because there was no room to buffer them
+    pub reconnects: u64, // Connections made after the first
+    pub connected: bool,
+}
+
+impl BufferMetrics {
+    /// Copy these into the stats a proxy reports to the monitor
+    pub fn apply_to(&self, stats: &mut ProxyStats) {
+        stats.ipc_connected = self.connected;
+        stats.ipc_buffered = self.buffered;
+        stats.ipc_dropped = self.dropped;
+        stats.ipc_reconnects = self.reconnects;
+    }
+}
+
+/// Shared between the client and its task, which keeps them current
+#[derive(Default)]
+struct Counters {
+    buffered: AtomicU64,
+    dropped: AtomicU64,
+    reconnects: AtomicU64,
+    connected: AtomicBool,
+}
 
 pub struct BufferedIpcClient {
-    buffer: Arc<Mutex<VecDeque<IpcMessage>>>,
+    buffer: Arc<Mutex<Backlog>>,
+    counters: Arc<Counters>,
     sender: mpsc::Sender<IpcMessage>,
     shutdown_tx: Option<mpsc::Sender<()>>,
     task_handle: Option<tokio::task::JoinHandle<()>>,
+    last_pong: Arc<Mutex<Option<Instant>>>, // When the monitor last answered a ping
+    registration: watch::Sender<Option<IpcMessage>>, // Sent first on every connection
+    inbound: broadcast::Sender<IpcMessage>, // Monitor messages the client doesn't answer itself
 }
 
 impl BufferedIpcClient {
     pub async fn new(socket_path: String) -> Self {
-        let buffer = Arc::new(Mutex::new(VecDeque::new()));
+        Self::with_keepalive(socket_path, PING_INTERVAL, PONG_TIMEOUT).await
+    }
+
+    /// Open every connection with a `Hello` carrying `token`, for a monitor that needs one
+    pub async fn with_token(socket_path: String, token: Option<String>) -> Self {
+        Self::with_framing(socket_path, token, IpcFraming::Lines).await
+    }
+
+    /// Like `with_token`, delimiting messages to the monitor with `framing`
+    pub async fn with_framing(
+        socket_path: String,
+        token: Option<String>,
+        framing: IpcFraming,
+    ) -> Self {
+        Self::with_disk_spill(socket_path, token, framing, None).await
+    }
+
+    /// Like `with_framing`, writing messages past the in-memory buffer to `spill` instead of
+    /// dropping them; they are replayed in order once the monitor is back
+    pub async fn with_disk_spill(
+        socket_path: String,
+        token: Option<String>,
+        framing: IpcFraming,
+        spill: Option<DiskSpill>,
+    ) -> Self {
+        let monitor = Monitor {
+            socket_path,
+            token,
+            framing,
+        };
+        Self::spawn(monitor, spill, PING_INTERVAL, PONG_TIMEOUT)
+    }
+
+    /// Ping the monitor every `ping_interval` and reconnect when a ping goes unanswered
+    /// for `pong_timeout`
+    pub async fn with_keepalive(
+        socket_path: String,
+        ping_interval: Duration,
+        pong_timeout: Duration,
+    ) -> Self {
+        let monitor = Monitor {
+            socket_path,
+            token: None,
+            framing: IpcFraming::Lines,
+        };
+        Self::spawn(monitor, None, ping_interval, pong_timeout)
+    }
+
+    fn spawn(
+        monitor: Monitor,
+        spill: Option<DiskSpill>,
+        ping_interval: Duration,
+        pong_timeout: Duration,
+    ) -> Self {
+        let counters = Arc::new(Counters::default());
+        let buffer = Arc::new(Mutex::new(Backlog::new(
+            spill.map(Spill::new),
+            counters.clone(),
+        )));
         let (sender, receiver) = mpsc::channel(1000);
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let last_pong = Arc::new(Mutex::new(None));
+        let (registration, registration_rx) = watch::channel(None);
+        let (inbound, _) = broadcast::channel(16);
 
         // Start the background task
         let task_handle = tokio::spawn(Self::run_client_task(
-            socket_path,
+            monitor,
             buffer.clone(),
             receiver,
             shutdown_rx,
+            registration_rx,
+            inbound.clone(),
+            Keepalive {
+                ping_interval,
+                pong_timeout,
+                last_pong: last_pong.clone(),
+            },
         ));
 
-        let client = Self {
+        Self {
             buffer,
+            counters,
             sender,
             shutdown_tx: Some(shutdown_tx),
             task_handle: Some(task_handle),
-        };
+            last_pong,
+            registration,
+            inbound,
+        }
+    }
+
+    /// Introduce this client with `message` (usually `ProxyStarted`) now if connected, and
+    /// again before the buffer is flushed on every reconnect, so a restarted monitor still
+    /// learns who is sending
+    pub fn set_registration(&self, message: IpcMessage) {
+        self.registration.send_replace(Some(message));
+    }
 
-        client
+    /// Messages from the monitor that are for the proxy to act on, such as `Shutdown`
+    pub fn subscribe(&self) -> broadcast::Receiver<IpcMessage> {
+        self.inbound.subscribe()
+    }
+
+    /// Whether a connection to the monitor is up
+    pub fn is_connected(&self) -> bool {
+        self.counters.connected.load(Ordering::Relaxed)
+    }
+
+    /// Whether the monitor is connected, and what is buffered or was dropped meanwhile
+    pub fn metrics(&self) -> BufferMetrics {
+        BufferMetrics {
+            buffered: self.counters.buffered.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+            reconnects: self.counters.reconnects.load(Ordering::Relaxed),
+            connected: self.counters.connected.load(Ordering::Relaxed),
+        }
+    }
+
+    /// When the monitor last answered a ping, if it has
+    pub async fn last_pong(&self) -> Option<Instant> {
+        *self.last_pong.lock().await
     }
 
     pub async fn send(&self, message: IpcMessage) -> Result<()> {
-        // Try to send through the channel (which will handle buffering if needed)
-        if let Err(_) = self.sender.send(message.clone()).await {
-            // If channel is full or closed, add directly to buffer
-            let mut buffer = self.buffer.lock().await;
-            if buffer.len() < MAX_BUFFER_SIZE {
-                buffer.push_back(message);
-            } else {
-                warn!("Buffer full, dropping message");
+        match message {
+            IpcMessage::LogEntry(entry) => {
+                self.enqueue(IpcMessage::LogEntry(entry.truncated(MAX_LOG_MESSAGE_BYTES)))
+                    .await
+            }
+            IpcMessage::LogsBatch(entries) => {
+                for batch in log_batches(entries) {
+                    self.enqueue(IpcMessage::LogsBatch(batch)).await;
+                }
             }
+            message => self.enqueue(message).await,
         }
         Ok(())
     }
 
+    async fn enqueue(&self, message: IpcMessage) {
+        // Try to send through the channel (which will handle buffering if needed)
+        if self.sender.send(message.clone()).await.is_err() {
+            // If channel is full or closed, add directly to buffer
+            self.buffer.lock().await.push(message);
+        }
+    }
+
     async fn run_client_task(
-        socket_path: String,
-        buffer: Arc<Mutex<VecDeque<IpcMessage>>>,
+        monitor: Monitor,
+        buffer: Arc<Mutex<Backlog>>,
         mut receiver: mpsc::Receiver<IpcMessage>,
         mut shutdown_rx: mpsc::Receiver<()>,
+        mut registration_rx: watch::Receiver<Option<IpcMessage>>,
+        inbound: broadcast::Sender<IpcMessage>,
+        keepalive: Keepalive,
     ) {
         let mut client: Option<IpcClient> = None;
         let mut reconnect_delay = INITIAL_RECONNECT_DELAY;
         let mut last_connect_attempt = Instant::now() - reconnect_delay;
+        let mut ping_timer = interval_at(
+            Instant::now() + keepalive.ping_interval,
+            keepalive.ping_interval,
+        );
+        // A persistent timer, so steady traffic can't keep postponing reconnects
+        let mut housekeeping = interval(Duration::from_millis(100));
+        let mut unanswered_ping: Option<Instant> = None; // Oldest ping still waiting for a pong
+
+        // Entries the monitor has received, over this connection and earlier ones: a
+        // restarted monitor asks for them with GetLogs, as does `L` in the monitor
+        let mut history: VecDeque<LogEntry> = VecDeque::with_capacity(HISTORY_SIZE);
+        let mut connected_before = false;
+        let counters = buffer.lock().await.counters.clone();
 
         loop {
+            counters
+                .connected
+                .store(client.is_some(), Ordering::Relaxed);
             tokio::select! {
                 // Check for shutdown
                 _ = shutdown_rx.recv() => {
                     info!("BufferedIpcClient shutting down");
+                    // Deliver what was sent before the shutdown, such as ProxyStopped
+                    if let Some(ref mut ipc_client) = client {
+                        while let Ok(message) = receiver.try_recv() {
+                            if ipc_client.send(message).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    buffer.lock().await.close();
                     break;
                 }
 
+                // Replies from the monitor
+                received = receive_from(&mut client) => {
+                    match received {
+                        Ok(Some(envelope)) => match envelope.message {
+                            IpcMessage::Pong => {
+                                *keepalive.last_pong.lock().await = Some(Instant::now());
+                                unanswered_ping = None;
+                            }
+                            IpcMessage::Ping => {
+                                if let Some(ref mut ipc_client) = client {
+                                    if let Err(e) = ipc_client.send(IpcMessage::Pong).await {
+                                        warn!("Failed to answer ping, will reconnect: {}", e);
+                                        client = None;
+                                        unanswered_ping = None;
+                                    }
+                                }
+                            }
+                            IpcMessage::GetLogs { proxy_id, limit } => {
+                                let entries = recent_logs(&history, &proxy_id, limit);
+                                for batch in log_batches(entries) {
+                                    let Some(ipc_client) = &mut client else {
+                                        break;
+                                    };
+                                    if let Err(e) = ipc_client.send(IpcMessage::LogsBatch(batch)).await {
+                                        warn!("Failed to answer GetLogs, will reconnect: {}", e);
+                                        client = None;
+                                        unanswered_ping = None;
+                                    }
+                                }
+                            }
+                            message => {
+                                // Nobody listening is fine, e.g. in standalone tests
+                                let _ = inbound.send(message);
+                            }
+                        },
+                        Ok(None) | Err(_) => {
+                            warn!("Monitor connection closed, will reconnect");
+                            client = None;
+                            unanswered_ping = None;
+                        }
+                    }
+                }
+
+                // A registration set while connected goes out right away
+                Ok(()) = registration_rx.changed() => {
+                    let registration = registration_rx.borrow_and_update().clone();
+                    if let (Some(ipc_client), Some(message)) = (&mut client, registration.map(connected_registration)) {
+                        if let Err(e) = ipc_client.send(message).await {
+                            warn!("Failed to send registration, will reconnect: {}", e);
+                            client = None;
+                            unanswered_ping = None;
+                        }
+                    }
+                }
+
+                _ = ping_timer.tick() => {
+                    if let Some(ref mut ipc_client) = client {
+                        if let Err(e) = ipc_client.send(IpcMessage::Ping).await {
+                            warn!("Failed to ping monitor: {}", e);
+                            client = None;
+                            unanswered_ping = None;
+                        } else {
+                            unanswered_ping.get_or_insert_with(Instant::now);
+                        }
+                    }
+                }
+
                 // Try to receive new messages
                 Some(message) = receiver.recv() => {
                     // Try to send the message
@@ -83,53 +344,67 @@ impl BufferedIpcClient {
                             // Connection failed, reset client
                             client = None;
                             // Buffer the message
-                            let mut buf = buffer.lock().await;
-                            if buf.len() < MAX_BUFFER_SIZE {
-                                buf.push_back(message);
-                            }
+                            buffer.lock().await.push(message);
+                        } else {
+                            remember_delivered(&mut history, &message);
                         }
                     } else {
                         // No connection, buffer the message
-                        let mut buf = buffer.lock().await;
-                        if buf.len() < MAX_BUFFER_SIZE {
-                            buf.push_back(message);
-                        }
+                        buffer.lock().await.push(message);
                     }
                 }
 
                 // Periodic reconnection attempts
-                _ = sleep(Duration::from_millis(100)) => {
+                _ = housekeeping.tick() => {
+                    if unanswered_ping.is_some_and(|sent| sent.elapsed() >= keepalive.pong_timeout) {
+                        warn!("No pong from monitor within {:?}, reconnecting", keepalive.pong_timeout);
+                        client = None;
+                        unanswered_ping = None;
+                    }
+
                     if client.is_none() && last_connect_attempt.elapsed() >= reconnect_delay {
                         last_connect_attempt = Instant::now();
 
-                        match IpcClient::connect(&socket_path).await {
-                            Ok(new_client) => {
-                                info!("Successfully connected to monitor at {}", socket_path);
+                        // The Hello goes first, then the registration, then anything buffered
+                        match IpcClient::connect_with_framing(&monitor.socket_path, monitor.framing, monitor.token.as_deref()).await {
+                            Ok(mut new_client) => {
+                                info!("Successfully connected to monitor at {}", monitor.socket_path);
+                                // Introduce ourselves before anything buffered arrives
+                                let registration = registration_rx.borrow_and_update().clone();
+                                if let Some(message) = registration.map(connected_registration) {
+                                    if let Err(e) = new_client.send(message).await {
+                                        warn!("Failed to send registration (will retry): {}", e);
+                                        continue;
+                                    }
+                                }
                                 client = Some(new_client);
+                                counters.connected.store(true, Ordering::Relaxed);
+                                if connected_before {
+                                    counters.reconnects.fetch_add(1, Ordering::Relaxed);
+                                }
+                                connected_before = true;
                                 reconnect_delay = INITIAL_RECONNECT_DELAY;
+                                // First ping a full interval after connecting
+                                ping_timer.reset();
 
-                                // Flush buffered messages
-                                let messages_to_send: Vec<IpcMessage> = {
-                                    let mut buf = buffer.lock().await;
-                                    buf.drain(..).collect()
-                                };
-
-                                if !messages_to_send.is_empty() {
+                                // Flush buffered messages, the in-memory ones and then the spilled
+                                'flush: while let Some(ref mut ipc_client) = client {
+                                    let mut messages_to_send = buffer.lock().await.take_batch();
+                                    if messages_to_send.is_empty() {
+                                        break;
+                                    }
                                     info!("Flushing {} buffered messages", messages_to_send.len());
-                                    if let Some(ref mut ipc_client) = client {
-                                        for msg in messages_to_send {
-                                            if let Err(e) = ipc_client.send(msg.clone()).await {
-                                                error!("Failed to flush buffered message: {}", e);
-                                                // Re-buffer failed messages
-                                                let mut buf = buffer.lock().await;
-                                                if buf.len() < MAX_BUFFER_SIZE {
-                                                    buf.push_back(msg);
-                                                }
-                                                // Connection failed during flush
-                                                client = None;
-                                                break;
-                                            }
+                                    while let Some(msg) = messages_to_send.pop_front() {
+                                        if let Err(e) = ipc_client.send(msg.clone()).await {
+                                            error!("Failed to flush buffered message: {}", e);
+                                            // Re-buffer the rest ahead of anything newer
+                                            messages_to_send.push_front(msg);
+                                            buffer.lock().await.requeue(messages_to_send);
+                                            // Connection failed during flush
+                                            client = None;
+                                            break 'flush;
                                         }
+                                        remember_delivered(&mut history, &msg);
                                     }
                                 }
                             }
@@ -160,6 +435,309 @@ impl BufferedIpcClient {
     }
 }
 
+/// Messages waiting for the monitor: the oldest in memory, then any spilled to disk
+struct Backlog {
+    memory: VecDeque<IpcMessage>,
+    spill: Option<Spill>,
+    counters: Arc<Counters>,
+}
+
+impl Backlog {
+    fn new(spill: Option<Spill>, counters: Arc<Counters>) -> Self {
+        Self {
+            memory: VecDeque::new(),
+            spill,
+            counters,
+        }
+    }
+
+    /// Publish how many messages are waiting
+    fn count(&self) {
+        let spilled = self
+            .spill
+            .as_ref()
+            .map_or(0, |spill| spill.pending_messages);
+        self.counters
+            .buffered
+            .store(self.memory.len() as u64 + spilled, Ordering::Relaxed);
+    }
+
+    fn drop_message(&self) {
+        self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Buffer `message` after everything already waiting, dropping it when memory is full
+    /// and there is no room on disk either
+    fn push(&mut self, message: IpcMessage) {
+        // Once anything is spilled, newer messages follow it to keep the order
+        let spilling = self.spill.as_ref().is_some_and(Spill::has_pending);
+        if !spilling && self.memory.len() < MAX_BUFFER_SIZE {
+            self.memory.push_back(message);
+        } else if let Some(ref mut spill) = self.spill {
+            if let Err(e) = spill.write(&message) {
+                warn!(
+                    "Failed to spill message to {}, dropping it: {}",
+                    spill.config.path.display(),
+                    e
+                );
+                self.drop_message();
+            }
+        } else {
+            warn!("Buffer full, dropping message");
+            self.drop_message();
+        }
+        self.count();
+    }
+
+    /// The next messages to send in order: all of memory, or else a batch read back from disk
+    fn take_batch(&mut self) -> VecDeque<IpcMessage> {
+        let batch = self.read_batch();
+        self.count();
+        batch
+    }
+
+    fn read_batch(&mut self) -> VecDeque<IpcMessage> {
+        if !self.memory.is_empty() {
+            return self.memory.drain(..).collect();
+        }
+        match self.spill {
+            Some(ref mut spill) => spill.read_batch().unwrap_or_else(|e| {
+                error!(
+                    "Failed to read spilled messages from {}: {}",
+                    spill.config.path.display(),
+                    e
+                );
+                spill.reset();
+                VecDeque::new()
+            }),
+            None => VecDeque::new(),
+        }
+    }
+
+    /// Put messages taken with `take_batch` but not delivered back at the front
+    fn requeue(&mut self, messages: VecDeque<IpcMessage>) {
+        for message in messages.into_iter().rev() {
+            self.memory.push_front(message);
+        }
+        self.count();
+    }
+
+    fn close(&mut self) {
+        if let Some(ref mut spill) = self.spill {
+            spill.close();
+        }
+    }
+}
+
+/// The spill file, appended to while disconnected and read back from `read_offset`
+struct Spill {
+    config: DiskSpill,
+    file: Option<File>,
+    written: u64,
+    read_offset: u64,
+    pending_messages: u64, // Written but not yet read back
+}
+
+impl Spill {
+    fn new(config: DiskSpill) -> Self {
+        Self {
+            config,
+            file: None,
+            written: 0,
+            read_offset: 0,
+            pending_messages: 0,
+        }
+    }
+
+    fn has_pending(&self) -> bool {
+        self.read_offset < self.written
+    }
+
+    fn write(&mut self, message: &IpcMessage) -> Result<()> {
+        let mut line = serde_json::to_vec(message)?;
+        line.push(b'\n');
+        if self.written + line.len() as u64 > self.config.max_bytes {
+            anyhow::bail!("spill file is full ({} bytes)", self.config.max_bytes);
+        }
+        let file = match self.file {
+            Some(ref mut file) => file,
+            None => self.file.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .truncate(true)
+                    .open(&self.config.path)?,
+            ),
+        };
+        file.write_all(&line)?;
+        self.written += line.len() as u64;
+        self.pending_messages += 1;
+        Ok(())
+    }
+
+    /// Up to `SPILL_BATCH_SIZE` messages from the read offset, which moves past them; once
+    /// everything is read back the file is emptied
+    fn read_batch(&mut self) -> Result<VecDeque<IpcMessage>> {
+        let mut batch = VecDeque::new();
+        if !self.has_pending() {
+            return Ok(batch);
+        }
+        let mut reader = BufReader::new(File::open(&self.config.path)?);
+        reader.seek(SeekFrom::Start(self.read_offset))?;
+        let mut line = String::new();
+        while batch.len() < SPILL_BATCH_SIZE && self.has_pending() {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            self.read_offset += read as u64;
+            self.pending_messages = self.pending_messages.saturating_sub(1);
+            match serde_json::from_str(&line) {
+                Ok(message) => batch.push_back(message),
+                Err(e) => warn!("Skipping unreadable spilled message: {}", e),
+            }
+        }
+        if !self.has_pending() {
+            self.reset();
+        }
+        Ok(batch)
+    }
+
+    /// Empty the file, forgetting anything not yet read back
+    fn reset(&mut self) {
+        if let Some(ref mut file) = self.file {
+            if let Err(e) = file.set_len(0).and_then(|()| file.rewind()) {
+                warn!(
+                    "Failed to empty spill file {}: {}",
+                    self.config.path.display(),
+                    e
+                );
+            }
+        }
+        self.written = 0;
+        self.read_offset = 0;
+        self.pending_messages = 0;
+    }
+
+    /// Sync what's left for a later look, or remove the file if nothing is
+    fn close(&mut self) {
+        let Some(file) = self.file.take() else {
+            return;
+        };
+        if self.has_pending() {
+            warn!(
+                "{} bytes of undelivered messages left in {}",
+                self.written - self.read_offset,
+                self.config.path.display()
+            );
+            if let Err(e) = file.sync_all() {
+                warn!(
+                    "Failed to sync spill file {}: {}",
+                    self.config.path.display(),
+                    e
+                );
+            }
+        } else {
+            drop(file);
+            let _ = std::fs::remove_file(&self.config.path);
+        }
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Where the monitor listens, and the token it may need
+struct Monitor {
+    socket_path: String,
+    token: Option<String>,
+    framing: IpcFraming,
+}
+
+struct Keepalive {
+    ping_interval: Duration,
+    pong_timeout: Duration,
+    last_pong: Arc<Mutex<Option<Instant>>>,
+}
+
+/// A registration about to go out over a live connection, which a `ProxyStarted` says
+fn connected_registration(message: IpcMessage) -> IpcMessage {
+    match message {
+        IpcMessage::ProxyStarted(mut info) => {
+            info.monitor_connected = true;
+            IpcMessage::ProxyStarted(info)
+        }
+        message => message,
+    }
+}
+
+/// Keep the entries of a message the monitor received, dropping the oldest past
+/// `HISTORY_SIZE`
+fn remember_delivered(history: &mut VecDeque<LogEntry>, message: &IpcMessage) {
+    let entries = match message {
+        IpcMessage::LogEntry(entry) => std::slice::from_ref(entry),
+        IpcMessage::LogsBatch(entries) => entries.as_slice(),
+        _ => return,
+    };
+    for entry in entries {
+        if history.len() == HISTORY_SIZE {
+            history.pop_front();
+        }
+        history.push_back(entry.clone());
+    }
+}
+
+/// The newest `limit` delivered entries of `proxy_id`, oldest first. The monitor skips
+/// the ones it still lists.
+/// `entries` cut down like single log entries and split into batches of at most
+/// `MAX_BATCH_BYTES` of messages, in order
+fn log_batches(entries: Vec<LogEntry>) -> Vec<Vec<LogEntry>> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut batch_bytes = 0;
+    for entry in entries {
+        let entry = entry.truncated(MAX_LOG_MESSAGE_BYTES);
+        if !batch.is_empty() && batch_bytes + entry.message.len() > MAX_BATCH_BYTES {
+            batches.push(std::mem::take(&mut batch));
+            batch_bytes = 0;
+        }
+        batch_bytes += entry.message.len();
+        batch.push(entry);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+fn recent_logs(
+    history: &VecDeque<LogEntry>,
+    proxy_id: &ProxyId,
+    limit: Option<usize>,
+) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = history
+        .iter()
+        .rev()
+        .filter(|entry| &entry.proxy_id == proxy_id)
+        .take(limit.unwrap_or(DEFAULT_GET_LOGS_LIMIT))
+        .cloned()
+        .collect();
+    entries.reverse();
+    entries
+}
+
+/// The next message from the monitor; never resolves while disconnected
+async fn receive_from(client: &mut Option<IpcClient>) -> Result<Option<IpcEnvelope>> {
+    match client {
+        Some(ipc_client) => ipc_client.receive().await,
+        None => std::future::pending().await,
+    }
+}
+
 impl Drop for BufferedIpcClient {
     fn drop(&mut self) {
         if let Some(shutdown_tx) = self.shutdown_tx.take() {
@@ -0,0 +1,221 @@
+use std::io;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// How JSON-RPC messages are delimited on the proxied stdio streams
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum FramingMode {
+    /// One message per line (the MCP stdio transport)
+    #[default]
+    #[value(alias = "line")]
+    Newline,
+    /// LSP-style `Content-Length: N` headers, a blank line, then N bytes of body
+    ContentLength,
+    /// Whichever of the two the first message uses, taking it for both directions
+    Auto,
+}
+
+/// Read the next message, returning `None` at end of stream.
+///
+/// In `Newline` mode the line is returned as read, including its terminator, so it can
+/// be forwarded unchanged; in `ContentLength` mode only the body is returned. `Auto`
+/// picks one of the two by the message's first bytes.
+pub async fn read_message<R>(reader: &mut R, mode: FramingMode) -> io::Result<Option<String>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    Ok(read_framed_message(reader, mode)
+        .await?
+        .map(|(message, _)| message))
+}
+
+/// Like `read_message`, also returning the framing the message was read with, which is
+/// never `Auto`
+pub(crate) async fn read_framed_message<R>(
+    reader: &mut R,
+    mode: FramingMode,
+) -> io::Result<Option<(String, FramingMode)>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mode = match mode {
+        FramingMode::Auto => detect_framing(reader).await?,
+        mode => mode,
+    };
+    let message = match mode {
+        FramingMode::Newline | FramingMode::Auto => {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(None);
+            }
+            Some(line)
+        }
+        FramingMode::ContentLength => read_content_length_message(reader).await?,
+    };
+    Ok(message.map(|message| (message, mode)))
+}
+
+/// What `read_chunk` got from a stream
+pub(crate) enum Chunk {
+    /// Bytes of newline-framed input as they arrived, to pass on untouched
+    Bytes(Vec<u8>),
+    /// The body of a `Content-Length` framed message
+    Message(String),
+}
+
+/// Read whatever newline-framed input is available, or the next whole message in
+/// `ContentLength` mode, with the framing it used (never `Auto`). `None` at end of
+/// stream. Newline-framed reads are cancel safe.
+pub(crate) async fn read_chunk<R>(
+    reader: &mut R,
+    mode: FramingMode,
+) -> io::Result<Option<(Chunk, FramingMode)>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mode = match mode {
+        FramingMode::Auto => detect_framing(reader).await?,
+        mode => mode,
+    };
+    if mode == FramingMode::ContentLength {
+        let message = read_content_length_message(reader).await?;
+        return Ok(message.map(|message| (Chunk::Message(message), mode)));
+    }
+    let buffered = reader.fill_buf().await?;
+    if buffered.is_empty() {
+        return Ok(None);
+    }
+    let bytes = buffered.to_vec();
+    reader.consume(bytes.len());
+    Ok(Some((Chunk::Bytes(bytes), FramingMode::Newline)))
+}
+
+/// Picks the complete lines out of newline-framed bytes being passed through, so they
+/// can be logged
+#[derive(Default)]
+pub(crate) struct LineScanner {
+    partial: Vec<u8>, // Start of a line whose newline hasn't arrived yet
+}
+
+impl LineScanner {
+    /// The lines `chunk` completes, each with its terminator; blank lines are skipped
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        let mut lines = Vec::new();
+        let mut rest = chunk;
+        while let Some(newline) = rest.iter().position(|&byte| byte == b'\n') {
+            self.partial.extend_from_slice(&rest[..=newline]);
+            rest = &rest[newline + 1..];
+            let line = std::mem::take(&mut self.partial);
+            if !is_blank(&line) {
+                lines.push(line);
+            }
+        }
+        self.partial.extend_from_slice(rest);
+        lines
+    }
+
+    /// The last line if the stream ended without its newline
+    pub(crate) fn finish(&mut self) -> Option<Vec<u8>> {
+        let line = std::mem::take(&mut self.partial);
+        (!is_blank(&line)).then_some(line)
+    }
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+/// The framing of the message at the front of `reader`, waiting for its first bytes:
+/// `ContentLength` if it opens with a header, `Newline` for anything else (JSON starts
+/// with `{` or `[`) or an empty stream
+pub async fn detect_framing<R>(reader: &mut R) -> io::Result<FramingMode>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let buffered = reader.fill_buf().await?;
+    let start = buffered
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(buffered.len());
+    let head = &buffered[start..];
+    // Any header counts, as LSP allows Content-Type ahead of Content-Length
+    let header = head.first().is_some_and(u8::is_ascii_alphabetic);
+    Ok(if header {
+        FramingMode::ContentLength
+    } else {
+        FramingMode::Newline
+    })
+}
+
+async fn read_content_length_message<R>(reader: &mut R) -> io::Result<Option<String>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut content_length = None;
+    let mut saw_header = false;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            if saw_header {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream ended inside message headers",
+                ));
+            }
+            return Ok(None);
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            // Blank line ends the headers; stray blank lines before them are skipped
+            if saw_header {
+                break;
+            }
+            continue;
+        }
+        saw_header = true;
+
+        // Other headers such as Content-Type are accepted and ignored
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                let length = value.trim().parse::<usize>().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid Content-Length header: {}", line),
+                    )
+                })?;
+                content_length = Some(length);
+            }
+        }
+    }
+
+    let length = content_length.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message headers have no Content-Length",
+        )
+    })?;
+
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write `message` with the framing `read_message` expects, and flush it
+pub async fn write_message<W>(writer: &mut W, message: &str, mode: FramingMode) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    match mode {
+        // Only read messages are written back, so `Auto` has already settled on a framing
+        FramingMode::Newline | FramingMode::Auto => writer.write_all(message.as_bytes()).await?,
+        FramingMode::ContentLength => {
+            // One write, so the header and body can't be split by other output
+            let framed = format!("Content-Length: {}\r\n\r\n{}", message.len(), message);
+            writer.write_all(framed.as_bytes()).await?;
+        }
+    }
+    writer.flush().await
+}
@@ -0,0 +1,399 @@
+use crate::buffered_ipc_client::BufferedIpcClient;
+use anyhow::Result;
+use bytes::Bytes;
+use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{self, HeaderName};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode, Uri};
+use hyper_util::rt::TokioIo;
+use mcp_common::{IpcMessage, JsonRpcMessage, LogEntry, LogLevel, ProxyId, ProxyStats};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+type ProxyBody = UnsyncBoxBody<Bytes, hyper::Error>;
+
+/// Headers that only concern one hop, so they aren't passed along
+const HOP_BY_HOP: [HeaderName; 6] = [
+    header::CONNECTION,
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+/// Proxies an MCP server speaking the streamable HTTP transport: client requests are
+/// forwarded to the target and both sides are logged to the monitor, including each
+/// event of an SSE response
+pub struct HttpHandler {
+    proxy_id: ProxyId,
+    target: Target,
+    stats: Arc<Mutex<ProxyStats>>,
+    ipc_client: Option<Arc<BufferedIpcClient>>,
+    started: Instant, // Reported as the proxy's uptime
+}
+
+impl HttpHandler {
+    /// Forward to `target`, an `http://` URL whose path (if any) prefixes every request's
+    pub fn new(
+        proxy_id: ProxyId,
+        target: &str,
+        stats: Arc<Mutex<ProxyStats>>,
+        ipc_client: Option<Arc<BufferedIpcClient>>,
+    ) -> Result<Self> {
+        Ok(Self {
+            proxy_id,
+            target: Target::parse(target)?,
+            stats,
+            ipc_client,
+            started: Instant::now(),
+        })
+    }
+
+    /// Serve clients connecting to `listener` until `shutdown_rx` fires
+    pub async fn serve(
+        self,
+        listener: TcpListener,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let (traffic_tx, mut traffic_rx) = mpsc::unbounded_channel();
+        let forwarder = Arc::new(Forwarder {
+            proxy_id: self.proxy_id.clone(),
+            target: self.target.clone(),
+            stats: self.stats.clone(),
+            traffic: traffic_tx,
+        });
+        let mut stats_interval = interval(Duration::from_secs(1));
+
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.recv() => {
+                    info!("Received shutdown signal");
+                    break;
+                }
+
+                accepted = listener.accept() => {
+                    let stream = match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(e) => {
+                            warn!("Failed to accept HTTP client: {}", e);
+                            continue;
+                        }
+                    };
+                    tokio::spawn(forwarder.clone().serve_connection(stream));
+                }
+
+                Some(traffic) = traffic_rx.recv() => {
+                    self.record(traffic).await;
+                }
+
+                _ = stats_interval.tick() => {
+                    if let Some(ref client) = self.ipc_client {
+                        report_stats(&self.stats, client, self.started).await;
+                    }
+                }
+            }
+        }
+
+        // Entries logged just before the shutdown still reach the monitor
+        while let Ok(traffic) = traffic_rx.try_recv() {
+            self.record(traffic).await;
+        }
+        Ok(())
+    }
+
+    async fn record(&self, traffic: Traffic) {
+        let entry = match traffic {
+            Traffic::Log(entry) => entry,
+            Traffic::Event(data) => {
+                {
+                    let mut stats = self.stats.lock().await;
+                    stats.record_response_bytes(data.len() as u64);
+                }
+                debug!("Event: {}", data);
+                LogEntry::new(
+                    LogLevel::Response,
+                    format!("← {}", data.trim()),
+                    self.proxy_id.clone(),
+                )
+                .with_error_code(&JsonRpcMessage::parse_all(data.trim()))
+            }
+        };
+        if let Some(ref client) = self.ipc_client {
+            if let Err(e) = client.send(IpcMessage::LogEntry(entry)).await {
+                warn!("Failed to send log entry: {}", e);
+            }
+        }
+    }
+}
+
+/// Send the monitor the latest stats, with the IPC client's own and the time since `started`
+pub(crate) async fn report_stats(
+    stats: &Mutex<ProxyStats>,
+    client: &BufferedIpcClient,
+    started: Instant,
+) {
+    let stats = {
+        let mut stats = stats.lock().await;
+        let now = Instant::now();
+        stats.update_rate(now);
+        stats.uptime = now.saturating_duration_since(started);
+        client.metrics().apply_to(&mut stats);
+        stats.clone()
+    };
+    if let Err(e) = client.send(IpcMessage::StatsUpdate(stats)).await {
+        warn!("Failed to send stats update: {}", e);
+    }
+}
+
+/// What the connection tasks hand back to `HttpHandler::serve`, in order
+enum Traffic {
+    Log(LogEntry),
+    Event(String), // The data of one SSE event from the target
+}
+
+/// The target server's address and the path its requests go under
+#[derive(Clone)]
+struct Target {
+    authority: String,
+    address: String, // host:port to connect to
+    base_path: String,
+}
+
+impl Target {
+    fn parse(target: &str) -> Result<Self> {
+        let uri: Uri = target
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid target URL {}: {}", target, e))?;
+        if uri.scheme_str() != Some("http") {
+            anyhow::bail!("target {} must be an http:// URL", target);
+        }
+        let authority = uri
+            .authority()
+            .ok_or_else(|| anyhow::anyhow!("target {} has no host", target))?;
+        Ok(Self {
+            authority: authority.to_string(),
+            address: format!(
+                "{}:{}",
+                authority.host(),
+                authority.port_u16().unwrap_or(80)
+            ),
+            base_path: uri.path().trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Where a request for `uri` on the proxy goes
+    fn uri_for(&self, uri: &Uri) -> String {
+        let path = uri.path_and_query().map_or("/", |path| path.as_str());
+        let path = match path {
+            "/" if !self.base_path.is_empty() => "",
+            path => path,
+        };
+        format!("http://{}{}{}", self.authority, self.base_path, path)
+    }
+}
+
+/// Shared by the connection tasks
+struct Forwarder {
+    proxy_id: ProxyId,
+    target: Target,
+    stats: Arc<Mutex<ProxyStats>>,
+    traffic: mpsc::UnboundedSender<Traffic>,
+}
+
+impl Forwarder {
+    async fn serve_connection(self: Arc<Self>, stream: TcpStream) {
+        self.stats.lock().await.active_connections += 1;
+        let forwarder = self.clone();
+        let service = service_fn(move |request| forwarder.clone().forward(request));
+        if let Err(e) = http1::Builder::new()
+            .serve_connection(TokioIo::new(stream), service)
+            .await
+        {
+            debug!("HTTP client connection ended: {}", e);
+        }
+        let mut stats = self.stats.lock().await;
+        stats.active_connections = stats.active_connections.saturating_sub(1);
+    }
+
+    async fn forward(
+        self: Arc<Self>,
+        request: Request<Incoming>,
+    ) -> Result<Response<ProxyBody>, Infallible> {
+        let started = Instant::now();
+        let (parts, body) = request.into_parts();
+        let body = match body.collect().await {
+            Ok(collected) => collected.to_bytes(),
+            Err(e) => {
+                return Ok(self
+                    .bad_gateway(format!("Failed to read request body: {}", e))
+                    .await);
+            }
+        };
+
+        if body.is_empty() {
+            // Opening an event stream (GET) or ending a session (DELETE)
+            self.log(LogLevel::Info, format!("{} {}", parts.method, parts.uri));
+        } else {
+            let content = String::from_utf8_lossy(&body);
+            debug!("Request: {}", content.trim());
+            self.log(LogLevel::Request, format!("→ {}", content.trim()));
+            let mut stats = self.stats.lock().await;
+            stats.total_requests += 1;
+            stats.record_request(Instant::now());
+            stats.record_request_bytes(body.len() as u64);
+        }
+
+        let mut outgoing = Request::builder()
+            .method(parts.method)
+            .uri(self.target.uri_for(&parts.uri));
+        for (name, value) in &parts.headers {
+            if *name != header::HOST && !HOP_BY_HOP.contains(name) {
+                outgoing = outgoing.header(name, value);
+            }
+        }
+        let outgoing = match outgoing
+            .header(header::HOST, &self.target.authority)
+            .body(Full::new(body))
+        {
+            Ok(outgoing) => outgoing,
+            Err(e) => return Ok(self.bad_gateway(format!("Invalid request: {}", e)).await),
+        };
+
+        let response = match self.send(outgoing).await {
+            Ok(response) => response,
+            Err(e) => {
+                return Ok(self
+                    .bad_gateway(format!("Failed to reach {}: {}", self.target.authority, e))
+                    .await);
+            }
+        };
+
+        let (mut parts, body) = response.into_parts();
+        {
+            let mut stats = self.stats.lock().await;
+            if parts.status.is_success() {
+                stats.successful_requests += 1;
+            } else {
+                stats.failed_requests += 1;
+            }
+        }
+        for name in &HOP_BY_HOP {
+            parts.headers.remove(name);
+        }
+
+        let event_stream = parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if event_stream {
+            // Streamed through as it arrives, logging each event once it is complete
+            let mut events = SseEvents::default();
+            let traffic = self.traffic.clone();
+            let body = body.map_frame(move |frame| {
+                if let Some(data) = frame.data_ref() {
+                    for event in events.push(data) {
+                        let _ = traffic.send(Traffic::Event(event));
+                    }
+                }
+                frame
+            });
+            return Ok(Response::from_parts(parts, body.boxed_unsync()));
+        }
+
+        let body = match body.collect().await {
+            Ok(collected) => collected.to_bytes(),
+            Err(e) => {
+                return Ok(self
+                    .bad_gateway(format!("Failed to read response body: {}", e))
+                    .await);
+            }
+        };
+        if !body.is_empty() {
+            let content = String::from_utf8_lossy(&body);
+            debug!("Response: {}", content.trim());
+            let mut entry = LogEntry::new(
+                LogLevel::Response,
+                format!("← {}", content.trim()),
+                self.proxy_id.clone(),
+            )
+            .with_error_code(&JsonRpcMessage::parse_all(content.trim()));
+            entry.duration = Some(started.elapsed());
+            let _ = self.traffic.send(Traffic::Log(entry));
+            let mut stats = self.stats.lock().await;
+            stats.record_response_bytes(body.len() as u64);
+        }
+        Ok(Response::from_parts(parts, full(body)))
+    }
+
+    /// One connection per request, kept open for as long as its response streams
+    async fn send(&self, request: Request<Full<Bytes>>) -> Result<Response<Incoming>> {
+        let stream = TcpStream::connect(&self.target.address).await?;
+        let (mut sender, connection) =
+            hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                debug!("Target connection ended: {}", e);
+            }
+        });
+        Ok(sender.send_request(request).await?)
+    }
+
+    fn log(&self, level: LogLevel, message: String) {
+        let entry = LogEntry::new(level, message, self.proxy_id.clone());
+        let _ = self.traffic.send(Traffic::Log(entry));
+    }
+
+    /// A 502 for the client, with `message` logged as an error
+    async fn bad_gateway(&self, message: String) -> Response<ProxyBody> {
+        warn!("{}", message);
+        self.log(LogLevel::Error, message.clone());
+        self.stats.lock().await.failed_requests += 1;
+        let mut response = Response::new(full(Bytes::from(message)));
+        *response.status_mut() = StatusCode::BAD_GATEWAY;
+        response
+    }
+}
+
+fn full(body: Bytes) -> ProxyBody {
+    Full::new(body)
+        .map_err(|never| match never {})
+        .boxed_unsync()
+}
+
+/// Splits an SSE stream into the data of its events, across chunk boundaries
+#[derive(Default)]
+struct SseEvents {
+    buffer: Vec<u8>,
+}
+
+impl SseEvents {
+    /// The data of each event `chunk` completes; events without data are skipped
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        // Lines may end in \r\n; dropping the \r leaves a blank line between events
+        self.buffer
+            .extend(chunk.iter().filter(|byte| **byte != b'\r'));
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|pair| pair == b"\n\n") {
+            let block: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            let block = String::from_utf8_lossy(&block);
+            let data: Vec<&str> = block
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+        events
+    }
+}
@@ -1,37 +1,175 @@
 use anyhow::Result;
-use mcp_common::ProxyId;
+use mcp_common::{IpcFraming, ProxyId};
+use std::fs::OpenOptions;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "opentelemetry")]
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
 use tracing::info;
 
 mod buffered_ipc_client;
+mod framing;
+mod http_handler;
 mod proxy;
 mod stdio_handler;
+#[cfg(feature = "opentelemetry")]
+mod telemetry;
+mod ws_handler;
 
 use proxy::MCPProxy;
 
 // Export modules for testing
-pub use buffered_ipc_client::BufferedIpcClient;
-pub use stdio_handler::StdioHandler;
+pub use buffered_ipc_client::{BufferMetrics, BufferedIpcClient, DiskSpill, MAX_LOG_MESSAGE_BYTES};
+pub use framing::{detect_framing, read_message, write_message, FramingMode};
+pub use http_handler::HttpHandler;
+pub use proxy::{
+    RestartPolicy, ShutdownHandle, DEFAULT_SHUTDOWN_TIMEOUT_MS, DEFAULT_STATS_INTERVAL_MS,
+};
+pub use stdio_handler::{detect_level_from_stderr, StdioError, StdioHandler};
+#[cfg(feature = "opentelemetry")]
+pub use telemetry::RequestTracer;
+pub use ws_handler::WsHandler;
 
 pub struct ProxyArgs {
-    pub command: String,
+    pub command: Vec<String>, // The MCP server's argv, see `server_command`
     pub name: String,
     pub ipc_socket: String,
     pub verbose: bool,
-    pub shell: bool,
+    pub log_file: Option<PathBuf>, // Proxy logs go here instead of stderr
+    pub env_vars: Vec<(String, String)>, // Never logged: they often hold tokens
+    pub env_clear: bool,
+    pub cwd: Option<PathBuf>,
+    pub ipc_token: Option<String>, // For a monitor listening on TCP with --ipc-token
+    pub ipc_framing: IpcFraming,
     pub no_monitor: bool,
+    pub restart_policy: RestartPolicy,
+    pub framing: FramingMode,
+    pub listen: Option<SocketAddr>, // With `target`, proxy HTTP instead of running `command`
+    pub target: Option<String>,
+    pub otlp_endpoint: Option<String>, // Needs the `opentelemetry` feature
+    pub otlp_span_timeout: Duration,
+    pub shutdown_timeout_ms: u64, // Between SIGTERM and SIGKILL for the server
+    pub request_timeout: Option<Duration>, // Warn about stdio requests unanswered this long
+    pub stats_interval_ms: u64,   // Between stdio stats updates for the monitor
+}
+
+/// The argv to run the MCP server with: the arguments after `--` as given, or else
+/// `--command` through `sh -c` (`shell`) or split on whitespace
+pub fn server_command(
+    command: Option<String>,
+    args: Vec<String>,
+    shell: bool,
+) -> Result<Vec<String>> {
+    match command {
+        Some(_) if !args.is_empty() => Err(anyhow::anyhow!(
+            "Give the MCP server command either with --command or after --, not both"
+        )),
+        Some(command) if shell => Ok(vec!["sh".to_string(), "-c".to_string(), command]),
+        Some(command) => Ok(command.split_whitespace().map(str::to_string).collect()),
+        None => Ok(args),
+    }
+}
+
+/// Parse a `KEY=VALUE` environment assignment, for `--env`
+pub fn parse_env_var(assignment: &str) -> Result<(String, String), String> {
+    match assignment.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{}`", assignment)),
+    }
+}
+
+/// Read a `.env` file: one `KEY=VALUE` per line, skipping blank lines and `#` comments
+pub fn load_env_file(path: &Path) -> Result<Vec<(String, String)>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("failed to read {}: {}", path.display(), e))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(index, line)| {
+            parse_env_var(line.trim())
+                .map_err(|e| anyhow::anyhow!("{} line {}: {}", path.display(), index + 1, e))
+        })
+        .collect()
+}
+
+/// The server's variables: `env_file`'s, with `env` (from `--env`) replacing any of the
+/// same name
+pub fn server_env(
+    env_file: Option<&Path>,
+    env: Vec<(String, String)>,
+) -> Result<Vec<(String, String)>> {
+    let mut env_vars = match env_file {
+        Some(path) => load_env_file(path)?,
+        None => Vec::new(),
+    };
+    for (key, value) in env {
+        match env_vars.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(entry) => entry.1 = value,
+            None => env_vars.push((key, value)),
+        }
+    }
+    Ok(env_vars)
+}
+
+/// The server's working directory from `--cwd`, with a leading `~` taken as `$HOME`.
+/// Checked here so a typo fails before anything starts rather than at each spawn.
+pub fn server_cwd(cwd: Option<PathBuf>) -> Result<Option<PathBuf>> {
+    let Some(cwd) = cwd else {
+        return Ok(None);
+    };
+    let cwd = match (cwd.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => cwd,
+    };
+    if !cwd.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Working directory '{}' does not exist",
+            cwd.display()
+        ));
+    }
+    Ok(Some(cwd))
 }
 
 pub async fn run_proxy_app(args: ProxyArgs) -> Result<()> {
-    // Initialize tracing
+    // Initialize tracing, never on stdout: that is the MCP client's message stream
     let log_level = if args.verbose { "debug" } else { "info" };
-    tracing_subscriber::fmt()
-        .with_env_filter(format!("mcp_proxy={},mcp_common={}", log_level, log_level))
-        .init();
+    let env_filter = format!("mcp_proxy={},mcp_common={}", log_level, log_level);
+    match args.log_file {
+        Some(ref path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| anyhow::anyhow!("failed to open {}: {}", path.display(), e))?;
+            tracing_subscriber::fmt()
+                .with_env_filter(env_filter)
+                .with_ansi(false)
+                .with_writer(std::sync::Mutex::new(file))
+                .init();
+        }
+        None => tracing_subscriber::fmt()
+            .with_env_filter(env_filter)
+            .with_writer(std::io::stderr)
+            .init(),
+    }
 
     info!("Starting MCP Proxy: {}", args.name);
-    info!("Target command: {}", args.command);
+    let http = match (args.listen, args.target) {
+        (Some(listen), Some(target)) => Some((listen, target)),
+        (None, None) => None,
+        _ => return Err(anyhow::anyhow!("--listen and --target go together")),
+    };
+    match http {
+        Some((_, ref target)) => info!("Target URL: {}", target),
+        None => info!("Target command: {:?}", args.command),
+    }
 
-    if args.command.is_empty() {
+    if args.command.is_empty() && http.is_none() {
         return Err(anyhow::anyhow!(
             "No command specified. Use --command to specify the MCP server command."
         ));
@@ -39,13 +177,49 @@ pub async fn run_proxy_app(args: ProxyArgs) -> Result<()> {
 
     // Create proxy instance
     let proxy_id = ProxyId::new();
-    let mut proxy = MCPProxy::new(
-        proxy_id.clone(),
-        args.name.clone(),
-        args.command.clone(),
-        args.shell,
-    )
-    .await?;
+    let mut proxy = MCPProxy::new(proxy_id.clone(), args.name.clone(), args.command.clone())
+        .await?
+        .with_restart_policy(args.restart_policy)
+        .with_framing(args.framing)
+        .with_env(args.env_vars, args.env_clear)
+        .with_cwd(args.cwd)
+        .with_ipc_token(args.ipc_token)
+        .with_ipc_framing(args.ipc_framing)
+        .with_graceful_shutdown_timeout_ms(args.shutdown_timeout_ms)
+        .with_request_timeout(args.request_timeout)
+        .with_stats_interval_ms(args.stats_interval_ms);
+    if let Some((listen, target)) = http {
+        proxy = proxy.with_http(listen, target);
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    let tracer = match args.otlp_endpoint {
+        Some(ref endpoint) => Some(Arc::new(RequestTracer::new(
+            endpoint,
+            args.name.clone(),
+            proxy_id.clone(),
+            args.otlp_span_timeout,
+        )?)),
+        None => None,
+    };
+    #[cfg(feature = "opentelemetry")]
+    {
+        proxy = proxy.with_tracer(tracer.clone());
+    }
+    #[cfg(not(feature = "opentelemetry"))]
+    if args.otlp_endpoint.is_some() {
+        return Err(anyhow::anyhow!(
+            "--otlp-endpoint needs mcp-proxy built with the opentelemetry feature"
+        ));
+    }
+
+    // Ctrl+C or a kill stops the MCP server too, and still reports ProxyStopped
+    let stop = stop_signal()?;
+    let shutdown = proxy.shutdown_handle();
+    tokio::spawn(async move {
+        stop.await;
+        shutdown.shutdown();
+    });
 
     // Start the proxy
     let ipc_socket = if args.no_monitor {
@@ -53,7 +227,39 @@ pub async fn run_proxy_app(args: ProxyArgs) -> Result<()> {
     } else {
         Some(args.ipc_socket.as_str())
     };
-    proxy.start(ipc_socket).await?;
+    let result = proxy.start(ipc_socket).await;
+
+    #[cfg(feature = "opentelemetry")]
+    if let Some(tracer) = tracer {
+        tracer.shutdown().await;
+    }
+    result
+}
+
+/// Resolves on SIGINT or SIGTERM; the handlers are installed before this returns, so a
+/// failure to install them is reported up front
+#[cfg(unix)]
+fn stop_signal() -> Result<impl Future<Output = ()>> {
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    Ok(async move {
+        tokio::select! {
+            _ = interrupt.recv() => info!("Received SIGINT"),
+            _ = terminate.recv() => info!("Received SIGTERM"),
+        }
+    })
+}
 
-    Ok(())
+/// Resolves on Ctrl+C, the one stop signal outside unix
+#[cfg(not(unix))]
+fn stop_signal() -> Result<impl Future<Output = ()>> {
+    Ok(async {
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => info!("Received Ctrl+C"),
+            Err(e) => {
+                tracing::warn!("Failed to listen for Ctrl+C: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    })
 }
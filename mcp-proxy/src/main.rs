@@ -1,29 +1,73 @@
 use anyhow::Result;
 use clap::Parser;
-use mcp_proxy::{run_proxy_app, ProxyArgs};
+use mcp_common::IpcFraming;
+use mcp_proxy::{
+    parse_env_var, run_proxy_app, server_command, server_cwd, server_env, FramingMode, ProxyArgs,
+    RestartPolicy, DEFAULT_SHUTDOWN_TIMEOUT_MS, DEFAULT_STATS_INTERVAL_MS,
+};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "mcp-proxy")]
 #[command(about = "STDIO-based MCP proxy server")]
 pub struct Args {
     /// MCP server command to proxy (as a single string, will be executed via shell)
-    #[arg(short, long)]
-    pub command: String,
+    #[arg(short, long, required_unless_present_any = ["command_args", "target"])]
+    pub command: Option<String>,
+
+    /// MCP server command and arguments after `--`, run as given without a shell
+    #[arg(last = true, value_name = "COMMAND", conflicts_with = "command")]
+    pub command_args: Vec<String>,
 
     /// Name for this proxy instance
     #[arg(short, long)]
     pub name: Option<String>,
 
-    /// IPC socket path for monitor communication
-    #[arg(short, long, default_value = "/tmp/mcp-monitor.sock")]
+    /// IPC socket path for monitor communication (or tcp://HOST:PORT)
+    #[arg(short, long, default_value = mcp_common::DEFAULT_IPC_SOCKET)]
     pub ipc_socket: String,
 
+    /// Token the monitor's TCP IPC socket expects (see the monitor's --ipc-token)
+    #[arg(
+        long,
+        env = "MCP_TRACE_IPC_TOKEN",
+        value_name = "TOKEN",
+        hide_env_values = true
+    )]
+    pub ipc_token: Option<String>,
+
+    /// Delimit messages to the monitor with lines or length-prefixed frames
+    #[arg(long, value_name = "FRAMING", default_value_t = IpcFraming::Lines)]
+    pub ipc_framing: IpcFraming,
+
     /// Verbose logging
     #[arg(short, long)]
     pub verbose: bool,
 
+    /// Write the proxy's own logs to this file instead of stderr
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Set an environment variable for the MCP server (repeatable)
+    #[arg(short, long, value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub env: Vec<(String, String)>,
+
+    /// Read environment variables for the MCP server from a .env file (--env wins)
+    #[arg(long, value_name = "PATH")]
+    pub env_file: Option<PathBuf>,
+
+    /// Start the MCP server from an empty environment, plus --env variables
+    #[arg(long, visible_alias = "clear-env", default_value_t = false)]
+    pub env_clear: bool,
+
+    /// Working directory for the MCP server; a leading ~ is the home directory
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
     /// Use shell to execute command (enabled by default)
     #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
     pub shell: bool,
@@ -31,6 +75,63 @@ pub struct Args {
     /// Skip connecting to monitor (standalone mode)
     #[arg(long, default_value_t = false)]
     pub no_monitor: bool,
+
+    /// When to restart the MCP server after it exits, with exponential backoff:
+    /// never, on-failure or always, optionally with a maximum like always:3
+    #[arg(long, value_name = "POLICY[:MAX]", conflicts_with = "restart_on_crash")]
+    pub restart: Option<String>,
+
+    /// Same as --restart on-failure
+    #[arg(long, default_value_t = false)]
+    pub restart_on_crash: bool,
+
+    /// Maximum number of restarts when --restart gives none
+    #[arg(long, default_value_t = 5)]
+    pub max_restarts: u32,
+
+    /// Delay before the first restart in milliseconds (doubles on each attempt)
+    #[arg(long, default_value_t = 500)]
+    pub restart_delay_ms: u64,
+
+    /// Message framing used by the MCP server and its client
+    #[arg(long, value_enum, default_value_t = FramingMode::Newline)]
+    pub framing: FramingMode,
+
+    /// Accept MCP clients over HTTP on this address, e.g. 127.0.0.1:9000 (with --target)
+    #[arg(long, value_name = "ADDR", requires = "target")]
+    pub listen: Option<SocketAddr>,
+
+    /// URL of an MCP server using the streamable HTTP transport to forward --listen
+    /// clients to, e.g. http://localhost:3000, or a ws:// URL to relay WebSocket clients
+    #[arg(long, value_name = "URL", requires = "listen", conflicts_with_all = ["command", "command_args"])]
+    pub target: Option<String>,
+
+    /// Export a span per request/response pair to this OTLP/HTTP collector, e.g.
+    /// http://localhost:4318 (needs the opentelemetry feature)
+    #[arg(long, value_name = "URL")]
+    pub otlp_endpoint: Option<String>,
+
+    /// Seconds without a response before a request's span ends as an error
+    #[arg(long, value_name = "SECS", default_value_t = 30)]
+    pub otlp_span_timeout: u64,
+
+    /// Milliseconds the MCP server gets to exit after SIGTERM before it is killed
+    #[arg(long, value_name = "MS", default_value_t = DEFAULT_SHUTDOWN_TIMEOUT_MS)]
+    pub shutdown_timeout_ms: u64,
+
+    /// Log a warning for each request the MCP server leaves unanswered this many seconds,
+    /// repeated every time that much longer passes
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub request_timeout: Option<u64>,
+
+    /// Milliseconds between the stats updates a stdio proxy sends the monitor
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = DEFAULT_STATS_INTERVAL_MS,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub stats_interval_ms: u64,
 }
 
 #[tokio::main]
@@ -47,13 +148,36 @@ async fn main() -> Result<()> {
         format!("mcp-proxy-{}", random_suffix)
     });
 
+    let restart_spec = match args.restart {
+        Some(ref spec) => spec.as_str(),
+        None if args.restart_on_crash => "on-failure",
+        None => "never",
+    };
+    let restart_policy =
+        RestartPolicy::from_spec(restart_spec, args.max_restarts, args.restart_delay_ms)
+            .map_err(anyhow::Error::msg)?;
+
     let proxy_args = ProxyArgs {
-        command: args.command,
+        command: server_command(args.command, args.command_args, args.shell)?,
         name,
         ipc_socket: args.ipc_socket,
+        ipc_token: args.ipc_token,
+        ipc_framing: args.ipc_framing,
         verbose: args.verbose,
-        shell: args.shell,
+        log_file: args.log_file,
+        env_vars: server_env(args.env_file.as_deref(), args.env)?,
+        env_clear: args.env_clear,
+        cwd: server_cwd(args.cwd)?,
         no_monitor: args.no_monitor,
+        restart_policy,
+        framing: args.framing,
+        listen: args.listen,
+        target: args.target,
+        otlp_endpoint: args.otlp_endpoint,
+        otlp_span_timeout: Duration::from_secs(args.otlp_span_timeout),
+        shutdown_timeout_ms: args.shutdown_timeout_ms,
+        request_timeout: args.request_timeout.map(Duration::from_secs),
+        stats_interval_ms: args.stats_interval_ms,
     };
 
     run_proxy_app(proxy_args).await
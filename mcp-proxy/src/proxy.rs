@@ -1,44 +1,274 @@
 use anyhow::Result;
-use mcp_common::{IpcMessage, ProxyId, ProxyInfo, ProxyStats, ProxyStatus};
-use std::process::Stdio;
+use mcp_common::{
+    IpcFraming, IpcMessage, LogEntry, LogLevel, ProxyId, ProxyInfo, ProxyStats, ProxyStatus,
+    REDACTED_ENV_VALUE,
+};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
 use tokio::process::{Child, Command};
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::{broadcast, mpsc, Mutex};
 use tracing::{info, warn};
 
 use crate::buffered_ipc_client::BufferedIpcClient;
-use crate::stdio_handler::StdioHandler;
+use crate::framing::FramingMode;
+use crate::http_handler::HttpHandler;
+use crate::stdio_handler::{StdioError, StdioHandler};
+#[cfg(feature = "opentelemetry")]
+use crate::telemetry::RequestTracer;
+use crate::ws_handler::WsHandler;
+
+/// What the proxy does when the MCP server process exits on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// Shut the proxy down along with the server
+    #[default]
+    Never,
+    /// Like `Always`, but only after the server exits with a failure status
+    OnFailure {
+        max_attempts: u32,
+        base_delay_ms: u64,
+    },
+    /// Restart the server up to `max_attempts` times, doubling the delay each time
+    Always {
+        max_attempts: u32,
+        base_delay_ms: u64,
+    },
+}
+
+impl RestartPolicy {
+    /// Parse `never`, `on-failure` or `always`, optionally followed by `:max_attempts`
+    /// (otherwise `default_max_attempts`)
+    pub fn from_spec(
+        spec: &str,
+        default_max_attempts: u32,
+        base_delay_ms: u64,
+    ) -> Result<Self, String> {
+        let (mode, max_attempts) = match spec.split_once(':') {
+            Some((mode, max)) => {
+                let max = max
+                    .parse()
+                    .map_err(|_| format!("invalid restart count '{}' in '{}'", max, spec))?;
+                (mode, max)
+            }
+            None => (spec, default_max_attempts),
+        };
+        match mode {
+            "never" => Ok(RestartPolicy::Never),
+            "on-failure" => Ok(RestartPolicy::OnFailure {
+                max_attempts,
+                base_delay_ms,
+            }),
+            "always" => Ok(RestartPolicy::Always {
+                max_attempts,
+                base_delay_ms,
+            }),
+            _ => Err(format!(
+                "unknown restart policy '{}', expected never, on-failure or always",
+                mode
+            )),
+        }
+    }
+
+    /// Whether an exit with `status` calls for a restart (if attempts remain)
+    pub fn restarts_after(&self, status: &ExitStatus) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure { .. } => !status.success(),
+            RestartPolicy::Always { .. } => true,
+        }
+    }
+
+    /// Backoff before restart number `attempt` (1-based), or `None` once attempts are used up
+    pub fn delay_for(&self, attempt: u32) -> Option<Duration> {
+        match *self {
+            RestartPolicy::Never => None,
+            RestartPolicy::OnFailure {
+                max_attempts,
+                base_delay_ms,
+            }
+            | RestartPolicy::Always {
+                max_attempts,
+                base_delay_ms,
+            } => {
+                if attempt == 0 || attempt > max_attempts {
+                    return None;
+                }
+                let factor = 1u64 << (attempt - 1).min(16);
+                Some(Duration::from_millis(base_delay_ms.saturating_mul(factor)))
+            }
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        match *self {
+            RestartPolicy::Never => 0,
+            RestartPolicy::OnFailure { max_attempts, .. }
+            | RestartPolicy::Always { max_attempts, .. } => max_attempts,
+        }
+    }
+}
+
+/// How long the MCP server gets to exit after SIGTERM before it is killed, unless
+/// `--shutdown-timeout-ms` says otherwise
+pub const DEFAULT_SHUTDOWN_TIMEOUT_MS: u64 = 5000;
+
+/// How often a stdio proxy sends the monitor its stats, unless `--stats-interval-ms`
+/// says otherwise
+pub const DEFAULT_STATS_INTERVAL_MS: u64 = 1000;
+
+/// Stops a running proxy from another task, like the monitor or a signal handler
+#[derive(Clone)]
+pub struct ShutdownHandle {
+    tx: broadcast::Sender<()>,
+    requested: Arc<AtomicBool>, // For a session that isn't listening yet
+}
+
+impl ShutdownHandle {
+    pub fn shutdown(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+        let _ = self.tx.send(());
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.tx.subscribe()
+    }
+
+    fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+}
 
 pub struct MCPProxy {
     id: ProxyId,
     name: String,
-    command: String,
-    use_shell: bool,
+    command: Vec<String>, // argv, run without a shell
     stats: Arc<Mutex<ProxyStats>>,
-    shutdown_tx: Option<broadcast::Sender<()>>,
+    shutdown: ShutdownHandle,
+    restart_policy: RestartPolicy,
+    framing: FramingMode,
+    env_vars: Vec<(String, String)>, // Set for the server on top of its environment
+    env_clear: bool,                 // Start the server from an empty environment
+    cwd: Option<PathBuf>,
+    ipc_token: Option<String>, // Sent to the monitor first on every connection
+    ipc_framing: IpcFraming,
+    http: Option<(SocketAddr, String)>, // Listen address and target URL, instead of a command
+    graceful_shutdown_timeout_ms: u64,  // SIGTERM to SIGKILL, see `terminate`
+    request_timeout: Option<Duration>,  // Unanswered this long, a request gets a warning
+    stats_interval_ms: u64,
+    #[cfg(feature = "opentelemetry")]
+    tracer: Option<Arc<RequestTracer>>,
 }
 
 impl MCPProxy {
-    pub async fn new(id: ProxyId, name: String, command: String, use_shell: bool) -> Result<Self> {
-        let mut stats = ProxyStats::default();
-        stats.proxy_id = id.clone();
+    pub async fn new(id: ProxyId, name: String, command: Vec<String>) -> Result<Self> {
+        let stats = ProxyStats {
+            proxy_id: id.clone(),
+            ..Default::default()
+        };
 
         Ok(Self {
             id,
             name,
             command,
-            use_shell,
             stats: Arc::new(Mutex::new(stats)),
-            shutdown_tx: None,
+            shutdown: ShutdownHandle {
+                tx: broadcast::channel(1).0,
+                requested: Arc::new(AtomicBool::new(false)),
+            },
+            restart_policy: RestartPolicy::Never,
+            framing: FramingMode::Newline,
+            env_vars: Vec::new(),
+            env_clear: false,
+            cwd: None,
+            ipc_token: None,
+            ipc_framing: IpcFraming::Lines,
+            http: None,
+            graceful_shutdown_timeout_ms: DEFAULT_SHUTDOWN_TIMEOUT_MS,
+            request_timeout: None,
+            stats_interval_ms: DEFAULT_STATS_INTERVAL_MS,
+            #[cfg(feature = "opentelemetry")]
+            tracer: None,
         })
     }
 
-    pub async fn start(&mut self, ipc_socket_path: Option<&str>) -> Result<()> {
-        info!("Starting MCP proxy: {}", self.name);
+    pub fn with_restart_policy(mut self, restart_policy: RestartPolicy) -> Self {
+        self.restart_policy = restart_policy;
+        self
+    }
+
+    pub fn with_framing(mut self, framing: FramingMode) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    /// Run the server with `env_vars` set, in an otherwise empty environment if `env_clear`
+    pub fn with_env(mut self, env_vars: Vec<(String, String)>, env_clear: bool) -> Self {
+        self.env_vars = env_vars;
+        self.env_clear = env_clear;
+        self
+    }
+
+    pub fn with_cwd(mut self, cwd: Option<PathBuf>) -> Self {
+        self.cwd = cwd;
+        self
+    }
+
+    pub fn with_ipc_token(mut self, ipc_token: Option<String>) -> Self {
+        self.ipc_token = ipc_token;
+        self
+    }
+
+    pub fn with_ipc_framing(mut self, ipc_framing: IpcFraming) -> Self {
+        self.ipc_framing = ipc_framing;
+        self
+    }
+
+    /// Give the server `timeout_ms` to exit after SIGTERM before killing it
+    pub fn with_graceful_shutdown_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.graceful_shutdown_timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Log a warning for stdio requests the server leaves unanswered for `request_timeout`
+    pub fn with_request_timeout(mut self, request_timeout: Option<Duration>) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
 
-        // Create shutdown channel
-        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
-        self.shutdown_tx = Some(shutdown_tx);
+    /// Send the monitor stdio stats every `interval_ms` rather than every second, at
+    /// least 1ms
+    pub fn with_stats_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.stats_interval_ms = interval_ms.max(1);
+        self
+    }
+
+    /// Proxy an MCP server over HTTP, or WebSocket for a `ws://` target: accept clients
+    /// on `listen` and forward them to the `target` URL, instead of running a command
+    pub fn with_http(mut self, listen: SocketAddr, target: String) -> Self {
+        self.http = Some((listen, target));
+        self
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    pub fn with_tracer(mut self, tracer: Option<Arc<RequestTracer>>) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    pub async fn start(&mut self, ipc_socket_path: Option<&str>) -> Result<()> {
+        match self.cwd {
+            Some(ref cwd) => info!("Starting MCP proxy: {} in {}", self.name, cwd.display()),
+            None => info!("Starting MCP proxy: {}", self.name),
+        }
 
         // Create buffered IPC client (unless monitor is explicitly disabled)
         let buffered_client = if let Some(socket_path) = ipc_socket_path {
@@ -47,46 +277,38 @@ impl MCPProxy {
                 socket_path
             );
             Some(Arc::new(
-                BufferedIpcClient::new(socket_path.to_string()).await,
+                BufferedIpcClient::with_framing(
+                    socket_path.to_string(),
+                    self.ipc_token.clone(),
+                    self.ipc_framing,
+                )
+                .await,
             ))
         } else {
             info!("Running in standalone mode (monitor disabled)");
             None
         };
 
-        // Send proxy started message
         if let Some(ref client) = buffered_client {
-            let proxy_info = ProxyInfo {
-                id: self.id.clone(),
-                name: self.name.clone(),
-                listen_address: "stdio".to_string(),
-                target_command: vec![self.command.clone()],
-                status: ProxyStatus::Starting,
-                stats: self.stats.lock().await.clone(),
-            };
-
-            if let Err(e) = client.send(IpcMessage::ProxyStarted(proxy_info)).await {
-                warn!("Failed to send proxy started message: {}", e);
-            }
+            // The monitor can stop this proxy, which stops the MCP server. Listening
+            // before announcing ourselves, so no Shutdown can arrive unheard.
+            tokio::spawn(forward_shutdown(
+                client.subscribe(),
+                self.id.clone(),
+                self.shutdown.clone(),
+            ));
         }
 
-        // Start MCP server process
-        let mut child = self.start_mcp_server().await?;
-
-        // Create STDIO handler
-        let mut handler =
-            StdioHandler::new(self.id.clone(), self.stats.clone(), buffered_client.clone()).await?;
-
-        // Note: ProxyStats doesn't have a status field, but we track it in ProxyInfo
-
-        // Handle STDIO communication
-        let result = handler.handle_communication(&mut child, shutdown_rx).await;
+        let result = match self.http {
+            Some((listen, ref target)) => {
+                let target = target.clone();
+                self.serve_http(listen, &target, buffered_client.clone())
+                    .await
+            }
+            None => self.run_stdio(buffered_client.clone()).await,
+        };
 
-        // Clean up
         info!("Proxy {} shutting down", self.name);
-        if let Err(e) = child.kill().await {
-            warn!("Failed to kill MCP server process: {}", e);
-        }
 
         // Send proxy stopped message and shutdown buffered client
         if let Some(client) = buffered_client {
@@ -102,39 +324,288 @@ impl MCPProxy {
         result
     }
 
-    async fn start_mcp_server(&self) -> Result<Child> {
-        if self.command.is_empty() {
-            return Err(anyhow::anyhow!("No command specified"));
+    /// Run the MCP server and relay its stdio, restarting it as the policy says
+    async fn run_stdio(&mut self, buffered_client: Option<Arc<BufferedIpcClient>>) -> Result<()> {
+        // One handler for every session, so client input that arrives while the server
+        // restarts waits in its reader instead of being dropped
+        let mut handler =
+            StdioHandler::new(self.id.clone(), self.stats.clone(), buffered_client.clone())
+                .await?
+                .with_framing(self.framing)
+                .with_request_timeout(self.request_timeout)
+                .with_stats_interval_ms(self.stats_interval_ms);
+        #[cfg(feature = "opentelemetry")]
+        {
+            handler = handler.with_tracer(self.tracer.clone());
         }
 
-        let child = if self.use_shell {
-            // Use shell to execute the command
-            Command::new("sh")
-                .arg("-c")
-                .arg(&self.command)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()?
-        } else {
-            // Parse command and arguments
-            let parts: Vec<&str> = self.command.split_whitespace().collect();
-            if parts.is_empty() {
-                return Err(anyhow::anyhow!("Empty command"));
+        let mut attempt = 0;
+        loop {
+            // Listen before checking, so a shutdown is either seen here or received
+            let session_shutdown = self.shutdown.subscribe();
+            if self.shutdown.is_requested() {
+                break Ok(());
             }
 
-            let mut cmd = Command::new(parts[0]);
-            if parts.len() > 1 {
-                cmd.args(&parts[1..]);
+            // Registered rather than sent, so a restarted monitor hears the latest status
+            self.announce(buffered_client.as_deref(), ProxyStatus::Starting)
+                .await;
+
+            // Start MCP server process
+            let mut child = self.start_mcp_server().await?;
+            self.announce(buffered_client.as_deref(), ProxyStatus::Running)
+                .await;
+
+            // Handle STDIO communication
+            let result = handler
+                .handle_communication(&mut child, session_shutdown)
+                .await;
+
+            // Clean up
+            terminate(
+                &mut child,
+                Duration::from_millis(self.graceful_shutdown_timeout_ms),
+            )
+            .await;
+
+            let status = match result {
+                Err(ref e) => match e.downcast_ref::<StdioError>() {
+                    Some(StdioError::ChildExited(status)) => *status,
+                    None => break result,
+                },
+                Ok(()) => break result,
+            };
+
+            if !self.restart_policy.restarts_after(&status) {
+                break Ok(());
             }
 
-            cmd.stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()?
+            attempt += 1;
+            let Some(delay) = self.restart_policy.delay_for(attempt) else {
+                warn!(
+                    "MCP server exited ({}), giving up after {} restarts",
+                    status,
+                    attempt - 1
+                );
+                break result;
+            };
+
+            let message = format!(
+                "Child exited (status {}), restarting attempt {}/{}",
+                describe_status(&status),
+                attempt,
+                self.restart_policy.max_attempts()
+            );
+            warn!("{}", message);
+            self.stats.lock().await.restarts = attempt;
+            if let Some(ref client) = buffered_client {
+                let level = if status.success() {
+                    LogLevel::Info
+                } else {
+                    LogLevel::Error
+                };
+                let entry = LogEntry::new(level, message, self.id.clone());
+                if let Err(e) = client.send(IpcMessage::LogEntry(entry)).await {
+                    warn!("Failed to send restart log entry: {}", e);
+                }
+            }
+
+            let mut shutdown_rx = self.shutdown.subscribe();
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = shutdown_rx.recv() => break Ok(()),
+            }
+        }
+    }
+
+    /// Relay HTTP or WebSocket clients connecting to `listen` to the server at `target`
+    async fn serve_http(
+        &mut self,
+        listen: SocketAddr,
+        target: &str,
+        buffered_client: Option<Arc<BufferedIpcClient>>,
+    ) -> Result<()> {
+        let websocket = target.starts_with("ws://");
+        let listener = TcpListener::bind(listen)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to listen on {}: {}", listen, e))?;
+        // Announce the port actually bound when `listen` asked for any
+        let listen = listener.local_addr().unwrap_or(listen);
+        self.http = Some((listen, target.to_string()));
+        let transport = if websocket { "WebSocket" } else { "HTTP" };
+        info!("Proxying {} clients on {} to {}", transport, listen, target);
+
+        let shutdown = self.shutdown.subscribe();
+        if self.shutdown.is_requested() {
+            return Ok(());
+        }
+        if !websocket {
+            let handler = HttpHandler::new(
+                self.id.clone(),
+                target,
+                self.stats.clone(),
+                buffered_client.clone(),
+            )?;
+            self.announce(buffered_client.as_deref(), ProxyStatus::Running)
+                .await;
+            return handler.serve(listener, shutdown).await;
+        }
+
+        let handler = WsHandler::new(
+            self.id.clone(),
+            target,
+            self.stats.clone(),
+            buffered_client.clone(),
+        )?;
+        self.announce(buffered_client.as_deref(), ProxyStatus::Running)
+            .await;
+        // Dropped connections show on the proxy until a new one reaches the server
+        let (status_tx, mut status_rx) = mpsc::unbounded_channel();
+        let serving = handler.serve(listener, shutdown, status_tx);
+        tokio::pin!(serving);
+        let mut running = true;
+        loop {
+            tokio::select! {
+                result = &mut serving => return result,
+                Some(status) = status_rx.recv() => {
+                    let was_running = running;
+                    running = matches!(status, ProxyStatus::Running);
+                    if !(running && was_running) {
+                        self.announce(buffered_client.as_deref(), status).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Keep the monitor's view of this proxy at `status`, with the latest stats
+    async fn announce(&self, client: Option<&BufferedIpcClient>, status: ProxyStatus) {
+        let Some(client) = client else {
+            return;
         };
+        let (listen_address, target_command) = match self.http {
+            Some((listen, ref target)) => (listen.to_string(), vec![target.clone()]),
+            None => ("stdio".to_string(), self.command.clone()),
+        };
+        client.set_registration(IpcMessage::ProxyStarted(ProxyInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            listen_address,
+            target_command,
+            env_vars: self
+                .env_vars
+                .iter()
+                .map(|(key, _)| (key.clone(), REDACTED_ENV_VALUE.to_string()))
+                .collect(),
+            status,
+            stats: self.stats.lock().await.clone(),
+            protocol_version: None,
+            monitor_connected: client.is_connected(),
+        }));
+    }
+
+    async fn start_mcp_server(&self) -> Result<Child> {
+        let Some((program, args)) = self.command.split_first() else {
+            return Err(anyhow::anyhow!("No command specified"));
+        };
+
+        let mut cmd = Command::new(program);
+        if self.env_clear {
+            cmd.env_clear();
+        }
+        cmd.envs(self.env_vars.iter().map(|(key, value)| (key, value)));
+        if let Some(ref cwd) = self.cwd {
+            cmd.current_dir(cwd);
+        }
+
+        cmd.args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // In its own process group, so stopping the server reaches what a shell started
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let child = cmd.spawn()?;
 
-        info!("Started MCP server process: {}", self.command);
+        info!("Started MCP server process: {:?}", self.command);
         Ok(child)
     }
 }
+
+fn describe_status(status: &ExitStatus) -> String {
+    match status.code() {
+        Some(code) => code.to_string(),
+        None => status.to_string(),
+    }
+}
+
+/// SIGTERM the MCP server's process group, then SIGKILL it if it is still running after
+/// `grace`
+#[cfg(unix)]
+async fn terminate(child: &mut Child, grace: Duration) {
+    // No pid once the server has been reaped; its group id may belong to others by then
+    let Some(pid) = child.id() else {
+        return;
+    };
+    signal_group(pid, libc::SIGTERM);
+    if let Ok(status) = tokio::time::timeout(grace, child.wait()).await {
+        match status {
+            Ok(status) => info!(
+                "MCP server exited after SIGTERM (status {})",
+                describe_status(&status)
+            ),
+            Err(e) => warn!("Failed to wait for MCP server process: {}", e),
+        }
+        return;
+    }
+
+    warn!(
+        "MCP server still running {}ms after SIGTERM, killing it",
+        grace.as_millis()
+    );
+    signal_group(pid, libc::SIGKILL);
+    if let Err(e) = child.kill().await {
+        warn!("Failed to kill MCP server process: {}", e);
+    }
+}
+
+/// Windows has no SIGTERM to offer the MCP server first, so it is killed right away
+#[cfg(not(unix))]
+async fn terminate(child: &mut Child, _grace: Duration) {
+    match child.kill().await {
+        Ok(()) => info!("MCP server killed"),
+        Err(e) => warn!("Failed to kill MCP server process: {}", e),
+    }
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: libc::c_int) {
+    // SAFETY: kill() has no memory effects; a negative pid addresses the process group
+    // that `start_mcp_server` made the server lead
+    let result = unsafe { libc::kill(-(pid as libc::pid_t), signal) };
+    if result != 0 {
+        tracing::debug!(
+            "Failed to signal MCP server process group {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+/// Turn a `Shutdown` for `id` from the monitor into a proxy shutdown
+async fn forward_shutdown(
+    mut inbound: broadcast::Receiver<IpcMessage>,
+    id: ProxyId,
+    shutdown: ShutdownHandle,
+) {
+    loop {
+        match inbound.recv().await {
+            Ok(IpcMessage::Shutdown(target)) if target == id => {
+                info!("Shutdown requested by the monitor");
+                shutdown.shutdown();
+            }
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
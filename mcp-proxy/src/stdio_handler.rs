@@ -1,19 +1,82 @@
 use anyhow::Result;
-use mcp_common::{IpcMessage, LogEntry, LogLevel, ProxyId, ProxyStats};
+use mcp_common::{IpcMessage, JsonRpcMessage, LogEntry, LogLevel, ProxyId, ProxyStats};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::process::ExitStatus;
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
-use tokio::process::Child;
-use tokio::sync::{broadcast, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, BufWriter};
+use tokio::process::{Child, ChildStdin};
+use tokio::sync::{broadcast, mpsc, Mutex};
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info, warn};
 
 use crate::buffered_ipc_client::BufferedIpcClient;
+use crate::framing::{read_chunk, write_message, Chunk, FramingMode, LineScanner};
+use crate::http_handler::report_stats;
+use crate::proxy::DEFAULT_STATS_INTERVAL_MS;
+#[cfg(feature = "opentelemetry")]
+use crate::telemetry::RequestTracer;
+
+/// How long to wait for the child to exit after it closes stdout
+const CHILD_EXIT_GRACE: Duration = Duration::from_millis(500);
+
+/// Chunks each reader or writer task may be ahead of, or behind, the forwarding loop
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Log entries are sent to the monitor once this many are queued...
+const LOG_BATCH_SIZE: usize = 50;
+/// ...or when this much time has passed, whichever comes first
+const LOG_BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Errors that end a `StdioHandler::handle_communication` session
+#[derive(Debug)]
+pub enum StdioError {
+    /// The MCP server process exited while the proxy was still serving
+    ChildExited(ExitStatus),
+}
+
+impl fmt::Display for StdioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StdioError::ChildExited(status) => write!(f, "MCP server exited ({})", status),
+        }
+    }
+}
+
+impl std::error::Error for StdioError {}
+
+/// A request forwarded to the MCP server that hasn't been answered yet
+struct PendingRequest {
+    id: serde_json::Value,
+    sent: Instant,
+    method: String,
+    warnings: u32, // Sent for taking longer than the request timeout, one per timeout
+    #[cfg(feature = "opentelemetry")]
+    span: Option<opentelemetry_sdk::trace::Span>, // Taken once the span ends
+}
 
 pub struct StdioHandler {
     proxy_id: ProxyId,
     stats: Arc<Mutex<ProxyStats>>,
     ipc_client: Option<Arc<BufferedIpcClient>>,
-    stats_interval: tokio::time::Interval,
+    stats_interval: tokio::time::Interval, // Also times the slow request warnings
+    started: Instant,                      // Reported as the proxy's uptime, across server restarts
+    framing: FramingMode,
+    log_batch: Vec<LogEntry>,
+    batch_interval: tokio::time::Interval,
+    /// Unanswered requests by JSON-RPC id
+    pending_requests: HashMap<String, PendingRequest>,
+    /// Warn about requests unanswered for this long, and again each time it passes
+    request_timeout: Option<Duration>,
+    /// What the user's stdin reader task got; kept across sessions, so input read ahead
+    /// survives a server restart. Started with the first session.
+    user_input: Option<mpsc::Receiver<ReadResult>>,
+    /// Read instead of stdin once the first session starts
+    input: Option<Box<dyn AsyncRead + Send + Sync + Unpin>>,
+    #[cfg(feature = "opentelemetry")]
+    tracer: Option<Arc<RequestTracer>>,
 }
 
 impl StdioHandler {
@@ -22,20 +85,86 @@ impl StdioHandler {
         stats: Arc<Mutex<ProxyStats>>,
         ipc_client: Option<Arc<BufferedIpcClient>>,
     ) -> Result<Self> {
-        let stats_interval = interval(Duration::from_secs(1));
-
         Ok(Self {
             proxy_id,
             stats,
             ipc_client,
-            stats_interval,
+            stats_interval: interval(Duration::from_millis(DEFAULT_STATS_INTERVAL_MS)),
+            started: Instant::now(),
+            framing: FramingMode::Newline,
+            log_batch: Vec::with_capacity(LOG_BATCH_SIZE),
+            batch_interval: interval(LOG_BATCH_INTERVAL),
+            pending_requests: HashMap::new(),
+            request_timeout: None,
+            user_input: None,
+            input: None,
+            #[cfg(feature = "opentelemetry")]
+            tracer: None,
         })
     }
 
+    pub fn with_framing(mut self, framing: FramingMode) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    /// Send the monitor stats every `stats_interval_ms` rather than every second, taking
+    /// 0 as 1ms since an interval needs a period
+    pub fn with_stats_interval_ms(mut self, stats_interval_ms: u64) -> Self {
+        self.stats_interval = interval(Duration::from_millis(stats_interval_ms.max(1)));
+        self
+    }
+
+    /// Log a warning for each request still unanswered after `request_timeout`, without
+    /// cancelling it
+    pub fn with_request_timeout(mut self, request_timeout: Option<Duration>) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Read the client's messages from `input` rather than the process's stdin, whose
+    /// blocking reads can't be cancelled and so hold up runtime shutdown
+    pub fn with_input(mut self, input: impl AsyncRead + Send + Sync + Unpin + 'static) -> Self {
+        self.input = Some(Box::new(input));
+        self
+    }
+
+    #[cfg(feature = "opentelemetry")]
+    pub fn with_tracer(mut self, tracer: Option<Arc<RequestTracer>>) -> Self {
+        self.tracer = tracer;
+        self
+    }
+
     pub async fn handle_communication(
+        &mut self,
+        child: &mut Child,
+        shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        let mut user_input = match self.user_input.take() {
+            Some(user_input) => user_input,
+            None => match self.input.take() {
+                Some(input) => spawn_reader(input, self.framing),
+                None => spawn_reader(tokio::io::stdin(), self.framing),
+            },
+        };
+        let result = self.forward(child, shutdown_rx, &mut user_input).await;
+        self.user_input = Some(user_input);
+        // Entries logged just before the session ended still reach the monitor
+        self.flush_logs().await;
+        self.abandon_pending_requests();
+        self.update_in_flight().await;
+        // So the monitor sees the abandoned requests go, even if no session follows
+        if let Some(ref client) = self.ipc_client {
+            report_stats(&self.stats, client, self.started).await;
+        }
+        result
+    }
+
+    async fn forward(
         &mut self,
         child: &mut Child,
         mut shutdown_rx: broadcast::Receiver<()>,
+        user_input: &mut mpsc::Receiver<ReadResult>,
     ) -> Result<()> {
         let stdin = child
             .stdin
@@ -50,14 +179,16 @@ impl StdioHandler {
             .take()
             .ok_or_else(|| anyhow::anyhow!("Failed to get child stderr"))?;
 
-        let mut child_stdin = BufWriter::new(stdin);
-        let mut child_stdout = BufReader::new(stdout);
-        let mut child_stderr = BufReader::new(stderr);
+        let to_child = spawn_writer(stdin);
+        let mut child_output = spawn_reader(stdout, self.framing);
+        let mut child_errors = spawn_line_reader(stderr);
 
-        let mut user_stdin = BufReader::new(tokio::io::stdin());
         let mut user_stdout = tokio::io::stdout();
 
-        // Channels removed - not needed for direct STDIO handling
+        // Newline-framed bytes are passed on as they arrive, invalid UTF-8 and all; these
+        // only pick out the lines to log
+        let mut request_lines = LineScanner::default();
+        let mut response_lines = LineScanner::default();
 
         loop {
             tokio::select! {
@@ -68,80 +199,69 @@ impl StdioHandler {
                 }
 
                 // Handle stats updates
-                _ = self.stats_interval.tick() => {
+                _ = self.stats_interval.tick(), if self.ticks_needed() => {
+                    #[cfg(feature = "opentelemetry")]
+                    self.end_unanswered_spans();
+                    self.warn_about_slow_requests().await;
+
+                    // Also lets the rate decay to zero while the proxy is idle
                     if let Some(ref client) = self.ipc_client {
-                        let stats = self.stats.lock().await.clone();
-                        if let Err(e) = client.send(IpcMessage::StatsUpdate(stats)).await {
-                            warn!("Failed to send stats update: {}", e);
-                        }
+                        report_stats(&self.stats, client, self.started).await;
                     }
                 }
 
-                // Read from user stdin and forward to child
-                result = async {
-                    let mut input = String::new();
-                    let bytes_read = user_stdin.read_line(&mut input).await?;
-                    Ok::<(usize, String), std::io::Error>((bytes_read, input))
-                } => {
-                    match result {
-                        Ok((0, _)) => break, // EOF
-                        Ok((_, input)) => {
-                            self.log_request(&input).await;
-
-                            if let Err(e) = child_stdin.write_all(input.as_bytes()).await {
-                                error!("Failed to write to child stdin: {}", e);
-                                break;
-                            }
-                            if let Err(e) = child_stdin.flush().await {
-                                error!("Failed to flush child stdin: {}", e);
-                                break;
-                            }
+                // Send queued log entries that didn't fill a batch
+                _ = self.batch_interval.tick() => {
+                    self.flush_logs().await;
+                }
 
-                            // Update stats
-                            {
-                                let mut stats = self.stats.lock().await;
-                                stats.total_requests += 1;
-                                stats.bytes_transferred += input.len() as u64;
-                            }
+                // Forward what the user's stdin reader got to the child. Input is only taken
+                // once the writer has room, so a child that is slow to read its stdin holds
+                // up the user rather than this loop, which must keep draining its stdout.
+                (permit, result) = async { (to_child.reserve().await, user_input.recv().await) } => {
+                    let Ok(permit) = permit else {
+                        break; // The writer failed, and logged why
+                    };
+                    match result {
+                        None | Some(Ok(None)) => break, // EOF
+                        Some(Ok(Some((chunk, framing)))) => {
+                            self.settle_framing(framing);
+                            permit.send(self.pass_request(chunk, &mut request_lines).await);
                         }
-                        Err(e) => {
+                        Some(Err(e)) => {
                             error!("Failed to read from user stdin: {}", e);
                             break;
                         }
                     }
                 }
 
-                // Read from child stdout and forward to user
-                result = async {
-                    let mut output = String::new();
-                    let bytes_read = child_stdout.read_line(&mut output).await?;
-                    Ok::<(usize, String), std::io::Error>((bytes_read, output))
-                } => {
+                // Forward what the child's stdout reader got to the user
+                result = child_output.recv() => {
                     match result {
-                        Ok((0, _)) => {
+                        None | Some(Ok(None)) => {
                             info!("Child stdout closed");
+                            if let Some(line) = response_lines.finish() {
+                                self.log_response(&line).await;
+                            }
+                            // A closed stdout usually means the server is exiting
+                            if let Ok(Ok(exit_status)) =
+                                tokio::time::timeout(CHILD_EXIT_GRACE, child.wait()).await
+                            {
+                                return Err(self.child_exited(exit_status).await);
+                            }
                             break;
                         }
-                        Ok((_, output)) => {
-                            self.log_response(&output).await;
-
-                            if let Err(e) = user_stdout.write_all(output.as_bytes()).await {
+                        Some(Ok(Some((chunk, framing)))) => {
+                            self.settle_framing(framing);
+                            let written = self
+                                .pass_response(chunk, &mut response_lines, &mut user_stdout)
+                                .await;
+                            if let Err(e) = written {
                                 error!("Failed to write to user stdout: {}", e);
                                 break;
                             }
-                            if let Err(e) = user_stdout.flush().await {
-                                error!("Failed to flush user stdout: {}", e);
-                                break;
-                            }
-
-                            // Update stats
-                            {
-                                let mut stats = self.stats.lock().await;
-                                stats.successful_requests += 1;
-                                stats.bytes_transferred += output.len() as u64;
-                            }
                         }
-                        Err(e) => {
+                        Some(Err(e)) => {
                             error!("Failed to read from child stdout: {}", e);
                             {
                                 let mut stats = self.stats.lock().await;
@@ -152,27 +272,14 @@ impl StdioHandler {
                     }
                 }
 
-                // Read from child stderr and log as errors
-                result = async {
-                    let mut error_msg = String::new();
-                    let bytes_read = child_stderr.read_line(&mut error_msg).await?;
-                    Ok::<(usize, String), std::io::Error>((bytes_read, error_msg))
-                } => {
-                    match result {
-                        Ok((0, _)) => {
-                            debug!("Child stderr closed");
-                        }
-                        Ok((_, error_msg)) => {
-                            self.log_error(&error_msg).await;
+                // Log each stderr line at the level it gives; the branch is off once the
+                // child closes stderr
+                Some(line) = child_errors.recv() => {
+                    self.log_error(&line).await;
 
-                            // Also forward stderr to user stderr
-                            if let Err(e) = tokio::io::stderr().write_all(error_msg.as_bytes()).await {
-                                warn!("Failed to write child stderr to user stderr: {}", e);
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to read from child stderr: {}", e);
-                        }
+                    // Also forward stderr to user stderr
+                    if let Err(e) = tokio::io::stderr().write_all(line.as_bytes()).await {
+                        warn!("Failed to write child stderr to user stderr: {}", e);
                     }
                 }
 
@@ -180,11 +287,23 @@ impl StdioHandler {
                 status = child.wait() => {
                     match status {
                         Ok(exit_status) => {
-                            info!("Child process exited with status: {}", exit_status);
-                            if !exit_status.success() {
-                                let mut stats = self.stats.lock().await;
-                                stats.failed_requests += 1;
+                            // Pass on what it wrote before exiting, which may not have
+                            // been read yet; a process it left running can keep stdout open
+                            let drain = async {
+                                while let Some(Ok(Some((chunk, _)))) = child_output.recv().await {
+                                    let written = self
+                                        .pass_response(chunk, &mut response_lines, &mut user_stdout)
+                                        .await;
+                                    if written.is_err() {
+                                        break;
+                                    }
+                                }
+                            };
+                            let _ = tokio::time::timeout(CHILD_EXIT_GRACE, drain).await;
+                            if let Some(line) = response_lines.finish() {
+                                self.log_response(&line).await;
                             }
+                            return Err(self.child_exited(exit_status).await);
                         }
                         Err(e) => {
                             error!("Failed to wait for child process: {}", e);
@@ -195,54 +314,402 @@ impl StdioHandler {
             }
         }
 
+        // Last lines without a newline were passed on as they came; log them now
+        if let Some(line) = request_lines.finish() {
+            self.log_request(&line).await;
+        }
+        if let Some(line) = response_lines.finish() {
+            self.log_response(&line).await;
+        }
         Ok(())
     }
 
-    async fn log_request(&mut self, content: &str) {
-        let log_entry = LogEntry::new(
-            LogLevel::Request,
-            format!("→ {}", content.trim()),
-            self.proxy_id.clone(),
-        );
+    /// Log the requests in a chunk of the client's input, returning the bytes to write to
+    /// the server
+    async fn pass_request(&mut self, chunk: Chunk, lines: &mut LineScanner) -> Vec<u8> {
+        match chunk {
+            Chunk::Bytes(bytes) => {
+                for line in lines.push(&bytes) {
+                    self.log_request(&line).await;
+                }
+                bytes
+            }
+            Chunk::Message(input) => {
+                self.log_request(input.as_bytes()).await;
+                let mut framed = Vec::new();
+                // Writing to memory can't fail
+                let _ = write_message(&mut framed, &input, self.framing).await;
+                framed
+            }
+        }
+    }
 
-        if let Some(ref client) = self.ipc_client {
-            if let Err(e) = client.send(IpcMessage::LogEntry(log_entry)).await {
-                warn!("Failed to send log entry: {}", e);
+    /// Write a chunk of the server's output to the client, logging the responses in it
+    async fn pass_response(
+        &mut self,
+        chunk: Chunk,
+        lines: &mut LineScanner,
+        writer: &mut (impl AsyncWrite + Unpin),
+    ) -> std::io::Result<()> {
+        match chunk {
+            Chunk::Bytes(bytes) => {
+                let written = write_raw(writer, &bytes).await;
+                for line in lines.push(&bytes) {
+                    self.log_response(&line).await;
+                }
+                written
+            }
+            Chunk::Message(output) => {
+                self.log_response(output.as_bytes()).await;
+                write_message(writer, &output, self.framing).await
+            }
+        }
+    }
+
+    async fn child_exited(&self, exit_status: ExitStatus) -> anyhow::Error {
+        info!("Child process exited with status: {}", exit_status);
+        if !exit_status.success() {
+            let mut stats = self.stats.lock().await;
+            stats.failed_requests += 1;
+        }
+        StdioError::ChildExited(exit_status).into()
+    }
+
+    /// Whether the stats tick has anything to do: without a monitor there are no stats
+    /// to send, but slow requests still get warnings and traced ones their spans ended
+    fn ticks_needed(&self) -> bool {
+        #[cfg(feature = "opentelemetry")]
+        if self.tracer.is_some() {
+            return true;
+        }
+        self.ipc_client.is_some() || self.request_timeout.is_some()
+    }
+
+    /// Queue an entry for the monitor, sending the batch once it is full
+    async fn queue_log(&mut self, log_entry: LogEntry) {
+        if self.ipc_client.is_none() {
+            return;
+        }
+        self.log_batch.push(log_entry);
+        if self.log_batch.len() >= LOG_BATCH_SIZE {
+            self.flush_logs().await;
+        }
+    }
+
+    async fn flush_logs(&mut self) {
+        let Some(ref client) = self.ipc_client else {
+            return;
+        };
+        let message = match self.log_batch.len() {
+            0 => return,
+            1 => IpcMessage::LogEntry(self.log_batch.remove(0)),
+            _ => IpcMessage::LogsBatch(std::mem::take(&mut self.log_batch)),
+        };
+        if let Err(e) = client.send(message).await {
+            warn!("Failed to send log entries: {}", e);
+        }
+    }
+
+    /// In `Auto` mode, keep the framing of the first message read from either side
+    fn settle_framing(&mut self, framing: FramingMode) {
+        if self.framing == FramingMode::Auto {
+            info!("Detected {:?} framing", framing);
+            self.framing = framing;
+        }
+    }
+
+    /// Log what the client sent and count the JSON-RPC messages in it, `raw` as it was
+    /// framed
+    async fn log_request(&mut self, raw: &[u8]) {
+        let content = String::from_utf8_lossy(raw);
+        let messages = JsonRpcMessage::parse_all(content.trim());
+        {
+            let mut stats = self.stats.lock().await;
+            let now = Instant::now();
+            for message in &messages {
+                stats.record_message(message, now);
             }
+            stats.record_request_bytes(raw.len() as u64);
+        }
+        for message in messages {
+            let JsonRpcMessage::Request(request) = message else {
+                continue;
+            };
+            let request_id = request.id.to_string();
+            let pending = PendingRequest {
+                id: request.id.clone(),
+                sent: Instant::now(),
+                method: request.method.clone(),
+                warnings: 0,
+                #[cfg(feature = "opentelemetry")]
+                span: self
+                    .tracer
+                    .as_ref()
+                    .map(|tracer| tracer.start_request(&request.method, &request_id)),
+            };
+            self.pending_requests.insert(request_id, pending);
         }
+        self.update_in_flight().await;
+        let lossy = matches!(content, Cow::Owned(_));
+        let log_entry = self.traffic_entry(LogLevel::Request, "→", &content, lossy);
+        self.queue_log(log_entry).await;
 
         debug!("Request: {}", content.trim());
     }
 
-    async fn log_response(&mut self, content: &str) {
-        let log_entry = LogEntry::new(
-            LogLevel::Response,
-            format!("← {}", content.trim()),
+    /// Log what the server sent and count the JSON-RPC messages in it, `raw` as it was
+    /// framed
+    async fn log_response(&mut self, raw: &[u8]) {
+        let content = String::from_utf8_lossy(raw);
+        let messages = JsonRpcMessage::parse_all(content.trim());
+        {
+            let mut stats = self.stats.lock().await;
+            let now = Instant::now();
+            for message in &messages {
+                stats.record_message(message, now);
+            }
+            stats.record_response_bytes(raw.len() as u64);
+        }
+        let lossy = matches!(content, Cow::Owned(_));
+        let mut log_entry = self
+            .traffic_entry(LogLevel::Response, "←", &content, lossy)
+            .with_error_code(&messages);
+        let mut protocol_version = None;
+        for message in messages {
+            let JsonRpcMessage::Response(response) = message else {
+                continue;
+            };
+            if let Some(pending) = self.pending_requests.remove(&response.id.to_string()) {
+                let latency = pending.sent.elapsed();
+                log_entry.duration = Some(latency);
+                if pending.method == "initialize" {
+                    protocol_version = response
+                        .result
+                        .as_ref()
+                        .and_then(|result| result.get("protocolVersion"))
+                        .and_then(|version| version.as_str())
+                        .map(str::to_string);
+                }
+                #[cfg(feature = "opentelemetry")]
+                if let Some(span) = pending.span {
+                    let error = response.error.as_ref().map(|e| e.message.as_str());
+                    RequestTracer::end_response(span, latency, error);
+                }
+            }
+        }
+        self.update_in_flight().await;
+        self.queue_log(log_entry).await;
+
+        if let (Some(protocol_version), Some(client)) = (protocol_version, &self.ipc_client) {
+            let message = IpcMessage::ProxyMetadata {
+                proxy_id: self.proxy_id.clone(),
+                protocol_version,
+            };
+            if let Err(e) = client.send(message).await {
+                warn!("Failed to send proxy metadata: {}", e);
+            }
+        }
+
+        debug!("Response: {}", content.trim());
+    }
+
+    /// An entry for a message passed through, marked when it wasn't valid UTF-8 and so
+    /// is shown with replacement characters
+    fn traffic_entry(&self, level: LogLevel, arrow: &str, content: &str, lossy: bool) -> LogEntry {
+        let mut log_entry = LogEntry::new(
+            level,
+            format!("{} {}", arrow, content.trim()),
             self.proxy_id.clone(),
         );
+        if lossy {
+            log_entry.metadata = Some(serde_json::json!({ "invalid_utf8": true }));
+        }
+        log_entry
+    }
 
-        if let Some(ref client) = self.ipc_client {
-            if let Err(e) = client.send(IpcMessage::LogEntry(log_entry)).await {
-                warn!("Failed to send log entry: {}", e);
+    /// Forget the requests still waiting when a session ends; that server won't answer
+    fn abandon_pending_requests(&mut self) {
+        #[cfg(feature = "opentelemetry")]
+        for (_, pending) in self.pending_requests.drain() {
+            if let Some(span) = pending.span {
+                RequestTracer::end_unanswered(span);
             }
         }
+        self.pending_requests.clear();
+    }
 
-        debug!("Response: {}", content.trim());
+    /// Log a warning for each request that has waited another `request_timeout` since
+    /// it was sent or last warned about
+    async fn warn_about_slow_requests(&mut self) {
+        let Some(timeout) = self.request_timeout else {
+            return;
+        };
+        let mut warnings = Vec::new();
+        for pending in self.pending_requests.values_mut() {
+            let waited = pending.sent.elapsed();
+            if waited < timeout * (pending.warnings + 1) {
+                continue;
+            }
+            pending.warnings += 1;
+            let message = format!(
+                "request {} ({}) pending for {}s",
+                pending.id,
+                pending.method,
+                waited.as_secs()
+            );
+            warn!("{}", message);
+            let mut entry = LogEntry::new(LogLevel::Warning, message, self.proxy_id.clone());
+            entry.metadata = Some(serde_json::json!({ "request_id": pending.id }));
+            warnings.push(entry);
+        }
+        for entry in warnings {
+            self.queue_log(entry).await;
+        }
+    }
+
+    /// Report the requests waiting for an answer as `in_flight_requests`
+    async fn update_in_flight(&self) {
+        self.stats.lock().await.in_flight_requests = self.pending_requests.len() as u32;
+    }
+
+    /// End the spans of requests that waited too long; a late response still gets its
+    /// duration logged
+    #[cfg(feature = "opentelemetry")]
+    fn end_unanswered_spans(&mut self) {
+        let Some(ref tracer) = self.tracer else {
+            return;
+        };
+        for pending in self.pending_requests.values_mut() {
+            if pending.sent.elapsed() >= tracer.span_timeout {
+                if let Some(span) = pending.span.take() {
+                    RequestTracer::end_unanswered(span);
+                }
+            }
+        }
     }
 
     async fn log_error(&mut self, content: &str) {
+        let level = detect_level_from_stderr(content);
         let log_entry = LogEntry::new(
-            LogLevel::Error,
+            level.clone(),
             format!("stderr: {}", content.trim()),
             self.proxy_id.clone(),
         );
+        self.queue_log(log_entry).await;
 
-        if let Some(ref client) = self.ipc_client {
-            if let Err(e) = client.send(IpcMessage::LogEntry(log_entry)).await {
-                warn!("Failed to send log entry: {}", e);
-            }
+        match level {
+            LogLevel::Error => error!("Child stderr: {}", content.trim()),
+            LogLevel::Warning => warn!("Child stderr: {}", content.trim()),
+            LogLevel::Info => info!("Child stderr: {}", content.trim()),
+            _ => debug!("Child stderr: {}", content.trim()),
         }
+    }
+}
+
+/// Level of a line the server wrote to stderr, from a leading `ERROR`, `WARN`,
+/// `WARNING`, `INFO` or `DEBUG` (any case, optionally in brackets). Anything else is a
+/// warning: servers log to stderr routinely, so it isn't necessarily an error.
+pub fn detect_level_from_stderr(line: &str) -> LogLevel {
+    let line = line.trim_start();
+    let line = line.strip_prefix('[').unwrap_or(line);
+    let word_end = line
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(line.len());
 
-        error!("Child stderr: {}", content.trim());
+    match line[..word_end].to_ascii_uppercase().as_str() {
+        "ERROR" => LogLevel::Error,
+        "WARN" | "WARNING" => LogLevel::Warning,
+        "INFO" => LogLevel::Info,
+        "DEBUG" => LogLevel::Debug,
+        _ => LogLevel::Warning,
     }
 }
+
+/// A message, or newline-framed bytes, read by a reader task; `None` at end of stream
+type ReadResult = std::io::Result<Option<(Chunk, FramingMode)>>;
+
+/// Read `stream` in a task of its own, so a read is never cut short by another branch of
+/// the forwarding loop winning. Ends after sending end of stream or an error.
+fn spawn_reader<R>(stream: R, framing: FramingMode) -> mpsc::Receiver<ReadResult>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(stream);
+        let mut framing = framing;
+        loop {
+            let result = read_chunk(&mut reader, framing).await;
+            let more = match result {
+                Ok(Some((_, read_framing))) => {
+                    // In `Auto` mode, the first message settles this stream's framing
+                    framing = read_framing;
+                    true
+                }
+                _ => false,
+            };
+            if tx.send(result).await.is_err() || !more {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Write what is sent to the returned channel to the child's stdin, in a task of its own,
+/// so a child that is slow to read never blocks the forwarding loop. Stdin is closed once
+/// the channel is dropped and drained.
+fn spawn_writer(stdin: ChildStdin) -> mpsc::Sender<Vec<u8>> {
+    let (tx, mut rx) = mpsc::channel::<Vec<u8>>(CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        let mut writer = BufWriter::new(stdin);
+        while let Some(bytes) = rx.recv().await {
+            if let Err(e) = write_raw(&mut writer, &bytes).await {
+                error!("Failed to write to child stdin: {}", e);
+                break;
+            }
+        }
+    });
+    tx
+}
+
+/// Read `stream` line by line in a task of its own, until it closes or fails
+fn spawn_line_reader<R>(stream: R) -> mpsc::Receiver<String>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(stream);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line).await {
+                Ok(0) => {
+                    debug!("Child stderr closed");
+                    break;
+                }
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&line).into_owned();
+                    if tx.send(line).await.is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to read from child stderr: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// Pass newline-framed bytes on as they were read
+async fn write_raw<W>(writer: &mut W, bytes: &[u8]) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(bytes).await?;
+    writer.flush().await
+}
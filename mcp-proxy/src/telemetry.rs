@@ -0,0 +1,86 @@
+use anyhow::Result;
+use mcp_common::ProxyId;
+use opentelemetry::trace::{Span as _, Status, Tracer as _, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::runtime::Tokio;
+use opentelemetry_sdk::trace::{Span, Tracer, TracerProvider};
+use std::time::Duration;
+use tracing::warn;
+
+/// Exports a span per JSON-RPC request/response pair to an OTLP/HTTP collector
+pub struct RequestTracer {
+    provider: TracerProvider,
+    tracer: Tracer,
+    proxy_name: String,
+    proxy_id: ProxyId,
+    pub span_timeout: Duration, // Requests unanswered this long end their span as errors
+}
+
+impl RequestTracer {
+    /// Export to the collector at `endpoint`, e.g. `http://localhost:4318`
+    pub fn new(
+        endpoint: &str,
+        proxy_name: String,
+        proxy_id: ProxyId,
+        span_timeout: Duration,
+    ) -> Result<Self> {
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()?;
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, Tokio)
+            .build();
+        let tracer = provider.tracer("mcp-proxy");
+
+        Ok(Self {
+            provider,
+            tracer,
+            proxy_name,
+            proxy_id,
+            span_timeout,
+        })
+    }
+
+    /// A span for a request just forwarded to the MCP server, named after its method
+    pub fn start_request(&self, method: &str, request_id: &str) -> Span {
+        let mut span = self.tracer.start(method.to_string());
+        span.set_attributes([
+            KeyValue::new("mcp.request_id", request_id.to_string()),
+            KeyValue::new("mcp.proxy_name", self.proxy_name.clone()),
+            KeyValue::new("mcp.proxy_id", self.proxy_id.0.to_string()),
+        ]);
+        span
+    }
+
+    /// End a request's span once its response arrived after `latency`; `error` is the
+    /// JSON-RPC error message if the server answered with one
+    pub fn end_response(mut span: Span, latency: Duration, error: Option<&str>) {
+        span.set_attribute(KeyValue::new(
+            "mcp.response_latency_ms",
+            latency.as_secs_f64() * 1000.0,
+        ));
+        if let Some(error) = error {
+            span.set_status(Status::error(error.to_string()));
+        }
+        span.end();
+    }
+
+    /// End the span of a request that got no response, in time or at all
+    pub fn end_unanswered(mut span: Span) {
+        span.set_status(Status::error("no response"));
+        span.end();
+    }
+
+    /// Send the spans still queued; ended spans are exported in batches
+    pub async fn shutdown(&self) {
+        let provider = self.provider.clone();
+        // Blocks until the batch processor's export finishes on the runtime
+        match tokio::task::spawn_blocking(move || provider.shutdown()).await {
+            Ok(Err(e)) => warn!("Failed to export the remaining spans: {}", e),
+            Err(e) => warn!("Failed to export the remaining spans: {}", e),
+            Ok(Ok(())) => {}
+        }
+    }
+}
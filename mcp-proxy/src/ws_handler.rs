@@ -0,0 +1,246 @@
+use crate::buffered_ipc_client::BufferedIpcClient;
+use crate::http_handler::report_stats;
+use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use mcp_common::{
+    IpcMessage, JsonRpcMessage, LogEntry, LogLevel, ProxyId, ProxyStats, ProxyStatus,
+};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::time::{interval, Duration};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+/// Proxies an MCP server reached over WebSocket: each client connecting to the proxy
+/// gets its own connection to the target, and text frames both ways are logged to the
+/// monitor as requests and responses
+pub struct WsHandler {
+    proxy_id: ProxyId,
+    target: String,
+    stats: Arc<Mutex<ProxyStats>>,
+    ipc_client: Option<Arc<BufferedIpcClient>>,
+    started: Instant, // Reported as the proxy's uptime
+}
+
+impl WsHandler {
+    /// Relay to `target`, a `ws://` URL
+    pub fn new(
+        proxy_id: ProxyId,
+        target: &str,
+        stats: Arc<Mutex<ProxyStats>>,
+        ipc_client: Option<Arc<BufferedIpcClient>>,
+    ) -> Result<Self> {
+        if !target.starts_with("ws://") {
+            anyhow::bail!("target {} must be a ws:// URL", target);
+        }
+        Ok(Self {
+            proxy_id,
+            target: target.to_string(),
+            stats,
+            ipc_client,
+            started: Instant::now(),
+        })
+    }
+
+    /// Serve clients connecting to `listener` until `shutdown_rx` fires. A connection
+    /// dropping on either side sends `ProxyStatus::Error` to `status`, and the next
+    /// connection that reaches the target sends `ProxyStatus::Running`.
+    pub async fn serve(
+        self,
+        listener: TcpListener,
+        mut shutdown_rx: broadcast::Receiver<()>,
+        status: mpsc::UnboundedSender<ProxyStatus>,
+    ) -> Result<()> {
+        let relay = Arc::new(Relay {
+            proxy_id: self.proxy_id.clone(),
+            target: self.target.clone(),
+            stats: self.stats.clone(),
+            ipc_client: self.ipc_client.clone(),
+            status,
+        });
+        let mut stats_interval = interval(Duration::from_secs(1));
+
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.recv() => {
+                    info!("Received shutdown signal");
+                    break;
+                }
+
+                accepted = listener.accept() => {
+                    match accepted {
+                        Ok((stream, _)) => {
+                            tokio::spawn(relay.clone().serve_connection(stream));
+                        }
+                        Err(e) => warn!("Failed to accept WebSocket client: {}", e),
+                    }
+                }
+
+                _ = stats_interval.tick() => {
+                    if let Some(ref client) = self.ipc_client {
+                        report_stats(&self.stats, client, self.started).await;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Shared by the connection tasks
+struct Relay {
+    proxy_id: ProxyId,
+    target: String,
+    stats: Arc<Mutex<ProxyStats>>,
+    ipc_client: Option<Arc<BufferedIpcClient>>,
+    status: mpsc::UnboundedSender<ProxyStatus>,
+}
+
+impl Relay {
+    async fn serve_connection(self: Arc<Self>, stream: TcpStream) {
+        let client = match tokio_tungstenite::accept_async(stream).await {
+            Ok(client) => client,
+            Err(e) => {
+                debug!("WebSocket handshake with client failed: {}", e);
+                return;
+            }
+        };
+        let (server, _) = match tokio_tungstenite::connect_async(self.target.as_str()).await {
+            Ok(server) => server,
+            Err(e) => {
+                self.dropped(format!("Failed to connect to {}: {}", self.target, e))
+                    .await;
+                return;
+            }
+        };
+        let _ = self.status.send(ProxyStatus::Running);
+        self.stats.lock().await.active_connections += 1;
+
+        let (mut client_tx, mut client_rx) = client.split();
+        let (mut server_tx, mut server_rx) = server.split();
+        // Once either side sends a close frame, the connections ending is expected
+        let mut closing = false;
+        loop {
+            tokio::select! {
+                message = client_rx.next() => match message {
+                    Some(Ok(message)) => {
+                        closing |= message.is_close();
+                        self.record(LogLevel::Request, &message).await;
+                        if let Err(e) = forward(&mut server_tx, message).await {
+                            if !closing {
+                                self.dropped(format!("Server connection dropped: {}", e)).await;
+                                let _ = client_tx.send(Message::Close(None)).await;
+                            }
+                            break;
+                        }
+                    }
+                    Some(Err(e)) if !closing => {
+                        self.dropped(format!("Client connection dropped: {}", e)).await;
+                        let _ = server_tx.send(Message::Close(None)).await;
+                        break;
+                    }
+                    None if !closing => {
+                        self.dropped("Client connection dropped".to_string()).await;
+                        let _ = server_tx.send(Message::Close(None)).await;
+                        break;
+                    }
+                    _ => break,
+                },
+
+                message = server_rx.next() => match message {
+                    Some(Ok(message)) => {
+                        closing |= message.is_close();
+                        self.record(LogLevel::Response, &message).await;
+                        if let Err(e) = forward(&mut client_tx, message).await {
+                            if !closing {
+                                self.dropped(format!("Client connection dropped: {}", e)).await;
+                                let _ = server_tx.send(Message::Close(None)).await;
+                            }
+                            break;
+                        }
+                    }
+                    Some(Err(e)) if !closing => {
+                        self.dropped(format!("Server connection dropped: {}", e)).await;
+                        let _ = client_tx.send(Message::Close(None)).await;
+                        break;
+                    }
+                    None if !closing => {
+                        self.dropped("Server connection dropped".to_string()).await;
+                        let _ = client_tx.send(Message::Close(None)).await;
+                        break;
+                    }
+                    _ => break,
+                },
+            }
+        }
+
+        let mut stats = self.stats.lock().await;
+        stats.active_connections = stats.active_connections.saturating_sub(1);
+    }
+
+    /// Log a text frame going to the server (`Request`) or coming back (`Response`)
+    async fn record(&self, level: LogLevel, message: &Message) {
+        let Message::Text(text) = message else {
+            return;
+        };
+        let arrow = match level {
+            LogLevel::Request => "→",
+            _ => "←",
+        };
+        debug!("{} {}", arrow, text);
+        let messages = JsonRpcMessage::parse_all(text.trim());
+        {
+            let mut stats = self.stats.lock().await;
+            let now = Instant::now();
+            for message in &messages {
+                stats.record_message(message, now);
+            }
+            let size = text.len() as u64;
+            if level == LogLevel::Request {
+                stats.record_request_bytes(size);
+            } else {
+                stats.record_response_bytes(size);
+            }
+        }
+        let entry = LogEntry::new(
+            level,
+            format!("{} {}", arrow, text.trim()),
+            self.proxy_id.clone(),
+        )
+        .with_error_code(&messages);
+        self.send_log(entry).await;
+    }
+
+    /// Log an unexpected end of a connection as an error and flag the proxy with it
+    async fn dropped(&self, message: String) {
+        warn!("{}", message);
+        let _ = self.status.send(ProxyStatus::Error(message.clone()));
+        self.log(LogLevel::Error, message).await;
+    }
+
+    async fn log(&self, level: LogLevel, message: String) {
+        self.send_log(LogEntry::new(level, message, self.proxy_id.clone()))
+            .await;
+    }
+
+    async fn send_log(&self, entry: LogEntry) {
+        if let Some(ref client) = self.ipc_client {
+            if let Err(e) = client.send(IpcMessage::LogEntry(entry)).await {
+                warn!("Failed to send log entry: {}", e);
+            }
+        }
+    }
+}
+
+/// Pass `message` on; pings and pongs are answered on each side of the proxy instead
+async fn forward<S>(sink: &mut S, message: Message) -> Result<(), S::Error>
+where
+    S: SinkExt<Message> + Unpin,
+{
+    match message {
+        Message::Text(_) | Message::Binary(_) | Message::Close(_) => sink.send(message).await,
+        Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => Ok(()),
+    }
+}
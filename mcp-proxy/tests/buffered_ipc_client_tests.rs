@@ -1,7 +1,7 @@
 use mcp_common::*;
-use mcp_proxy::BufferedIpcClient;
+use mcp_proxy::{BufferedIpcClient, DiskSpill, MAX_LOG_MESSAGE_BYTES};
 use tempfile::tempdir;
-use tokio::time::{sleep, Duration};
+use tokio::time::{sleep, timeout, Duration};
 
 #[tokio::test]
 async fn test_buffered_client_creation() {
@@ -68,6 +68,177 @@ async fn test_buffered_client_with_server() {
     client.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_buffered_client_over_tcp() {
+    let server = IpcServer::bind("tcp://127.0.0.1:0").await.unwrap();
+    let client = BufferedIpcClient::new(server.address().to_string()).await;
+
+    let proxy_id = ProxyId::new();
+    let log_entry = LogEntry::new(LogLevel::Info, "Over TCP".to_string(), proxy_id);
+    client
+        .send(IpcMessage::LogEntry(log_entry.clone()))
+        .await
+        .unwrap();
+
+    let mut server_connection = server.accept().await.unwrap();
+    match server_connection
+        .receive_message()
+        .await
+        .unwrap()
+        .unwrap()
+        .message
+    {
+        IpcMessage::LogEntry(entry) => assert_eq!(entry.message, log_entry.message),
+        other => panic!("Expected LogEntry message, got {:?}", other),
+    }
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_buffered_client_sends_token_on_every_connection() {
+    let server = IpcServer::bind("tcp://127.0.0.1:0")
+        .await
+        .unwrap()
+        .with_token(Some("s3cret".to_string()));
+    let client =
+        BufferedIpcClient::with_token(server.address().to_string(), Some("s3cret".to_string()))
+            .await;
+    let proxy_id = ProxyId::new();
+    client.set_registration(IpcMessage::ProxyStopped(proxy_id.clone()));
+
+    // Accepted before and after a reconnect, registration first each time
+    for _ in 0..2 {
+        let mut connection = timeout(Duration::from_secs(10), server.accept())
+            .await
+            .unwrap()
+            .unwrap();
+        let envelope = timeout(Duration::from_secs(5), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(matches!(envelope.message, IpcMessage::ProxyStopped(ref id) if *id == proxy_id));
+    }
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_buffered_client_length_prefixed_framing() {
+    let server = IpcServer::bind("tcp://127.0.0.1:0").await.unwrap();
+    let client = BufferedIpcClient::with_framing(
+        server.address().to_string(),
+        None,
+        IpcFraming::LengthPrefixed,
+    )
+    .await;
+    let proxy_id = ProxyId::new();
+    client.set_registration(IpcMessage::ProxyStopped(proxy_id.clone()));
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    let envelope = timeout(Duration::from_secs(5), connection.receive_message())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(envelope.message, IpcMessage::ProxyStopped(ref id) if *id == proxy_id));
+    assert_eq!(connection.framing(), Some(IpcFraming::LengthPrefixed));
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_buffered_client_truncates_long_log_messages() {
+    let server = IpcServer::bind("tcp://127.0.0.1:0").await.unwrap();
+    let client = BufferedIpcClient::new(server.address().to_string()).await;
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let message = "x".repeat(MAX_LOG_MESSAGE_BYTES + 1000);
+    let entry = LogEntry::new(LogLevel::Response, message, ProxyId::new());
+    client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+
+    let envelope = timeout(Duration::from_secs(5), connection.receive_message())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    match envelope.message {
+        IpcMessage::LogEntry(entry) => {
+            assert_eq!(entry.message.len(), MAX_LOG_MESSAGE_BYTES);
+            assert_eq!(
+                entry.metadata.unwrap()["truncated"]["original_bytes"],
+                MAX_LOG_MESSAGE_BYTES + 1000
+            );
+        }
+        other => panic!("Expected LogEntry, got {:?}", other),
+    }
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_buffered_client_splits_batches_of_long_log_messages() {
+    let server = IpcServer::bind("tcp://127.0.0.1:0").await.unwrap();
+    let client = BufferedIpcClient::new(server.address().to_string()).await;
+    // Accepted with the default message limit, which the batch as one message would exceed
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let proxy_id = ProxyId::new();
+    let mut entries: Vec<LogEntry> = (0..5)
+        .map(|_| {
+            LogEntry::new(
+                LogLevel::Response,
+                "x".repeat(MAX_LOG_MESSAGE_BYTES + 1000),
+                proxy_id.clone(),
+            )
+        })
+        .collect();
+    for message in ["small one", "small two"] {
+        entries.push(LogEntry::new(
+            LogLevel::Info,
+            message.to_string(),
+            proxy_id.clone(),
+        ));
+    }
+    client.send(IpcMessage::LogsBatch(entries)).await.unwrap();
+
+    let mut batches = Vec::new();
+    while batches.len() < 6 {
+        let envelope = timeout(Duration::from_secs(5), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        match envelope.message {
+            IpcMessage::LogsBatch(batch) => batches.push(batch),
+            other => panic!("Expected LogsBatch, got {:?}", other),
+        }
+    }
+
+    let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![1, 1, 1, 1, 1, 2]);
+    for entry in batches.iter().take(5).flatten() {
+        assert_eq!(entry.message.len(), MAX_LOG_MESSAGE_BYTES);
+        assert_eq!(
+            entry.metadata.as_ref().unwrap()["truncated"]["original_bytes"],
+            MAX_LOG_MESSAGE_BYTES + 1000
+        );
+    }
+    assert_eq!(batches[5][1].message, "small two");
+
+    client.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_buffered_client_reconnection() {
     let temp_dir = tempdir().unwrap();
@@ -112,9 +283,9 @@ async fn test_buffered_client_reconnection() {
 
     // Accept connection and receive all buffered messages
     let mut server_connection = server.accept().await.unwrap();
-    for i in 0..messages.len() {
+    for (i, message) in messages.iter().enumerate() {
         let received_envelope = server_connection.receive_message().await.unwrap().unwrap();
-        match (&messages[i], &received_envelope.message) {
+        match (message, &received_envelope.message) {
             (IpcMessage::LogEntry(sent), IpcMessage::LogEntry(received)) => {
                 assert_eq!(sent.message, received.message);
                 assert_eq!(sent.proxy_id, received.proxy_id);
@@ -126,6 +297,143 @@ async fn test_buffered_client_reconnection() {
     client.shutdown().await;
 }
 
+#[tokio::test]
+async fn test_buffered_client_spills_to_disk_in_order() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+    let spill_path = temp_dir.path().join("spill.jsonl");
+
+    let spill = DiskSpill {
+        path: spill_path.clone(),
+        max_bytes: 100 * 1024 * 1024,
+    };
+    let client = BufferedIpcClient::with_disk_spill(
+        socket_path.clone(),
+        None,
+        IpcFraming::Lines,
+        Some(spill),
+    )
+    .await;
+
+    // Five times what fits in memory, sent during the outage
+    let proxy_id = ProxyId::new();
+    for i in 0..50_000 {
+        let entry = LogEntry::new(LogLevel::Info, format!("Message {}", i), proxy_id.clone());
+        client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+    }
+
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let mut server_connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    for i in 0..50_000 {
+        // The client may ping the monitor mid-replay
+        match next_non_ping(&mut server_connection).await {
+            IpcMessage::LogEntry(entry) => assert_eq!(entry.message, format!("Message {}", i)),
+            other => panic!("Expected LogEntry, got {:?}", other),
+        }
+    }
+
+    client.shutdown().await;
+    // Everything was replayed, so nothing is left behind
+    assert!(!spill_path.exists());
+}
+
+#[tokio::test]
+async fn test_buffered_client_metrics() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let client = BufferedIpcClient::new(socket_path.clone()).await;
+    let proxy_id = ProxyId::new();
+    // Past the 10,000 messages kept in memory
+    for i in 0..10_050 {
+        let entry = LogEntry::new(LogLevel::Info, format!("Message {}", i), proxy_id.clone());
+        client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+    }
+    timeout(Duration::from_secs(5), async {
+        while client.metrics().buffered + client.metrics().dropped < 10_050 {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let metrics = client.metrics();
+    assert!(!metrics.connected);
+    assert_eq!(metrics.buffered, 10_000);
+    assert_eq!(metrics.dropped, 50);
+    assert_eq!(metrics.reconnects, 0);
+
+    // Connecting flushes the buffer
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let mut server_connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    for _ in 0..10_000 {
+        next_non_ping(&mut server_connection).await;
+    }
+    timeout(Duration::from_secs(5), async {
+        while !client.metrics().connected || client.metrics().buffered > 0 {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(client.metrics().dropped, 50);
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_buffered_client_is_connected() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let client = BufferedIpcClient::new(socket_path.clone()).await;
+    sleep(Duration::from_millis(200)).await;
+    assert!(!client.is_connected());
+
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    timeout(Duration::from_secs(5), async {
+        while !client.is_connected() {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    // The client notices the monitor going away
+    drop(connection);
+    drop(server);
+    timeout(Duration::from_secs(5), async {
+        while client.is_connected() {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    client.shutdown().await;
+}
+
 #[tokio::test]
 async fn test_buffered_client_multiple_messages() {
     let temp_dir = tempdir().unwrap();
@@ -260,3 +568,250 @@ async fn test_buffered_client_connection_failure_recovery() {
 
     client.shutdown().await;
 }
+
+#[tokio::test]
+async fn test_buffered_client_keepalive_answered() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("keepalive.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let client = BufferedIpcClient::with_keepalive(
+        socket_path,
+        Duration::from_millis(100),
+        Duration::from_millis(300),
+    )
+    .await;
+    let mut connection = server.accept().await.unwrap();
+    assert!(client.last_pong().await.is_none());
+
+    // Answer every ping for longer than the pong timeout
+    let mut pings = 0;
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(800);
+    while tokio::time::Instant::now() < deadline {
+        let Ok(received) =
+            tokio::time::timeout(Duration::from_millis(200), connection.receive_message()).await
+        else {
+            continue;
+        };
+        let envelope = received.unwrap().unwrap();
+        if let IpcMessage::Ping = envelope.message {
+            pings += 1;
+            connection.send_message(IpcMessage::Pong).await.unwrap();
+        }
+    }
+
+    assert!(pings >= 3);
+    assert!(client.last_pong().await.is_some());
+    // The connection was never considered stale, so the client didn't reconnect
+    assert!(
+        tokio::time::timeout(Duration::from_millis(300), server.accept())
+            .await
+            .is_err()
+    );
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_buffered_client_reconnects_without_pong() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("stale.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let client = BufferedIpcClient::with_keepalive(
+        socket_path,
+        Duration::from_millis(100),
+        Duration::from_millis(300),
+    )
+    .await;
+    let mut stale = server.accept().await.unwrap();
+
+    // Read the pings but never answer them
+    let envelope = tokio::time::timeout(Duration::from_secs(2), stale.receive_message())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(envelope.message, IpcMessage::Ping));
+
+    // The unanswered ping makes the client drop the connection and dial again
+    let mut fresh = tokio::time::timeout(Duration::from_secs(5), server.accept())
+        .await
+        .expect("client should reconnect after the pong timeout")
+        .unwrap();
+    assert!(client.last_pong().await.is_none());
+
+    let proxy_id = ProxyId::new();
+    client
+        .send(IpcMessage::ProxyStopped(proxy_id.clone()))
+        .await
+        .unwrap();
+    loop {
+        let envelope = tokio::time::timeout(Duration::from_secs(2), fresh.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::ProxyStopped(id) = envelope.message {
+            assert_eq!(id, proxy_id);
+            break;
+        }
+    }
+
+    client.shutdown().await;
+}
+
+/// Next message from the client that isn't a keepalive ping
+async fn next_non_ping(connection: &mut IpcConnection) -> IpcMessage {
+    loop {
+        let envelope = tokio::time::timeout(Duration::from_secs(5), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if !matches!(envelope.message, IpcMessage::Ping) {
+            return envelope.message;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_buffered_client_reannounces_registration() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("register.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let client = BufferedIpcClient::new(socket_path.clone()).await;
+    let proxy_id = ProxyId::new();
+    client.set_registration(IpcMessage::ProxyStarted(ProxyInfo {
+        id: proxy_id.clone(),
+        name: "files".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Starting,
+        stats: ProxyStats::default(),
+    }));
+
+    let mut connection = server.accept().await.unwrap();
+    assert!(
+        matches!(next_non_ping(&mut connection).await, IpcMessage::ProxyStarted(info) if info.id == proxy_id)
+    );
+
+    // Restart the monitor; entries sent meanwhile are buffered
+    drop(connection);
+    drop(server);
+    sleep(Duration::from_millis(200)).await;
+    let entry = LogEntry::new(LogLevel::Info, "while down".to_string(), proxy_id.clone());
+    client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    // The registration arrives again, ahead of the buffered entry
+    let mut connection = tokio::time::timeout(Duration::from_secs(5), server.accept())
+        .await
+        .expect("client should reconnect")
+        .unwrap();
+    assert!(
+        matches!(next_non_ping(&mut connection).await, IpcMessage::ProxyStarted(info) if info.id == proxy_id)
+    );
+    assert!(
+        matches!(next_non_ping(&mut connection).await, IpcMessage::LogEntry(entry) if entry.message == "while down")
+    );
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_buffered_client_answers_monitor_pings() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("pong.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let client = BufferedIpcClient::new(socket_path).await;
+    let mut connection = server.accept().await.unwrap();
+    connection.send_message(IpcMessage::Ping).await.unwrap();
+
+    assert!(matches!(
+        next_non_ping(&mut connection).await,
+        IpcMessage::Pong
+    ));
+
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_buffered_client_answers_get_logs() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("get-logs.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let client = BufferedIpcClient::new(socket_path).await;
+    let mut connection = server.accept().await.unwrap();
+    let proxy_id = ProxyId::new();
+    for index in 0..8 {
+        let entry = LogEntry::new(LogLevel::Info, format!("entry {}", index), proxy_id.clone());
+        client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+    }
+    // Another proxy's entry sharing the client isn't handed out
+    let other = LogEntry::new(LogLevel::Info, "other".to_string(), ProxyId::new());
+    client.send(IpcMessage::LogEntry(other)).await.unwrap();
+    for _ in 0..9 {
+        assert!(matches!(
+            next_non_ping(&mut connection).await,
+            IpcMessage::LogEntry(_)
+        ));
+    }
+
+    connection
+        .send_message(IpcMessage::GetLogs {
+            proxy_id: proxy_id.clone(),
+            limit: Some(5),
+        })
+        .await
+        .unwrap();
+    let IpcMessage::LogsBatch(entries) = next_non_ping(&mut connection).await else {
+        panic!("GetLogs should be answered with a LogsBatch");
+    };
+    let messages: Vec<_> = entries.iter().map(|entry| entry.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        ["entry 3", "entry 4", "entry 5", "entry 6", "entry 7"]
+    );
+
+    // Asked again, it answers again
+    connection
+        .send_message(IpcMessage::GetLogs {
+            proxy_id,
+            limit: None,
+        })
+        .await
+        .unwrap();
+    let IpcMessage::LogsBatch(entries) = next_non_ping(&mut connection).await else {
+        panic!("GetLogs should be answered with a LogsBatch");
+    };
+    assert_eq!(entries.len(), 8);
+
+    client.shutdown().await;
+}
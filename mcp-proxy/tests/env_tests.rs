@@ -0,0 +1,261 @@
+use mcp_common::*;
+use mcp_proxy::{parse_env_var, server_cwd, server_env};
+use std::process::Stdio;
+use tempfile::tempdir;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+/// Lines a standalone proxy prints for `args`, read before its stdin closes. The server
+/// should keep running until then, as output it leaves behind on exit can be lost.
+async fn proxy_output(args: &[&str], line_count: usize) -> Vec<String> {
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--name", "env", "--no-monitor"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let stdin = proxy.stdin.take().unwrap();
+
+    let mut stdout = BufReader::new(proxy.stdout.take().unwrap()).lines();
+    let mut lines = Vec::new();
+    while lines.len() < line_count {
+        let line = timeout(Duration::from_secs(10), stdout.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .expect("proxy stdout closed early");
+        lines.push(line);
+    }
+
+    drop(stdin);
+    timeout(Duration::from_secs(10), proxy.wait())
+        .await
+        .expect("proxy did not exit")
+        .unwrap();
+    lines
+}
+
+#[test]
+fn test_parse_env_var() {
+    assert_eq!(
+        parse_env_var("TOKEN=a=b c"),
+        Ok(("TOKEN".to_string(), "a=b c".to_string()))
+    );
+    assert_eq!(
+        parse_env_var("EMPTY="),
+        Ok(("EMPTY".to_string(), String::new()))
+    );
+    assert!(parse_env_var("NO_VALUE").is_err());
+    assert!(parse_env_var("=value").is_err());
+}
+
+#[test]
+fn test_server_env_merges_env_file_with_overrides() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(".env");
+    std::fs::write(&path, "# tokens\nMY_VAR=file\n\n  OTHER=from file\n").unwrap();
+
+    let env_vars = server_env(
+        Some(&path),
+        vec![
+            ("MY_VAR".to_string(), "flag".to_string()),
+            ("EXTRA".to_string(), "1".to_string()),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        env_vars,
+        [
+            ("MY_VAR".to_string(), "flag".to_string()),
+            ("OTHER".to_string(), "from file".to_string()),
+            ("EXTRA".to_string(), "1".to_string()),
+        ]
+    );
+
+    std::fs::write(&path, "OK=1\nbroken\n").unwrap();
+    let error = server_env(Some(&path), Vec::new()).unwrap_err();
+    assert!(error.to_string().contains("line 2"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_proxy_env_reaches_server_but_not_monitor() {
+    let dir = tempdir().unwrap();
+    let socket_path = dir.path().join("env.sock").to_string_lossy().to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let env_file = dir.path().join(".env");
+    std::fs::write(&env_file, "MY_VAR=secret\nOTHER=world\n").unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--name", "env", "--ipc-socket", &socket_path])
+        .args([
+            "--env-file",
+            env_file.to_str().unwrap(),
+            "--env",
+            "MY_VAR=hello",
+        ])
+        .args(["--command", "echo \"$MY_VAR $OTHER\"; cat"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    let mut started = None;
+    let mut response = None;
+    while started.is_none() || response.is_none() {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .expect("proxy disconnected");
+        let entries = match envelope.message {
+            IpcMessage::ProxyStarted(info) => {
+                started = Some(info);
+                continue;
+            }
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(entries) => entries,
+            _ => continue,
+        };
+        for entry in entries {
+            assert!(!entry.message.contains("secret"));
+            if entry.level == LogLevel::Response {
+                response = Some(entry.message);
+            }
+        }
+    }
+
+    assert!(response.unwrap().contains("hello world"));
+    assert_eq!(
+        started.unwrap().env_vars,
+        [
+            ("MY_VAR".to_string(), REDACTED_ENV_VALUE.to_string()),
+            ("OTHER".to_string(), REDACTED_ENV_VALUE.to_string()),
+        ]
+    );
+
+    proxy.kill().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_proxy_sets_server_env_and_cwd() {
+    let dir = tempdir().unwrap();
+    let cwd = dir.path().canonicalize().unwrap();
+    let cwd = cwd.to_str().unwrap();
+
+    // Both through the shell and without it
+    let shell_lines = proxy_output(
+        &[
+            "--env",
+            "MY_VAR=hello world",
+            "--cwd",
+            cwd,
+            "--command",
+            "echo \"$MY_VAR\"; pwd; cat",
+        ],
+        2,
+    )
+    .await;
+    assert_eq!(shell_lines, ["hello world", cwd]);
+
+    let argv_lines = proxy_output(
+        &[
+            "-e",
+            "MY_VAR=hi",
+            "--cwd",
+            cwd,
+            "--",
+            "sh",
+            "-c",
+            "echo \"$MY_VAR\"; pwd; cat",
+        ],
+        2,
+    )
+    .await;
+    assert_eq!(argv_lines, ["hi", cwd]);
+}
+
+#[test]
+fn test_server_cwd_expands_home_and_checks_the_directory() {
+    assert_eq!(server_cwd(None).unwrap(), None);
+
+    let home = std::path::PathBuf::from(std::env::var_os("HOME").unwrap());
+    assert_eq!(server_cwd(Some("~".into())).unwrap(), Some(home.join("")));
+
+    let dir = tempdir().unwrap();
+    assert_eq!(
+        server_cwd(Some(dir.path().to_path_buf())).unwrap(),
+        Some(dir.path().to_path_buf())
+    );
+
+    let missing = dir.path().join("missing");
+    let error = server_cwd(Some(missing.clone())).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!("Working directory '{}' does not exist", missing.display())
+    );
+    // A file isn't a directory either
+    std::fs::write(&missing, "").unwrap();
+    assert!(server_cwd(Some(missing)).is_err());
+}
+
+#[tokio::test]
+async fn test_proxy_runs_server_in_cwd() {
+    let tmp = std::path::Path::new("/tmp").canonicalize().unwrap();
+    let lines = proxy_output(&["--cwd", "/tmp", "--command", "pwd; cat"], 1).await;
+    assert_eq!(lines, [tmp.to_str().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_proxy_refuses_missing_cwd() {
+    let dir = tempdir().unwrap();
+    let missing = dir.path().join("missing");
+    let output = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--name", "env", "--no-monitor", "--command", "pwd"])
+        .args(["--cwd", missing.to_str().unwrap()])
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&format!(
+        "Working directory '{}' does not exist",
+        missing.display()
+    )));
+}
+
+#[tokio::test]
+async fn test_proxy_env_clear_drops_inherited_variables() {
+    let lines = proxy_output(
+        &[
+            "--clear-env",
+            "--env",
+            "ONLY=1",
+            "--command",
+            "echo \"${HOME:-unset} $ONLY\"; cat",
+        ],
+        1,
+    )
+    .await;
+    assert_eq!(lines, ["unset 1"]);
+}
+
+#[tokio::test]
+async fn test_proxy_rejects_malformed_env() {
+    let output = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--env", "NOT_AN_ASSIGNMENT", "--command", "cat"])
+        .output()
+        .await
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expected KEY=VALUE"));
+}
@@ -0,0 +1,271 @@
+use mcp_common::*;
+use mcp_proxy::{detect_framing, read_message, write_message, FramingMode};
+use std::process::Stdio;
+use tempfile::tempdir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+const REQUEST: &str = r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#;
+
+#[tokio::test]
+async fn test_content_length_round_trip_over_pipe() {
+    let (mut writer, reader) = tokio::io::duplex(1024);
+    let mut reader = BufReader::new(reader);
+
+    write_message(&mut writer, REQUEST, FramingMode::ContentLength)
+        .await
+        .unwrap();
+    // Headers are case-insensitive and may include Content-Type
+    let second = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+    let framed = format!(
+        "content-length: {}\r\nContent-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n{}",
+        second.len(),
+        second
+    );
+    writer.write_all(framed.as_bytes()).await.unwrap();
+    drop(writer);
+
+    let first = read_message(&mut reader, FramingMode::ContentLength)
+        .await
+        .unwrap();
+    assert_eq!(first.as_deref(), Some(REQUEST));
+    let next = read_message(&mut reader, FramingMode::ContentLength)
+        .await
+        .unwrap();
+    assert_eq!(next.as_deref(), Some(second));
+    let eof = read_message(&mut reader, FramingMode::ContentLength)
+        .await
+        .unwrap();
+    assert_eq!(eof, None);
+}
+
+#[tokio::test]
+async fn test_content_length_body_may_contain_newlines() {
+    let body = "{\n  \"jsonrpc\": \"2.0\",\n  \"id\": 2,\n  \"result\": {}\n}";
+    let (mut writer, reader) = tokio::io::duplex(1024);
+    let mut reader = BufReader::new(reader);
+
+    write_message(&mut writer, body, FramingMode::ContentLength)
+        .await
+        .unwrap();
+    drop(writer);
+
+    let message = read_message(&mut reader, FramingMode::ContentLength)
+        .await
+        .unwrap();
+    assert_eq!(message.as_deref(), Some(body));
+}
+
+#[tokio::test]
+async fn test_content_length_missing_header_is_an_error() {
+    let (mut writer, reader) = tokio::io::duplex(1024);
+    let mut reader = BufReader::new(reader);
+
+    writer
+        .write_all(b"Content-Type: application/json\r\n\r\n{}")
+        .await
+        .unwrap();
+    drop(writer);
+
+    let error = read_message(&mut reader, FramingMode::ContentLength)
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[tokio::test]
+async fn test_newline_framing_keeps_line_terminator() {
+    let (mut writer, reader) = tokio::io::duplex(1024);
+    let mut reader = BufReader::new(reader);
+
+    let line = format!("{}\n", REQUEST);
+    write_message(&mut writer, &line, FramingMode::Newline)
+        .await
+        .unwrap();
+    drop(writer);
+
+    let message = read_message(&mut reader, FramingMode::Newline)
+        .await
+        .unwrap();
+    assert_eq!(message, Some(line));
+}
+
+#[tokio::test]
+async fn test_auto_framing_detects_headers_and_lines() {
+    let (mut writer, reader) = tokio::io::duplex(1024);
+    let mut reader = BufReader::new(reader);
+    write_message(&mut writer, REQUEST, FramingMode::ContentLength)
+        .await
+        .unwrap();
+    assert_eq!(
+        detect_framing(&mut reader).await.unwrap(),
+        FramingMode::ContentLength
+    );
+    // Detection only peeks, so the whole message is still there
+    let message = read_message(&mut reader, FramingMode::Auto).await.unwrap();
+    assert_eq!(message.as_deref(), Some(REQUEST));
+
+    let line = format!("{}\n", REQUEST);
+    writer.write_all(line.as_bytes()).await.unwrap();
+    drop(writer);
+    assert_eq!(
+        detect_framing(&mut reader).await.unwrap(),
+        FramingMode::Newline
+    );
+    let message = read_message(&mut reader, FramingMode::Auto).await.unwrap();
+    assert_eq!(message, Some(line));
+}
+
+#[tokio::test]
+async fn test_proxy_auto_framing_logs_one_entry_per_message() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("auto.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    // `cat` stands in for a header-framed server, echoing each request back
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "auto"])
+        .args(["--ipc-socket", &socket_path])
+        .args(["--framing", "auto"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let second = r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#;
+    let mut stdin = proxy.stdin.take().unwrap();
+    for body in [REQUEST, second] {
+        write_message(&mut stdin, body, FramingMode::ContentLength)
+            .await
+            .unwrap();
+    }
+
+    let mut messages = Vec::new();
+    while messages.len() < 4 {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap();
+        let entries = match envelope.map(|e| e.message) {
+            Some(IpcMessage::LogEntry(entry)) => vec![entry],
+            Some(IpcMessage::LogsBatch(entries)) => entries,
+            _ => continue,
+        };
+        for entry in entries {
+            if matches!(entry.level, LogLevel::Request | LogLevel::Response) {
+                messages.push(entry.message);
+            }
+        }
+    }
+    messages.sort();
+    assert_eq!(
+        messages,
+        vec![
+            format!("← {}", REQUEST),
+            format!("← {}", second),
+            format!("→ {}", REQUEST),
+            format!("→ {}", second),
+        ]
+    );
+
+    // Responses go back to the caller framed the way its requests were
+    drop(stdin);
+    let mut stdout = proxy.stdout.take().unwrap();
+    let mut forwarded = String::new();
+    timeout(
+        Duration::from_secs(10),
+        stdout.read_to_string(&mut forwarded),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert!(
+        forwarded.starts_with(&format!("Content-Length: {}\r\n\r\n", REQUEST.len())),
+        "stdout: {:?}",
+        forwarded
+    );
+
+    let _ = timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_proxy_logs_content_length_body_without_headers() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("framing.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    // `cat` echoes the framed request back as the server's response
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "framed"])
+        .args(["--ipc-socket", &socket_path])
+        .args(["--framing", "content-length"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut stdin = proxy.stdin.take().unwrap();
+    let framed = format!("Content-Length: {}\r\n\r\n{}", REQUEST.len(), REQUEST);
+    stdin.write_all(framed.as_bytes()).await.unwrap();
+    stdin.flush().await.unwrap();
+
+    let mut messages = Vec::new();
+    while messages.len() < 2 {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap();
+        let entries = match envelope.map(|e| e.message) {
+            Some(IpcMessage::LogEntry(entry)) => vec![entry],
+            Some(IpcMessage::LogsBatch(entries)) => entries,
+            _ => continue,
+        };
+        for entry in entries {
+            if matches!(entry.level, LogLevel::Request | LogLevel::Response) {
+                messages.push((entry.level, entry.message));
+            }
+        }
+    }
+    assert_eq!(
+        messages,
+        vec![
+            (LogLevel::Request, format!("→ {}", REQUEST)),
+            (LogLevel::Response, format!("← {}", REQUEST)),
+        ]
+    );
+
+    // The response reaches the caller with its framing intact
+    drop(stdin);
+    let mut stdout = proxy.stdout.take().unwrap();
+    let mut forwarded = String::new();
+    timeout(
+        Duration::from_secs(10),
+        stdout.read_to_string(&mut forwarded),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert!(forwarded.contains(&framed), "stdout: {:?}", forwarded);
+
+    let _ = timeout(Duration::from_secs(10), proxy.wait()).await;
+}
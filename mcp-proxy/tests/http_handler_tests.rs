@@ -0,0 +1,207 @@
+use axum::Router;
+use axum::{body::Bytes, http::header, http::HeaderMap, response::IntoResponse, routing::post};
+use mcp_common::*;
+use mcp_proxy::*;
+use std::sync::Arc;
+use tempfile::tempdir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, Mutex};
+use tokio::time::{timeout, Duration};
+
+/// An MCP server on the streamable HTTP transport: it answers with JSON, or with an SSE
+/// stream of a progress notification and the response when the client accepts one
+async fn mock_server() -> String {
+    async fn mcp(headers: HeaderMap, body: Bytes) -> impl IntoResponse {
+        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let response = format!(
+            r#"{{"jsonrpc":"2.0","id":{},"result":{{}}}}"#,
+            request["id"]
+        );
+        let accept = headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if accept.contains("text/event-stream") {
+            let progress = r#"{"jsonrpc":"2.0","method":"notifications/progress"}"#;
+            let events = format!(
+                "data: {}\n\nevent: message\ndata: {}\n\n",
+                progress, response
+            );
+            ([(header::CONTENT_TYPE, "text/event-stream")], events)
+        } else {
+            ([(header::CONTENT_TYPE, "application/json")], response)
+        }
+    }
+
+    let app = Router::new().route("/mcp", post(mcp));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    url
+}
+
+/// POST `body` to the proxy at `address`, returning the whole HTTP response
+async fn post_to(address: std::net::SocketAddr, accept: &str, body: &str) -> String {
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    let request = format!(
+        "POST / HTTP/1.1\r\nHost: {}\r\nAccept: {}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        address,
+        accept,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    timeout(
+        Duration::from_secs(10),
+        stream.read_to_string(&mut response),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_http_handler_rejects_non_http_targets() {
+    let stats = Arc::new(Mutex::new(ProxyStats::default()));
+    assert!(HttpHandler::new(
+        ProxyId::new(),
+        "https://example.com/mcp",
+        stats.clone(),
+        None
+    )
+    .is_err());
+    assert!(HttpHandler::new(ProxyId::new(), "not a url", stats.clone(), None).is_err());
+    assert!(HttpHandler::new(ProxyId::new(), "http://localhost:3000/mcp", stats, None).is_ok());
+}
+
+#[tokio::test]
+async fn test_http_handler_logs_json_and_sse_responses() {
+    let target = mock_server().await;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("http.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let proxy_id = ProxyId::new();
+    let stats = Arc::new(Mutex::new(ProxyStats::default()));
+    let ipc_client = Arc::new(BufferedIpcClient::new(socket_path).await);
+    let handler =
+        HttpHandler::new(proxy_id.clone(), &target, stats.clone(), Some(ipc_client)).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let serving = tokio::spawn(handler.serve(listener, shutdown_rx));
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let response = post_to(
+        address,
+        "application/json",
+        r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.ends_with(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#));
+
+    let response = post_to(
+        address,
+        "application/json, text/event-stream",
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/call"}"#,
+    )
+    .await;
+    assert!(response.contains("text/event-stream"));
+    assert!(response.contains(r#"data: {"jsonrpc":"2.0","id":2,"result":{}}"#));
+
+    let mut messages = Vec::new();
+    while messages.len() < 5 {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let entries = match envelope.message {
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(entries) => entries,
+            _ => continue,
+        };
+        for entry in entries {
+            assert_eq!(entry.proxy_id, proxy_id);
+            messages.push((entry.level, entry.message));
+        }
+    }
+
+    assert_eq!(
+        messages,
+        vec![
+            (
+                LogLevel::Request,
+                r#"→ {"jsonrpc":"2.0","id":1,"method":"tools/list"}"#.to_string()
+            ),
+            (
+                LogLevel::Response,
+                r#"← {"jsonrpc":"2.0","id":1,"result":{}}"#.to_string()
+            ),
+            (
+                LogLevel::Request,
+                r#"→ {"jsonrpc":"2.0","id":2,"method":"tools/call"}"#.to_string()
+            ),
+            (
+                LogLevel::Response,
+                r#"← {"jsonrpc":"2.0","method":"notifications/progress"}"#.to_string()
+            ),
+            (
+                LogLevel::Response,
+                r#"← {"jsonrpc":"2.0","id":2,"result":{}}"#.to_string()
+            ),
+        ]
+    );
+
+    {
+        let stats = stats.lock().await;
+        assert_eq!(stats.total_requests, 2);
+        assert_eq!(stats.successful_requests, 2);
+        assert_eq!(stats.failed_requests, 0);
+    }
+
+    shutdown_tx.send(()).unwrap();
+    timeout(Duration::from_secs(10), serving)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_http_handler_answers_bad_gateway_when_target_is_down() {
+    // Bound then dropped, so nothing is listening there
+    let unused = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let target = format!("http://{}/mcp", unused.local_addr().unwrap());
+    drop(unused);
+
+    let stats = Arc::new(Mutex::new(ProxyStats::default()));
+    let handler = HttpHandler::new(ProxyId::new(), &target, stats.clone(), None).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    tokio::spawn(handler.serve(listener, shutdown_rx));
+
+    let response = post_to(
+        address,
+        "application/json",
+        r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 502"));
+    assert_eq!(stats.lock().await.failed_requests, 1);
+}
@@ -0,0 +1,145 @@
+use mcp_common::*;
+use mcp_proxy::server_command;
+use std::process::Stdio;
+use tempfile::tempdir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+const MESSAGES: &str = concat!(
+    r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#,
+    "\n",
+    r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+    "\n",
+);
+
+/// Run a verbose proxy around `cat`, send `MESSAGES` and collect what it printed
+async fn run_echo_proxy(extra_args: &[&str]) -> std::process::Output {
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args([
+            "--command",
+            "cat",
+            "--name",
+            "echo",
+            "--no-monitor",
+            "--verbose",
+        ])
+        .args(extra_args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdin = proxy.stdin.take().unwrap();
+    stdin.write_all(MESSAGES.as_bytes()).await.unwrap();
+    stdin.flush().await.unwrap();
+    // Give the proxy time to echo both messages before the server sees EOF
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    drop(stdin);
+
+    timeout(Duration::from_secs(10), proxy.wait_with_output())
+        .await
+        .expect("proxy did not exit")
+        .unwrap()
+}
+
+#[test]
+fn test_server_command_forms() {
+    let words = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        server_command(Some("python server.py --port 1".to_string()), vec![], true).unwrap(),
+        words(&["sh", "-c", "python server.py --port 1"])
+    );
+    assert_eq!(
+        server_command(Some("python server.py".to_string()), vec![], false).unwrap(),
+        words(&["python", "server.py"])
+    );
+    let args = words(&["node", "server.js", "--token", "a 'b' \"c\""]);
+    assert_eq!(server_command(None, args.clone(), true).unwrap(), args);
+    assert!(server_command(Some("node".to_string()), args, true).is_err());
+}
+
+#[tokio::test]
+async fn test_proxy_runs_trailing_command_with_arguments_intact() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("argv.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    // The server prints its arguments and exits at once: what it wrote must still get out
+    let argv = [
+        "printf",
+        "%s\\n",
+        "two words",
+        "it's",
+        "\"quoted\"",
+        "$HOME",
+    ];
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--name", "argv", "--ipc-socket", &socket_path, "--"])
+        .args(argv)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let stdin = proxy.stdin.take().unwrap();
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    match envelope.message {
+        IpcMessage::ProxyStarted(info) => assert_eq!(info.target_command, argv),
+        other => panic!("expected ProxyStarted, got {:?}", other),
+    }
+
+    let mut stdout = BufReader::new(proxy.stdout.take().unwrap()).lines();
+    let mut printed = Vec::new();
+    while printed.len() < 4 {
+        let line = timeout(Duration::from_secs(10), stdout.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .expect("proxy stdout closed early");
+        printed.push(line);
+    }
+    assert_eq!(printed, ["two words", "it's", "\"quoted\"", "$HOME"]);
+
+    drop(stdin);
+    timeout(Duration::from_secs(10), proxy.wait())
+        .await
+        .expect("proxy did not exit")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_proxy_logs_stay_off_stdout() {
+    let output = run_echo_proxy(&[]).await;
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), MESSAGES);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Starting MCP Proxy: echo"), "{}", stderr);
+}
+
+#[tokio::test]
+async fn test_proxy_logs_to_file() {
+    let temp_dir = tempdir().unwrap();
+    let log_path = temp_dir.path().join("proxy.log");
+    let output = run_echo_proxy(&["--log-file", log_path.to_str().unwrap()]).await;
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), MESSAGES);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("Starting MCP Proxy"));
+    let logged = std::fs::read_to_string(&log_path).unwrap();
+    assert!(logged.contains("Starting MCP Proxy: echo"), "{}", logged);
+}
@@ -0,0 +1,241 @@
+use mcp_common::*;
+use mcp_proxy::RestartPolicy;
+use std::process::Stdio;
+use tempfile::tempdir;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+#[test]
+fn test_restart_policy_backoff() {
+    let policy = RestartPolicy::Always {
+        max_attempts: 3,
+        base_delay_ms: 100,
+    };
+
+    assert_eq!(policy.delay_for(1), Some(Duration::from_millis(100)));
+    assert_eq!(policy.delay_for(2), Some(Duration::from_millis(200)));
+    assert_eq!(policy.delay_for(3), Some(Duration::from_millis(400)));
+    assert_eq!(policy.delay_for(4), None);
+    assert_eq!(RestartPolicy::Never.delay_for(1), None);
+}
+
+#[test]
+fn test_restart_policy_from_spec() {
+    assert_eq!(
+        RestartPolicy::from_spec("on-failure:3", 5, 100),
+        Ok(RestartPolicy::OnFailure {
+            max_attempts: 3,
+            base_delay_ms: 100,
+        })
+    );
+    assert_eq!(
+        RestartPolicy::from_spec("always", 5, 100),
+        Ok(RestartPolicy::Always {
+            max_attempts: 5,
+            base_delay_ms: 100,
+        })
+    );
+    assert_eq!(
+        RestartPolicy::from_spec("never", 5, 100),
+        Ok(RestartPolicy::Never)
+    );
+    assert!(RestartPolicy::from_spec("sometimes", 5, 100).is_err());
+    assert!(RestartPolicy::from_spec("always:many", 5, 100).is_err());
+}
+
+#[tokio::test]
+async fn test_proxy_restarts_crashed_server_up_to_max_attempts() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("restart.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    // Keep stdin piped and open so only the child exiting ends each session
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "exit 3", "--name", "crashy"])
+        .args(["--ipc-socket", &socket_path])
+        .args(["--restart-on-crash", "--max-restarts", "3"])
+        .args(["--restart-delay-ms", "100"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut restart_messages = Vec::new();
+    loop {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap();
+        match envelope.map(|e| e.message) {
+            Some(IpcMessage::LogEntry(entry)) if entry.level == LogLevel::Error => {
+                restart_messages.push(entry.message)
+            }
+            Some(IpcMessage::ProxyStopped(_)) | None => break,
+            Some(_) => {}
+        }
+    }
+
+    assert_eq!(
+        restart_messages,
+        vec![
+            "Child exited (status 3), restarting attempt 1/3",
+            "Child exited (status 3), restarting attempt 2/3",
+            "Child exited (status 3), restarting attempt 3/3",
+        ]
+    );
+
+    let status = timeout(Duration::from_secs(10), proxy.wait())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!status.success(), "proxy should fail once restarts run out");
+}
+
+#[tokio::test]
+async fn test_restart_on_crash_leaves_clean_exits_alone() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("clean-exit.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "exit 0", "--name", "tidy"])
+        .args(["--ipc-socket", &socket_path])
+        .args(["--restart-on-crash", "--restart-delay-ms", "100"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    loop {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap();
+        match envelope.map(|e| e.message) {
+            Some(IpcMessage::LogEntry(entry)) => {
+                assert!(
+                    !entry.message.contains("restarting"),
+                    "clean exit restarted: {}",
+                    entry.message
+                );
+            }
+            Some(IpcMessage::ProxyStopped(_)) | None => break,
+            Some(_) => {}
+        }
+    }
+
+    timeout(Duration::from_secs(10), proxy.wait())
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_proxy_holds_client_input_while_server_restarts() {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("restart-input.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    // Each server answers one line and exits cleanly, which `always` restarts
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "head -n 1", "--name", "one-shot"])
+        .args(["--ipc-socket", &socket_path])
+        .args(["--restart", "always:1", "--restart-delay-ms", "300"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = proxy.stdin.take().unwrap();
+    let mut stdout = BufReader::new(proxy.stdout.take().unwrap()).lines();
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let first = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+    stdin
+        .write_all(format!("{}\n", first).as_bytes())
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+
+    // Sent only once the first server is gone, during the restart delay
+    loop {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::LogEntry(entry) = envelope.message {
+            if entry.message.contains("restarting attempt 1/1") {
+                assert_eq!(entry.level, LogLevel::Info);
+                break;
+            }
+        }
+    }
+    let second = r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#;
+    stdin
+        .write_all(format!("{}\n", second).as_bytes())
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+
+    let mut answered = Vec::new();
+    while answered.len() < 2 {
+        let line = timeout(Duration::from_secs(10), stdout.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .expect("proxy stdout closed early");
+        answered.push(line);
+    }
+    assert_eq!(answered, [first, second]);
+
+    // The monitor sees the restarted server come up, with the restart counted
+    loop {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap();
+        match envelope.map(|e| e.message) {
+            Some(IpcMessage::ProxyStarted(info)) if info.stats.restarts == 1 => {
+                if matches!(info.status, ProxyStatus::Running) {
+                    break;
+                }
+            }
+            Some(_) => {}
+            None => panic!("proxy never announced the restarted server"),
+        }
+    }
+
+    drop(stdin);
+    let _ = timeout(Duration::from_secs(10), proxy.wait()).await;
+}
@@ -0,0 +1,226 @@
+use mcp_common::*;
+use std::process::Stdio;
+use tempfile::tempdir;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+#[tokio::test]
+async fn test_monitor_shutdown_kills_long_running_server() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("shutdown.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    // Keep stdin piped and open so only the shutdown can end the session
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "sleep 60", "--name", "sleepy"])
+        .args(["--ipc-socket", &socket_path, "--verbose"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    let proxy_id = loop {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::ProxyStarted(info) = envelope.message {
+            break info.id;
+        }
+    };
+
+    connection
+        .send_message(IpcMessage::Shutdown(proxy_id.clone()))
+        .await
+        .unwrap();
+
+    loop {
+        let envelope = timeout(Duration::from_secs(5), connection.receive_message())
+            .await
+            .expect("proxy should report that it stopped")
+            .unwrap();
+        match envelope.map(|e| e.message) {
+            Some(IpcMessage::ProxyStopped(id)) => {
+                assert_eq!(id, proxy_id);
+                break;
+            }
+            Some(_) => {}
+            None => panic!("proxy closed the connection without ProxyStopped"),
+        }
+    }
+
+    // Well before the 60 second sleep would have ended on its own
+    timeout(Duration::from_secs(5), proxy.wait())
+        .await
+        .expect("proxy should exit after the shutdown")
+        .unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sigterm_stops_the_whole_server_process_group() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("sigterm.sock")
+        .to_string_lossy()
+        .to_string();
+    let pid_file = temp_dir.path().join("sleep.pid");
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    // The shell starts `sleep` in the background, so only signalling the group reaches it
+    let command = format!("sleep 60 & echo $! > {}; wait", pid_file.display());
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", &command, "--name", "terminated"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    let proxy_id = loop {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::ProxyStarted(info) = envelope.message {
+            if matches!(info.status, ProxyStatus::Running) {
+                break info.id;
+            }
+        }
+    };
+    let sleep_pid: libc::pid_t = loop {
+        match std::fs::read_to_string(&pid_file) {
+            Ok(pid) if pid.ends_with('\n') => break pid.trim().parse().unwrap(),
+            _ => tokio::time::sleep(Duration::from_millis(20)).await,
+        }
+    };
+
+    let proxy_pid = proxy.id().unwrap() as libc::pid_t;
+    assert_eq!(unsafe { libc::kill(proxy_pid, libc::SIGTERM) }, 0);
+
+    loop {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .expect("proxy should report that it stopped")
+            .unwrap();
+        match envelope.map(|e| e.message) {
+            Some(IpcMessage::ProxyStopped(id)) => {
+                assert_eq!(id, proxy_id);
+                break;
+            }
+            Some(_) => {}
+            None => panic!("proxy closed the connection without ProxyStopped"),
+        }
+    }
+
+    // Exits even though the client never closed stdin
+    timeout(Duration::from_secs(10), proxy.wait())
+        .await
+        .expect("proxy should exit after SIGTERM")
+        .unwrap();
+    // Gone, or a zombie waiting for whoever inherited it to reap it
+    let sleep_stopped = || match std::fs::read_to_string(format!("/proc/{}/stat", sleep_pid)) {
+        Ok(stat) => stat
+            .rsplit(')')
+            .next()
+            .unwrap()
+            .trim_start()
+            .starts_with('Z'),
+        Err(_) => true,
+    };
+    timeout(Duration::from_secs(5), async {
+        while !sleep_stopped() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("the backgrounded sleep should be stopped too");
+}
+
+#[cfg(unix)]
+/// SIGTERM a proxy around `script` once it has run `$READY`, returning how long the proxy
+/// took to exit and its log
+async fn stop_proxy_around(script: &str, shutdown_timeout_ms: &str) -> (Duration, String) {
+    let temp_dir = tempdir().unwrap();
+    let log_file = temp_dir.path().join("proxy.log");
+    let ready_file = temp_dir.path().join("ready");
+    let script = script.replace("$READY", &format!("touch {}", ready_file.display()));
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--name", "graceful", "--no-monitor"])
+        .args(["--shutdown-timeout-ms", shutdown_timeout_ms])
+        .arg("--log-file")
+        .arg(&log_file)
+        .args(["--", "sh", "-c", &script])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    timeout(Duration::from_secs(10), async {
+        while !ready_file.exists() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("the server should start");
+
+    let started = std::time::Instant::now();
+    let proxy_pid = proxy.id().unwrap() as libc::pid_t;
+    assert_eq!(unsafe { libc::kill(proxy_pid, libc::SIGTERM) }, 0);
+    timeout(Duration::from_secs(10), proxy.wait())
+        .await
+        .expect("proxy should exit after SIGTERM")
+        .unwrap();
+
+    (
+        started.elapsed(),
+        std::fs::read_to_string(&log_file).unwrap(),
+    )
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_server_handling_sigterm_is_not_killed() {
+    let (elapsed, log) = stop_proxy_around(r#"trap "exit 0" TERM; $READY; sleep 10"#, "5000").await;
+
+    assert!(elapsed < Duration::from_secs(5), "took {:?}", elapsed);
+    assert!(
+        log.contains("MCP server exited after SIGTERM (status 0)"),
+        "{}",
+        log
+    );
+    assert!(!log.contains("killing it"), "{}", log);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_server_ignoring_sigterm_is_killed_after_timeout() {
+    // Ignored signals stay ignored in the shell's children
+    let (elapsed, log) =
+        stop_proxy_around(r#"trap "" TERM; $READY; while :; do sleep 1; done"#, "300").await;
+
+    assert!(elapsed >= Duration::from_millis(300), "took {:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(5), "took {:?}", elapsed);
+    assert!(
+        log.contains("MCP server still running 300ms after SIGTERM, killing it"),
+        "{}",
+        log
+    );
+}
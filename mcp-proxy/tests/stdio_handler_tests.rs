@@ -70,14 +70,16 @@ async fn test_stdio_handler_stats_collection() {
 
 // Mock child process simulation tests
 #[tokio::test]
-#[ignore = "Hangs due to handle_communication not terminating - needs investigation"]
 async fn test_stdio_handler_process_lifecycle() {
     let proxy_id = ProxyId::new();
     let stats = Arc::new(Mutex::new(ProxyStats::default()));
 
+    // Client input that stays open until the test ends
+    let (_client, input) = tokio::io::duplex(64);
     let mut handler = StdioHandler::new(proxy_id.clone(), stats.clone(), None)
         .await
-        .unwrap();
+        .unwrap()
+        .with_input(input);
 
     // Create a simple echo process for testing
     let mut child = Command::new("echo")
@@ -103,9 +105,13 @@ async fn test_stdio_handler_process_lifecycle() {
     // Send shutdown signal
     let _ = shutdown_tx.send(());
 
-    // Wait for handler to finish with timeout
+    // Wait for handler to finish with timeout; `echo` has likely exited by now
     match tokio::time::timeout(Duration::from_secs(2), handle).await {
-        Ok(Ok(result)) => assert!(result.is_ok()),
+        Ok(Ok(Ok(()))) => {}
+        Ok(Ok(Err(e))) => match e.downcast_ref::<StdioError>() {
+            Some(StdioError::ChildExited(status)) => assert!(status.success()),
+            None => panic!("Handler failed: {}", e),
+        },
         Ok(Err(_)) => panic!("Handler task panicked"),
         Err(_) => {
             // Force kill the child process if still running
@@ -121,14 +127,16 @@ async fn test_stdio_handler_process_lifecycle() {
 }
 
 #[tokio::test]
-#[ignore = "Hangs due to handle_communication not terminating - needs investigation"]
 async fn test_stdio_handler_with_long_running_process() {
     let proxy_id = ProxyId::new();
     let stats = Arc::new(Mutex::new(ProxyStats::default()));
 
+    // Client input that stays open until the test ends
+    let (_client, input) = tokio::io::duplex(64);
     let mut handler = StdioHandler::new(proxy_id.clone(), stats.clone(), None)
         .await
-        .unwrap();
+        .unwrap()
+        .with_input(input);
 
     // Use 'cat' as a long-running process that echoes input
     let mut child = Command::new("cat")
@@ -203,9 +211,12 @@ async fn test_stdio_handler_shutdown_signal() {
     let proxy_id = ProxyId::new();
     let stats = Arc::new(Mutex::new(ProxyStats::default()));
 
+    // Client input that stays open until the test ends
+    let (_client, input) = tokio::io::duplex(64);
     let mut handler = StdioHandler::new(proxy_id.clone(), stats.clone(), None)
         .await
-        .unwrap();
+        .unwrap()
+        .with_input(input);
 
     // Use sleep command as a controllable process
     let mut child = Command::new("sleep")
@@ -238,7 +249,6 @@ async fn test_stdio_handler_shutdown_signal() {
 }
 
 #[tokio::test]
-#[ignore = "Hangs due to handle_communication not terminating - needs investigation"]
 async fn test_stdio_handler_stats_updates() {
     let temp_dir = tempdir().unwrap();
     let socket_path = temp_dir
@@ -259,17 +269,20 @@ async fn test_stdio_handler_stats_updates() {
         active_connections: 2,
         uptime: Duration::from_secs(60),
         bytes_transferred: 2048,
+        ..Default::default()
     }));
 
     let ipc_client = Arc::new(BufferedIpcClient::new(socket_path).await);
 
+    // Client input that stays open until the test ends
+    let (_client, input) = tokio::io::duplex(64);
     let mut handler = StdioHandler::new(proxy_id.clone(), stats.clone(), Some(ipc_client.clone()))
         .await
-        .unwrap();
+        .unwrap()
+        .with_input(input);
 
-    // Create a simple process
-    let mut child = Command::new("echo")
-        .arg("test")
+    // A process that writes nothing, so the stats stay as they were set
+    let mut child = Command::new("cat")
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
@@ -321,3 +334,815 @@ async fn test_stdio_handler_stats_updates() {
         }
     }
 }
+
+#[tokio::test]
+async fn test_stdio_handler_batches_log_entries() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("batch.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "batched"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // 60 requests in one write, each echoed back as a response by `cat`
+    let input: String = (0..60)
+        .map(|i| format!("{{\"jsonrpc\":\"2.0\",\"id\":{},\"method\":\"ping\"}}\n", i))
+        .collect();
+    let mut stdin = proxy.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes()).await.unwrap();
+    stdin.flush().await.unwrap();
+
+    let mut requests = Vec::new();
+    let mut batches = 0;
+    while requests.len() < 60 {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let entries = match envelope.message {
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(entries) => {
+                assert!(entries.len() <= 50);
+                batches += 1;
+                entries
+            }
+            _ => continue,
+        };
+        requests.extend(
+            entries
+                .into_iter()
+                .filter(|entry| entry.level == LogLevel::Request)
+                .map(|entry| entry.message),
+        );
+    }
+
+    assert!(batches > 0, "a burst of traffic should be batched");
+    let expected: Vec<String> = (0..60)
+        .map(|i| format!("→ {{\"jsonrpc\":\"2.0\",\"id\":{},\"method\":\"ping\"}}", i))
+        .collect();
+    assert_eq!(requests, expected);
+
+    drop(stdin);
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_tracks_max_message_sizes() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("sizes.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "sizes"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Requests of 100, 500 and 200 bytes, newline included, echoed back by `cat`
+    let request = |size: usize| {
+        let prefix = r#"{"jsonrpc":"2.0","id":1,"method":"ping","params":""#;
+        let padding = "x".repeat(size - prefix.len() - 3);
+        format!("{}{}\"}}\n", prefix, padding)
+    };
+    let mut stdin = proxy.stdin.take().unwrap();
+    for size in [100, 500, 200] {
+        let line = request(size);
+        assert_eq!(line.len(), size);
+        stdin.write_all(line.as_bytes()).await.unwrap();
+    }
+    stdin.flush().await.unwrap();
+
+    let stats = loop {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::StatsUpdate(stats) = envelope.message {
+            // Each request is counted going in, and again when `cat` sends it back
+            if stats.total_requests == 6 {
+                break stats;
+            }
+        }
+    };
+
+    assert_eq!(stats.max_request_bytes, 500);
+    assert_eq!(stats.max_response_bytes, 500);
+    // Each direction is counted on its own, and the total covers both
+    assert_eq!(stats.bytes_client_to_server, 800);
+    assert_eq!(stats.bytes_server_to_client, 800);
+    assert_eq!(stats.bytes_transferred, 1600);
+
+    drop(stdin);
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_times_responses_to_requests() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("duration.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "timed"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // `cat` echoes both lines, so the response comes back at least 50ms after the
+    // request (waiting a little longer, as the proxy may read the request late)
+    let mut stdin = proxy.stdin.take().unwrap();
+    stdin
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"tools/list\"}\n")
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+    sleep(Duration::from_millis(60)).await;
+    stdin
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":7,\"result\":{}}\n")
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+
+    let timed = loop {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let entries = match envelope.message {
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(entries) => entries,
+            _ => continue,
+        };
+        if let Some(entry) = entries
+            .into_iter()
+            .find(|entry| entry.level == LogLevel::Response && entry.message.contains("result"))
+        {
+            break entry;
+        }
+    };
+
+    let duration = timed.duration.expect("response should be timed");
+    assert!(duration >= Duration::from_millis(50), "{:?}", duration);
+
+    drop(stdin);
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_reports_negotiated_protocol_version() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("metadata.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "initialized"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // `cat` echoes the request, then the response standing in for the server's
+    let mut stdin = proxy.stdin.take().unwrap();
+    stdin
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"initialize\",\"params\":{}}\n")
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+    sleep(Duration::from_millis(50)).await;
+    stdin
+        .write_all(
+            b"{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"protocolVersion\":\"2024-11-05\"}}\n",
+        )
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+
+    let (proxy_id, protocol_version) = loop {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::ProxyMetadata {
+            proxy_id,
+            protocol_version,
+        } = envelope.message
+        {
+            break (proxy_id, protocol_version);
+        }
+    };
+    assert_eq!(protocol_version, "2024-11-05");
+    assert_ne!(proxy_id, ProxyId::monitor());
+
+    drop(stdin);
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_passes_bytes_through_untouched() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("bytes.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "bytes"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Binary garbage, then a request without a trailing newline
+    let garbage: &[u8] = b"\xff\xfe\x00garbage\n";
+    let request: &[u8] = br#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+    let mut stdin = proxy.stdin.take().unwrap();
+    stdin.write_all(garbage).await.unwrap();
+    stdin.write_all(request).await.unwrap();
+    stdin.flush().await.unwrap();
+
+    // `cat` echoes both back unchanged, the unterminated one without waiting for more
+    let mut stdout = proxy.stdout.take().unwrap();
+    let mut echoed = vec![0u8; garbage.len() + request.len()];
+    tokio::time::timeout(Duration::from_secs(10), stdout.read_exact(&mut echoed))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(echoed, [garbage, request].concat());
+
+    // The unterminated lines are logged once the session ends
+    drop(stdin);
+    let mut entries = Vec::new();
+    while entries.len() < 4 {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let batch = match envelope.message {
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(batch) => batch,
+            _ => continue,
+        };
+        entries.extend(
+            batch
+                .into_iter()
+                .filter(|entry| matches!(entry.level, LogLevel::Request | LogLevel::Response)),
+        );
+    }
+
+    let invalid_utf8 = serde_json::json!({ "invalid_utf8": true });
+    let garbage_entries: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry.message.contains("garbage"))
+        .collect();
+    assert_eq!(garbage_entries.len(), 2);
+    for entry in garbage_entries {
+        assert!(entry.message.contains('\u{FFFD}'));
+        assert_eq!(entry.metadata.as_ref(), Some(&invalid_utf8));
+    }
+    let request = std::str::from_utf8(request).unwrap();
+    for (level, arrow) in [(LogLevel::Request, "→"), (LogLevel::Response, "←")] {
+        let entry = entries
+            .iter()
+            .find(|entry| entry.level == level && !entry.message.contains("garbage"))
+            .unwrap();
+        assert_eq!(entry.message, format!("{} {}", arrow, request));
+        assert_eq!(entry.metadata, None);
+    }
+
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_counts_json_rpc_messages() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("counts.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "counted"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // `cat` echoes every line, so each is counted going in and coming back out
+    let input = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":1,"result":{}}"#,
+        r#"{"jsonrpc":"2.0","id":2,"error":{"code":-32601,"message":"Method not found"}}"#,
+        "not json-rpc",
+        r#"[{"jsonrpc":"2.0","id":3,"method":"ping"},{"jsonrpc":"2.0","method":"notifications/progress"}]"#,
+    ]
+    .map(|line| format!("{}\n", line))
+    .concat();
+    let mut stdin = proxy.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes()).await.unwrap();
+    stdin.flush().await.unwrap();
+
+    // Error responses are also tagged with their code, for the monitor to pick out
+    let mut error_codes = Vec::new();
+    let mut stats = None;
+    while stats.is_none() || error_codes.is_empty() {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        match envelope.message {
+            IpcMessage::StatsUpdate(update)
+                if update.total_requests + update.notifications >= 8 =>
+            {
+                stats = Some(update);
+            }
+            IpcMessage::LogEntry(entry) => error_codes.extend(entry.error_code()),
+            IpcMessage::LogsBatch(entries) => {
+                error_codes.extend(entries.iter().filter_map(LogEntry::error_code));
+            }
+            _ => {}
+        }
+    }
+    // Only responses from the server are tagged, so just the copy `cat` sent back
+    assert_eq!(error_codes, vec![-32601]);
+    let stats = stats.unwrap();
+    assert_eq!(stats.total_requests, 4);
+    assert_eq!(stats.notifications, 4);
+    assert_eq!(stats.successful_requests, 2);
+    assert_eq!(stats.failed_requests, 2);
+
+    drop(stdin);
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+/// Stats updates from `connection` until one has `in_flight` requests outstanding
+async fn wait_for_in_flight(connection: &mut IpcConnection, in_flight: u32) {
+    loop {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .expect("in-flight count should reach the expected value")
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::StatsUpdate(stats) = envelope.message {
+            if stats.in_flight_requests == in_flight {
+                return;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_stdio_handler_tracks_in_flight_requests() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("in-flight.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "in-flight"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+    let mut stdin = proxy.stdin.take().unwrap();
+
+    // `cat` answers with whatever it is sent, so the responses come only when written here
+    stdin
+        .write_all(
+            concat!(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#,
+                "\n",
+                r#"{"jsonrpc":"2.0","id":2,"method":"tools/call"}"#,
+                "\n",
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+    wait_for_in_flight(&mut connection, 2).await;
+
+    stdin
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{}}\n")
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+    wait_for_in_flight(&mut connection, 1).await;
+
+    stdin
+        .write_all(
+            b"{\"jsonrpc\":\"2.0\",\"id\":2,\"error\":{\"code\":-32000,\"message\":\"boom\"}}\n",
+        )
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+    wait_for_in_flight(&mut connection, 0).await;
+
+    // A request the server never answers is dropped from the count when it exits
+    stdin
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"ping\"}\n")
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+    wait_for_in_flight(&mut connection, 1).await;
+    drop(stdin);
+    wait_for_in_flight(&mut connection, 0).await;
+
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_reports_uptime() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("uptime.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "uptime"])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut uptimes = Vec::new();
+    while uptimes.len() < 3 {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .expect("the proxy should keep sending stats")
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::StatsUpdate(stats) = envelope.message {
+            uptimes.push(stats.uptime);
+        }
+        sleep(Duration::from_millis(50)).await;
+    }
+    // Stats go out every second, each a little later in the proxy's life
+    assert!(
+        uptimes.windows(2).all(|pair| pair[0] < pair[1]),
+        "{:?}",
+        uptimes
+    );
+    assert!(uptimes[2] >= Duration::from_secs(1));
+
+    drop(proxy.stdin.take());
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_stats_interval() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("interval.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let proxy_id = ProxyId::new();
+    let stats = Arc::new(Mutex::new(ProxyStats::default()));
+    let ipc_client = Arc::new(BufferedIpcClient::new(socket_path).await);
+    let (mut client, input) = tokio::io::duplex(1024);
+    let mut handler = StdioHandler::new(proxy_id, stats, Some(ipc_client))
+        .await
+        .unwrap()
+        .with_stats_interval_ms(50)
+        .with_input(input);
+
+    let mut child = Command::new("cat")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let handle =
+        tokio::spawn(async move { handler.handle_communication(&mut child, shutdown_rx).await });
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    for id in 1..=2 {
+        let request = format!(r#"{{"jsonrpc":"2.0","id":{},"method":"ping"}}"#, id);
+        client
+            .write_all(format!("{}\n", request).as_bytes())
+            .await
+            .unwrap();
+    }
+    client.flush().await.unwrap();
+    sleep(Duration::from_millis(60)).await;
+
+    // Well before the default second is up, the requests show up in a stats update
+    let stats = tokio::time::timeout(Duration::from_millis(500), async {
+        loop {
+            let envelope = connection.receive_message().await.unwrap().unwrap();
+            if let IpcMessage::StatsUpdate(stats) = envelope.message {
+                if stats.total_requests >= 2 {
+                    break stats;
+                }
+            }
+        }
+    })
+    .await
+    .expect("stats should go out every 50ms");
+    assert!(stats.bytes_client_to_server > 0);
+
+    let _ = shutdown_tx.send(());
+    let _ = tokio::time::timeout(Duration::from_secs(2), handle).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_zero_stats_interval() {
+    // A zero period would panic in tokio's interval; it is taken as 1ms instead
+    let stats = Arc::new(Mutex::new(ProxyStats::default()));
+    let handler = StdioHandler::new(ProxyId::new(), stats, None)
+        .await
+        .unwrap()
+        .with_stats_interval_ms(0);
+    drop(handler);
+}
+
+#[tokio::test]
+async fn test_stdio_handler_warns_about_unanswered_requests() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("timeout.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args([
+            "--command",
+            "cat",
+            "--name",
+            "hung",
+            "--request-timeout",
+            "2",
+        ])
+        .args(["--ipc-socket", &socket_path])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut connection = tokio::time::timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // `cat` echoes the request back, which is no answer to it
+    let mut stdin = proxy.stdin.take().unwrap();
+    stdin
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":42,\"method\":\"tools/call\"}\n")
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+
+    // One warning once the timeout has passed, and no more until it passes again
+    let mut warnings = Vec::new();
+    let collecting = tokio::time::sleep(Duration::from_millis(3500));
+    tokio::pin!(collecting);
+    loop {
+        let envelope = tokio::select! {
+            _ = &mut collecting => break,
+            envelope = connection.receive_message() => envelope.unwrap().unwrap(),
+        };
+        let entries = match envelope.message {
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(entries) => entries,
+            _ => continue,
+        };
+        warnings.extend(
+            entries
+                .into_iter()
+                .filter(|entry| entry.level == LogLevel::Warning),
+        );
+    }
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert_eq!(
+        warnings[0].message,
+        "request 42 (tools/call) pending for 2s"
+    );
+    assert_eq!(
+        warnings[0].metadata,
+        Some(serde_json::json!({ "request_id": 42 }))
+    );
+
+    // The request is still waiting, so a late answer is timed from when it was sent
+    stdin
+        .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":42,\"result\":{}}\n")
+        .await
+        .unwrap();
+    stdin.flush().await.unwrap();
+    let answered = loop {
+        let envelope = tokio::time::timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let entries = match envelope.message {
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(entries) => entries,
+            _ => continue,
+        };
+        if let Some(entry) = entries
+            .into_iter()
+            .find(|entry| entry.level == LogLevel::Response && entry.message.contains("result"))
+        {
+            break entry;
+        }
+    };
+    assert!(answered.duration.unwrap() >= Duration::from_secs(3));
+
+    drop(stdin);
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[tokio::test]
+async fn test_stdio_handler_loses_nothing_under_load() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "load", "--no-monitor"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Requests of varying sizes, written while the echoes are read back, so reads on both
+    // sides keep racing each other and the stats ticks
+    let input: Vec<u8> = (0..5000)
+        .flat_map(|id| {
+            format!(
+                "{{\"jsonrpc\":\"2.0\",\"id\":{},\"method\":\"ping\",\"params\":\"{}\"}}\n",
+                id,
+                "x".repeat(id % 300)
+            )
+            .into_bytes()
+        })
+        .collect();
+    let mut stdin = proxy.stdin.take().unwrap();
+    let to_write = input.clone();
+    let writer = tokio::spawn(async move {
+        for chunk in to_write.chunks(4096) {
+            stdin.write_all(chunk).await.unwrap();
+        }
+        // Closing stdin ends the session, so keep it open until the echo has been read
+        stdin
+    });
+
+    let mut stdout = proxy.stdout.take().unwrap();
+    let mut echoed = vec![0u8; input.len()];
+    tokio::time::timeout(Duration::from_secs(30), stdout.read_exact(&mut echoed))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(echoed == input, "output differs from input");
+
+    drop(writer.await.unwrap());
+    let _ = tokio::time::timeout(Duration::from_secs(10), proxy.wait()).await;
+}
+
+#[test]
+fn test_detect_level_from_stderr() {
+    assert_eq!(
+        detect_level_from_stderr("ERROR: something"),
+        LogLevel::Error
+    );
+    assert_eq!(
+        detect_level_from_stderr("WARN: something"),
+        LogLevel::Warning
+    );
+    assert_eq!(
+        detect_level_from_stderr("INFO: server ready"),
+        LogLevel::Info
+    );
+    assert_eq!(detect_level_from_stderr("random output"), LogLevel::Warning);
+
+    assert_eq!(
+        detect_level_from_stderr("debug loading tools\n"),
+        LogLevel::Debug
+    );
+    assert_eq!(
+        detect_level_from_stderr("[warning] slow start"),
+        LogLevel::Warning
+    );
+    assert_eq!(
+        detect_level_from_stderr("  Error - bad config"),
+        LogLevel::Error
+    );
+    // A whole word only: "Information" isn't INFO
+    assert_eq!(
+        detect_level_from_stderr("Information follows"),
+        LogLevel::Warning
+    );
+    assert_eq!(detect_level_from_stderr(""), LogLevel::Warning);
+}
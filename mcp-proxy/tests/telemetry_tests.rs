@@ -0,0 +1,116 @@
+#![cfg(feature = "opentelemetry")]
+
+use axum::{body::Bytes, extract::State, routing::post, Router};
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::tonic::common::v1::any_value::Value;
+use opentelemetry_proto::tonic::trace::v1::{status::StatusCode, Span};
+use prost::Message;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::time::{timeout, Duration};
+
+/// An OTLP/HTTP collector that hands every exported span to the test
+async fn mock_collector() -> (String, mpsc::UnboundedReceiver<Span>) {
+    async fn export(State(spans): State<mpsc::UnboundedSender<Span>>, body: Bytes) {
+        let request = ExportTraceServiceRequest::decode(body).unwrap();
+        for resource_spans in request.resource_spans {
+            for scope_spans in resource_spans.scope_spans {
+                for span in scope_spans.spans {
+                    let _ = spans.send(span);
+                }
+            }
+        }
+    }
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let app = Router::new()
+        .route("/v1/traces", post(export))
+        .with_state(tx);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    (endpoint, rx)
+}
+
+fn attribute(span: &Span, key: &str) -> Option<Value> {
+    span.attributes
+        .iter()
+        .find(|attribute| attribute.key == key)
+        .and_then(|attribute| attribute.value.clone()?.value)
+}
+
+#[tokio::test]
+async fn test_request_response_pair_exports_a_span() {
+    let (endpoint, mut spans) = mock_collector().await;
+
+    let mut proxy = Command::new(env!("CARGO_BIN_EXE_mcp-proxy"))
+        .args(["--command", "cat", "--name", "traced", "--no-monitor"])
+        .args(["--otlp-endpoint", &endpoint])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // `cat` echoes the response back; request 8 is never answered
+    let mut stdin = proxy.stdin.take().unwrap();
+    for line in [
+        r#"{"jsonrpc":"2.0","id":7,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","id":7,"result":{"tools":[]}}"#,
+        r#"{"jsonrpc":"2.0","id":8,"method":"tools/call"}"#,
+    ] {
+        stdin
+            .write_all(format!("{}\n", line).as_bytes())
+            .await
+            .unwrap();
+    }
+    stdin.flush().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Queued spans are exported when the proxy shuts down
+    drop(stdin);
+    timeout(Duration::from_secs(10), proxy.wait())
+        .await
+        .expect("proxy should exit once stdin closes")
+        .unwrap();
+
+    let mut exported = Vec::new();
+    while exported.len() < 2 {
+        let span = timeout(Duration::from_secs(10), spans.recv())
+            .await
+            .expect("both spans should be exported")
+            .unwrap();
+        exported.push(span);
+    }
+
+    let answered = exported.iter().find(|s| s.name == "tools/list").unwrap();
+    assert_eq!(
+        attribute(answered, "mcp.request_id"),
+        Some(Value::StringValue("7".to_string()))
+    );
+    assert_eq!(
+        attribute(answered, "mcp.proxy_name"),
+        Some(Value::StringValue("traced".to_string()))
+    );
+    assert!(matches!(
+        attribute(answered, "mcp.proxy_id"),
+        Some(Value::StringValue(id)) if id.len() == 36
+    ));
+    assert!(matches!(
+        attribute(answered, "mcp.response_latency_ms"),
+        Some(Value::DoubleValue(ms)) if ms >= 0.0
+    ));
+    assert_ne!(
+        answered.status.as_ref().map(|s| s.code),
+        Some(StatusCode::Error as i32)
+    );
+
+    let unanswered = exported.iter().find(|s| s.name == "tools/call").unwrap();
+    assert_eq!(attribute(unanswered, "mcp.response_latency_ms"), None);
+    assert_eq!(
+        unanswered.status.as_ref().map(|s| s.code),
+        Some(StatusCode::Error as i32)
+    );
+}
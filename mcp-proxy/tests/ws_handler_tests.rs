@@ -0,0 +1,150 @@
+use futures_util::{SinkExt, StreamExt};
+use mcp_common::*;
+use mcp_proxy::*;
+use std::sync::Arc;
+use tempfile::tempdir;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::tungstenite::Message;
+
+/// A WebSocket MCP server answering every request, that drops the connection without a
+/// close frame when sent `drop`
+async fn mock_server() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/mcp", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(message)) = socket.next().await {
+                    let Message::Text(text) = message else {
+                        continue;
+                    };
+                    if text == "drop" {
+                        return;
+                    }
+                    let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    let response = format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"result":{{}}}}"#,
+                        request["id"]
+                    );
+                    socket.send(Message::Text(response)).await.unwrap();
+                }
+            });
+        }
+    });
+    url
+}
+
+/// Log entries the proxy sends to `connection`, until `count` have arrived
+async fn receive_logs(connection: &mut IpcConnection, count: usize) -> Vec<(LogLevel, String)> {
+    let mut messages = Vec::new();
+    while messages.len() < count {
+        let envelope = timeout(Duration::from_secs(10), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let entries = match envelope.message {
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(entries) => entries,
+            _ => continue,
+        };
+        messages.extend(
+            entries
+                .into_iter()
+                .map(|entry| (entry.level, entry.message)),
+        );
+    }
+    messages
+}
+
+#[tokio::test]
+async fn test_ws_handler_rejects_non_ws_targets() {
+    let stats = Arc::new(Mutex::new(ProxyStats::default()));
+    assert!(WsHandler::new(ProxyId::new(), "http://localhost:3000", stats.clone(), None).is_err());
+    assert!(WsHandler::new(ProxyId::new(), "ws://localhost:3000/mcp", stats, None).is_ok());
+}
+
+#[tokio::test]
+async fn test_ws_handler_logs_frames_and_dropped_connections() {
+    let target = mock_server().await;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("ws.sock")
+        .to_string_lossy()
+        .to_string();
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+
+    let stats = Arc::new(Mutex::new(ProxyStats::default()));
+    let ipc_client = Arc::new(BufferedIpcClient::new(socket_path).await);
+    let handler = WsHandler::new(ProxyId::new(), &target, stats.clone(), Some(ipc_client)).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let (status_tx, mut status_rx) = mpsc::unbounded_channel();
+    tokio::spawn(handler.serve(listener, shutdown_rx, status_tx));
+
+    let mut connection = timeout(Duration::from_secs(10), server.accept())
+        .await
+        .unwrap()
+        .unwrap();
+
+    let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}", address))
+        .await
+        .unwrap();
+    let request = r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#;
+    client
+        .send(Message::Text(request.to_string()))
+        .await
+        .unwrap();
+    let response = timeout(Duration::from_secs(10), client.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        response,
+        Message::Text(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#.to_string())
+    );
+    assert!(matches!(status_rx.recv().await, Some(ProxyStatus::Running)));
+
+    // The server going away is passed on to the client as a close
+    client
+        .send(Message::Text("drop".to_string()))
+        .await
+        .unwrap();
+    let closed = timeout(Duration::from_secs(10), client.next())
+        .await
+        .unwrap();
+    assert!(matches!(closed, Some(Ok(Message::Close(_))) | None));
+
+    let mut logs = receive_logs(&mut connection, 4).await;
+    let (level, dropped) = logs.pop().unwrap();
+    assert_eq!(level, LogLevel::Error);
+    assert!(dropped.starts_with("Server connection dropped"));
+    assert_eq!(
+        logs,
+        vec![
+            (LogLevel::Request, format!("→ {}", request)),
+            (
+                LogLevel::Response,
+                r#"← {"jsonrpc":"2.0","id":1,"result":{}}"#.to_string()
+            ),
+            (LogLevel::Request, "→ drop".to_string()),
+        ]
+    );
+    match status_rx.recv().await {
+        Some(ProxyStatus::Error(reason)) => assert_eq!(reason, dropped),
+        other => panic!("expected an error status, got {:?}", other),
+    }
+    {
+        // `drop` isn't JSON-RPC, so only the first request and its response count
+        let stats = stats.lock().await;
+        assert_eq!(stats.total_requests, 1);
+        assert_eq!(stats.successful_requests, 1);
+    }
+}
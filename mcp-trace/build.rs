@@ -0,0 +1,22 @@
+use std::process::Command;
+
+// Record the compiler and target for `mcp-trace doctor`
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| "an unknown rustc".to_string());
+
+    println!("cargo:rustc-env=MCP_TRACE_RUSTC_VERSION={}", version);
+    println!(
+        "cargo:rustc-env=MCP_TRACE_TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
@@ -0,0 +1,236 @@
+use anyhow::{Context, Result};
+use mcp_common::{message_json, IpcEnvelope, IpcMessage, LogEntry, ProxyId};
+use mcp_monitor::{compute_diff, DiffLine};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+pub struct DiffArgs {
+    pub a: PathBuf,
+    pub b: PathBuf,
+    pub key: DiffKey,
+    pub json: bool,
+    pub color: bool, // Color the report, for a terminal
+}
+
+/// What makes an entry of one session the same entry in the other
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiffKey {
+    /// Level and JSON-RPC method, a response taking its request's; the nth entry with a
+    /// key in one session is matched with the nth in the other
+    #[default]
+    Method,
+    /// Identical timestamps, e.g. for a recording and a filtered copy of it
+    Timestamp,
+    /// Log entry IDs
+    Id,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SessionDiff {
+    pub only_in_a: Vec<LogEntry>,
+    pub only_in_b: Vec<LogEntry>,
+    /// Matched entries whose messages differ, from A and from B
+    pub changed: Vec<(LogEntry, LogEntry)>,
+}
+
+impl SessionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The log entries of an NDJSON recording, IPC envelopes or bare entries as `filter` reads
+pub fn read_entries<R: BufRead>(reader: R) -> Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<IpcEnvelope>(&line) {
+            Ok(envelope) => match envelope.message {
+                IpcMessage::LogEntry(entry) => entries.push(entry),
+                IpcMessage::LogsBatch(batch) => entries.extend(batch),
+                _ => {}
+            },
+            Err(_) => entries.push(serde_json::from_str(&line).with_context(|| {
+                format!(
+                    "line {} is neither an IPC envelope nor a log entry",
+                    index + 1
+                )
+            })?),
+        }
+    }
+    Ok(entries)
+}
+
+/// Match the entries of `a` and `b` by `key`, in order, and sort out what differs
+pub fn diff_sessions(a: &[LogEntry], b: &[LogEntry], key: DiffKey) -> SessionDiff {
+    let mut unmatched_b: HashMap<String, VecDeque<usize>> = HashMap::new();
+    for (index, entry_key) in entry_keys(b, key).into_iter().enumerate() {
+        unmatched_b.entry(entry_key).or_default().push_back(index);
+    }
+
+    let mut diff = SessionDiff::default();
+    let mut matched_b = vec![false; b.len()];
+    for (entry, entry_key) in a.iter().zip(entry_keys(a, key)) {
+        let Some(index) = unmatched_b
+            .get_mut(&entry_key)
+            .and_then(|indices| indices.pop_front())
+        else {
+            diff.only_in_a.push(entry.clone());
+            continue;
+        };
+        matched_b[index] = true;
+        if !same_message(entry, &b[index]) {
+            diff.changed.push((entry.clone(), b[index].clone()));
+        }
+    }
+    diff.only_in_b = b
+        .iter()
+        .zip(matched_b)
+        .filter(|(_, matched)| !matched)
+        .map(|(entry, _)| entry.clone())
+        .collect();
+    diff
+}
+
+/// Diff `args.a` against `args.b` and print the report to `out`
+pub fn run_diff<W: Write>(args: DiffArgs, out: &mut W) -> Result<SessionDiff> {
+    let a = read_file(&args.a)?;
+    let b = read_file(&args.b)?;
+    let diff = diff_sessions(&a, &b, args.key);
+
+    if args.json {
+        serde_json::to_writer_pretty(&mut *out, &diff)?;
+        writeln!(out)?;
+    } else {
+        write_report(&diff, &args, out)?;
+    }
+    Ok(diff)
+}
+
+fn read_file(path: &Path) -> Result<Vec<LogEntry>> {
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    read_entries(BufReader::new(file)).with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// The key of each entry; for `DiffKey::Method`, responses are keyed by the method of
+/// the request with their JSON-RPC id
+fn entry_keys(entries: &[LogEntry], key: DiffKey) -> Vec<String> {
+    let mut request_methods: HashMap<(ProxyId, String), String> = HashMap::new();
+    entries
+        .iter()
+        .map(|entry| match key {
+            DiffKey::Id => entry.id.to_string(),
+            DiffKey::Timestamp => entry.timestamp.to_rfc3339(),
+            DiffKey::Method => {
+                let json = message_json(entry);
+                let id = json
+                    .as_ref()
+                    .and_then(|json| json.get("id"))
+                    .map(|id| (entry.proxy_id.clone(), id.to_string()));
+                let method = json
+                    .as_ref()
+                    .and_then(|json| json.get("method")?.as_str())
+                    .map(str::to_string);
+                let method = match (method, id) {
+                    (Some(method), Some(id)) => {
+                        request_methods.insert(id, method.clone());
+                        Some(method)
+                    }
+                    (Some(method), None) => Some(method),
+                    (None, Some(id)) => request_methods.get(&id).cloned(),
+                    (None, None) => None,
+                };
+                match method {
+                    Some(method) => format!("{} {}", entry.level, method),
+                    None => entry.level.to_string(),
+                }
+            }
+        })
+        .collect()
+}
+
+/// Equal JSON bodies, or equal text for entries that aren't JSON
+fn same_message(a: &LogEntry, b: &LogEntry) -> bool {
+    match (message_json(a), message_json(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.message == b.message,
+    }
+}
+
+/// The message pretty-printed when it is JSON, as the monitor's detail view shows it
+fn formatted_message(entry: &LogEntry) -> String {
+    message_json(entry)
+        .and_then(|json| serde_json::to_string_pretty(&json).ok())
+        .unwrap_or_else(|| entry.message.trim().to_string())
+}
+
+fn write_report<W: Write>(diff: &SessionDiff, args: &DiffArgs, out: &mut W) -> Result<()> {
+    let paint = |code: &str, text: String| {
+        if args.color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text
+        }
+    };
+    let summary = |entry: &LogEntry| {
+        format!(
+            "{} {} {}",
+            entry.timestamp.format("%H:%M:%S%.3f"),
+            entry.level,
+            entry.message.trim()
+        )
+    };
+
+    writeln!(
+        out,
+        "Only in {}: {}",
+        args.a.display(),
+        diff.only_in_a.len()
+    )?;
+    for entry in &diff.only_in_a {
+        writeln!(out, "{}", paint("31", format!("- {}", summary(entry))))?;
+    }
+    writeln!(
+        out,
+        "Only in {}: {}",
+        args.b.display(),
+        diff.only_in_b.len()
+    )?;
+    for entry in &diff.only_in_b {
+        writeln!(out, "{}", paint("32", format!("+ {}", summary(entry))))?;
+    }
+
+    writeln!(out, "Changed: {}", diff.changed.len())?;
+    for (a, b) in &diff.changed {
+        writeln!(
+            out,
+            "{}",
+            paint(
+                "33",
+                format!(
+                    "~ {} {} / {}",
+                    a.level,
+                    a.timestamp.format("%H:%M:%S%.3f"),
+                    b.timestamp.format("%H:%M:%S%.3f")
+                )
+            )
+        )?;
+        for line in compute_diff(&formatted_message(a), &formatted_message(b)) {
+            match line {
+                DiffLine::Removed(text) => {
+                    writeln!(out, "{}", paint("31", format!("  -{}", text)))?
+                }
+                DiffLine::Added(text) => writeln!(out, "{}", paint("32", format!("  +{}", text)))?,
+                DiffLine::Context(text) => writeln!(out, "   {}", text)?,
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use mcp_common::{IpcAddress, IpcConnection};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// How long to wait for a monitor to take the connection
+pub const MONITOR_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct DoctorArgs {
+    pub ipc_socket: String,
+    pub color: bool, // Color the check marks, for a terminal
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticResult {
+    pub ok: bool,
+    pub detail: String,
+}
+
+impl DiagnosticResult {
+    fn pass(detail: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn fail(detail: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Run every check and print a checklist to `out`. Returns whether all passed.
+pub async fn run_doctor<W: Write>(args: DoctorArgs, out: &mut W) -> Result<bool> {
+    let checks = [
+        ("IPC socket", check_socket_path(&args.ipc_socket)),
+        ("Monitor", check_monitor(&args.ipc_socket).await),
+        ("Shell", check_shell()),
+        ("Terminal colors", check_color()),
+        ("Unicode", check_unicode()),
+        ("Build", check_build()),
+    ];
+
+    for (name, result) in &checks {
+        let mark = match (result.ok, args.color) {
+            (true, true) => "\x1b[32m✓\x1b[0m",
+            (false, true) => "\x1b[31m✗\x1b[0m",
+            (true, false) => "✓",
+            (false, false) => "✗",
+        };
+        writeln!(out, "{} {}: {}", mark, name, result.detail)?;
+    }
+    Ok(checks.iter().all(|(_, result)| result.ok))
+}
+
+/// Whether the monitor could create its socket: the directory of a Unix socket path is
+/// writable, or a TCP address can be listened on
+pub fn check_socket_path(address: &str) -> DiagnosticResult {
+    match IpcAddress::parse(address) {
+        IpcAddress::Unix(path) => {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            let probe = dir.join(format!(".mcp-trace-doctor-{}", std::process::id()));
+            match std::fs::File::create(&probe) {
+                Ok(_) => {
+                    let _ = std::fs::remove_file(&probe);
+                    DiagnosticResult::pass(format!("{} is writable", dir.display()))
+                }
+                Err(e) => {
+                    DiagnosticResult::fail(format!("can't write to {}: {}", dir.display(), e))
+                }
+            }
+        }
+        IpcAddress::Tcp(host_port) => match std::net::TcpListener::bind(&host_port) {
+            Ok(_) => DiagnosticResult::pass(format!("can listen on {}", address)),
+            Err(e) if e.kind() == ErrorKind::AddrInUse => {
+                DiagnosticResult::pass(format!("{} is already taken (see Monitor)", address))
+            }
+            Err(e) => DiagnosticResult::fail(format!("can't listen on {}: {}", address, e)),
+        },
+    }
+}
+
+/// Whether a monitor is listening at `address`. None running is fine; a socket that
+/// can't be reached for any other reason is not.
+pub async fn check_monitor(address: &str) -> DiagnosticResult {
+    let connect = IpcConnection::connect(address);
+    match tokio::time::timeout(MONITOR_CONNECT_TIMEOUT, connect).await {
+        Ok(Ok(_)) => DiagnosticResult::pass(format!("a monitor is running at {}", address)),
+        Ok(Err(e)) => match e.downcast_ref::<std::io::Error>().map(|e| e.kind()) {
+            Some(ErrorKind::NotFound | ErrorKind::ConnectionRefused) => {
+                DiagnosticResult::pass(format!(
+                    "no monitor running at {} (start one with `mcp-trace`)",
+                    address
+                ))
+            }
+            _ => DiagnosticResult::fail(format!("can't connect to {}: {}", address, e)),
+        },
+        Err(_) => DiagnosticResult::fail(format!(
+            "{} didn't answer within {}s",
+            address,
+            MONITOR_CONNECT_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+/// Whether `sh` runs, as `--shell` mode needs it
+pub fn check_shell() -> DiagnosticResult {
+    match Command::new("sh").args(["-c", "exit 0"]).status() {
+        Ok(status) if status.success() => DiagnosticResult::pass("sh is available for --shell"),
+        Ok(status) => DiagnosticResult::fail(format!("sh -c exited with {}", status)),
+        Err(e) => DiagnosticResult::fail(format!("can't run sh, needed for --shell: {}", e)),
+    }
+}
+
+/// Whether the terminal can draw the monitor, and with how many colors
+pub fn check_color() -> DiagnosticResult {
+    let term = std::env::var("TERM").unwrap_or_default();
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+
+    if term == "dumb" {
+        DiagnosticResult::fail("TERM=dumb can't draw the monitor")
+    } else if std::env::var_os("NO_COLOR").is_some() {
+        DiagnosticResult::pass("colors are turned off by NO_COLOR")
+    } else if colorterm == "truecolor" || colorterm == "24bit" {
+        DiagnosticResult::pass("24-bit color")
+    } else if term.contains("256color") {
+        DiagnosticResult::pass(format!("256 colors (TERM={})", term))
+    } else if term.is_empty() {
+        DiagnosticResult::pass("TERM is not set; colors depend on where the monitor runs")
+    } else {
+        DiagnosticResult::pass(format!("basic colors (TERM={})", term))
+    }
+}
+
+/// Whether the locale is UTF-8, which the monitor's borders and symbols need
+pub fn check_unicode() -> DiagnosticResult {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty());
+
+    match locale {
+        Some(locale) => {
+            let lower = locale.to_lowercase();
+            if lower.contains("utf-8") || lower.contains("utf8") {
+                DiagnosticResult::pass(format!("UTF-8 locale ({})", locale))
+            } else {
+                DiagnosticResult::fail(format!(
+                    "locale {} is not UTF-8; borders and symbols may not render",
+                    locale
+                ))
+            }
+        }
+        None => DiagnosticResult::pass("no locale set; assuming UTF-8"),
+    }
+}
+
+/// The compiler and target this binary was built with
+pub fn check_build() -> DiagnosticResult {
+    DiagnosticResult::pass(format!(
+        "mcp-trace {} built with {} for {}",
+        env!("CARGO_PKG_VERSION"),
+        env!("MCP_TRACE_RUSTC_VERSION"),
+        env!("MCP_TRACE_TARGET")
+    ))
+}
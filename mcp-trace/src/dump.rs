@@ -0,0 +1,85 @@
+use anyhow::Result;
+use mcp_common::{IpcClient, IpcMessage, LogEntry, LogLevel, ProxyId};
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+pub struct DumpArgs {
+    pub ipc_socket: String,
+    pub ipc_token: Option<String>,
+    pub follow: bool,
+    pub level: Option<LogLevel>,
+    pub proxy: Option<String>,
+}
+
+/// Parse a log level name case-insensitively (used as a clap value parser)
+pub fn parse_level(value: &str) -> Result<LogLevel, String> {
+    LogLevel::from_str_case_insensitive(value).ok_or_else(|| {
+        format!(
+            "unknown level '{}' (expected Debug, Info, Warning, Error, Request or Response)",
+            value
+        )
+    })
+}
+
+/// Stream log entries from a running monitor to `out` as JSON lines
+pub async fn run_dump<W: Write>(args: DumpArgs, out: &mut W) -> Result<()> {
+    // Proxy names are only known once their ProxyStarted message arrives
+    let mut proxy_names: HashMap<ProxyId, String> = HashMap::new();
+    let token = args.ipc_token.as_deref();
+    let mut client = IpcClient::connect_with_token(&args.ipc_socket, token).await?;
+
+    loop {
+        client
+            .send(IpcMessage::Subscribe {
+                follow: args.follow,
+            })
+            .await?;
+
+        while let Some(envelope) = client.receive().await? {
+            match envelope.message {
+                IpcMessage::ProxyStarted(info) => {
+                    proxy_names.insert(info.id, info.name);
+                }
+                IpcMessage::LogEntry(entry) if matches_filters(&args, &proxy_names, &entry) => {
+                    serde_json::to_writer(&mut *out, &entry)?;
+                    out.write_all(b"\n")?;
+                    out.flush()?;
+                }
+                _ => {}
+            }
+        }
+
+        if !args.follow {
+            return Ok(());
+        }
+
+        // The monitor went away; wait for it to come back
+        client = loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            if let Ok(client) = IpcClient::connect_with_token(&args.ipc_socket, token).await {
+                break client;
+            }
+        };
+    }
+}
+
+fn matches_filters(
+    args: &DumpArgs,
+    proxy_names: &HashMap<ProxyId, String>,
+    entry: &LogEntry,
+) -> bool {
+    if let Some(level) = &args.level {
+        if &entry.level != level {
+            return false;
+        }
+    }
+
+    if let Some(name) = &args.proxy {
+        if proxy_names.get(&entry.proxy_id) != Some(name) {
+            return false;
+        }
+    }
+
+    true
+}
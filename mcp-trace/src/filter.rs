@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use mcp_common::{message_json, IpcEnvelope, IpcMessage, LogEntry, LogFilter};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+pub struct FilterArgs {
+    pub input: PathBuf, // `-` reads stdin
+    pub output: Option<PathBuf>,
+    pub filter: LogFilter,
+}
+
+/// Copy the log entries of an NDJSON recording (IPC envelopes or bare entries) that
+/// pass `filter` to `out` as JSON lines, returning how many were written
+pub fn filter_logs<R: BufRead, W: Write>(
+    reader: R,
+    mut filter: LogFilter,
+    out: &mut W,
+) -> Result<usize> {
+    let mut written = 0;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let entries = match serde_json::from_str::<IpcEnvelope>(&line) {
+            Ok(envelope) => match envelope.message {
+                IpcMessage::ProxyStarted(info) => {
+                    filter.note_proxy(&info);
+                    continue;
+                }
+                IpcMessage::LogEntry(entry) => vec![entry],
+                IpcMessage::LogsBatch(entries) => entries,
+                _ => continue,
+            },
+            Err(_) => vec![serde_json::from_str::<LogEntry>(&line).with_context(|| {
+                format!(
+                    "line {} is neither an IPC envelope nor a log entry",
+                    index + 1
+                )
+            })?],
+        };
+
+        for entry in entries {
+            let json = if filter.needs_json() {
+                message_json(&entry)
+            } else {
+                None
+            };
+            if filter.matches(&entry, json.as_ref()) {
+                serde_json::to_writer(&mut *out, &entry)?;
+                out.write_all(b"\n")?;
+                written += 1;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(written)
+}
+
+/// Filter `args.input` into `args.output`, or into `stdout` without one
+pub fn run_filter<W: Write>(args: FilterArgs, stdout: &mut W) -> Result<usize> {
+    let reader: Box<dyn BufRead> = if args.input.as_os_str() == "-" {
+        Box::new(BufReader::new(std::io::stdin()))
+    } else {
+        let file = File::open(&args.input)
+            .with_context(|| format!("failed to open {}", args.input.display()))?;
+        Box::new(BufReader::new(file))
+    };
+
+    match args.output {
+        Some(path) => {
+            let file = File::create(&path)
+                .with_context(|| format!("failed to create {}", path.display()))?;
+            filter_logs(reader, args.filter, &mut BufWriter::new(file))
+        }
+        None => filter_logs(reader, args.filter, stdout),
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use mcp_common::{message_json, IpcClient, IpcEnvelope, IpcMessage, LogEntry};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+pub struct InspectArgs {
+    pub id: Uuid,
+    pub source: InspectSource,
+    pub format: InspectFormat,
+}
+
+/// Where to look for the entry
+pub enum InspectSource {
+    /// A recorded session: NDJSON of IPC envelopes or log entries, like `filter` reads
+    File(PathBuf),
+    /// The recent history of a running monitor, as `dump` gets it
+    Live {
+        ipc_socket: String,
+        ipc_token: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum InspectFormat {
+    /// The entry as pretty-printed JSON
+    #[default]
+    Json,
+    /// Timestamp, level, proxy, message and notes, one per line
+    Text,
+}
+
+/// A log entry with the note exported alongside it, if any
+#[derive(Debug)]
+pub struct InspectedEntry {
+    pub entry: LogEntry,
+    pub annotation: Option<String>,
+}
+
+/// Find the entry with `args.id` and print it to `out`, returning whether it was found
+pub async fn run_inspect<W: Write>(args: InspectArgs, out: &mut W) -> Result<bool> {
+    let found = match args.source {
+        InspectSource::File(ref path) => {
+            let file =
+                File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+            find_in_recording(BufReader::new(file), args.id)?
+        }
+        InspectSource::Live {
+            ref ipc_socket,
+            ref ipc_token,
+        } => find_live(ipc_socket, ipc_token.as_deref(), args.id).await?,
+    };
+
+    let Some(inspected) = found else {
+        return Ok(false);
+    };
+    match args.format {
+        InspectFormat::Json => write_json(&inspected, out)?,
+        InspectFormat::Text => write_text(&inspected, out)?,
+    }
+    Ok(true)
+}
+
+/// The entry numbered `id` in an NDJSON recording. Bookmark exports keep notes in an
+/// `annotation` field next to the entry's own.
+pub fn find_in_recording<R: BufRead>(reader: R, id: Uuid) -> Result<Option<InspectedEntry>> {
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Ok(envelope) = serde_json::from_str::<IpcEnvelope>(&line) {
+            let entries = match envelope.message {
+                IpcMessage::LogEntry(entry) => vec![entry],
+                IpcMessage::LogsBatch(entries) => entries,
+                _ => continue,
+            };
+            if let Some(entry) = entries.into_iter().find(|entry| entry.id == id) {
+                return Ok(Some(InspectedEntry {
+                    entry,
+                    annotation: None,
+                }));
+            }
+            continue;
+        }
+
+        let value: serde_json::Value = serde_json::from_str(&line).with_context(|| {
+            format!(
+                "line {} is neither an IPC envelope nor a log entry",
+                index + 1
+            )
+        })?;
+        let annotation = value
+            .get("annotation")
+            .and_then(|note| note.as_str())
+            .map(str::to_string);
+        let entry: LogEntry = serde_json::from_value(value).with_context(|| {
+            format!(
+                "line {} is neither an IPC envelope nor a log entry",
+                index + 1
+            )
+        })?;
+        if entry.id == id {
+            return Ok(Some(InspectedEntry { entry, annotation }));
+        }
+    }
+    Ok(None)
+}
+
+/// Look through the entries a running monitor replays to a new subscriber
+async fn find_live(
+    ipc_socket: &str,
+    ipc_token: Option<&str>,
+    id: Uuid,
+) -> Result<Option<InspectedEntry>> {
+    let mut client = IpcClient::connect_with_token(ipc_socket, ipc_token).await?;
+    client.send(IpcMessage::Subscribe { follow: false }).await?;
+
+    while let Some(envelope) = client.receive().await? {
+        if let IpcMessage::LogEntry(entry) = envelope.message {
+            if entry.id == id {
+                return Ok(Some(InspectedEntry {
+                    entry,
+                    annotation: None,
+                }));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn write_json<W: Write>(inspected: &InspectedEntry, out: &mut W) -> Result<()> {
+    let mut value = serde_json::to_value(&inspected.entry)?;
+    if let (Some(note), Some(fields)) = (&inspected.annotation, value.as_object_mut()) {
+        fields.insert("annotation".to_string(), note.clone().into());
+    }
+    serde_json::to_writer_pretty(&mut *out, &value)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(inspected: &InspectedEntry, out: &mut W) -> Result<()> {
+    let entry = &inspected.entry;
+    writeln!(out, "Timestamp:  {}", entry.timestamp.to_rfc3339())?;
+    writeln!(out, "Level:      {:?}", entry.level)?;
+    writeln!(out, "Proxy:      {}", entry.proxy_id.0)?;
+    if let Some(duration) = entry.duration {
+        writeln!(out, "Duration:   {:?}", duration)?;
+    }
+    if let Some(ref note) = inspected.annotation {
+        writeln!(out, "Annotation: {}", note)?;
+    }
+
+    writeln!(out, "Message:")?;
+    match message_json(entry) {
+        Some(json) => writeln!(out, "{}", serde_json::to_string_pretty(&json)?)?,
+        None => writeln!(out, "{}", entry.message)?,
+    }
+    if let Some(ref metadata) = entry.metadata {
+        writeln!(out, "Metadata:")?;
+        writeln!(out, "{}", serde_json::to_string_pretty(metadata)?)?;
+    }
+    Ok(())
+}
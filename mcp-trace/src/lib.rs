@@ -0,0 +1,7 @@
+pub mod diff;
+pub mod doctor;
+pub mod dump;
+pub mod filter;
+pub mod inspect;
+pub mod status;
+pub mod validate;
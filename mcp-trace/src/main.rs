@@ -1,5 +1,19 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
+use mcp_common::{IpcFraming, LogFilter, LogLevel};
+use mcp_monitor::MonitorArgs;
+use mcp_proxy::{
+    parse_env_var, server_command, server_cwd, server_env, FramingMode, ProxyArgs, RestartPolicy,
+};
+use mcp_trace::diff::{DiffArgs, DiffKey};
+use mcp_trace::doctor::DoctorArgs;
+use mcp_trace::dump::DumpArgs;
+use mcp_trace::inspect::{InspectArgs, InspectFormat, InspectSource};
+use mcp_trace::status::StatusArgs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use uuid::Uuid;
 
 #[derive(Parser)]
 #[command(name = "mcp-trace")]
@@ -11,35 +25,102 @@ pub struct Cli {
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // Parsed once at startup
 pub enum Commands {
     /// Start the MCP monitor (default if no subcommand provided)
     Monitor {
-        /// IPC socket path for proxy communication
-        #[arg(short, long, default_value = "/tmp/mcp-monitor.sock")]
+        /// IPC socket path for proxy communication (or tcp://HOST:PORT)
+        #[arg(short, long, default_value = mcp_common::DEFAULT_IPC_SOCKET)]
         ipc_socket: String,
 
+        /// Shared token for a TCP IPC socket; proxies and clients must send the same one
+        #[arg(
+            long,
+            env = "MCP_TRACE_IPC_TOKEN",
+            value_name = "TOKEN",
+            hide_env_values = true
+        )]
+        ipc_token: Option<String>,
+
         /// Verbose logging
         #[arg(short, long)]
         verbose: bool,
+
+        /// Hide messages whose JSON-RPC method contains this text (repeatable)
+        #[arg(short = 'x', long)]
+        exclude: Vec<String>,
+
+        /// Serve Prometheus metrics on http://127.0.0.1:PORT/metrics
+        #[arg(long, value_name = "PORT")]
+        metrics_port: Option<u16>,
+
+        /// Log entries to keep before dropping the oldest (0 for no limit)
+        #[arg(long, value_name = "N", default_value_t = mcp_monitor::DEFAULT_MAX_LOGS)]
+        max_logs: usize,
+
+        /// Proxy connections to accept at once before rejecting new ones (0 for no limit)
+        #[arg(long, value_name = "N", default_value_t = mcp_monitor::DEFAULT_MAX_CONNECTIONS)]
+        max_connections: usize,
+
+        /// Milliseconds between UI redraws (default 250, or `tick_rate_ms` in the config file)
+        #[arg(long, value_name = "MS")]
+        tick_rate_ms: Option<u64>,
     },
     /// Start an MCP proxy server
     Proxy {
         /// MCP server command to proxy (as a single string, will be executed via shell)
-        #[arg(short, long)]
-        command: String,
+        #[arg(short, long, required_unless_present_any = ["command_args", "target"])]
+        command: Option<String>,
+
+        /// MCP server command and arguments after `--`, run as given without a shell
+        #[arg(last = true, value_name = "COMMAND", conflicts_with = "command")]
+        command_args: Vec<String>,
 
         /// Name for this proxy instance
         #[arg(short, long, default_value = "mcp-proxy")]
         name: String,
 
-        /// IPC socket path for monitor communication
-        #[arg(short, long, default_value = "/tmp/mcp-monitor.sock")]
+        /// IPC socket path for monitor communication (or tcp://HOST:PORT)
+        #[arg(short, long, default_value = mcp_common::DEFAULT_IPC_SOCKET)]
         ipc_socket: String,
 
+        /// Shared token for a TCP IPC socket; proxies and clients must send the same one
+        #[arg(
+            long,
+            env = "MCP_TRACE_IPC_TOKEN",
+            value_name = "TOKEN",
+            hide_env_values = true
+        )]
+        ipc_token: Option<String>,
+
+        /// Delimit messages to the monitor with lines or length-prefixed frames
+        #[arg(long, value_name = "FRAMING", default_value_t = IpcFraming::Lines)]
+        ipc_framing: IpcFraming,
+
         /// Verbose logging
         #[arg(short, long)]
         verbose: bool,
 
+        /// Write the proxy's own logs to this file instead of stderr
+        #[arg(long, value_name = "PATH")]
+        log_file: Option<PathBuf>,
+
+        /// Set an environment variable for the MCP server (repeatable)
+        #[arg(short, long, value_name = "KEY=VALUE", value_parser = parse_env_var)]
+        env: Vec<(String, String)>,
+
+        /// Read environment variables for the MCP server from a .env file (--env wins)
+        #[arg(long, value_name = "PATH")]
+        env_file: Option<PathBuf>,
+
+        /// Start the MCP server from an empty environment, plus --env variables
+        #[arg(long, visible_alias = "clear-env", default_value_t = false)]
+        env_clear: bool,
+
+        /// Working directory for the MCP server; a leading ~ is the home directory
+        #[arg(long, value_name = "DIR")]
+        cwd: Option<PathBuf>,
+
         /// Use shell to execute command (enabled by default)
         #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
         shell: bool,
@@ -47,6 +128,225 @@ pub enum Commands {
         /// Skip connecting to monitor (standalone mode)
         #[arg(long, default_value_t = false)]
         no_monitor: bool,
+
+        /// When to restart the MCP server after it exits, with exponential backoff:
+        /// never, on-failure or always, optionally with a maximum like always:3
+        #[arg(long, value_name = "POLICY[:MAX]", conflicts_with = "restart_on_crash")]
+        restart: Option<String>,
+
+        /// Same as --restart on-failure
+        #[arg(long, default_value_t = false)]
+        restart_on_crash: bool,
+
+        /// Maximum number of restarts when --restart gives none
+        #[arg(long, default_value_t = 5)]
+        max_restarts: u32,
+
+        /// Delay before the first restart in milliseconds (doubles on each attempt)
+        #[arg(long, default_value_t = 500)]
+        restart_delay_ms: u64,
+
+        /// Message framing used by the MCP server and its client
+        #[arg(long, value_enum, default_value_t = FramingMode::Newline)]
+        framing: FramingMode,
+
+        /// Accept MCP clients over HTTP on this address, e.g. 127.0.0.1:9000 (with --target)
+        #[arg(long, value_name = "ADDR", requires = "target")]
+        listen: Option<SocketAddr>,
+
+        /// URL of an MCP server using the streamable HTTP transport to forward --listen
+        /// clients to, e.g. http://localhost:3000, or a ws:// URL to relay WebSocket clients
+        #[arg(long, value_name = "URL", requires = "listen", conflicts_with_all = ["command", "command_args"])]
+        target: Option<String>,
+
+        /// Export a span per request/response pair to this OTLP/HTTP collector, e.g.
+        /// http://localhost:4318 (needs the opentelemetry feature)
+        #[arg(long, value_name = "URL")]
+        otlp_endpoint: Option<String>,
+
+        /// Seconds without a response before a request's span ends as an error
+        #[arg(long, value_name = "SECS", default_value_t = 30)]
+        otlp_span_timeout: u64,
+
+        /// Milliseconds the MCP server gets to exit after SIGTERM before it is killed
+        #[arg(long, value_name = "MS", default_value_t = mcp_proxy::DEFAULT_SHUTDOWN_TIMEOUT_MS)]
+        shutdown_timeout_ms: u64,
+
+        /// Log a warning for each request the MCP server leaves unanswered this many
+        /// seconds, repeated every time that much longer passes
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        request_timeout: Option<u64>,
+
+        /// Milliseconds between the stats updates a stdio proxy sends the monitor
+        #[arg(
+            long,
+            value_name = "MS",
+            default_value_t = mcp_proxy::DEFAULT_STATS_INTERVAL_MS,
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        stats_interval_ms: u64,
+    },
+    /// Stream log entries from a running monitor to stdout as JSON lines
+    Dump {
+        /// IPC socket path of the running monitor (or tcp://HOST:PORT)
+        #[arg(short, long, default_value = mcp_common::DEFAULT_IPC_SOCKET)]
+        ipc_socket: String,
+
+        /// Shared token for a TCP IPC socket; proxies and clients must send the same one
+        #[arg(
+            long,
+            env = "MCP_TRACE_IPC_TOKEN",
+            value_name = "TOKEN",
+            hide_env_values = true
+        )]
+        ipc_token: Option<String>,
+
+        /// Keep streaming new entries instead of exiting after the backlog
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Only print entries with this level (e.g. Error, Request)
+        #[arg(short, long, value_parser = mcp_trace::dump::parse_level)]
+        level: Option<LogLevel>,
+
+        /// Only print entries from the proxy with this name
+        #[arg(short, long)]
+        proxy: Option<String>,
+    },
+    /// Print the latest stats of every proxy connected to a running monitor
+    Status {
+        /// IPC socket path of the running monitor (or tcp://HOST:PORT)
+        #[arg(short, long, default_value = mcp_common::DEFAULT_IPC_SOCKET)]
+        ipc_socket: String,
+
+        /// Shared token for a TCP IPC socket; proxies and clients must send the same one
+        #[arg(
+            long,
+            env = "MCP_TRACE_IPC_TOKEN",
+            value_name = "TOKEN",
+            hide_env_values = true
+        )]
+        ipc_token: Option<String>,
+
+        /// Print JSON (the default)
+        #[arg(long, conflicts_with = "table")]
+        json: bool,
+
+        /// Print a human-readable table instead of JSON
+        #[arg(long)]
+        table: bool,
+    },
+    /// Check that this machine can run the monitor and proxies, and whether a monitor
+    /// is running
+    Doctor {
+        /// IPC socket path to check (or tcp://HOST:PORT)
+        #[arg(short, long, default_value = mcp_common::DEFAULT_IPC_SOCKET)]
+        ipc_socket: String,
+    },
+    /// Check a recorded session (NDJSON of IPC envelopes) for JSON-RPC 2.0 compliance
+    Validate {
+        /// Session recording to check
+        file: PathBuf,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the log entries of a recorded session (NDJSON of IPC envelopes or log
+    /// entries) that match every given filter, as JSON lines
+    Filter {
+        /// Session recording to read, or - for stdin
+        #[arg(short, long)]
+        input: PathBuf,
+
+        /// Write matching entries here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only entries with this level (e.g. Error, Request)
+        #[arg(short, long, value_parser = mcp_trace::dump::parse_level)]
+        level: Option<LogLevel>,
+
+        /// Only entries from the proxy with this name
+        #[arg(long)]
+        proxy_name: Option<String>,
+
+        /// Only requests and notifications with this JSON-RPC method
+        #[arg(short, long)]
+        method: Option<String>,
+
+        /// Only entries at or after this RFC 3339 timestamp
+        #[arg(long)]
+        from: Option<DateTime<Utc>>,
+
+        /// Only entries at or before this RFC 3339 timestamp
+        #[arg(long)]
+        to: Option<DateTime<Utc>>,
+
+        /// Only entries whose message contains this text (case-insensitive)
+        #[arg(short, long)]
+        search: Option<String>,
+    },
+    /// Print one log entry, found by its ID in a recorded session or a running monitor
+    Inspect {
+        /// ID of the log entry
+        id: Uuid,
+
+        /// Session recording to search (NDJSON of IPC envelopes or log entries)
+        #[arg(short, long, conflicts_with = "live", required_unless_present = "live")]
+        file: Option<PathBuf>,
+
+        /// Search the history of a running monitor instead
+        #[arg(long)]
+        live: bool,
+
+        /// IPC socket path of the running monitor (or tcp://HOST:PORT)
+        #[arg(short, long, default_value = mcp_common::DEFAULT_IPC_SOCKET)]
+        ipc_socket: String,
+
+        /// Shared token for a TCP IPC socket; proxies and clients must send the same one
+        #[arg(
+            long,
+            env = "MCP_TRACE_IPC_TOKEN",
+            value_name = "TOKEN",
+            hide_env_values = true
+        )]
+        ipc_token: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = InspectFormat::Json)]
+        format: InspectFormat,
+    },
+    /// Compare two recorded sessions (NDJSON of IPC envelopes or log entries): entries
+    /// only in either, and matched entries whose messages differ. Exits with 1 when they
+    /// differ.
+    Diff {
+        /// Session recording to compare from, e.g. before a server upgrade
+        a: PathBuf,
+
+        /// Session recording to compare to
+        b: PathBuf,
+
+        /// How entries of one session are matched with the other's
+        #[arg(long, value_enum, default_value_t = DiffKey::Method)]
+        by: DiffKey,
+
+        /// Print the differences as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Play a recorded session (NDJSON of IPC envelopes) back in the monitor UI
+    Replay {
+        /// Session recording to play
+        file: PathBuf,
+
+        /// Speed factor, 2.0 plays twice as fast as recorded (0.1 to 100)
+        #[arg(short, long, default_value_t = 1.0)]
+        speed: f64,
+
+        /// Log entries to keep before dropping the oldest (0 for no limit)
+        #[arg(long, value_name = "N", default_value_t = mcp_monitor::DEFAULT_MAX_LOGS)]
+        max_logs: usize,
     },
 }
 
@@ -57,54 +357,282 @@ async fn main() -> Result<()> {
     match cli.command {
         Some(Commands::Monitor {
             ipc_socket,
+            ipc_token,
             verbose,
-        }) => run_monitor(ipc_socket, verbose).await,
+            exclude,
+            metrics_port,
+            max_logs,
+            max_connections,
+            tick_rate_ms,
+        }) => {
+            run_monitor(MonitorArgs {
+                ipc_socket,
+                ipc_token,
+                verbose,
+                exclude,
+                metrics_port,
+                max_logs,
+                max_connections,
+                tick_rate_ms,
+            })
+            .await
+        }
         Some(Commands::Proxy {
             command,
+            command_args,
             name,
             ipc_socket,
+            ipc_token,
+            ipc_framing,
             verbose,
+            log_file,
+            env,
+            env_file,
+            env_clear,
+            cwd,
             shell,
             no_monitor,
-        }) => run_proxy(command, name, ipc_socket, verbose, shell, no_monitor).await,
+            restart,
+            restart_on_crash,
+            max_restarts,
+            restart_delay_ms,
+            framing,
+            listen,
+            target,
+            otlp_endpoint,
+            otlp_span_timeout,
+            shutdown_timeout_ms,
+            request_timeout,
+            stats_interval_ms,
+        }) => {
+            let restart_spec = match restart {
+                Some(ref spec) => spec.as_str(),
+                None if restart_on_crash => "on-failure",
+                None => "never",
+            };
+            let restart_policy =
+                RestartPolicy::from_spec(restart_spec, max_restarts, restart_delay_ms)
+                    .map_err(anyhow::Error::msg)?;
+            run_proxy(ProxyArgs {
+                command: server_command(command, command_args, shell)?,
+                name,
+                ipc_socket,
+                ipc_token,
+                ipc_framing,
+                verbose,
+                log_file,
+                env_vars: server_env(env_file.as_deref(), env)?,
+                env_clear,
+                cwd: server_cwd(cwd)?,
+                no_monitor,
+                restart_policy,
+                framing,
+                listen,
+                target,
+                otlp_endpoint,
+                otlp_span_timeout: std::time::Duration::from_secs(otlp_span_timeout),
+                shutdown_timeout_ms,
+                request_timeout: request_timeout.map(std::time::Duration::from_secs),
+                stats_interval_ms,
+            })
+            .await
+        }
+        Some(Commands::Dump {
+            ipc_socket,
+            ipc_token,
+            follow,
+            level,
+            proxy,
+        }) => {
+            run_dump(DumpArgs {
+                ipc_socket,
+                ipc_token,
+                follow,
+                level,
+                proxy,
+            })
+            .await
+        }
+        Some(Commands::Status {
+            ipc_socket,
+            ipc_token,
+            table,
+            ..
+        }) => {
+            run_status(StatusArgs {
+                ipc_socket,
+                ipc_token,
+                table,
+            })
+            .await
+        }
+        Some(Commands::Doctor { ipc_socket }) => run_doctor(ipc_socket).await,
+        Some(Commands::Validate { file, json }) => run_validate(file, json),
+        Some(Commands::Filter {
+            input,
+            output,
+            level,
+            proxy_name,
+            method,
+            from,
+            to,
+            search,
+        }) => run_filter(
+            input,
+            output,
+            LogFilter {
+                level,
+                proxy_name,
+                method,
+                from,
+                to,
+                search,
+                ..Default::default()
+            },
+        ),
+        Some(Commands::Inspect {
+            id,
+            file,
+            ipc_socket,
+            ipc_token,
+            format,
+            ..
+        }) => {
+            let source = match file {
+                Some(file) => InspectSource::File(file),
+                None => InspectSource::Live {
+                    ipc_socket,
+                    ipc_token,
+                },
+            };
+            run_inspect(InspectArgs { id, source, format }).await
+        }
+        Some(Commands::Diff { a, b, by, json }) => run_diff(a, b, by, json),
+        Some(Commands::Replay {
+            file,
+            speed,
+            max_logs,
+        }) => run_replay(file, speed, max_logs).await,
         None => {
             // Default to monitor
-            run_monitor("/tmp/mcp-monitor.sock".to_string(), false).await
+            run_monitor(MonitorArgs {
+                ipc_socket: mcp_common::DEFAULT_IPC_SOCKET.to_string(),
+                ipc_token: std::env::var("MCP_TRACE_IPC_TOKEN").ok(),
+                verbose: false,
+                exclude: Vec::new(),
+                metrics_port: None,
+                max_logs: mcp_monitor::DEFAULT_MAX_LOGS,
+                max_connections: mcp_monitor::DEFAULT_MAX_CONNECTIONS,
+                tick_rate_ms: None,
+            })
+            .await
         }
     }
 }
 
-async fn run_monitor(ipc_socket: String, verbose: bool) -> Result<()> {
+async fn run_monitor(args: MonitorArgs) -> Result<()> {
     // Import the monitor functionality
-    use mcp_monitor::{run_monitor_app, MonitorArgs};
+    use mcp_monitor::run_monitor_app;
 
-    let args = MonitorArgs {
-        ipc_socket,
-        verbose,
+    run_monitor_app(args).await
+}
+
+fn run_filter(input: PathBuf, output: Option<PathBuf>, filter: LogFilter) -> Result<()> {
+    use mcp_trace::filter::{run_filter, FilterArgs};
+
+    let args = FilterArgs {
+        input,
+        output,
+        filter,
     };
+    run_filter(args, &mut std::io::stdout().lock())?;
+    Ok(())
+}
 
-    run_monitor_app(args).await
+async fn run_replay(file: PathBuf, speed: f64, max_logs: usize) -> Result<()> {
+    use mcp_monitor::{run_replay_app, ReplayArgs};
+
+    let args = ReplayArgs {
+        file,
+        speed,
+        max_logs,
+    };
+
+    run_replay_app(args).await
 }
 
-async fn run_proxy(
-    command: String,
-    name: String,
-    ipc_socket: String,
-    verbose: bool,
-    shell: bool,
-    no_monitor: bool,
-) -> Result<()> {
+async fn run_proxy(args: ProxyArgs) -> Result<()> {
     // Import the proxy functionality
-    use mcp_proxy::{run_proxy_app, ProxyArgs};
+    use mcp_proxy::run_proxy_app;
+
+    run_proxy_app(args).await
+}
+
+async fn run_dump(args: DumpArgs) -> Result<()> {
+    use mcp_trace::dump::run_dump;
+
+    run_dump(args, &mut std::io::stdout().lock()).await
+}
+
+async fn run_status(args: StatusArgs) -> Result<()> {
+    use mcp_trace::status::run_status;
+
+    run_status(args, &mut std::io::stdout().lock()).await?;
+    Ok(())
+}
 
-    let args = ProxyArgs {
-        command,
-        name,
+async fn run_doctor(ipc_socket: String) -> Result<()> {
+    use mcp_trace::doctor::run_doctor;
+    use std::io::IsTerminal;
+
+    let args = DoctorArgs {
         ipc_socket,
-        verbose,
-        shell,
-        no_monitor,
+        color: std::io::stdout().is_terminal(),
+    };
+    if !run_doctor(args, &mut std::io::stdout().lock()).await? {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+async fn run_inspect(args: InspectArgs) -> Result<()> {
+    use mcp_trace::inspect::run_inspect;
+
+    let id = args.id;
+    if !run_inspect(args, &mut std::io::stdout().lock()).await? {
+        eprintln!("No log entry with ID {}", id);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_diff(a: PathBuf, b: PathBuf, key: DiffKey, json: bool) -> Result<()> {
+    use mcp_trace::diff::run_diff;
+    use std::io::IsTerminal;
+
+    let args = DiffArgs {
+        a,
+        b,
+        key,
+        json,
+        color: !json && std::io::stdout().is_terminal(),
     };
+    let diff = run_diff(args, &mut std::io::stdout().lock())?;
 
-    run_proxy_app(args).await
+    if !diff.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_validate(file: PathBuf, json: bool) -> Result<()> {
+    use mcp_trace::validate::{run_validate, ValidateArgs};
+
+    let args = ValidateArgs { file, json };
+    let report = run_validate(args, &mut std::io::stdout().lock())?;
+
+    if !report.violations.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
 }
@@ -0,0 +1,147 @@
+use anyhow::{anyhow, bail, Result};
+use mcp_common::{IpcClient, IpcMessage, ProxyId, ProxyInfo, ProxyStats, ProxyStatus};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+/// How long to wait for the monitor to answer
+pub const STATUS_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct StatusArgs {
+    pub ipc_socket: String,
+    pub ipc_token: Option<String>,
+    pub table: bool,
+}
+
+/// One proxy in the status output
+#[derive(Debug, Serialize)]
+pub struct ProxyStatusRow {
+    pub proxy_id: ProxyId,
+    pub name: String,
+    pub status: ProxyStatus,
+    pub total_requests: u64,
+    pub failed_requests: u64,
+    pub bytes_transferred: u64,
+    pub uptime_secs: u64,
+}
+
+/// Ask the monitor for every proxy's stats and print them to `out`
+pub async fn run_status<W: Write>(args: StatusArgs, out: &mut W) -> Result<Vec<ProxyStatusRow>> {
+    let mut client =
+        IpcClient::connect_with_token(&args.ipc_socket, args.ipc_token.as_deref()).await?;
+    client.send(IpcMessage::GetAllStats).await?;
+
+    let (proxies, stats) = tokio::time::timeout(STATUS_TIMEOUT, receive_stats(&mut client))
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "no response from the monitor within {}s",
+                STATUS_TIMEOUT.as_secs()
+            )
+        })??;
+
+    let rows = status_rows(&proxies, stats);
+    if args.table {
+        write_table(&rows, out)?;
+    } else {
+        serde_json::to_writer_pretty(&mut *out, &rows)?;
+        writeln!(out)?;
+    }
+    Ok(rows)
+}
+
+/// Collect proxy announcements until the stats answer arrives
+async fn receive_stats(
+    client: &mut IpcClient,
+) -> Result<(HashMap<ProxyId, ProxyInfo>, Vec<ProxyStats>)> {
+    let mut proxies = HashMap::new();
+    while let Some(envelope) = client.receive().await? {
+        match envelope.message {
+            IpcMessage::ProxyStarted(info) => {
+                proxies.insert(info.id.clone(), info);
+            }
+            IpcMessage::AllStatsResponse(stats) => return Ok((proxies, stats)),
+            IpcMessage::Error { message, .. } => bail!("monitor error: {}", message),
+            _ => {}
+        }
+    }
+    bail!("the monitor closed the connection without answering")
+}
+
+/// Rows sorted by proxy name; stats of proxies the monitor didn't announce are listed
+/// under their id
+fn status_rows(
+    proxies: &HashMap<ProxyId, ProxyInfo>,
+    stats: Vec<ProxyStats>,
+) -> Vec<ProxyStatusRow> {
+    let mut rows: Vec<ProxyStatusRow> = stats
+        .into_iter()
+        .map(|stats| {
+            let info = proxies.get(&stats.proxy_id);
+            ProxyStatusRow {
+                name: info.map_or_else(|| stats.proxy_id.0.to_string(), |info| info.name.clone()),
+                status: info.map_or(ProxyStatus::Running, |info| info.status.clone()),
+                proxy_id: stats.proxy_id,
+                total_requests: stats.total_requests,
+                failed_requests: stats.failed_requests,
+                bytes_transferred: stats.bytes_transferred,
+                uptime_secs: stats.uptime.as_secs(),
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+    rows
+}
+
+pub fn write_table<W: Write>(rows: &[ProxyStatusRow], out: &mut W) -> Result<()> {
+    let name_width = rows
+        .iter()
+        .map(|row| row.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("PROXY".len());
+
+    writeln!(
+        out,
+        "{:<name_width$}  {:<10}  {:>10}  {:>10}  {:>12}  {:>10}",
+        "PROXY", "STATUS", "REQUESTS", "FAILED", "BYTES", "UPTIME"
+    )?;
+    for row in rows {
+        writeln!(
+            out,
+            "{:<name_width$}  {:<10}  {:>10}  {:>10}  {:>12}  {:>10}",
+            row.name,
+            status_label(&row.status),
+            row.total_requests,
+            row.failed_requests,
+            row.bytes_transferred,
+            format_uptime(row.uptime_secs)
+        )?;
+    }
+    if rows.is_empty() {
+        writeln!(out, "no proxies connected")?;
+    }
+    Ok(())
+}
+
+fn status_label(status: &ProxyStatus) -> String {
+    match status {
+        ProxyStatus::Starting => "starting".to_string(),
+        ProxyStatus::Running => "running".to_string(),
+        ProxyStatus::Stopped => "stopped".to_string(),
+        ProxyStatus::Error(message) => format!("error: {}", message),
+    }
+}
+
+/// "45s", "3m05s" or "2h03m05s"
+fn format_uptime(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}h{:02}m{:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m{:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use mcp_common::{IpcEnvelope, IpcMessage, LogLevel, SessionValidator, ViolationKind};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+pub struct ValidateArgs {
+    pub file: PathBuf,
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Violation {
+    pub entry_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub kind: ViolationKind,
+    pub detail: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationReport {
+    pub entries_checked: usize,
+    pub violations: Vec<Violation>,
+}
+
+/// Check every request/response entry of an NDJSON `IpcEnvelope` recording
+pub fn validate_session<R: BufRead>(reader: R) -> Result<ValidationReport> {
+    let mut validator = SessionValidator::new();
+    let mut report = ValidationReport::default();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let envelope: IpcEnvelope = serde_json::from_str(&line)
+            .with_context(|| format!("line {} is not an IPC envelope", index + 1))?;
+        let entries = match envelope.message {
+            IpcMessage::LogEntry(entry) => vec![entry],
+            IpcMessage::LogsBatch(entries) => entries,
+            _ => continue,
+        };
+
+        for entry in entries {
+            if !matches!(entry.level, LogLevel::Request | LogLevel::Response) {
+                continue;
+            }
+
+            report.entries_checked += 1;
+            for error in validator.check_entry(&entry) {
+                report.violations.push(Violation {
+                    entry_id: entry.id,
+                    timestamp: entry.timestamp,
+                    kind: error.kind,
+                    detail: error.detail,
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+pub fn write_report<W: Write>(report: &ValidationReport, json: bool, out: &mut W) -> Result<()> {
+    if json {
+        serde_json::to_writer_pretty(&mut *out, report)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    for violation in &report.violations {
+        writeln!(
+            out,
+            "{} {} {}: {}",
+            violation.timestamp.format("%H:%M:%S%.3f"),
+            violation.entry_id,
+            violation.kind,
+            violation.detail
+        )?;
+    }
+    writeln!(
+        out,
+        "{} violation(s) in {} message(s)",
+        report.violations.len(),
+        report.entries_checked
+    )?;
+    Ok(())
+}
+
+/// Validate the recording at `args.file` and print the report to `out`
+pub fn run_validate<W: Write>(args: ValidateArgs, out: &mut W) -> Result<ValidationReport> {
+    let file = File::open(&args.file)
+        .with_context(|| format!("failed to open {}", args.file.display()))?;
+    let report = validate_session(BufReader::new(file))?;
+    write_report(&report, args.json, out)?;
+    Ok(report)
+}
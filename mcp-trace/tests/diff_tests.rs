@@ -0,0 +1,152 @@
+use mcp_common::*;
+use mcp_trace::diff::{diff_sessions, run_diff, DiffArgs, DiffKey};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn envelope(message: IpcMessage) -> String {
+    serde_json::to_string(&IpcEnvelope {
+        message,
+        timestamp: chrono::Utc::now(),
+        correlation_id: None,
+        sequence: 0,
+        session_id: None,
+    })
+    .unwrap()
+}
+
+fn entry(level: LogLevel, message: &str, proxy_id: &ProxyId) -> LogEntry {
+    LogEntry::new(level, message.to_string(), proxy_id.clone())
+}
+
+fn recording(entries: &[LogEntry]) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    for entry in entries {
+        writeln!(file, "{}", envelope(IpcMessage::LogEntry(entry.clone()))).unwrap();
+    }
+    file
+}
+
+/// A session listing tools, answered with `tools`
+fn session(proxy_id: &ProxyId, tools: &str) -> Vec<LogEntry> {
+    vec![
+        entry(
+            LogLevel::Request,
+            r#"→ {"jsonrpc":"2.0","id":1,"method":"initialize"}"#,
+            proxy_id,
+        ),
+        entry(
+            LogLevel::Response,
+            r#"← {"jsonrpc":"2.0","id":1,"result":{}}"#,
+            proxy_id,
+        ),
+        entry(
+            LogLevel::Request,
+            r#"→ {"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            proxy_id,
+        ),
+        entry(
+            LogLevel::Response,
+            &format!(
+                r#"← {{"jsonrpc":"2.0","id":2,"result":{{"tools":{}}}}}"#,
+                tools
+            ),
+            proxy_id,
+        ),
+    ]
+}
+
+#[test]
+fn test_diff_finds_entry_only_in_b() {
+    let a: Vec<LogEntry> = session(&ProxyId::new(), "[]");
+    let mut b = a.clone();
+    let extra = entry(LogLevel::Error, "server crashed", &b[0].proxy_id);
+    b.insert(2, extra.clone());
+
+    let a_file = recording(&a);
+    let b_file = recording(&b);
+    let mut out = Vec::new();
+    let diff = run_diff(
+        DiffArgs {
+            a: a_file.path().to_path_buf(),
+            b: b_file.path().to_path_buf(),
+            key: DiffKey::Id,
+            json: false,
+            color: false,
+        },
+        &mut out,
+    )
+    .unwrap();
+
+    assert!(diff.only_in_a.is_empty());
+    assert!(diff.changed.is_empty());
+    assert_eq!(diff.only_in_b.len(), 1);
+    assert_eq!(diff.only_in_b[0].id, extra.id);
+
+    let report = String::from_utf8(out).unwrap();
+    assert!(report.contains("Changed: 0"));
+    assert!(report.contains("+ "));
+    assert!(report.contains("server crashed"));
+}
+
+#[test]
+fn test_diff_by_method_matches_responses_to_their_requests() {
+    // Recorded separately, so nothing but the methods lines the sessions up
+    let before = session(&ProxyId::new(), "[]");
+    let after = session(&ProxyId::new(), r#"[{"name":"search"}]"#);
+
+    let diff = diff_sessions(&before, &after, DiffKey::Method);
+    assert!(diff.only_in_a.is_empty());
+    assert!(diff.only_in_b.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    let (old, new) = &diff.changed[0];
+    assert_eq!(old.id, before[3].id);
+    assert_eq!(new.id, after[3].id);
+
+    // By ID, no entry of one session is in the other
+    let diff = diff_sessions(&before, &after, DiffKey::Id);
+    assert_eq!(diff.only_in_a.len(), 4);
+    assert_eq!(diff.only_in_b.len(), 4);
+}
+
+#[test]
+fn test_diff_prints_changed_messages_as_json_diff() {
+    let proxy_id = ProxyId::new();
+    let a_file = recording(&session(&proxy_id, "[]"));
+    let b_file = recording(&session(&proxy_id, r#"[{"name":"search"}]"#));
+
+    let mut out = Vec::new();
+    run_diff(
+        DiffArgs {
+            a: a_file.path().to_path_buf(),
+            b: b_file.path().to_path_buf(),
+            key: DiffKey::Method,
+            json: false,
+            color: false,
+        },
+        &mut out,
+    )
+    .unwrap();
+    let report = String::from_utf8(out).unwrap();
+    assert!(report.contains("Changed: 1"));
+    assert!(report.contains(r#"  -    "tools": []"#));
+    assert!(report.contains(r#"  +        "name": "search""#));
+
+    let mut out = Vec::new();
+    let diff = run_diff(
+        DiffArgs {
+            a: a_file.path().to_path_buf(),
+            b: b_file.path().to_path_buf(),
+            key: DiffKey::Method,
+            json: true,
+            color: false,
+        },
+        &mut out,
+    )
+    .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["changed"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        json["changed"][0][1]["id"],
+        diff.changed[0].1.id.to_string()
+    );
+}
@@ -0,0 +1,72 @@
+use mcp_common::IpcServer;
+use std::process::{Command, Output};
+use tempfile::tempdir;
+
+/// Run `mcp-trace doctor` in a UTF-8, color terminal environment plus `env`
+fn doctor(ipc_socket: &str, env: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_mcp-trace"));
+    command
+        .args(["doctor", "--ipc-socket", ipc_socket])
+        .env("TERM", "xterm-256color")
+        .env("LANG", "C.UTF-8")
+        .env_remove("LC_ALL")
+        .env_remove("LC_CTYPE")
+        .env_remove("NO_COLOR");
+    for (name, value) in env {
+        command.env(name, value);
+    }
+    command.output().unwrap()
+}
+
+fn checklist(output: &Output) -> Vec<String> {
+    String::from_utf8(output.stdout.clone())
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_doctor_passes() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir.path().join("doctor.sock");
+    let output = doctor(socket_path.to_str().unwrap(), &[]);
+
+    assert_eq!(output.status.code(), Some(0));
+    let lines = checklist(&output);
+    assert_eq!(lines.len(), 6);
+    assert!(lines.iter().all(|line| line.starts_with("✓ ")));
+    assert!(lines.iter().any(|line| line.contains("no monitor running")));
+    // Not a terminal, so no color codes
+    assert!(lines.iter().all(|line| !line.contains('\x1b')));
+}
+
+#[test]
+fn test_doctor_fails_on_a_non_utf8_locale() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir.path().join("doctor.sock");
+    let output = doctor(socket_path.to_str().unwrap(), &[("LC_ALL", "C")]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let lines = checklist(&output);
+    assert!(lines
+        .iter()
+        .all(|line| line.contains('✓') || line.contains('✗')));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("✗ Unicode: locale C")));
+}
+
+#[tokio::test]
+async fn test_doctor_finds_a_running_monitor() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir.path().join("doctor.sock");
+    let socket_path = socket_path.to_str().unwrap();
+    let _server = IpcServer::bind(socket_path).await.unwrap();
+
+    let output = doctor(socket_path, &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(checklist(&output)
+        .iter()
+        .any(|line| line.starts_with("✓ Monitor: a monitor is running")));
+}
@@ -0,0 +1,156 @@
+use mcp_common::*;
+use mcp_trace::dump::{parse_level, run_dump, DumpArgs};
+use tempfile::tempdir;
+
+fn proxy_info(id: &ProxyId, name: &str) -> ProxyInfo {
+    ProxyInfo {
+        id: id.clone(),
+        name: name.to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    }
+}
+
+/// Run a mock monitor that answers one subscription with `messages`, then closes
+async fn dump_with(
+    messages: Vec<IpcMessage>,
+    level: Option<LogLevel>,
+    proxy: Option<&str>,
+) -> Vec<LogEntry> {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept().await.unwrap();
+        let envelope = connection.receive_message().await.unwrap().unwrap();
+        assert!(matches!(
+            envelope.message,
+            IpcMessage::Subscribe { follow: false }
+        ));
+
+        for message in messages {
+            connection.send_message(message).await.unwrap();
+        }
+        // Dropping the connection ends the stream
+    });
+
+    let args = DumpArgs {
+        ipc_socket: socket_path,
+        ipc_token: None,
+        follow: false,
+        level,
+        proxy: proxy.map(str::to_string),
+    };
+
+    let mut out = Vec::new();
+    run_dump(args, &mut out).await.unwrap();
+    server_task.await.unwrap();
+
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_dump_filters_by_level() {
+    let proxy_id = ProxyId::new();
+    let messages = vec![
+        IpcMessage::ProxyStarted(proxy_info(&proxy_id, "alpha")),
+        IpcMessage::LogEntry(LogEntry::new(
+            LogLevel::Info,
+            "starting".to_string(),
+            proxy_id.clone(),
+        )),
+        IpcMessage::LogEntry(LogEntry::new(
+            LogLevel::Error,
+            "boom".to_string(),
+            proxy_id.clone(),
+        )),
+        IpcMessage::StatsUpdate(ProxyStats::default()),
+    ];
+
+    let entries = dump_with(messages, Some(LogLevel::Error), None).await;
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].message, "boom");
+    assert_eq!(entries[0].level, LogLevel::Error);
+}
+
+#[tokio::test]
+async fn test_dump_filters_by_proxy_name() {
+    let alpha = ProxyId::new();
+    let beta = ProxyId::new();
+    let messages = vec![
+        IpcMessage::ProxyStarted(proxy_info(&alpha, "alpha")),
+        IpcMessage::ProxyStarted(proxy_info(&beta, "beta")),
+        IpcMessage::LogEntry(LogEntry::new(
+            LogLevel::Request,
+            "from alpha".to_string(),
+            alpha.clone(),
+        )),
+        IpcMessage::LogEntry(LogEntry::new(
+            LogLevel::Request,
+            "from beta".to_string(),
+            beta.clone(),
+        )),
+        // Entries from proxies we never saw start are skipped too
+        IpcMessage::LogEntry(LogEntry::new(
+            LogLevel::Request,
+            "from unknown".to_string(),
+            ProxyId::new(),
+        )),
+    ];
+
+    let entries = dump_with(messages, None, Some("beta")).await;
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].message, "from beta");
+    assert_eq!(entries[0].proxy_id, beta);
+}
+
+#[tokio::test]
+async fn test_dump_exits_cleanly_when_stream_ends() {
+    let proxy_id = ProxyId::new();
+    let messages = vec![
+        IpcMessage::LogEntry(LogEntry::new(
+            LogLevel::Info,
+            "one".to_string(),
+            proxy_id.clone(),
+        )),
+        IpcMessage::LogEntry(LogEntry::new(
+            LogLevel::Response,
+            "two".to_string(),
+            proxy_id.clone(),
+        )),
+    ];
+
+    let entries = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        dump_with(messages, None, None),
+    )
+    .await
+    .expect("dump should exit once the monitor closes the stream");
+
+    let texts: Vec<_> = entries.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(texts, vec!["one", "two"]);
+}
+
+#[test]
+fn test_parse_level_is_case_insensitive() {
+    assert_eq!(parse_level("error"), Ok(LogLevel::Error));
+    assert_eq!(parse_level("Request"), Ok(LogLevel::Request));
+    assert_eq!(parse_level("WARN"), Ok(LogLevel::Warning));
+    assert!(parse_level("loud").is_err());
+}
@@ -0,0 +1,124 @@
+use mcp_common::*;
+use mcp_trace::filter::{filter_logs, run_filter, FilterArgs};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn envelope(message: IpcMessage) -> String {
+    serde_json::to_string(&IpcEnvelope {
+        message,
+        timestamp: chrono::Utc::now(),
+        correlation_id: None,
+        sequence: 0,
+        session_id: None,
+    })
+    .unwrap()
+}
+
+fn written_entries(output: &[u8]) -> Vec<LogEntry> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_filter_keeps_only_errors() {
+    let proxy_id = ProxyId::new();
+    let levels = [
+        LogLevel::Info,
+        LogLevel::Error,
+        LogLevel::Request,
+        LogLevel::Response,
+        LogLevel::Error,
+        LogLevel::Warning,
+        LogLevel::Debug,
+        LogLevel::Request,
+        LogLevel::Response,
+        LogLevel::Error,
+    ];
+    let entries: Vec<LogEntry> = levels
+        .iter()
+        .enumerate()
+        .map(|(index, level)| {
+            LogEntry::new(level.clone(), format!("entry {}", index), proxy_id.clone())
+        })
+        .collect();
+
+    let mut input = NamedTempFile::new().unwrap();
+    for entry in &entries {
+        writeln!(input, "{}", envelope(IpcMessage::LogEntry(entry.clone()))).unwrap();
+    }
+    let output = NamedTempFile::new().unwrap();
+
+    let args = FilterArgs {
+        input: input.path().to_path_buf(),
+        output: Some(output.path().to_path_buf()),
+        filter: LogFilter {
+            level: Some(LogLevel::Error),
+            ..Default::default()
+        },
+    };
+    let mut stdout = Vec::new();
+    assert_eq!(run_filter(args, &mut stdout).unwrap(), 3);
+    assert!(stdout.is_empty());
+
+    let written = written_entries(&std::fs::read(output.path()).unwrap());
+    let ids: Vec<_> = written.iter().map(|entry| entry.id).collect();
+    assert_eq!(ids, [entries[1].id, entries[4].id, entries[9].id]);
+}
+
+#[test]
+fn test_filter_by_proxy_name_and_method() {
+    let github = ProxyId::new();
+    let other = ProxyId::new();
+    let info = |id: &ProxyId, name: &str| ProxyInfo {
+        id: id.clone(),
+        name: name.to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    };
+    let call = r#"→ {"jsonrpc":"2.0","id":1,"method":"tools/call"}"#;
+    let list = r#"→ {"jsonrpc":"2.0","id":2,"method":"tools/list"}"#;
+
+    // Envelopes and bare entries can be mixed, and batches are split up
+    let lines = [
+        envelope(IpcMessage::ProxyStarted(info(&github, "github"))),
+        envelope(IpcMessage::ProxyStarted(info(&other, "other"))),
+        envelope(IpcMessage::LogsBatch(vec![
+            LogEntry::new(LogLevel::Request, call.to_string(), github.clone()),
+            LogEntry::new(LogLevel::Request, list.to_string(), github.clone()),
+        ])),
+        serde_json::to_string(&LogEntry::new(
+            LogLevel::Request,
+            call.to_string(),
+            other.clone(),
+        ))
+        .unwrap(),
+        serde_json::to_string(&LogEntry::new(
+            LogLevel::Request,
+            call.to_string(),
+            github.clone(),
+        ))
+        .unwrap(),
+    ];
+    let filter = LogFilter {
+        proxy_name: Some("github".to_string()),
+        method: Some("tools/call".to_string()),
+        ..Default::default()
+    };
+
+    let mut out = Vec::new();
+    let count = filter_logs(lines.join("\n").as_bytes(), filter, &mut out).unwrap();
+    assert_eq!(count, 2);
+    assert!(written_entries(&out)
+        .iter()
+        .all(|entry| entry.proxy_id == github && entry.message == call));
+
+    let error = filter_logs("not json\n".as_bytes(), LogFilter::default(), &mut out).unwrap_err();
+    assert!(error.to_string().contains("line 1"));
+}
@@ -0,0 +1,155 @@
+use mcp_common::*;
+use mcp_trace::inspect::{run_inspect, InspectArgs, InspectFormat, InspectSource};
+use std::io::Write;
+use tempfile::{tempdir, NamedTempFile};
+use uuid::Uuid;
+
+fn envelope(message: IpcMessage) -> String {
+    serde_json::to_string(&IpcEnvelope {
+        message,
+        timestamp: chrono::Utc::now(),
+        correlation_id: None,
+        sequence: 0,
+        session_id: None,
+    })
+    .unwrap()
+}
+
+/// A recording of a request, its response and a log line
+fn session() -> (NamedTempFile, Vec<LogEntry>) {
+    let proxy_id = ProxyId::new();
+    let entries = vec![
+        LogEntry::new(
+            LogLevel::Request,
+            r#"→ {"jsonrpc":"2.0","id":1,"method":"tools/list"}"#.to_string(),
+            proxy_id.clone(),
+        ),
+        LogEntry::new(
+            LogLevel::Response,
+            r#"← {"jsonrpc":"2.0","id":1,"result":{"tools":[]}}"#.to_string(),
+            proxy_id.clone(),
+        ),
+        LogEntry::new(LogLevel::Info, "done".to_string(), proxy_id),
+    ];
+
+    let mut file = NamedTempFile::new().unwrap();
+    for entry in &entries {
+        writeln!(file, "{}", envelope(IpcMessage::LogEntry(entry.clone()))).unwrap();
+    }
+    (file, entries)
+}
+
+async fn inspect(source: InspectSource, id: Uuid, format: InspectFormat) -> Option<String> {
+    let mut out = Vec::new();
+    let found = run_inspect(InspectArgs { id, source, format }, &mut out)
+        .await
+        .unwrap();
+    found.then(|| String::from_utf8(out).unwrap())
+}
+
+#[tokio::test]
+async fn test_inspect_prints_entry_as_json() {
+    let (file, entries) = session();
+    let output = inspect(
+        InspectSource::File(file.path().to_path_buf()),
+        entries[1].id,
+        InspectFormat::Json,
+    )
+    .await
+    .unwrap();
+
+    let printed: LogEntry = serde_json::from_str(&output).unwrap();
+    assert_eq!(printed.id, entries[1].id);
+    assert_eq!(printed.message, entries[1].message);
+}
+
+#[tokio::test]
+async fn test_inspect_prints_entry_as_text() {
+    let (file, entries) = session();
+    let output = inspect(
+        InspectSource::File(file.path().to_path_buf()),
+        entries[1].id,
+        InspectFormat::Text,
+    )
+    .await
+    .unwrap();
+
+    assert!(output.contains("Level:      Response"));
+    assert!(output.contains(&entries[1].proxy_id.0.to_string()));
+    // The JSON-RPC message is pretty-printed
+    assert!(output.contains(r#""tools": []"#));
+    assert!(!output.contains("tools/list"));
+}
+
+#[tokio::test]
+async fn test_inspect_keeps_exported_annotations() {
+    let entry = LogEntry::new(LogLevel::Error, "boom".to_string(), ProxyId::new());
+    let mut exported = serde_json::to_value(&entry).unwrap();
+    exported["annotation"] = "look at this".into();
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(file, "{}", exported).unwrap();
+
+    let output = inspect(
+        InspectSource::File(file.path().to_path_buf()),
+        entry.id,
+        InspectFormat::Text,
+    )
+    .await
+    .unwrap();
+    assert!(output.contains("Annotation: look at this"));
+    assert!(output.contains("boom"));
+}
+
+#[tokio::test]
+async fn test_inspect_reports_missing_entry() {
+    let (file, _) = session();
+    let output = inspect(
+        InspectSource::File(file.path().to_path_buf()),
+        Uuid::new_v4(),
+        InspectFormat::Json,
+    )
+    .await;
+    assert!(output.is_none());
+}
+
+#[tokio::test]
+async fn test_inspect_searches_running_monitor() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+    let (_, entries) = session();
+    let wanted = entries[2].id;
+
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept().await.unwrap();
+        let envelope = connection.receive_message().await.unwrap().unwrap();
+        assert!(matches!(
+            envelope.message,
+            IpcMessage::Subscribe { follow: false }
+        ));
+        for entry in entries {
+            // The client may hang up as soon as it has its entry
+            let _ = connection.send_message(IpcMessage::LogEntry(entry)).await;
+        }
+    });
+
+    let output = inspect(
+        InspectSource::Live {
+            ipc_socket: socket_path,
+            ipc_token: None,
+        },
+        wanted,
+        InspectFormat::Json,
+    )
+    .await
+    .unwrap();
+    server_task.await.unwrap();
+
+    let printed: LogEntry = serde_json::from_str(&output).unwrap();
+    assert_eq!(printed.id, wanted);
+    assert_eq!(printed.message, "done");
+}
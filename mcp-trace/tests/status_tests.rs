@@ -0,0 +1,121 @@
+use mcp_common::*;
+use mcp_trace::status::{run_status, StatusArgs};
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn stats(id: &ProxyId, total: u64, failed: u64) -> ProxyStats {
+    ProxyStats {
+        proxy_id: id.clone(),
+        total_requests: total,
+        successful_requests: total - failed,
+        failed_requests: failed,
+        bytes_transferred: total * 100,
+        uptime: Duration::from_secs(125),
+        ..ProxyStats::default()
+    }
+}
+
+/// Run a mock monitor that answers one `GetAllStats` with `messages`
+async fn status_with(messages: Vec<IpcMessage>, table: bool) -> String {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept().await.unwrap();
+        let envelope = connection.receive_message().await.unwrap().unwrap();
+        assert!(matches!(envelope.message, IpcMessage::GetAllStats));
+
+        for message in messages {
+            connection.send_message(message).await.unwrap();
+        }
+    });
+
+    let args = StatusArgs {
+        ipc_socket: socket_path,
+        ipc_token: None,
+        table,
+    };
+    let mut out = Vec::new();
+    run_status(args, &mut out).await.unwrap();
+    server_task.await.unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[tokio::test]
+async fn test_status_prints_json_for_every_proxy() {
+    let (alpha, beta) = (ProxyId::new(), ProxyId::new());
+    let messages = vec![
+        IpcMessage::ProxyStarted(ProxyInfo {
+            id: alpha.clone(),
+            name: "alpha".to_string(),
+            listen_address: "stdio".to_string(),
+            target_command: vec!["server".to_string()],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
+            status: ProxyStatus::Running,
+            stats: ProxyStats::default(),
+        }),
+        IpcMessage::AllStatsResponse(vec![stats(&beta, 3, 0), stats(&alpha, 10, 2)]),
+    ];
+
+    let output = status_with(messages, false).await;
+    let rows: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let rows = rows.as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+
+    // Announced proxies are listed by name, the rest by id
+    let row = |name: &str| rows.iter().find(|row| row["name"] == name).unwrap();
+    let alpha_row = row("alpha");
+    assert_eq!(alpha_row["status"], "Running");
+    assert_eq!(alpha_row["total_requests"], 10);
+    assert_eq!(alpha_row["failed_requests"], 2);
+    assert_eq!(alpha_row["bytes_transferred"], 1000);
+    assert_eq!(alpha_row["uptime_secs"], 125);
+    assert_eq!(row(&beta.0.to_string())["total_requests"], 3);
+}
+
+#[tokio::test]
+async fn test_status_prints_table() {
+    let alpha = ProxyId::new();
+    let messages = vec![IpcMessage::AllStatsResponse(vec![stats(&alpha, 10, 2)])];
+
+    let output = status_with(messages, true).await;
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("PROXY"));
+    let columns: Vec<&str> = lines[1].split_whitespace().collect();
+    assert_eq!(columns[1..], ["running", "10", "2", "1000", "2m05s"]);
+}
+
+#[tokio::test]
+async fn test_status_times_out_without_answer() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("test.sock")
+        .to_string_lossy()
+        .to_string();
+
+    // Accept the request but never answer it
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let server_task = tokio::spawn(async move {
+        let mut connection = server.accept().await.unwrap();
+        let _ = connection.receive_message().await;
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    });
+
+    let args = StatusArgs {
+        ipc_socket: socket_path,
+        ipc_token: None,
+        table: false,
+    };
+    let error = run_status(args, &mut Vec::new()).await.unwrap_err();
+    assert!(error.to_string().contains("no response"));
+    server_task.abort();
+}
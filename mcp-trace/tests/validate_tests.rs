@@ -0,0 +1,94 @@
+use mcp_common::*;
+use mcp_trace::validate::{run_validate, validate_session, write_report, ValidateArgs};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn recording(entries: &[(LogLevel, &str)]) -> String {
+    let proxy_id = ProxyId::new();
+    entries
+        .iter()
+        .map(|(level, message)| {
+            let entry = LogEntry::new(level.clone(), message.to_string(), proxy_id.clone());
+            serde_json::to_string(&IpcEnvelope {
+                message: IpcMessage::LogEntry(entry),
+                timestamp: chrono::Utc::now(),
+                correlation_id: None,
+                sequence: 0,
+                session_id: None,
+            })
+            .unwrap()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_validate_session_reports_violations() {
+    let session = recording(&[
+        (LogLevel::Request, r#"→ {"method":"tools/list","id":1}"#),
+        (
+            LogLevel::Response,
+            r#"← {"jsonrpc":"2.0","result":{},"id":1}"#,
+        ),
+        (
+            LogLevel::Response,
+            r#"← {"jsonrpc":"2.0","result":{},"id":2}"#,
+        ),
+        (LogLevel::Info, "Proxy started"),
+    ]);
+
+    let report = validate_session(session.as_bytes()).unwrap();
+    assert_eq!(report.entries_checked, 3);
+    let kinds: Vec<ViolationKind> = report.violations.iter().map(|v| v.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![ViolationKind::InvalidVersion, ViolationKind::OrphanResponse]
+    );
+
+    let mut out = Vec::new();
+    write_report(&report, true, &mut out).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["entries_checked"], 3);
+    assert_eq!(json["violations"][1]["kind"], "orphan_response");
+    assert_eq!(
+        json["violations"][1]["entry_id"],
+        report.violations[1].entry_id.to_string()
+    );
+}
+
+#[test]
+fn test_run_validate_clean_session() {
+    let mut file = NamedTempFile::new().unwrap();
+    let session = recording(&[
+        (
+            LogLevel::Request,
+            r#"→ {"jsonrpc":"2.0","method":"ping","id":"a"}"#,
+        ),
+        (
+            LogLevel::Response,
+            r#"← {"jsonrpc":"2.0","result":{},"id":"a"}"#,
+        ),
+    ]);
+    writeln!(file, "{}", session).unwrap();
+
+    let mut out = Vec::new();
+    let report = run_validate(
+        ValidateArgs {
+            file: file.path().to_path_buf(),
+            json: false,
+        },
+        &mut out,
+    )
+    .unwrap();
+
+    assert!(report.violations.is_empty());
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "0 violation(s) in 2 message(s)\n"
+    );
+}
+
+#[test]
+fn test_validate_session_rejects_non_envelopes() {
+    assert!(validate_session("{\"not\":\"an envelope\"}".as_bytes()).is_err());
+}
@@ -42,6 +42,9 @@ async fn test_end_to_end_proxy_monitor_communication() {
             name: format!("Test Proxy {}", i),
             listen_address: format!("127.0.0.1:808{}", i),
             target_command: vec!["python".to_string(), format!("server{}.py", i)],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
             status: ProxyStatus::Running,
             stats: ProxyStats::default(),
         };
@@ -60,8 +63,8 @@ async fn test_end_to_end_proxy_monitor_communication() {
     }
 
     // Process proxy registration messages
-    for i in 0..num_proxies {
-        if let Some(envelope) = connections[i].receive_message().await.unwrap() {
+    for connection in connections.iter_mut() {
+        if let Some(envelope) = connection.receive_message().await.unwrap() {
             match envelope.message {
                 IpcMessage::ProxyStarted(proxy_info) => {
                     app.handle_event(AppEvent::ProxyConnected(proxy_info));
@@ -111,12 +114,13 @@ async fn test_end_to_end_proxy_monitor_communication() {
             // Update stats
             let stats = ProxyStats {
                 proxy_id: proxy_id.clone(),
-                total_requests: (iteration + 1) as u64,
-                successful_requests: (iteration + 1) as u64,
+                total_requests: (iteration + 1),
+                successful_requests: (iteration + 1),
                 failed_requests: 0,
                 active_connections: 1,
                 uptime: Duration::from_secs((iteration + 1) * 10),
                 bytes_transferred: (iteration + 1) * 256,
+                ..Default::default()
             };
 
             proxy_clients[i]
@@ -136,20 +140,18 @@ async fn test_end_to_end_proxy_monitor_communication() {
 
     while total_processed < total_expected_messages {
         for connection in &mut connections {
-            match tokio::time::timeout(Duration::from_millis(100), connection.receive_message())
-                .await
+            // Timeout or error, continue
+            if let Ok(Ok(Some(envelope))) =
+                tokio::time::timeout(Duration::from_millis(100), connection.receive_message()).await
             {
-                Ok(Ok(Some(envelope))) => {
-                    let event = match envelope.message {
-                        IpcMessage::LogEntry(entry) => AppEvent::NewLogEntry(entry),
-                        IpcMessage::StatsUpdate(stats) => AppEvent::StatsUpdate(stats),
-                        _ => continue,
-                    };
-
-                    app.handle_event(event);
-                    total_processed += 1;
-                }
-                _ => {} // Timeout or error, continue
+                let event = match envelope.message {
+                    IpcMessage::LogEntry(entry) => AppEvent::NewLogEntry(entry),
+                    IpcMessage::StatsUpdate(stats) => AppEvent::StatsUpdate(stats),
+                    _ => continue,
+                };
+
+                app.handle_event(event);
+                total_processed += 1;
             }
         }
 
@@ -193,7 +195,7 @@ async fn test_end_to_end_proxy_monitor_communication() {
 
     // Test proxy-specific filtering
     app.switch_tab(mcp_monitor::TabType::All);
-    app.selected_proxy = Some(proxy_ids[0].clone());
+    app.set_proxy_filter(Some(proxy_ids[0].clone()));
     let proxy_0_logs = app.get_filtered_logs();
     assert_eq!(proxy_0_logs.len(), 10); // 5 iterations × 2 log entries
 
@@ -217,12 +219,14 @@ async fn test_end_to_end_proxy_monitor_communication() {
         }
     }
 
-    // Verify proxy was removed
-    assert_eq!(app.proxies.len(), num_proxies - 1);
-    assert!(!app.proxies.contains_key(&proxy_ids[0]));
-
-    // Verify selected proxy is cleared if it was the disconnected one
-    assert!(app.selected_proxy.is_none());
+    // Verify proxy is kept as stopped, and still filterable
+    assert_eq!(app.proxies.len(), num_proxies);
+    assert!(matches!(
+        app.proxies[&proxy_ids[0]].status,
+        ProxyStatus::Stopped
+    ));
+    assert_eq!(app.selected_proxy.as_ref(), Some(&proxy_ids[0]));
+    assert_eq!(app.total_stats().active_connections, 2);
 
     // Clean up remaining proxy clients
     for _client in proxy_clients {
@@ -257,6 +261,9 @@ async fn test_error_handling_end_to_end() {
         name: "Error Test Proxy".to_string(),
         listen_address: "127.0.0.1:8080".to_string(),
         target_command: vec!["python".to_string(), "error_server.py".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats::default(),
     };
@@ -321,6 +328,7 @@ async fn test_error_handling_end_to_end() {
         active_connections: 1,
         uptime: Duration::from_secs(300),
         bytes_transferred: 1024,
+        ..Default::default()
     };
 
     proxy_client
@@ -369,7 +377,7 @@ async fn test_error_handling_end_to_end() {
     }
 
     let search_results = app.get_search_filtered_logs();
-    assert!(search_results.len() >= 1); // Should find timeout error message
+    assert!(!search_results.is_empty()); // Should find timeout error message
 
     // Verify search results contain timeout-related content
     for log in &search_results {
@@ -413,6 +421,9 @@ async fn test_high_throughput_end_to_end() {
             "python".to_string(),
             "high_throughput_server.py".to_string(),
         ],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
         status: ProxyStatus::Running,
         stats: ProxyStats::default(),
     };
@@ -467,12 +478,13 @@ async fn test_high_throughput_end_to_end() {
         if i % 25 == 24 {
             let stats = ProxyStats {
                 proxy_id: proxy_id.clone(),
-                total_requests: (i + 1) as u64,
-                successful_requests: (i + 1) as u64,
+                total_requests: (i + 1),
+                successful_requests: (i + 1),
                 failed_requests: 0,
                 active_connections: 1,
                 uptime: Duration::from_secs((i + 1) / 10),
                 bytes_transferred: (i + 1) * 128,
+                ..Default::default()
             };
             proxy_client
                 .send(IpcMessage::StatsUpdate(stats))
@@ -516,8 +528,8 @@ async fn test_high_throughput_end_to_end() {
 
     // Verify final stats
     let proxy = &app.proxies[&proxy_id];
-    assert_eq!(proxy.stats.total_requests, num_requests as u64);
-    assert_eq!(proxy.stats.successful_requests, num_requests as u64);
+    assert_eq!(proxy.stats.total_requests, num_requests);
+    assert_eq!(proxy.stats.successful_requests, num_requests);
     assert_eq!(proxy.stats.failed_requests, 0);
 
     // Test that navigation still works with many logs
@@ -545,3 +557,380 @@ async fn test_high_throughput_end_to_end() {
 
     app.exit_search_mode();
 }
+
+#[tokio::test]
+async fn test_restarted_monitor_backfills_earlier_logs() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("backfill.sock")
+        .to_string_lossy()
+        .to_string();
+
+    // The first monitor receives two entries, then goes away
+    let server = IpcServer::bind(&socket_path).await.unwrap();
+    let client = BufferedIpcClient::new(socket_path.clone()).await;
+    let proxy_id = ProxyId::new();
+    client.set_registration(IpcMessage::ProxyStarted(ProxyInfo {
+        id: proxy_id.clone(),
+        name: "files".to_string(),
+        listen_address: "stdio".to_string(),
+        target_command: vec!["server".to_string()],
+        env_vars: Vec::new(),
+        protocol_version: None,
+        monitor_connected: true,
+        status: ProxyStatus::Running,
+        stats: ProxyStats::default(),
+    }));
+    for index in 0..2 {
+        let entry = LogEntry::new(
+            LogLevel::Info,
+            format!("before {}", index),
+            proxy_id.clone(),
+        );
+        client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+    }
+
+    let mut connection = server.accept().await.unwrap();
+    let mut received = 0;
+    while received < 2 {
+        let envelope = tokio::time::timeout(Duration::from_secs(5), connection.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::LogEntry(_) = envelope.message {
+            received += 1;
+        }
+    }
+    drop(connection);
+    drop(server);
+    sleep(Duration::from_millis(200)).await;
+
+    // A fresh monitor learns about the proxy again and asks for what it missed
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(100);
+    let (_command_tx, command_rx) = tokio::sync::mpsc::channel(1);
+    let monitor_socket = socket_path.clone();
+    let monitor = tokio::spawn(async move {
+        mcp_monitor::run_ipc_server(&monitor_socket, None, 0, event_tx, command_rx).await
+    });
+    let mut app = App::new();
+    app.switch_tab(mcp_monitor::TabType::All);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while app.logs.len() < 2 {
+        let event = tokio::time::timeout_at(deadline, event_rx.recv())
+            .await
+            .expect("earlier entries should be backfilled")
+            .unwrap();
+        app.handle_event(event);
+    }
+
+    assert_eq!(app.proxies[&proxy_id].name, "files");
+    let messages: Vec<_> = app.logs.iter().map(|log| log.message.clone()).collect();
+    assert_eq!(messages, ["before 0", "before 1"]);
+
+    // Entries sent after the reconnect are not backfilled a second time
+    let entry = LogEntry::new(LogLevel::Info, "after".to_string(), proxy_id.clone());
+    client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+    while let Ok(Some(event)) =
+        tokio::time::timeout(Duration::from_millis(500), event_rx.recv()).await
+    {
+        app.handle_event(event);
+    }
+    assert_eq!(app.logs.len(), 3);
+    assert_eq!(app.logs[2].message, "after");
+
+    monitor.abort();
+    client.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_monitor_survives_oversized_message() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("oversized.sock")
+        .to_string_lossy()
+        .to_string();
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(100);
+    let (_command_tx, command_rx) = tokio::sync::mpsc::channel(1);
+    let monitor_socket = socket_path.clone();
+    let monitor = tokio::spawn(async move {
+        mcp_monitor::run_ipc_server(&monitor_socket, None, 0, event_tx, command_rx).await
+    });
+    sleep(Duration::from_millis(200)).await;
+
+    // Straight over IPC, as the proxy's client would cut the message down first
+    let mut client = IpcClient::connect(&socket_path).await.unwrap();
+    let proxy_id = ProxyId::new();
+    for message in [
+        "x".repeat(DEFAULT_MAX_MESSAGE_BYTES + 1),
+        "after".to_string(),
+    ] {
+        let entry = LogEntry::new(LogLevel::Response, message, proxy_id.clone());
+        client.send(IpcMessage::LogEntry(entry)).await.unwrap();
+    }
+
+    let mut app = App::new();
+    app.switch_tab(mcp_monitor::TabType::All);
+    while app.logs.len() < 2 {
+        let event = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
+            .await
+            .expect("monitor should keep reading after the oversized message")
+            .unwrap();
+        app.handle_event(event);
+    }
+    assert_eq!(app.skipped_messages, 1);
+    let messages: Vec<_> = app
+        .logs
+        .iter()
+        .map(|log| (log.level.clone(), log.message.clone()))
+        .collect();
+    assert_eq!(messages[0].0, LogLevel::Warning);
+    assert!(messages[0].1.starts_with("Skipped an IPC message"));
+    assert_eq!(messages[1], (LogLevel::Response, "after".to_string()));
+
+    monitor.abort();
+}
+
+// Writes raw bytes over the Unix socket
+#[cfg(unix)]
+#[tokio::test]
+async fn test_monitor_skips_malformed_messages() {
+    use tokio::io::AsyncWriteExt;
+
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("malformed.sock")
+        .to_string_lossy()
+        .to_string();
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(100);
+    let (_command_tx, command_rx) = tokio::sync::mpsc::channel(1);
+    let monitor_socket = socket_path.clone();
+    let monitor = tokio::spawn(async move {
+        mcp_monitor::run_ipc_server(&monitor_socket, None, 0, event_tx, command_rx).await
+    });
+    sleep(Duration::from_millis(200)).await;
+
+    let proxy_id = ProxyId::new();
+    let envelope = |message: &str| {
+        let entry = LogEntry::new(LogLevel::Info, message.to_string(), proxy_id.clone());
+        serde_json::to_string(&IpcEnvelope {
+            timestamp: entry.timestamp,
+            message: IpcMessage::LogEntry(entry),
+            correlation_id: None,
+            sequence: 0,
+            session_id: None,
+        })
+        .unwrap()
+    };
+    let stream = [
+        "not json at all".to_string(),
+        envelope("one"),
+        r#"{"not": "an envelope"}"#.to_string(),
+        envelope("two"),
+    ]
+    .join("\n")
+        + "\n";
+    let mut proxy = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+    proxy.write_all(stream.as_bytes()).await.unwrap();
+
+    let mut app = App::new();
+    app.switch_tab(mcp_monitor::TabType::All);
+    while app.logs.len() < 4 {
+        let event = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
+            .await
+            .expect("monitor should keep reading after a malformed message")
+            .unwrap();
+        app.handle_event(event);
+    }
+
+    assert_eq!(app.skipped_messages, 2);
+    let levels: Vec<_> = app.logs.iter().map(|log| log.level.clone()).collect();
+    assert_eq!(
+        levels,
+        [
+            LogLevel::Warning,
+            LogLevel::Info,
+            LogLevel::Warning,
+            LogLevel::Info
+        ]
+    );
+    assert_eq!(app.logs[1].message, "one");
+    assert_eq!(app.logs[3].message, "two");
+    assert!(app.logs[0].message.starts_with("Skipped an IPC message"));
+
+    monitor.abort();
+}
+
+/// Feed monitor events to `app` up to and including the first one matching `done`
+async fn apply_events_until(
+    app: &mut App,
+    event_rx: &mut tokio::sync::mpsc::Receiver<AppEvent>,
+    done: impl Fn(&AppEvent) -> bool,
+) {
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
+            .await
+            .expect("monitor should report the proxy")
+            .unwrap();
+        let finished = done(&event);
+        app.handle_event(event);
+        if finished {
+            return;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_monitor_flags_proxy_that_stops_responding() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("keepalive.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(100);
+    let (_command_tx, command_rx) = tokio::sync::mpsc::channel(1);
+    let monitor_socket = socket_path.clone();
+    let monitor = tokio::spawn(async move {
+        mcp_monitor::run_ipc_server_with_keepalive(
+            &monitor_socket,
+            None,
+            0,
+            Duration::from_millis(100),
+            Duration::from_millis(300),
+            event_tx,
+            command_rx,
+        )
+        .await
+    });
+    sleep(Duration::from_millis(100)).await;
+
+    // A proxy that announces itself and then goes quiet, ignoring the monitor's pings
+    let mut proxy = IpcConnection::connect(&socket_path).await.unwrap();
+    let proxy_id = ProxyId::new();
+    proxy
+        .send_message(IpcMessage::ProxyStarted(ProxyInfo {
+            id: proxy_id.clone(),
+            name: "hung".to_string(),
+            listen_address: "stdio".to_string(),
+            target_command: vec!["server".to_string()],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
+            status: ProxyStatus::Running,
+            stats: ProxyStats::default(),
+        }))
+        .await
+        .unwrap();
+
+    let mut app = App::new();
+    apply_events_until(&mut app, &mut event_rx, |event| {
+        matches!(event, AppEvent::ProxyUnresponsive(_))
+    })
+    .await;
+    assert!(
+        matches!(&app.proxies[&proxy_id].status, ProxyStatus::Error(reason) if reason == "unresponsive")
+    );
+
+    // Answering a ping brings it back (the monitor also asked for earlier logs)
+    loop {
+        let envelope = tokio::time::timeout(Duration::from_secs(5), proxy.receive_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let IpcMessage::Ping = envelope.message {
+            break;
+        }
+    }
+    proxy.send_message(IpcMessage::Pong).await.unwrap();
+    apply_events_until(&mut app, &mut event_rx, |event| {
+        matches!(event, AppEvent::ProxyHeartbeat(_))
+    })
+    .await;
+    assert!(matches!(
+        app.proxies[&proxy_id].status,
+        ProxyStatus::Running
+    ));
+
+    // Dying without ProxyStopped still shows the proxy as stopped
+    drop(proxy);
+    apply_events_until(&mut app, &mut event_rx, |event| {
+        matches!(event, AppEvent::ProxyDisconnected(_))
+    })
+    .await;
+    assert!(matches!(
+        app.proxies[&proxy_id].status,
+        ProxyStatus::Stopped
+    ));
+
+    monitor.abort();
+}
+
+#[tokio::test]
+async fn test_monitor_routes_shutdown_to_the_named_proxy() {
+    let temp_dir = tempdir().unwrap();
+    let socket_path = temp_dir
+        .path()
+        .join("commands.sock")
+        .to_string_lossy()
+        .to_string();
+
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::channel(100);
+    let (command_tx, command_rx) = tokio::sync::mpsc::channel(4);
+    let monitor_socket = socket_path.clone();
+    let monitor = tokio::spawn(async move {
+        mcp_monitor::run_ipc_server(&monitor_socket, None, 0, event_tx, command_rx).await
+    });
+    sleep(Duration::from_millis(100)).await;
+
+    // Two proxies, each listening for what the monitor sends it
+    let mut proxies = Vec::new();
+    for name in ["first", "second"] {
+        let client = BufferedIpcClient::new(socket_path.clone()).await;
+        let proxy_id = ProxyId::new();
+        let inbound = client.subscribe();
+        client.set_registration(IpcMessage::ProxyStarted(ProxyInfo {
+            id: proxy_id.clone(),
+            name: name.to_string(),
+            listen_address: "stdio".to_string(),
+            target_command: vec!["server".to_string()],
+            env_vars: Vec::new(),
+            protocol_version: None,
+            monitor_connected: true,
+            status: ProxyStatus::Running,
+            stats: ProxyStats::default(),
+        }));
+        proxies.push((client, proxy_id, inbound));
+    }
+    let mut app = App::new();
+    while app.proxies.len() < 2 {
+        let event = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        app.handle_event(event);
+    }
+
+    let target = proxies[1].1.clone();
+    command_tx
+        .send((target.clone(), IpcMessage::Shutdown(target.clone())))
+        .await
+        .unwrap();
+
+    let message = tokio::time::timeout(Duration::from_secs(5), proxies[1].2.recv())
+        .await
+        .expect("the named proxy should receive the shutdown")
+        .unwrap();
+    assert!(matches!(message, IpcMessage::Shutdown(id) if id == target));
+    assert!(proxies[0].2.try_recv().is_err());
+
+    monitor.abort();
+    for (client, _, _) in proxies {
+        client.shutdown().await;
+    }
+}